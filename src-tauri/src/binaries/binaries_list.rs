@@ -22,6 +22,8 @@
 
 use std::path::PathBuf;
 
+use serde::{Deserialize, Serialize};
+
 use crate::utils::platform_utils::{CurrentOperatingSystem, PlatformUtils};
 
 pub enum BinaryPlatformAssets {
@@ -31,7 +33,7 @@ pub enum BinaryPlatformAssets {
     MacOSArm64,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Binaries {
     Xmrig,
     MergeMiningProxy,
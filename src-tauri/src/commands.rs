@@ -31,24 +31,46 @@ use crate::configs::config_mining::{
 use crate::configs::config_pools::{ConfigPools, ConfigPoolsContent};
 use crate::configs::config_ui::{ConfigUI, ConfigUIContent, DisplayMode};
 use crate::configs::config_wallet::{ConfigWallet, ConfigWalletContent, WalletId};
+use crate::configs::pools::custom_pools::CustomPoolDefinition;
+use crate::configs::pools::worker_name::{hostname_worker_name, validate_worker_name};
 use crate::configs::pools::BasePoolData;
 use crate::configs::pools::{cpu_pools::CpuPool, gpu_pools::GpuPool};
 use crate::configs::trait_config::ConfigImpl;
-use crate::event_scheduler::{EventScheduler, SchedulerEventTiming, SchedulerEventType};
+use crate::control_api::{ControlApi, ControlApiSettings};
+use crate::event_scheduler::{
+    EventScheduler, SchedulerEventTiming, SchedulerEventType, SchedulerTransition,
+};
 use crate::events::ConnectionStatusPayload;
 use crate::events_emitter::EventsEmitter;
 use crate::events_manager::EventsManager;
 use crate::internal_wallet::{mnemonic_to_tari_cipher_seed, InternalWallet, PaperWalletConfig};
+use crate::metrics_server::{MetricsServer, MetricsServerSettings};
 use crate::mining::cpu::manager::CpuManager;
-use crate::mining::gpu::consts::{EngineType, GpuMinerType};
+use crate::mining::cpu::tuning::CpuTuningOverrides;
+use crate::mining::cpu::CpuMinerStatus;
+use crate::mining::gpu::benchmark::{GpuBenchmark, DEFAULT_RUN_DURATION};
+use crate::mining::gpu::consts::{EngineType, GpuMinerType, GpuMiningAlgorithm};
 use crate::mining::gpu::manager::GpuManager;
+use crate::mining::gpu::watchdog::{
+    GpuDeviceLimits, GpuWatchdog, GpuWatchdogAction, GpuWatchdogIntervention,
+};
+use crate::mining::mode_selection;
 use crate::mining::pools::cpu_pool_manager::CpuPoolManager;
+use crate::mining::pools::earnings_history::{
+    PoolEarningsAggregate, PoolEarningsDataset, PoolEarningsExportFormat, PoolEarningsFilter,
+    PoolEarningsHistory, PoolEarningsPeriod, PoolPayout,
+};
 use crate::mining::pools::gpu_pool_manager::GpuPoolManager;
+use crate::mining::pools::pool_probe::{probe_cpu_pool, probe_gpu_pool, PoolProbeResult};
+use crate::mining::pools::pool_selection::{select_cpu_pool, select_gpu_pool};
 use crate::mining::pools::PoolManagerInterfaceTrait;
 use crate::node::node_adapter::BaseNodeStatus;
 use crate::node::node_manager::NodeType;
 use crate::pin::PinManager;
+use crate::process_isolation::{release_cgroups, ProcessIsolation};
+use crate::process_output::{ProcessLogLevel, ProcessOutputBuffers, ProcessOutputLine};
 use crate::release_notes::ReleaseNotes;
+use crate::scheduler_history::SchedulerExecutionRecord;
 use crate::setup::setup_manager::{SetupManager, SetupPhase};
 use crate::shutdown_manager::{ShutdownManager, ShutdownMode};
 use crate::system_dependencies::system_dependencies_manager::SystemDependenciesManager;
@@ -69,6 +91,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::fs::{read_dir, remove_dir_all, remove_file, File};
+use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::atomic::Ordering;
 use std::thread::sleep;
@@ -84,9 +107,12 @@ use tari_utilities::SafePassword;
 use tauri::ipc::InvokeError;
 use tauri::Manager;
 use urlencoding::encode;
+use uuid::Uuid;
 
 const MAX_ACCEPTABLE_COMMAND_TIME: Duration = Duration::from_secs(1);
 const LOG_TARGET_WEB: &str = "tari::universe::web";
+const MIN_POOL_FAILOVER_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_PROCESS_LOG_LINES: usize = 200;
 
 #[derive(Debug, Serialize)]
 pub struct ApplicationsInformation {
@@ -231,6 +257,7 @@ pub async fn exit_application(
     state.tor_manager.on_app_exit().await;
     state.wallet_manager.on_app_exit().await;
     state.node_manager.on_app_exit().await;
+    release_cgroups();
 
     app.exit(0);
     Ok(())
@@ -859,6 +886,7 @@ pub async fn reset_settings(
     }
 
     info!(target: LOG_TARGET_APP_LOGIC, "[reset_settings] Restarting the app");
+    release_cgroups();
     app_handle.restart()
 }
 
@@ -867,6 +895,7 @@ pub async fn restart_application(
     _window: tauri::Window,
     app: tauri::AppHandle,
 ) -> Result<(), String> {
+    release_cgroups();
     app.restart();
 }
 
@@ -1032,6 +1061,301 @@ pub async fn toggle_device_exclusion(device_index: u32, excluded: bool) -> Resul
     Ok(())
 }
 
+#[tauri::command]
+pub async fn set_gpu_device_intensity(
+    device_index: u32,
+    intensity_percentage: Option<u32>,
+) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[set_gpu_device_intensity] called with device_index: {device_index}, intensity_percentage: {intensity_percentage:?}");
+
+    if intensity_percentage.is_some_and(|intensity| !(1..=100).contains(&intensity)) {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "GPU device intensity must be between 1 and 100"
+        )));
+    }
+    if !ConfigMining::content()
+        .await
+        .gpu_devices_settings()
+        .contains(device_index)
+    {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "Unknown GPU device {device_index}"
+        )));
+    }
+    ConfigMining::update_field(
+        ConfigMiningContent::set_gpu_device_intensity,
+        (device_index, intensity_percentage),
+    )
+    .await
+    .map_err(InvokeError::from_anyhow)?;
+    EventsEmitter::emit_update_gpu_devices_settings(
+        ConfigMining::content().await.gpu_devices_settings().clone(),
+    )
+    .await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "set_gpu_device_intensity took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_gpu_device_engine(
+    device_index: u32,
+    engine: Option<String>,
+) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[set_gpu_device_engine] called with device_index: {device_index}, engine: {engine:?}");
+
+    let engine = engine
+        .map(|engine| EngineType::from_string(&engine))
+        .transpose()
+        .map_err(InvokeError::from_anyhow)?;
+    if !ConfigMining::content()
+        .await
+        .gpu_devices_settings()
+        .contains(device_index)
+    {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "Unknown GPU device {device_index}"
+        )));
+    }
+    ConfigMining::update_field(
+        ConfigMiningContent::set_gpu_device_engine,
+        (device_index, engine),
+    )
+    .await
+    .map_err(InvokeError::from_anyhow)?;
+    EventsEmitter::emit_update_gpu_devices_settings(
+        ConfigMining::content().await.gpu_devices_settings().clone(),
+    )
+    .await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "set_gpu_device_engine took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_gpu_device_miner(
+    device_index: u32,
+    miner: Option<GpuMinerType>,
+) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[set_gpu_device_miner] called with device_index: {device_index}, miner: {miner:?}");
+
+    let mut mining_config = ConfigMining::content().await;
+    if !mining_config.gpu_devices_settings().contains(device_index) {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "Unknown GPU device {device_index}"
+        )));
+    }
+    if let Some(miner) = &miner {
+        if !GpuManager::read().await.is_miner_healthy(miner).await {
+            return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+                "GPU miner {miner} is not available"
+            )));
+        }
+    }
+    mining_config.set_gpu_device_miner((device_index, miner.clone()));
+    if !mining_config.has_gpu_devices_for_miner(mining_config.gpu_miner_type()) {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "At least one device has to stay with the selected GPU miner"
+        )));
+    }
+
+    ConfigMining::update_field(
+        ConfigMiningContent::set_gpu_device_miner,
+        (device_index, miner),
+    )
+    .await
+    .map_err(InvokeError::from_anyhow)?;
+    EventsEmitter::emit_update_gpu_devices_settings(
+        ConfigMining::content().await.gpu_devices_settings().clone(),
+    )
+    .await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "set_gpu_device_miner took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn start_gpu_benchmark(
+    run_duration_secs: Option<u64>,
+    apply_recommendations: bool,
+) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[start_gpu_benchmark] called with run_duration_secs: {run_duration_secs:?}, apply_recommendations: {apply_recommendations}");
+
+    let run_duration = run_duration_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_RUN_DURATION);
+    GpuBenchmark::start(run_duration, apply_recommendations)
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "start_gpu_benchmark took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn cancel_gpu_benchmark() -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[cancel_gpu_benchmark] called");
+
+    GpuBenchmark::cancel()
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "cancel_gpu_benchmark took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn apply_gpu_benchmark_recommendations() -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[apply_gpu_benchmark_recommendations] called");
+
+    let report = ConfigMining::content()
+        .await
+        .gpu_benchmark_report()
+        .clone()
+        .ok_or_else(|| InvokeError::from_anyhow(anyhow::anyhow!("No GPU benchmark report")))?;
+    GpuBenchmark::apply_recommendations(&report.recommendations)
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "apply_gpu_benchmark_recommendations took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_gpu_device_limits(
+    device_index: u32,
+    max_temperature: Option<u32>,
+    max_power_watts: Option<u32>,
+) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[set_gpu_device_limits] called with device_index: {device_index}, max_temperature: {max_temperature:?}, max_power_watts: {max_power_watts:?}");
+
+    if max_temperature.is_some_and(|temperature| !(40..=110).contains(&temperature)) {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "GPU temperature limit must be between 40 and 110 degrees Celsius"
+        )));
+    }
+    if max_power_watts == Some(0) {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "GPU power limit must be above 0 W"
+        )));
+    }
+    if !ConfigMining::content()
+        .await
+        .gpu_devices_settings()
+        .contains(device_index)
+    {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "Unknown GPU device {device_index}"
+        )));
+    }
+    ConfigMining::update_field(
+        ConfigMiningContent::set_gpu_device_limits,
+        (
+            device_index,
+            GpuDeviceLimits {
+                max_temperature,
+                max_power_watts,
+            },
+        ),
+    )
+    .await
+    .map_err(InvokeError::from_anyhow)?;
+    EventsEmitter::emit_update_gpu_devices_settings(
+        ConfigMining::content().await.gpu_devices_settings().clone(),
+    )
+    .await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "set_gpu_device_limits took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_gpu_watchdog_action(action: GpuWatchdogAction) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[set_gpu_watchdog_action] called with action: {action:?}");
+
+    ConfigMining::update_field(ConfigMiningContent::set_gpu_watchdog_action, action)
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "set_gpu_watchdog_action took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_gpu_watchdog_interventions() -> Result<Vec<GpuWatchdogIntervention>, InvokeError> {
+    let timer = Instant::now();
+
+    let interventions = GpuWatchdog::history().await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "get_gpu_watchdog_interventions took too long: {:?}", timer.elapsed());
+    }
+    Ok(interventions)
+}
+
+#[tauri::command]
+pub async fn get_process_logs(
+    binary: Binaries,
+    limit: Option<usize>,
+    min_level: Option<ProcessLogLevel>,
+) -> Result<Vec<ProcessOutputLine>, InvokeError> {
+    let timer = Instant::now();
+
+    let lines = ProcessOutputBuffers::tail(
+        binary,
+        limit.unwrap_or(DEFAULT_PROCESS_LOG_LINES),
+        min_level,
+    )
+    .await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "get_process_logs took too long: {:?}", timer.elapsed());
+    }
+    Ok(lines)
+}
+
+#[tauri::command]
+pub async fn search_process_logs(
+    binary: Binaries,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<ProcessOutputLine>, InvokeError> {
+    let timer = Instant::now();
+
+    let lines =
+        ProcessOutputBuffers::search(binary, &query, limit.unwrap_or(DEFAULT_PROCESS_LOG_LINES))
+            .await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "search_process_logs took too long: {:?}", timer.elapsed());
+    }
+    Ok(lines)
+}
+
 #[tauri::command]
 pub async fn set_gpu_mining_enabled(enabled: bool) -> Result<(), InvokeError> {
     let timer = Instant::now();
@@ -1074,25 +1398,25 @@ pub async fn select_mining_mode(mode: String) -> Result<(), InvokeError> {
     let timer = Instant::now();
     info!(target: LOG_TARGET_APP_LOGIC, "[select_mining_mode] called with mode: {mode:?}");
 
-    ConfigMining::update_field(ConfigMiningContent::set_selected_mining_mode, mode.clone())
+    mode_selection::select_mining_mode(mode)
         .await
         .map_err(InvokeError::from_anyhow)?;
 
-    if mode != "Eco" {
-        ConfigMining::update_field(ConfigMiningContent::set_eco_alert_needed, false)
-            .await
-            .map_err(InvokeError::from_anyhow)?;
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "select_mining_mode took too long: {:?}", timer.elapsed());
     }
+    Ok(())
+}
 
-    SystemTrayManager::send_event(SystemTrayEvents::MiningMode(MiningModeType::from(
-        mode.clone(),
-    )))
-    .await;
+#[tauri::command]
+pub async fn get_cpu_miner_stats() -> Result<CpuMinerStatus, InvokeError> {
+    let timer = Instant::now();
+    let status = CpuManager::read().await.status();
 
     if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
-        warn!(target: LOG_TARGET_APP_LOGIC, "select_mining_mode took too long: {:?}", timer.elapsed());
+        warn!(target: LOG_TARGET_APP_LOGIC, "get_cpu_miner_stats took too long: {:?}", timer.elapsed());
     }
-    Ok(())
+    Ok(status)
 }
 
 #[tauri::command]
@@ -1117,12 +1441,95 @@ pub async fn update_custom_mining_mode(
     .await
     .map_err(InvokeError::from_anyhow)?;
 
+    if ConfigMining::content().await.selected_mining_mode() == "Custom" {
+        CpuManager::write()
+            .await
+            .apply_selected_mining_mode()
+            .await
+            .map_err(InvokeError::from_anyhow)?;
+    }
+
     if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
         warn!(target: LOG_TARGET_APP_LOGIC, "update_custom_mining_mode took too long: {:?}", timer.elapsed());
     }
     Ok(())
 }
 
+#[tauri::command]
+pub async fn update_mode_cpu_tuning(
+    mode_name: String,
+    cpu_tuning: CpuTuningOverrides,
+) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[update_mode_cpu_tuning] called with mode_name: {mode_name:?}, cpu_tuning: {cpu_tuning:?}");
+
+    cpu_tuning.validate().map_err(InvokeError::from_anyhow)?;
+    let mode_type = ConfigMining::content()
+        .await
+        .mining_modes()
+        .get(&mode_name)
+        .map(|mode| mode.mode_type);
+    match mode_type {
+        Some(MiningModeType::Custom | MiningModeType::User) => {}
+        Some(_) => {
+            return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+                "Mining mode {mode_name} does not support cpu tuning overrides"
+            )));
+        }
+        None => {
+            return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+                "Mining mode {mode_name} does not exist"
+            )));
+        }
+    }
+
+    ConfigMining::update_field(
+        ConfigMiningContent::update_mode_cpu_tuning,
+        (mode_name.clone(), cpu_tuning),
+    )
+    .await
+    .map_err(InvokeError::from_anyhow)?;
+
+    if *ConfigMining::content().await.selected_mining_mode() == mode_name {
+        CpuManager::write()
+            .await
+            .apply_selected_mining_mode()
+            .await
+            .map_err(InvokeError::from_anyhow)?;
+    }
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "update_mode_cpu_tuning took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+/// Scheduling and resource limits are applied the next time the cpu miner starts
+#[tauri::command]
+pub async fn update_custom_mode_process_isolation(
+    process_isolation: Option<ProcessIsolation>,
+) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[update_custom_mode_process_isolation] called with process_isolation: {process_isolation:?}");
+
+    if let Some(process_isolation) = &process_isolation {
+        process_isolation
+            .validate()
+            .map_err(InvokeError::from_anyhow)?;
+    }
+    ConfigMining::update_field(
+        ConfigMiningContent::update_custom_mode_process_isolation,
+        process_isolation,
+    )
+    .await
+    .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "update_custom_mode_process_isolation took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn set_monero_address(monero_address: String) -> Result<(), InvokeError> {
     let timer = Instant::now();
@@ -1701,49 +2108,179 @@ pub async fn set_node_type(
 }
 
 #[tauri::command]
-pub async fn change_cpu_pool(cpu_pool: String) -> Result<(), InvokeError> {
+pub async fn change_cpu_pool(cpu_pool: String, force: Option<bool>) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[change_cpu_pool] called with cpu_pool: {cpu_pool:?}, force: {force:?}");
+
+    let cpu_pool = CpuPool::from_string(&cpu_pool).map_err(InvokeError::from_anyhow)?;
+    select_cpu_pool(cpu_pool, force.unwrap_or(false))
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "change_cpu_pool took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn change_gpu_pool(gpu_pool: String, force: Option<bool>) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[change_gpu_pool] called with gpu_pool: {gpu_pool:?}, force: {force:?}");
+
+    let gpu_pool = GpuPool::from_string(&gpu_pool).map_err(InvokeError::from_anyhow)?;
+    select_gpu_pool(gpu_pool, force.unwrap_or(false))
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "change_gpu_pool took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn probe_pool(pool_key: String) -> Result<PoolProbeResult, InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[probe_pool] called with pool_key: {pool_key:?}");
+
+    let pools_config = ConfigPools::content().await;
+    let probe_result = if let Some(pool_data) = CpuPool::from_string(&pool_key)
+        .ok()
+        .and_then(|pool| pools_config.cpu_pools().get(&pool).cloned())
+    {
+        probe_cpu_pool(&pool_data).await
+    } else if let Some(pool_data) = GpuPool::from_string(&pool_key)
+        .ok()
+        .and_then(|pool| pools_config.gpu_pools().get(&pool).cloned())
+    {
+        probe_gpu_pool(&pool_data).await
+    } else {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "Unknown pool: {pool_key}"
+        )));
+    };
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "probe_pool took too long: {:?}", timer.elapsed());
+    }
+    Ok(probe_result)
+}
+
+#[tauri::command]
+pub async fn set_cpu_fallback_pools(cpu_pools: Vec<String>) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[set_cpu_fallback_pools] called with cpu_pools: {cpu_pools:?}");
+
+    let cpu_pools = cpu_pools
+        .iter()
+        .map(|cpu_pool| CpuPool::from_string(cpu_pool))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(InvokeError::from_anyhow)?;
+    ConfigPools::update_field(ConfigPoolsContent::set_cpu_fallback_pools, cpu_pools)
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+    EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "set_cpu_fallback_pools took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_gpu_fallback_pools(
+    algorithm: GpuMiningAlgorithm,
+    gpu_pools: Vec<String>,
+) -> Result<(), InvokeError> {
     let timer = Instant::now();
-    info!(target: LOG_TARGET_APP_LOGIC, "[change_cpu_pool] called with cpu_pool: {cpu_pool:?}");
+    info!(target: LOG_TARGET_APP_LOGIC, "[set_gpu_fallback_pools] called with algorithm: {algorithm:?}, gpu_pools: {gpu_pools:?}");
+
+    let gpu_pools = gpu_pools
+        .iter()
+        .map(|gpu_pool| GpuPool::from_string(gpu_pool))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(InvokeError::from_anyhow)?;
+    if let Some(gpu_pool) = gpu_pools.iter().find(|pool| pool.algorithm() != algorithm) {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "Pool {gpu_pool} does not mine {algorithm:?}"
+        )));
+    }
 
     ConfigPools::update_field(
-        ConfigPoolsContent::set_current_cpu_pool,
-        CpuPool::from_string(&cpu_pool).map_err(InvokeError::from_anyhow)?,
+        |config: &mut ConfigPoolsContent, gpu_pools| {
+            config.set_gpu_fallback_pools_for_algorithm(algorithm, gpu_pools)
+        },
+        gpu_pools,
     )
     .await
     .map_err(InvokeError::from_anyhow)?;
-
-    let cpu_pool_content = ConfigPools::content().await.current_cpu_pool();
-
-    CpuPoolManager::handle_new_selected_pool(cpu_pool_content).await;
+    EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
 
     if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
-        warn!(target: LOG_TARGET_APP_LOGIC, "change_cpu_pool took too long: {:?}", timer.elapsed());
+        warn!(target: LOG_TARGET_APP_LOGIC, "set_gpu_fallback_pools took too long: {:?}", timer.elapsed());
     }
     Ok(())
 }
 
 #[tauri::command]
-pub async fn change_gpu_pool(gpu_pool: String) -> Result<(), InvokeError> {
+pub async fn set_pool_failover_timeout(timeout_secs: u64) -> Result<(), InvokeError> {
     let timer = Instant::now();
-    info!(target: LOG_TARGET_APP_LOGIC, "[change_gpu_pool] called with gpu_pool: {gpu_pool:?}");
+    info!(target: LOG_TARGET_APP_LOGIC, "[set_pool_failover_timeout] called with timeout_secs: {timeout_secs:?}");
 
+    if timeout_secs < MIN_POOL_FAILOVER_TIMEOUT_SECS {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "Pool failover timeout must be at least {MIN_POOL_FAILOVER_TIMEOUT_SECS} seconds"
+        )));
+    }
     ConfigPools::update_field(
-        ConfigPoolsContent::set_current_gpu_pool,
-        GpuPool::from_string(&gpu_pool).map_err(InvokeError::from_anyhow)?,
+        ConfigPoolsContent::set_pool_failover_timeout_secs,
+        timeout_secs,
     )
     .await
     .map_err(InvokeError::from_anyhow)?;
+    EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
 
-    let gpu_pool_content = ConfigPools::content().await.current_gpu_pool();
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "set_pool_failover_timeout took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
 
-    GpuPoolManager::handle_new_selected_pool(gpu_pool_content).await;
+#[tauri::command]
+pub async fn set_pool_worker_name(worker_name: String) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[set_pool_worker_name] called with worker_name: {worker_name:?}");
+
+    validate_worker_name(&worker_name).map_err(InvokeError::from_anyhow)?;
+    ConfigPools::update_field(ConfigPoolsContent::set_worker_name, worker_name)
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+    EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
 
     if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
-        warn!(target: LOG_TARGET_APP_LOGIC, "change_gpu_pool took too long: {:?}", timer.elapsed());
+        warn!(target: LOG_TARGET_APP_LOGIC, "set_pool_worker_name took too long: {:?}", timer.elapsed());
     }
     Ok(())
 }
 
+#[tauri::command]
+pub async fn use_hostname_as_pool_worker_name() -> Result<String, InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[use_hostname_as_pool_worker_name] called");
+
+    let worker_name = hostname_worker_name();
+    ConfigPools::update_field(ConfigPoolsContent::set_worker_name, worker_name.clone())
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+    EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "use_hostname_as_pool_worker_name took too long: {:?}", timer.elapsed());
+    }
+    Ok(worker_name)
+}
+
 #[tauri::command]
 pub async fn update_selected_gpu_pool_config(
     updated_config: BasePoolData<GpuPool>,
@@ -1792,6 +2329,11 @@ pub async fn reset_gpu_pool_config(gpu_pool_type: String) -> Result<(), InvokeEr
     info!(target: LOG_TARGET_APP_LOGIC, "[reset_pool_gpu_pool_config] called with gpu_pool_name: {gpu_pool_type:?}");
 
     let gpu_pool = GpuPool::from_string(&gpu_pool_type).map_err(InvokeError::from_anyhow)?;
+    if gpu_pool.is_custom() {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "Custom pools have no default configuration"
+        )));
+    }
 
     ConfigPools::update_field(
         ConfigPoolsContent::update_current_gpu_config,
@@ -1814,6 +2356,11 @@ pub async fn reset_cpu_pool_config(cpu_pool_type: String) -> Result<(), InvokeEr
     info!(target: LOG_TARGET_APP_LOGIC, "[reset_pool_cpu_pool_config] called with cpu_pool_name: {cpu_pool_type:?}");
 
     let cpu_pool = CpuPool::from_string(&cpu_pool_type).map_err(InvokeError::from_anyhow)?;
+    if cpu_pool.is_custom() {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "Custom pools have no default configuration"
+        )));
+    }
 
     ConfigPools::update_field(
         ConfigPoolsContent::update_current_cpu_config,
@@ -1830,6 +2377,224 @@ pub async fn reset_cpu_pool_config(cpu_pool_type: String) -> Result<(), InvokeEr
     Ok(())
 }
 
+#[tauri::command]
+pub async fn create_custom_pool(custom_pool: CustomPoolDefinition) -> Result<String, InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[create_custom_pool] called with custom_pool: {custom_pool:?}");
+
+    custom_pool.validate().map_err(InvokeError::from_anyhow)?;
+    let pool_id = Uuid::new_v4().to_string();
+    if custom_pool.algorithm.gpu_algorithm().is_some() {
+        let pool_data = custom_pool
+            .to_gpu_pool_data(&pool_id)
+            .map_err(InvokeError::from_anyhow)?;
+        ConfigPools::update_field(ConfigPoolsContent::set_custom_gpu_pool, pool_data)
+            .await
+            .map_err(InvokeError::from_anyhow)?;
+    } else {
+        let pool_data = custom_pool
+            .to_cpu_pool_data(&pool_id)
+            .map_err(InvokeError::from_anyhow)?;
+        ConfigPools::update_field(ConfigPoolsContent::set_custom_cpu_pool, pool_data)
+            .await
+            .map_err(InvokeError::from_anyhow)?;
+    }
+    EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "create_custom_pool took too long: {:?}", timer.elapsed());
+    }
+    Ok(custom_pool.pool_key(&pool_id))
+}
+
+#[tauri::command]
+pub async fn update_custom_pool(
+    pool_key: String,
+    custom_pool: CustomPoolDefinition,
+) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[update_custom_pool] called with pool_key: {pool_key:?}, custom_pool: {custom_pool:?}");
+
+    custom_pool.validate().map_err(InvokeError::from_anyhow)?;
+    let pools_config = ConfigPools::content().await;
+    if let Ok(CpuPool::Custom(pool_id)) = CpuPool::from_string(&pool_key) {
+        let cpu_pool = CpuPool::Custom(pool_id.clone());
+        if !pools_config.cpu_pools().contains_key(&cpu_pool) {
+            return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+                "Custom pool {pool_key} does not exist"
+            )));
+        }
+        let pool_data = custom_pool
+            .to_cpu_pool_data(&pool_id)
+            .map_err(InvokeError::from_anyhow)?;
+        ConfigPools::update_field(ConfigPoolsContent::set_custom_cpu_pool, pool_data)
+            .await
+            .map_err(InvokeError::from_anyhow)?;
+        if pools_config.current_cpu_pool().pool_type == cpu_pool {
+            CpuPoolManager::handle_new_selected_pool(
+                ConfigPools::content().await.current_cpu_pool(),
+            )
+            .await;
+        }
+    } else if let Ok(GpuPool::Custom {
+        id: pool_id,
+        algorithm,
+    }) = GpuPool::from_string(&pool_key)
+    {
+        if custom_pool.algorithm.gpu_algorithm() != Some(algorithm.clone()) {
+            return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+                "The algorithm of a custom pool cannot be changed"
+            )));
+        }
+        let gpu_pool = GpuPool::Custom {
+            id: pool_id.clone(),
+            algorithm,
+        };
+        if !pools_config.gpu_pools().contains_key(&gpu_pool) {
+            return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+                "Custom pool {pool_key} does not exist"
+            )));
+        }
+        let pool_data = custom_pool
+            .to_gpu_pool_data(&pool_id)
+            .map_err(InvokeError::from_anyhow)?;
+        ConfigPools::update_field(ConfigPoolsContent::set_custom_gpu_pool, pool_data)
+            .await
+            .map_err(InvokeError::from_anyhow)?;
+        if pools_config.current_gpu_pool().pool_type == gpu_pool {
+            GpuPoolManager::handle_new_selected_pool(
+                ConfigPools::content().await.current_gpu_pool(),
+            )
+            .await;
+        }
+    } else {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "{pool_key} is not a custom pool"
+        )));
+    }
+    EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "update_custom_pool took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_custom_pool(pool_key: String) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[delete_custom_pool] called with pool_key: {pool_key:?}");
+
+    let pools_config = ConfigPools::content().await;
+    if let Some(cpu_pool) = CpuPool::from_string(&pool_key)
+        .ok()
+        .filter(CpuPool::is_custom)
+    {
+        let was_selected = pools_config.current_cpu_pool().pool_type == cpu_pool;
+        ConfigPools::update_field(ConfigPoolsContent::remove_custom_cpu_pool, cpu_pool)
+            .await
+            .map_err(InvokeError::from_anyhow)?;
+        if was_selected {
+            CpuPoolManager::handle_new_selected_pool(
+                ConfigPools::content().await.current_cpu_pool(),
+            )
+            .await;
+        }
+    } else if let Some(gpu_pool) = GpuPool::from_string(&pool_key)
+        .ok()
+        .filter(GpuPool::is_custom)
+    {
+        let was_selected = pools_config.current_gpu_pool().pool_type == gpu_pool;
+        ConfigPools::update_field(ConfigPoolsContent::remove_custom_gpu_pool, gpu_pool)
+            .await
+            .map_err(InvokeError::from_anyhow)?;
+        if was_selected {
+            GpuPoolManager::handle_new_selected_pool(
+                ConfigPools::content().await.current_gpu_pool(),
+            )
+            .await;
+        }
+    } else {
+        return Err(InvokeError::from_anyhow(anyhow::anyhow!(
+            "{pool_key} is not a custom pool"
+        )));
+    }
+    EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "delete_custom_pool took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_pool_earnings_aggregates(
+    period: PoolEarningsPeriod,
+    filter: Option<PoolEarningsFilter>,
+) -> Result<Vec<PoolEarningsAggregate>, InvokeError> {
+    let timer = Instant::now();
+    let aggregates = PoolEarningsHistory::read()
+        .await
+        .aggregates(period, &filter.unwrap_or_default());
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "get_pool_earnings_aggregates took too long: {:?}", timer.elapsed());
+    }
+    Ok(aggregates)
+}
+
+#[tauri::command]
+pub async fn get_pool_payouts(
+    filter: Option<PoolEarningsFilter>,
+) -> Result<Vec<PoolPayout>, InvokeError> {
+    let timer = Instant::now();
+    let payouts = PoolEarningsHistory::read()
+        .await
+        .payouts(&filter.unwrap_or_default());
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "get_pool_payouts took too long: {:?}", timer.elapsed());
+    }
+    Ok(payouts)
+}
+
+#[tauri::command]
+pub async fn export_pool_earnings(
+    dataset: PoolEarningsDataset,
+    format: PoolEarningsExportFormat,
+    filter: Option<PoolEarningsFilter>,
+) -> Result<String, InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[export_pool_earnings] called with dataset: {dataset:?}, format: {format:?}");
+
+    let export = PoolEarningsHistory::read()
+        .await
+        .export(dataset, format, &filter.unwrap_or_default())
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "export_pool_earnings took too long: {:?}", timer.elapsed());
+    }
+    Ok(export)
+}
+
+#[tauri::command]
+pub async fn clear_pool_earnings_history() -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[clear_pool_earnings_history] called");
+
+    PoolEarningsHistory::write()
+        .await
+        .clear()
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "clear_pool_earnings_history took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn create_pin(app_handle: tauri::AppHandle) -> Result<(), String> {
     InternalWallet::create_pin(&app_handle)
@@ -1925,11 +2690,17 @@ pub async fn add_scheduler_event(
     event_id: String,
     event_time: SchedulerEventTiming,
     event_type: SchedulerEventType,
+    priority: Option<i32>,
 ) -> Result<(), String> {
-    info!(target: LOG_TARGET_APP_LOGIC, "add_scheduler_event called with event_id: {event_id:?}, event_time: {event_time:?}, event_type: {event_type:?}");
+    info!(target: LOG_TARGET_APP_LOGIC, "add_scheduler_event called with event_id: {event_id:?}, event_time: {event_time:?}, event_type: {event_type:?}, priority: {priority:?}");
 
     EventScheduler::instance()
-        .schedule_event(event_type, event_id, event_time)
+        .schedule_event(
+            event_type,
+            event_id,
+            event_time,
+            priority.unwrap_or_default(),
+        )
         .await
         .map_err(|e| e.to_string())?;
 
@@ -1972,6 +2743,40 @@ pub async fn resume_scheduler_event(event_id: String) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_scheduler_next_transitions(
+    count: usize,
+) -> Result<Vec<SchedulerTransition>, String> {
+    info!(target: LOG_TARGET_APP_LOGIC, "get_scheduler_next_transitions called with count: {count:?}");
+
+    EventScheduler::instance()
+        .get_next_transitions(count)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_scheduler_history(
+    limit: Option<usize>,
+) -> Result<Vec<SchedulerExecutionRecord>, String> {
+    info!(target: LOG_TARGET_APP_LOGIC, "get_scheduler_history called with limit: {limit:?}");
+
+    EventScheduler::instance()
+        .get_history(limit)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clear_scheduler_history() -> Result<(), String> {
+    info!(target: LOG_TARGET_APP_LOGIC, "clear_scheduler_history called");
+
+    EventScheduler::instance()
+        .clear_history()
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn refresh_wallet_history(
     state: tauri::State<'_, UniverseAppState>,
@@ -2150,6 +2955,74 @@ pub async fn mark_feedback_survey_as_completed() -> Result<(), InvokeError> {
     Ok(())
 }
 
+#[tauri::command]
+pub async fn update_metrics_server_settings(
+    settings: MetricsServerSettings,
+) -> Result<(), InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[update_metrics_server_settings] called with settings: {settings:?}");
+
+    MetricsServer::apply_settings(settings)
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+    ConfigCore::update_field(ConfigCoreContent::set_metrics_server, settings)
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "update_metrics_server_settings took too long: {:?}", timer.elapsed());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn update_control_api_settings(
+    enabled: bool,
+    bind_address: SocketAddr,
+) -> Result<ControlApiSettings, InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[update_control_api_settings] called with enabled: {enabled}, bind_address: {bind_address}");
+
+    let mut settings = ConfigCore::content().await.control_api().clone();
+    settings.enabled = enabled;
+    settings.bind_address = bind_address;
+    if settings.token.is_empty() {
+        settings.token = ControlApiSettings::generate_token();
+    }
+    ControlApi::apply_settings(&settings)
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+    ConfigCore::update_field(ConfigCoreContent::set_control_api, settings.clone())
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "update_control_api_settings took too long: {:?}", timer.elapsed());
+    }
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn regenerate_control_api_token() -> Result<ControlApiSettings, InvokeError> {
+    let timer = Instant::now();
+    info!(target: LOG_TARGET_APP_LOGIC, "[regenerate_control_api_token] called");
+
+    let mut settings = ConfigCore::content().await.control_api().clone();
+    settings.token = ControlApiSettings::generate_token();
+    // Restarting revokes the previous token
+    ControlApi::apply_settings(&settings)
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+    ConfigCore::update_field(ConfigCoreContent::set_control_api, settings.clone())
+        .await
+        .map_err(InvokeError::from_anyhow)?;
+
+    if timer.elapsed() > MAX_ACCEPTABLE_COMMAND_TIME {
+        warn!(target: LOG_TARGET_APP_LOGIC, "regenerate_control_api_token took too long: {:?}", timer.elapsed());
+    }
+    Ok(settings)
+}
+
 #[tauri::command]
 pub async fn update_shutdown_mode_selection(
     shutdown_mode: ShutdownMode,
@@ -31,7 +31,9 @@ use tokio::sync::RwLock;
 
 use crate::ab_test_selector::ABTestSelector;
 use crate::app_in_memory_config::{MinerType, DEFAULT_EXCHANGE_ID};
+use crate::control_api::ControlApiSettings;
 use crate::event_scheduler::ScheduledEventInfo;
+use crate::metrics_server::MetricsServerSettings;
 use crate::node::node_manager::NodeType;
 use crate::shutdown_manager::ShutdownMode;
 use crate::utils::rand_utils;
@@ -73,6 +75,8 @@ pub struct ConfigCoreContent {
     exchange_id: String,
     scheduler_events: HashMap<String, ScheduledEventInfo>,
     shutdown_mode: ShutdownMode,
+    metrics_server: MetricsServerSettings,
+    control_api: ControlApiSettings,
 }
 
 fn default_monero_nodes() -> Vec<String> {
@@ -127,6 +131,8 @@ impl Default for ConfigCoreContent {
             exchange_id: DEFAULT_EXCHANGE_ID.to_string(),
             scheduler_events: HashMap::new(),
             shutdown_mode: ShutdownMode::Tasktray,
+            metrics_server: MetricsServerSettings::default(),
+            control_api: ControlApiSettings::default(),
         }
     }
 }
@@ -22,7 +22,11 @@
 
 use super::trait_config::{ConfigContentImpl, ConfigImpl};
 use crate::events_emitter::EventsEmitter;
+use crate::mining::cpu::tuning::{CpuTuningOverrides, CpuTuningProfile};
+use crate::mining::gpu::benchmark::GpuBenchmarkReport;
 use crate::mining::gpu::consts::{EngineType, GpuMinerType};
+use crate::mining::gpu::watchdog::{GpuDeviceLimits, GpuWatchdogAction, GpuWatchdogRestore};
+use crate::process_isolation::ProcessIsolation;
 use crate::LOG_TARGET_APP_LOGIC;
 use getset::{Getters, Setters};
 use log::{info, warn};
@@ -87,12 +91,34 @@ pub struct MiningMode {
     pub mode_name: String,
     pub cpu_usage_percentage: u32,
     pub gpu_usage_percentage: u32,
+    /// CPU miner tuning values, only used by Custom and User modes
+    #[serde(default)]
+    pub cpu_tuning: CpuTuningOverrides,
+    /// Overrides the scheduling and resource limits of the CPU miner for this mode
+    #[serde(default)]
+    pub process_isolation: Option<ProcessIsolation>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct GpuDeviceSettings {
     device_id: u32,
     is_excluded: bool,
+    /// Overrides the intensity of the selected mining mode for this device
+    #[serde(default)]
+    intensity_percentage: Option<u32>,
+    /// Engine this device should be mined with, the globally selected engine when not set
+    #[serde(default)]
+    engine: Option<EngineType>,
+    /// Miner this device is assigned to, the selected miner when not set
+    #[serde(default)]
+    miner: Option<GpuMinerType>,
+    /// Temperature and power limits enforced by the GPU watchdog
+    #[serde(default)]
+    limits: GpuDeviceLimits,
+    /// Settings the GPU watchdog changed on this device, restored once the device recovers or
+    /// on the next start when the app exits during an intervention
+    #[serde(default)]
+    watchdog_restore: Option<GpuWatchdogRestore>,
 }
 #[derive(Serialize, Deserialize, Clone)]
 pub struct GpuDevicesSettings(HashMap<u32, GpuDeviceSettings>);
@@ -105,7 +131,7 @@ impl GpuDevicesSettings {
     pub fn add(&mut self, device_id: u32) {
         self.0.entry(device_id).or_insert(GpuDeviceSettings {
             device_id,
-            is_excluded: false,
+            ..Default::default()
         });
     }
     pub fn set_excluded(&mut self, device_id: u32, is_excluded: bool) {
@@ -113,6 +139,34 @@ impl GpuDevicesSettings {
             settings.is_excluded = is_excluded;
         }
     }
+    pub fn set_intensity_percentage(&mut self, device_id: u32, intensity_percentage: Option<u32>) {
+        if let Some(settings) = self.0.get_mut(&device_id) {
+            settings.intensity_percentage = intensity_percentage;
+        }
+    }
+    pub fn set_engine(&mut self, device_id: u32, engine: Option<EngineType>) {
+        if let Some(settings) = self.0.get_mut(&device_id) {
+            settings.engine = engine;
+        }
+    }
+    pub fn set_miner(&mut self, device_id: u32, miner: Option<GpuMinerType>) {
+        if let Some(settings) = self.0.get_mut(&device_id) {
+            settings.miner = miner;
+        }
+    }
+    pub fn set_limits(&mut self, device_id: u32, limits: GpuDeviceLimits) {
+        if let Some(settings) = self.0.get_mut(&device_id) {
+            settings.limits = limits;
+        }
+    }
+    pub fn set_watchdog_restore(&mut self, device_id: u32, restore: Option<GpuWatchdogRestore>) {
+        if let Some(settings) = self.0.get_mut(&device_id) {
+            settings.watchdog_restore = restore;
+        }
+    }
+    pub fn contains(&self, device_id: u32) -> bool {
+        self.0.contains_key(&device_id)
+    }
 }
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum PauseOnBatteryModeState {
@@ -151,6 +205,10 @@ pub struct ConfigMiningContent {
     pause_on_battery_mode: PauseOnBatteryModeState,
     is_lolminer_tested: bool,
     is_gpu_mining_recommended: bool,
+    /// Results of the last completed GPU benchmark
+    gpu_benchmark_report: Option<GpuBenchmarkReport>,
+    /// What the GPU watchdog does when a device exceeds its limits
+    gpu_watchdog_action: GpuWatchdogAction,
 
     eco_alert_needed: bool,
     mode_mining_times: HashMap<String, Duration>, // we only need Eco for now, but we can add to this if needed
@@ -176,6 +234,8 @@ impl Default for ConfigMiningContent {
                         mode_name: "Eco".to_string(),
                         cpu_usage_percentage: 1,
                         gpu_usage_percentage: 1,
+                        cpu_tuning: CpuTuningOverrides::default(),
+                        process_isolation: None,
                     },
                 ),
                 (
@@ -185,6 +245,8 @@ impl Default for ConfigMiningContent {
                         mode_name: "Turbo".to_string(),
                         cpu_usage_percentage: 10,
                         gpu_usage_percentage: 10,
+                        cpu_tuning: CpuTuningOverrides::default(),
+                        process_isolation: None,
                     },
                 ),
                 (
@@ -194,6 +256,8 @@ impl Default for ConfigMiningContent {
                         mode_name: "Ludicrous".to_string(),
                         cpu_usage_percentage: 85,
                         gpu_usage_percentage: 95,
+                        cpu_tuning: CpuTuningOverrides::default(),
+                        process_isolation: None,
                     },
                 ),
                 (
@@ -203,6 +267,8 @@ impl Default for ConfigMiningContent {
                         mode_name: "Custom".to_string(),
                         cpu_usage_percentage: 75,
                         gpu_usage_percentage: 75,
+                        cpu_tuning: CpuTuningOverrides::default(),
+                        process_isolation: None,
                     },
                 ),
             ]),
@@ -214,6 +280,8 @@ impl Default for ConfigMiningContent {
             squad_override: None,
             is_lolminer_tested: false,
             is_gpu_mining_recommended: true,
+            gpu_benchmark_report: None,
+            gpu_watchdog_action: GpuWatchdogAction::default(),
             eco_alert_needed: true,
             mode_mining_times: HashMap::from([("Eco".to_string(), Duration::new(0, 0))]),
         }
@@ -235,6 +303,20 @@ impl ConfigMiningContent {
         self
     }
 
+    /// Only Custom and User modes read their tuning overrides, other modes are left untouched.
+    pub fn update_mode_cpu_tuning(&mut self, payload: (String, CpuTuningOverrides)) -> &mut Self {
+        let (mode_name, cpu_tuning) = payload;
+        if let Some(mode) = self.mining_modes.get_mut(&mode_name) {
+            if matches!(
+                mode.mode_type,
+                MiningModeType::Custom | MiningModeType::User
+            ) {
+                mode.cpu_tuning = cpu_tuning;
+            }
+        }
+        self
+    }
+
     /// Populate the GPU devices settings with the given device IDs.
     /// If a device ID already exists, it will not be added again.
     pub fn populate_gpu_devices_settings(&mut self, device_ids: Vec<u32>) -> &mut Self {
@@ -255,6 +337,48 @@ impl ConfigMiningContent {
         self
     }
 
+    pub fn set_gpu_device_intensity(
+        &mut self,
+        (device_id, intensity_percentage): (u32, Option<u32>),
+    ) -> &mut Self {
+        self.gpu_devices_settings
+            .set_intensity_percentage(device_id, intensity_percentage);
+        self
+    }
+
+    pub fn set_gpu_device_miner(
+        &mut self,
+        (device_id, miner): (u32, Option<GpuMinerType>),
+    ) -> &mut Self {
+        self.gpu_devices_settings.set_miner(device_id, miner);
+        self
+    }
+
+    pub fn set_gpu_device_engine(
+        &mut self,
+        (device_id, engine): (u32, Option<EngineType>),
+    ) -> &mut Self {
+        self.gpu_devices_settings.set_engine(device_id, engine);
+        self
+    }
+
+    pub fn set_gpu_device_limits(
+        &mut self,
+        (device_id, limits): (u32, GpuDeviceLimits),
+    ) -> &mut Self {
+        self.gpu_devices_settings.set_limits(device_id, limits);
+        self
+    }
+
+    pub fn set_gpu_device_watchdog_restore(
+        &mut self,
+        (device_id, restore): (u32, Option<GpuWatchdogRestore>),
+    ) -> &mut Self {
+        self.gpu_devices_settings
+            .set_watchdog_restore(device_id, restore);
+        self
+    }
+
     pub fn get_selected_cpu_usage_percentage(&self) -> u32 {
         match self.mining_modes.get(&self.selected_mining_mode) {
             Some(mode) => mode.cpu_usage_percentage,
@@ -265,16 +389,129 @@ impl ConfigMiningContent {
         }
     }
 
-    pub fn get_excluded_devices(&self) -> Vec<u32> {
+    pub fn get_selected_cpu_tuning_profile(&self) -> CpuTuningProfile {
+        match self.mining_modes.get(&self.selected_mining_mode) {
+            Some(mode) => CpuTuningProfile::for_mining_mode(mode),
+            None => {
+                warn!("Mining mode '{}' not found", self.selected_mining_mode);
+                CpuTuningProfile::default()
+            }
+        }
+    }
+
+    pub fn get_selected_process_isolation(&self) -> ProcessIsolation {
+        match self.mining_modes.get(&self.selected_mining_mode) {
+            Some(mode) => ProcessIsolation::for_mining_mode(mode),
+            None => {
+                warn!("Mining mode '{}' not found", self.selected_mining_mode);
+                ProcessIsolation::default()
+            }
+        }
+    }
+
+    pub fn update_custom_mode_process_isolation(
+        &mut self,
+        process_isolation: Option<ProcessIsolation>,
+    ) -> &mut Self {
+        if let Some(custom_mode) = self.mining_modes.get_mut("Custom") {
+            custom_mode.process_isolation = process_isolation;
+        }
+        self
+    }
+
+    /// Devices the given miner leaves out, the excluded devices and devices assigned to another miner.
+    pub fn get_excluded_devices_for_miner(&self, miner: &GpuMinerType) -> Vec<u32> {
         self.gpu_devices_settings
             .0
             .iter()
-            .filter_map(|(&device_id, settings)| {
-                if settings.is_excluded {
-                    Some(device_id)
-                } else {
-                    None
+            .filter(|(_, settings)| {
+                settings.is_excluded
+                    || settings.miner.as_ref().unwrap_or(&self.gpu_miner_type) != miner
+            })
+            .map(|(&device_id, _)| device_id)
+            .collect()
+    }
+
+    /// Devices that are not excluded from mining, ordered by device id.
+    pub fn get_included_gpu_devices(&self) -> Vec<u32> {
+        let mut device_ids: Vec<u32> = self
+            .gpu_devices_settings
+            .0
+            .iter()
+            .filter(|(_, settings)| !settings.is_excluded)
+            .map(|(&device_id, _)| device_id)
+            .collect();
+        device_ids.sort_unstable();
+        device_ids
+    }
+
+    /// Whether at least one device is left for the given miner to mine on.
+    pub fn has_gpu_devices_for_miner(&self, miner: &GpuMinerType) -> bool {
+        self.get_excluded_devices_for_miner(miner).len() < self.gpu_devices_settings.0.len()
+    }
+
+    /// Miners devices are assigned to besides the selected miner, they run next to it.
+    pub fn get_additional_gpu_miners(&self) -> Vec<GpuMinerType> {
+        let mut miners: Vec<GpuMinerType> = Vec::new();
+        for settings in self.gpu_devices_settings.0.values() {
+            match &settings.miner {
+                Some(miner)
+                    if !settings.is_excluded
+                        && miner != &self.gpu_miner_type
+                        && !miners.contains(miner) =>
+                {
+                    miners.push(miner.clone());
                 }
+                _ => {}
+            }
+        }
+        miners
+    }
+
+    /// Devices with their own intensity, all other devices use the selected mining mode's intensity.
+    pub fn get_gpu_device_intensities(&self) -> HashMap<u32, u32> {
+        self.gpu_devices_settings
+            .0
+            .iter()
+            .filter_map(|(&device_id, settings)| {
+                settings
+                    .intensity_percentage
+                    .map(|intensity| (device_id, intensity))
+            })
+            .collect()
+    }
+
+    /// Devices with temperature or power limits set.
+    pub fn get_gpu_device_limits(&self) -> HashMap<u32, GpuDeviceLimits> {
+        self.gpu_devices_settings
+            .0
+            .iter()
+            .filter(|(_, settings)| settings.limits.is_set())
+            .map(|(&device_id, settings)| (device_id, settings.limits.clone()))
+            .collect()
+    }
+
+    /// Devices with settings changed by the GPU watchdog that are still to be restored.
+    pub fn get_gpu_device_watchdog_restores(&self) -> HashMap<u32, GpuWatchdogRestore> {
+        self.gpu_devices_settings
+            .0
+            .iter()
+            .filter_map(|(&device_id, settings)| {
+                settings
+                    .watchdog_restore
+                    .clone()
+                    .map(|restore| (device_id, restore))
+            })
+            .collect()
+    }
+
+    /// Devices with an engine preference, all other devices use the selected engine.
+    pub fn get_gpu_device_engines(&self) -> HashMap<u32, EngineType> {
+        self.gpu_devices_settings
+            .0
+            .iter()
+            .filter_map(|(&device_id, settings)| {
+                settings.engine.clone().map(|engine| (device_id, engine))
             })
             .collect()
     }
@@ -350,6 +587,8 @@ impl ConfigMining {
             mode_name: "Turbo".to_string(),
             cpu_usage_percentage: 10,
             gpu_usage_percentage: 10,
+            cpu_tuning: CpuTuningOverrides::default(),
+            process_isolation: None,
         };
 
         mining_modes
@@ -27,13 +27,19 @@ use tauri::AppHandle;
 use tokio::sync::RwLock;
 
 use crate::{
-    configs::pools::{cpu_pools::CpuPool, gpu_pools::GpuPool, BasePoolData},
-    mining::pools::{cpu_pool_manager::CpuPoolManager, gpu_pool_manager::GpuPoolManager},
+    configs::pools::{
+        cpu_pools::CpuPool, gpu_pools::GpuPool, worker_name::default_worker_name, BasePoolData,
+    },
+    mining::{
+        gpu::consts::GpuMiningAlgorithm,
+        pools::{cpu_pool_manager::CpuPoolManager, gpu_pool_manager::GpuPoolManager},
+    },
 };
 
 use super::trait_config::{ConfigContentImpl, ConfigImpl};
 
 pub const POOLS_CONFIG_VERSION: u32 = 0;
+const DEFAULT_POOL_FAILOVER_TIMEOUT_SECS: u64 = 120;
 static INSTANCE: LazyLock<RwLock<ConfigPools>> = LazyLock::new(|| RwLock::new(ConfigPools::new()));
 
 #[allow(clippy::struct_excessive_bools)]
@@ -61,6 +67,20 @@ pub struct ConfigPoolsContent {
     current_cpu_pool: CpuPool,
     #[getset(get = "pub", set = "pub")]
     cpu_pools: HashMap<CpuPool, BasePoolData<CpuPool>>,
+    // ======= Pool failover =======
+    /// Pools tried in order when the current CPU pool is unreachable
+    #[getset(get = "pub", set = "pub")]
+    cpu_fallback_pools: Vec<CpuPool>,
+    /// Pools tried in order when the current GPU pool is unreachable, per algorithm
+    #[getset(get = "pub", set = "pub")]
+    gpu_fallback_pools: HashMap<GpuMiningAlgorithm, Vec<GpuPool>>,
+    /// Seconds a miner has to be disconnected before switching to the next pool
+    #[getset(get = "pub", set = "pub")]
+    pool_failover_timeout_secs: u64,
+    // ======= Worker =======
+    /// Identifies this installation on pool dashboards, appended to the mining address
+    #[getset(get = "pub", set = "pub")]
+    worker_name: String,
 }
 
 impl Default for ConfigPoolsContent {
@@ -77,6 +97,12 @@ impl Default for ConfigPoolsContent {
             cpu_pool_enabled: true,
             current_cpu_pool: CpuPool::default(),
             cpu_pools: CpuPool::load_default_pools_data(),
+            // ======= Pool failover =======
+            cpu_fallback_pools: Vec::new(),
+            gpu_fallback_pools: HashMap::new(),
+            pool_failover_timeout_secs: DEFAULT_POOL_FAILOVER_TIMEOUT_SECS,
+            // ======= Worker =======
+            worker_name: default_worker_name(),
         }
     }
 }
@@ -96,6 +122,123 @@ impl ConfigPoolsContent {
             .unwrap_or_else(|| CpuPool::default().default_content())
     }
 
+    /// CPU pools in failover order, starting with the current pool.
+    pub fn cpu_failover_pools(&self) -> Vec<BasePoolData<CpuPool>> {
+        let mut pools = vec![self.current_cpu_pool()];
+        for pool in &self.cpu_fallback_pools {
+            if pools.iter().any(|p| &p.pool_type == pool) {
+                continue;
+            }
+            if let Some(pool_data) = self.cpu_pools.get(pool) {
+                pools.push(pool_data.clone());
+            }
+        }
+        pools
+    }
+
+    /// GPU pools in failover order, starting with the current pool.
+    /// Only fallback pools of the current pool's algorithm are included.
+    pub fn gpu_failover_pools(&self) -> Vec<BasePoolData<GpuPool>> {
+        let current_pool = self.current_gpu_pool();
+        let mut pools = vec![current_pool.clone()];
+        let fallback_pools = self
+            .gpu_fallback_pools
+            .get(&current_pool.pool_type.algorithm())
+            .cloned()
+            .unwrap_or_default();
+        for pool in &fallback_pools {
+            if pools.iter().any(|p| &p.pool_type == pool) {
+                continue;
+            }
+            if let Some(pool_data) = self.gpu_pools.get(pool) {
+                pools.push(pool_data.clone());
+            }
+        }
+        pools
+    }
+
+    /// GPU pool for miners of the given algorithm, the current pool when it matches the algorithm
+    /// or the first fallback pool configured for the algorithm otherwise.
+    pub fn gpu_pool_for_algorithm(
+        &self,
+        algorithm: &GpuMiningAlgorithm,
+    ) -> Option<BasePoolData<GpuPool>> {
+        let current_pool = self.current_gpu_pool();
+        if &current_pool.pool_type.algorithm() == algorithm {
+            return Some(current_pool);
+        }
+        self.gpu_fallback_pools
+            .get(algorithm)
+            .into_iter()
+            .flatten()
+            .find_map(|pool| self.gpu_pools.get(pool).cloned())
+    }
+
+    pub fn set_gpu_fallback_pools_for_algorithm(
+        &mut self,
+        algorithm: GpuMiningAlgorithm,
+        pools: Vec<GpuPool>,
+    ) -> &mut Self {
+        let pools = pools
+            .into_iter()
+            .filter(|pool| pool.algorithm() == algorithm)
+            .collect();
+        self.gpu_fallback_pools.insert(algorithm, pools);
+        self
+    }
+
+    /// Adds a custom CPU pool or replaces it when it already exists.
+    pub fn set_custom_cpu_pool(&mut self, pool: BasePoolData<CpuPool>) -> &mut Self {
+        if pool.pool_type.is_custom() {
+            self.cpu_pools.insert(pool.pool_type.clone(), pool);
+        }
+        self
+    }
+
+    /// Adds a custom GPU pool or replaces it when it already exists.
+    pub fn set_custom_gpu_pool(&mut self, pool: BasePoolData<GpuPool>) -> &mut Self {
+        if pool.pool_type.is_custom() {
+            self.gpu_pools.insert(pool.pool_type.clone(), pool);
+        }
+        self
+    }
+
+    /// Removes a custom CPU pool, falling back to the default pool if it was selected.
+    pub fn remove_custom_cpu_pool(&mut self, pool: CpuPool) -> &mut Self {
+        if !pool.is_custom() {
+            return self;
+        }
+        self.cpu_pools.remove(&pool);
+        self.cpu_fallback_pools.retain(|fallback| fallback != &pool);
+        if self.current_cpu_pool == pool {
+            self.current_cpu_pool = CpuPool::default();
+        }
+        self
+    }
+
+    /// Removes a custom GPU pool, falling back to the default pool of its algorithm if it was selected.
+    pub fn remove_custom_gpu_pool(&mut self, pool: GpuPool) -> &mut Self {
+        if !pool.is_custom() {
+            return self;
+        }
+        self.gpu_pools.remove(&pool);
+        for fallback_pools in self.gpu_fallback_pools.values_mut() {
+            fallback_pools.retain(|fallback| fallback != &pool);
+        }
+        if self.current_gpu_pool == pool {
+            self.current_gpu_pool = self
+                .gpu_pools
+                .keys()
+                .filter(|gpu_pool| {
+                    !gpu_pool.is_custom() && gpu_pool.algorithm() == pool.algorithm()
+                })
+                .min_by_key(|gpu_pool| gpu_pool.key_string())
+                .cloned()
+                .unwrap_or_default();
+        }
+        self
+    }
+
     pub fn update_current_cpu_config(
         &mut self,
         updated_config: BasePoolData<CpuPool>,
@@ -166,3 +309,103 @@ impl ConfigImpl for ConfigPools {
         &mut self.content
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::configs::pools::custom_pools::{
+        CustomPoolAlgorithm, CustomPoolDefinition, PoolStatsMapping,
+    };
+
+    fn custom_pool(algorithm: CustomPoolAlgorithm) -> CustomPoolDefinition {
+        CustomPoolDefinition {
+            pool_name: "My pool".to_string(),
+            pool_url: "pool.example.com:3333".to_string(),
+            algorithm,
+            worker_name_template: None,
+            stats_url: String::new(),
+            stats_mapping: PoolStatsMapping::default(),
+        }
+    }
+
+    #[test]
+    fn test_custom_cpu_pool_is_persisted_and_removed() {
+        let mut config = ConfigPoolsContent::default();
+        let pool = custom_pool(CustomPoolAlgorithm::RandomX)
+            .to_cpu_pool_data("abc")
+            .unwrap();
+        config
+            .set_custom_cpu_pool(pool.clone())
+            .set_current_cpu_pool(pool.pool_type.clone())
+            .set_cpu_fallback_pools(vec![CpuPool::KryptexPoolRANDOMX, pool.pool_type.clone()]);
+
+        let restored: ConfigPoolsContent =
+            serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(
+            restored.current_cpu_pool().pool_url,
+            "pool.example.com:3333"
+        );
+
+        config.remove_custom_cpu_pool(pool.pool_type.clone());
+        assert!(!config.cpu_pools().contains_key(&pool.pool_type));
+        assert_eq!(config.current_cpu_pool().pool_type, CpuPool::default());
+        assert_eq!(
+            config.cpu_fallback_pools(),
+            &vec![CpuPool::KryptexPoolRANDOMX]
+        );
+    }
+
+    #[test]
+    fn test_removing_selected_custom_gpu_pool_keeps_algorithm() {
+        let mut config = ConfigPoolsContent::default();
+        let pool = custom_pool(CustomPoolAlgorithm::SHA3X)
+            .to_gpu_pool_data("abc")
+            .unwrap();
+        config
+            .set_custom_gpu_pool(pool.clone())
+            .set_current_gpu_pool(pool.pool_type.clone());
+        assert_eq!(config.current_gpu_pool().pool_url, "pool.example.com:3333");
+
+        config.remove_custom_gpu_pool(pool.pool_type);
+        assert_eq!(
+            config.current_gpu_pool().pool_type.algorithm(),
+            GpuMiningAlgorithm::SHA3X
+        );
+    }
+
+    #[test]
+    fn test_gpu_pool_for_algorithm_uses_fallback_pool_of_other_algorithm() {
+        let mut config = ConfigPoolsContent::default();
+        config.set_current_gpu_pool(GpuPool::LuckyPoolSHA3X);
+        assert_eq!(
+            config
+                .gpu_pool_for_algorithm(&GpuMiningAlgorithm::SHA3X)
+                .unwrap()
+                .pool_type,
+            GpuPool::LuckyPoolSHA3X
+        );
+        assert!(config
+            .gpu_pool_for_algorithm(&GpuMiningAlgorithm::C29)
+            .is_none());
+
+        config.set_gpu_fallback_pools_for_algorithm(
+            GpuMiningAlgorithm::C29,
+            vec![GpuPool::KryptexPoolC29, GpuPool::LuckyPoolC29],
+        );
+        assert_eq!(
+            config
+                .gpu_pool_for_algorithm(&GpuMiningAlgorithm::C29)
+                .unwrap()
+                .pool_type,
+            GpuPool::KryptexPoolC29
+        );
+    }
+
+    #[test]
+    fn test_built_in_pools_cannot_be_removed() {
+        let mut config = ConfigPoolsContent::default();
+        config.remove_custom_cpu_pool(CpuPool::LuckyPoolRANDOMX);
+        assert!(config.cpu_pools().contains_key(&CpuPool::LuckyPoolRANDOMX));
+    }
+}
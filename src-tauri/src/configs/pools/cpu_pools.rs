@@ -25,7 +25,7 @@ use std::{collections::HashMap, fmt::Display, sync::LazyLock};
 use serde::{Deserialize, Serialize};
 use tari_common::configuration::Network;
 
-use crate::configs::pools::{BasePoolData, PoolOrigin};
+use crate::configs::pools::{custom_pools::CUSTOM_POOL_KEY_PREFIX, BasePoolData, PoolOrigin};
 
 fn global_tari_cpu_mining_pool_url() -> String {
     match Network::get_current_or_user_setting_or_default() {
@@ -78,22 +78,35 @@ static DEFAULT_CPU_KRYPTEX_RANDOMX: LazyLock<BasePoolData<CpuPool>> =
         pool_origin: PoolOrigin::Kryptex,
     });
 
+/// Serialized as its key string, so custom pools can be used as keys of the pools map.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(into = "String", try_from = "String")]
 pub enum CpuPool {
     SupportXTMPoolRANDOMX,
     #[default]
     LuckyPoolRANDOMX,
     KryptexPoolRANDOMX,
+    /// User-defined pool, identified by its ID
+    Custom(String),
 }
 
 impl Display for CpuPool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let name = match self {
-            CpuPool::SupportXTMPoolRANDOMX => "SupportXTMPoolRANDOMX",
-            CpuPool::LuckyPoolRANDOMX => "LuckyPoolRANDOMX",
-            CpuPool::KryptexPoolRANDOMX => "KryptexPoolRANDOMX",
-        };
-        write!(f, "{name}")
+        write!(f, "{}", self.key_string())
+    }
+}
+
+impl From<CpuPool> for String {
+    fn from(pool: CpuPool) -> Self {
+        pool.key_string()
+    }
+}
+
+impl TryFrom<String> for CpuPool {
+    type Error = anyhow::Error;
+
+    fn try_from(pool_name: String) -> Result<Self, Self::Error> {
+        CpuPool::from_string(&pool_name)
     }
 }
 
@@ -103,7 +116,12 @@ impl CpuPool {
             "SupportXTMPoolRANDOMX" => Ok(CpuPool::SupportXTMPoolRANDOMX),
             "LuckyPoolRANDOMX" => Ok(CpuPool::LuckyPoolRANDOMX),
             "KryptexPoolRANDOMX" => Ok(CpuPool::KryptexPoolRANDOMX),
-            _ => Err(anyhow::anyhow!("Invalid CPU pool name")),
+            _ => match pool_name.strip_prefix(CUSTOM_POOL_KEY_PREFIX) {
+                Some(id) if !id.is_empty() && !id.contains(':') => {
+                    Ok(CpuPool::Custom(id.to_string()))
+                }
+                _ => Err(anyhow::anyhow!("Invalid CPU pool name")),
+            },
         }
     }
 
@@ -112,14 +130,22 @@ impl CpuPool {
             CpuPool::SupportXTMPoolRANDOMX => "SupportXTMPoolRANDOMX".to_string(),
             CpuPool::LuckyPoolRANDOMX => "LuckyPoolRANDOMX".to_string(),
             CpuPool::KryptexPoolRANDOMX => "KryptexPoolRANDOMX".to_string(),
+            CpuPool::Custom(id) => format!("{CUSTOM_POOL_KEY_PREFIX}{id}"),
         }
     }
 
+    pub fn is_custom(&self) -> bool {
+        matches!(self, CpuPool::Custom(_))
+    }
+
+    /// Built-in configuration of the pool.
+    /// Custom pools have none, the default pool's configuration is returned for them.
     pub fn default_content(&self) -> BasePoolData<CpuPool> {
         match self {
             CpuPool::SupportXTMPoolRANDOMX => DEFAULT_CPU_SUPPORTXTM_RANDOMX.clone(),
             CpuPool::LuckyPoolRANDOMX => DEFAULT_CPU_LUCKYPOOL_RANDOMX.clone(),
             CpuPool::KryptexPoolRANDOMX => DEFAULT_CPU_KRYPTEX_RANDOMX.clone(),
+            CpuPool::Custom(_) => CpuPool::default().default_content(),
         }
     }
 
@@ -135,3 +161,35 @@ impl CpuPool {
         cpu_pools
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    #[test]
+    fn test_pool_keys_round_trip() {
+        for pool in [
+            CpuPool::LuckyPoolRANDOMX,
+            CpuPool::Custom("0b5c6d0e".to_string()),
+        ] {
+            assert_eq!(CpuPool::from_string(&pool.key_string()).unwrap(), pool);
+        }
+        assert!(CpuPool::from_string("Custom:").is_err());
+    }
+
+    #[test]
+    fn test_pools_map_serializes_with_custom_keys() {
+        let mut pools = CpuPool::load_default_pools_data();
+        let custom_pool = CpuPool::Custom("0b5c6d0e".to_string());
+        pools.insert(custom_pool.clone(), CpuPool::default().default_content());
+
+        let serialized = serde_json::to_string(&pools).unwrap();
+        assert!(serialized.contains("\"Custom:0b5c6d0e\""));
+
+        let deserialized: HashMap<CpuPool, BasePoolData<CpuPool>> =
+            serde_json::from_str(&serialized).unwrap();
+        assert!(deserialized.contains_key(&custom_pool));
+        assert!(deserialized.contains_key(&CpuPool::KryptexPoolRANDOMX));
+    }
+}
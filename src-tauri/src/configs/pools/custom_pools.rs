@@ -0,0 +1,369 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! User-defined pools.
+//!
+//! Custom pools are stored next to the built-in pools in `ConfigPools` under a
+//! `CpuPool::Custom` / `GpuPool::Custom` key. Instead of a hand-written stats adapter they
+//! describe where each `PoolStatus` value can be found in the pool's stats response.
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    configs::pools::{cpu_pools::CpuPool, gpu_pools::GpuPool, BasePoolData, PoolOrigin},
+    mining::gpu::consts::GpuMiningAlgorithm,
+};
+
+/// Prefix of the key custom pools are stored under, followed by the pool ID
+pub const CUSTOM_POOL_KEY_PREFIX: &str = "Custom:";
+/// Replaced with the worker name in a custom pool's worker name template
+pub const WORKER_NAME_PLACEHOLDER: &str = "%WORKER_NAME%";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CustomPoolAlgorithm {
+    RandomX,
+    SHA3X,
+    C29,
+}
+
+impl CustomPoolAlgorithm {
+    /// Algorithm used by GPU miners, `None` for CPU algorithms.
+    pub fn gpu_algorithm(&self) -> Option<GpuMiningAlgorithm> {
+        match self {
+            CustomPoolAlgorithm::RandomX => None,
+            CustomPoolAlgorithm::SHA3X => Some(GpuMiningAlgorithm::SHA3X),
+            CustomPoolAlgorithm::C29 => Some(GpuMiningAlgorithm::C29),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Simple JSON path pointing to a single value, e.g. `$.stats.balance` or `workers[0].shares`.
+/// The leading `$` is optional, keys containing dots can be written as `['some.key']`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct JsonPath {
+    path: String,
+    segments: Vec<JsonPathSegment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self, anyhow::Error> {
+        let trimmed = path.trim();
+        let mut rest = trimmed.strip_prefix('$').unwrap_or(trimmed);
+        let mut segments = Vec::new();
+
+        while !rest.is_empty() {
+            if let Some(after_dot) = rest.strip_prefix('.') {
+                rest = after_dot;
+                if rest.is_empty() || rest.starts_with(['.', '[']) {
+                    return Err(anyhow!("Empty key in JSON path {path:?}"));
+                }
+            }
+
+            if let Some(after_bracket) = rest.strip_prefix('[') {
+                let end = after_bracket
+                    .find(']')
+                    .ok_or_else(|| anyhow!("Missing ']' in JSON path {path:?}"))?;
+                let inner = &after_bracket[..end];
+                let quoted_key = inner
+                    .strip_prefix('\'')
+                    .and_then(|key| key.strip_suffix('\''))
+                    .or_else(|| {
+                        inner
+                            .strip_prefix('"')
+                            .and_then(|key| key.strip_suffix('"'))
+                    });
+                let segment =
+                    match quoted_key {
+                        Some(key) => JsonPathSegment::Key(key.to_string()),
+                        None => JsonPathSegment::Index(inner.parse().map_err(|_| {
+                            anyhow!("Invalid index {inner:?} in JSON path {path:?}")
+                        })?),
+                    };
+                segments.push(segment);
+                rest = &after_bracket[end + 1..];
+            } else {
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                segments.push(JsonPathSegment::Key(rest[..end].to_string()));
+                rest = &rest[end..];
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(anyhow!("JSON path {path:?} does not point to a value"));
+        }
+
+        Ok(Self {
+            path: trimmed.to_string(),
+            segments,
+        })
+    }
+
+    /// Looks up the value the path points to, `None` if any part of it is missing.
+    pub fn resolve<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |current, segment| match segment {
+                JsonPathSegment::Key(key) => current.get(key),
+                JsonPathSegment::Index(index) => current.get(index),
+            })
+    }
+}
+
+impl std::fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path)
+    }
+}
+
+impl From<JsonPath> for String {
+    fn from(path: JsonPath) -> Self {
+        path.path
+    }
+}
+
+impl TryFrom<String> for JsonPath {
+    type Error = anyhow::Error;
+
+    fn try_from(path: String) -> Result<Self, Self::Error> {
+        JsonPath::parse(&path)
+    }
+}
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+/// Location of one `PoolStatus` value in the stats response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolStatsField {
+    /// Values of all paths are summed, e.g. locked and unlocked balance for the unpaid amount
+    pub paths: Vec<JsonPath>,
+    /// Applied to the summed value, e.g. `1000000` for pools reporting XTM instead of µT
+    #[serde(default = "default_multiplier")]
+    pub multiplier: f64,
+}
+
+/// Mapping of the pool's stats response to `PoolStatus`, fields without a mapping are reported as 0.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolStatsMapping {
+    pub accepted_shares: Option<PoolStatsField>,
+    pub unpaid: Option<PoolStatsField>,
+    pub balance: Option<PoolStatsField>,
+    pub min_payout: Option<PoolStatsField>,
+}
+
+/// Settings of a custom pool that built-in pools derive from their origin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomPoolSettings {
+    /// Appended to the wallet address, including the separator the pool expects, e.g. `.%WORKER_NAME%`
+    pub worker_name_template: Option<String>,
+    pub stats_mapping: PoolStatsMapping,
+}
+
+impl CustomPoolSettings {
+    pub fn worker_name(&self, worker_name: &str) -> Option<String> {
+        self.worker_name_template
+            .as_ref()
+            .filter(|template| !template.is_empty())
+            .map(|template| template.replace(WORKER_NAME_PLACEHOLDER, worker_name))
+    }
+}
+
+/// Custom pool as created and edited through the commands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomPoolDefinition {
+    pub pool_name: String,
+    pub pool_url: String,
+    pub algorithm: CustomPoolAlgorithm,
+    pub worker_name_template: Option<String>,
+    /// Stats endpoint, `%TARI_ADDRESS%` is replaced with the mining address
+    pub stats_url: String,
+    pub stats_mapping: PoolStatsMapping,
+}
+
+impl CustomPoolDefinition {
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if self.pool_name.trim().is_empty() {
+            return Err(anyhow!("Custom pool name cannot be empty"));
+        }
+        if self.pool_url.trim().is_empty() || self.pool_url.contains(char::is_whitespace) {
+            return Err(anyhow!("Invalid custom pool url {:?}", self.pool_url));
+        }
+        if !self.stats_url.is_empty()
+            && !self.stats_url.starts_with("http://")
+            && !self.stats_url.starts_with("https://")
+        {
+            return Err(anyhow!("Custom pool stats url must use http or https"));
+        }
+        if let Some(template) = &self.worker_name_template {
+            if template.contains(char::is_whitespace) {
+                return Err(anyhow!("Worker name template cannot contain whitespace"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Key the pool is stored under, custom GPU pools keep their algorithm in the key.
+    pub fn pool_key(&self, id: &str) -> String {
+        match self.algorithm.gpu_algorithm() {
+            Some(algorithm) => GpuPool::Custom {
+                id: id.to_string(),
+                algorithm,
+            }
+            .key_string(),
+            None => CpuPool::Custom(id.to_string()).key_string(),
+        }
+    }
+
+    fn settings(&self) -> CustomPoolSettings {
+        CustomPoolSettings {
+            worker_name_template: self.worker_name_template.clone(),
+            stats_mapping: self.stats_mapping.clone(),
+        }
+    }
+
+    pub fn to_cpu_pool_data(&self, id: &str) -> Result<BasePoolData<CpuPool>, anyhow::Error> {
+        if self.algorithm.gpu_algorithm().is_some() {
+            return Err(anyhow!(
+                "{:?} pools cannot be used for CPU mining",
+                self.algorithm
+            ));
+        }
+        Ok(BasePoolData {
+            pool_name: self.pool_name.trim().to_string(),
+            pool_url: self.pool_url.trim().to_string(),
+            stats_url: self.stats_url.trim().to_string(),
+            pool_type: CpuPool::Custom(id.to_string()),
+            pool_origin: PoolOrigin::Custom(self.settings()),
+        })
+    }
+
+    pub fn to_gpu_pool_data(&self, id: &str) -> Result<BasePoolData<GpuPool>, anyhow::Error> {
+        let algorithm = self
+            .algorithm
+            .gpu_algorithm()
+            .ok_or_else(|| anyhow!("{:?} pools cannot be used for GPU mining", self.algorithm))?;
+        Ok(BasePoolData {
+            pool_name: self.pool_name.trim().to_string(),
+            pool_url: self.pool_url.trim().to_string(),
+            stats_url: self.stats_url.trim().to_string(),
+            pool_type: GpuPool::Custom {
+                id: id.to_string(),
+                algorithm,
+            },
+            pool_origin: PoolOrigin::Custom(self.settings()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_path_resolves_keys_and_indexes() {
+        let value = json!({
+            "stats": { "balance": 12, "workers": [{ "shares": 3 }, { "shares": 4 }] },
+            "some.key": "5"
+        });
+
+        let path = JsonPath::parse("$.stats.balance").unwrap();
+        assert_eq!(path.resolve(&value), Some(&json!(12)));
+
+        let path = JsonPath::parse("stats.workers[1].shares").unwrap();
+        assert_eq!(path.resolve(&value), Some(&json!(4)));
+
+        let path = JsonPath::parse("$['some.key']").unwrap();
+        assert_eq!(path.resolve(&value), Some(&json!("5")));
+
+        let path = JsonPath::parse("$.stats.missing").unwrap();
+        assert_eq!(path.resolve(&value), None);
+    }
+
+    #[test]
+    fn test_invalid_json_paths_are_rejected() {
+        assert!(JsonPath::parse("").is_err());
+        assert!(JsonPath::parse("$").is_err());
+        assert!(JsonPath::parse("stats..balance").is_err());
+        assert!(JsonPath::parse("workers[first]").is_err());
+        assert!(JsonPath::parse("workers[0").is_err());
+    }
+
+    #[test]
+    fn test_definition_converts_to_pool_data() {
+        let definition = CustomPoolDefinition {
+            pool_name: "My pool".to_string(),
+            pool_url: "pool.example.com:3333".to_string(),
+            algorithm: CustomPoolAlgorithm::SHA3X,
+            worker_name_template: Some(".%WORKER_NAME%".to_string()),
+            stats_url: "https://pool.example.com/api/%TARI_ADDRESS%".to_string(),
+            stats_mapping: PoolStatsMapping::default(),
+        };
+        definition.validate().unwrap();
+
+        assert!(definition.to_cpu_pool_data("abc").is_err());
+        let pool_data = definition.to_gpu_pool_data("abc").unwrap();
+        assert_eq!(
+            pool_data.pool_type,
+            GpuPool::Custom {
+                id: "abc".to_string(),
+                algorithm: GpuMiningAlgorithm::SHA3X
+            }
+        );
+        assert_eq!(
+            pool_data.pool_origin.worker_name("rig-1"),
+            Some(".rig-1".to_string())
+        );
+        assert_eq!(definition.pool_key("abc"), "Custom:SHA3X:abc");
+    }
+
+    #[test]
+    fn test_stats_mapping_deserializes_from_config() {
+        let mapping: PoolStatsMapping = serde_json::from_value(json!({
+            "unpaid": { "paths": ["$.stats.unlocked", "$.stats.locked"] },
+            "min_payout": { "paths": ["threshold"], "multiplier": 1000000.0 }
+        }))
+        .unwrap();
+
+        let unpaid = mapping.unpaid.unwrap();
+        assert_eq!(unpaid.paths.len(), 2);
+        assert_eq!(unpaid.multiplier, 1.0);
+        assert_eq!(mapping.min_payout.unwrap().multiplier, 1000000.0);
+        assert!(mapping.accepted_shares.is_none());
+
+        let invalid: Result<PoolStatsMapping, _> = serde_json::from_value(json!({
+            "unpaid": { "paths": ["stats..unlocked"] }
+        }));
+        assert!(invalid.is_err());
+    }
+}
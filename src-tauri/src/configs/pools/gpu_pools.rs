@@ -24,7 +24,8 @@ use std::{collections::HashMap, fmt::Display, sync::LazyLock};
 
 use serde::{Deserialize, Serialize};
 
-use crate::configs::pools::{BasePoolData, PoolOrigin};
+use crate::configs::pools::{custom_pools::CUSTOM_POOL_KEY_PREFIX, BasePoolData, PoolOrigin};
+use crate::mining::gpu::consts::GpuMiningAlgorithm;
 
 static DEFAULT_GPU_LUCKYPOOL_SHA3X: LazyLock<BasePoolData<GpuPool>> =
     LazyLock::new(|| BasePoolData {
@@ -73,7 +74,9 @@ static DEFAULT_GPU_KRYPTEX_C29: LazyLock<BasePoolData<GpuPool>> = LazyLock::new(
     pool_origin: PoolOrigin::Kryptex,
 });
 
+/// Serialized as its key string, so custom pools can be used as keys of the pools map.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(into = "String", try_from = "String")]
 pub enum GpuPool {
     #[default]
     LuckyPoolSHA3X,
@@ -81,18 +84,30 @@ pub enum GpuPool {
     SupportXTMPoolSHA3X,
     KryptexPoolSHA3X,
     KryptexPoolC29,
+    /// User-defined pool, the algorithm is part of the key as it decides which miners can use it
+    Custom {
+        id: String,
+        algorithm: GpuMiningAlgorithm,
+    },
 }
 
 impl Display for GpuPool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let name = match self {
-            GpuPool::LuckyPoolSHA3X => "LuckyPoolSHA3X",
-            GpuPool::LuckyPoolC29 => "LuckyPoolC29",
-            GpuPool::SupportXTMPoolSHA3X => "SupportXTMPoolSHA3X",
-            GpuPool::KryptexPoolSHA3X => "KryptexPoolSHA3X",
-            GpuPool::KryptexPoolC29 => "KryptexPoolC29",
-        };
-        write!(f, "{name}")
+        write!(f, "{}", self.key_string())
+    }
+}
+
+impl From<GpuPool> for String {
+    fn from(pool: GpuPool) -> Self {
+        pool.key_string()
+    }
+}
+
+impl TryFrom<String> for GpuPool {
+    type Error = anyhow::Error;
+
+    fn try_from(pool_name: String) -> Result<Self, Self::Error> {
+        GpuPool::from_string(&pool_name)
     }
 }
 
@@ -104,7 +119,24 @@ impl GpuPool {
             "SupportXTMPoolSHA3X" => Ok(GpuPool::SupportXTMPoolSHA3X),
             "KryptexPoolSHA3X" => Ok(GpuPool::KryptexPoolSHA3X),
             "KryptexPoolC29" => Ok(GpuPool::KryptexPoolC29),
-            _ => Err(anyhow::anyhow!("Invalid GPU pool name")),
+            _ => {
+                let (algorithm, id) = pool_name
+                    .strip_prefix(CUSTOM_POOL_KEY_PREFIX)
+                    .and_then(|key| key.split_once(':'))
+                    .ok_or_else(|| anyhow::anyhow!("Invalid GPU pool name"))?;
+                let algorithm = match algorithm {
+                    "SHA3X" => GpuMiningAlgorithm::SHA3X,
+                    "C29" => GpuMiningAlgorithm::C29,
+                    _ => return Err(anyhow::anyhow!("Invalid GPU pool algorithm")),
+                };
+                if id.is_empty() || id.contains(':') {
+                    return Err(anyhow::anyhow!("Invalid GPU pool name"));
+                }
+                Ok(GpuPool::Custom {
+                    id: id.to_string(),
+                    algorithm,
+                })
+            }
         }
     }
 
@@ -115,9 +147,28 @@ impl GpuPool {
             GpuPool::SupportXTMPoolSHA3X => "SupportXTMPoolSHA3X".to_string(),
             GpuPool::KryptexPoolSHA3X => "KryptexPoolSHA3X".to_string(),
             GpuPool::KryptexPoolC29 => "KryptexPoolC29".to_string(),
+            GpuPool::Custom { id, algorithm } => {
+                format!("{CUSTOM_POOL_KEY_PREFIX}{algorithm:?}:{id}")
+            }
+        }
+    }
+
+    pub fn is_custom(&self) -> bool {
+        matches!(self, GpuPool::Custom { .. })
+    }
+
+    pub fn algorithm(&self) -> GpuMiningAlgorithm {
+        match self {
+            GpuPool::LuckyPoolSHA3X | GpuPool::SupportXTMPoolSHA3X | GpuPool::KryptexPoolSHA3X => {
+                GpuMiningAlgorithm::SHA3X
+            }
+            GpuPool::LuckyPoolC29 | GpuPool::KryptexPoolC29 => GpuMiningAlgorithm::C29,
+            GpuPool::Custom { algorithm, .. } => algorithm.clone(),
         }
     }
 
+    /// Built-in configuration of the pool.
+    /// Custom pools have none, the default pool's configuration is returned for them.
     pub fn default_content(&self) -> BasePoolData<GpuPool> {
         match self {
             GpuPool::LuckyPoolSHA3X => DEFAULT_GPU_LUCKYPOOL_SHA3X.clone(),
@@ -125,6 +176,7 @@ impl GpuPool {
             GpuPool::SupportXTMPoolSHA3X => DEFAULT_GPU_SUPPORTXTM_SHA3X.clone(),
             GpuPool::KryptexPoolSHA3X => DEFAULT_GPU_KRYPTEX_SHA3X.clone(),
             GpuPool::KryptexPoolC29 => DEFAULT_GPU_KRYPTEX_C29.clone(),
+            GpuPool::Custom { .. } => GpuPool::default().default_content(),
         }
     }
 
@@ -139,3 +191,30 @@ impl GpuPool {
         gpu_pools
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    #[test]
+    fn test_pool_keys_round_trip() {
+        for pool in [
+            GpuPool::KryptexPoolC29,
+            GpuPool::Custom {
+                id: "0b5c6d0e".to_string(),
+                algorithm: GpuMiningAlgorithm::C29,
+            },
+        ] {
+            assert_eq!(GpuPool::from_string(&pool.key_string()).unwrap(), pool);
+        }
+        assert_eq!(
+            GpuPool::from_string("Custom:SHA3X:0b5c6d0e")
+                .unwrap()
+                .algorithm(),
+            GpuMiningAlgorithm::SHA3X
+        );
+        assert!(GpuPool::from_string("Custom:RandomX:0b5c6d0e").is_err());
+        assert!(GpuPool::from_string("Custom:0b5c6d0e").is_err());
+    }
+}
@@ -22,15 +22,45 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::configs::pools::custom_pools::CustomPoolSettings;
+
 pub mod cpu_pools;
+pub mod custom_pools;
 pub mod gpu_pools;
+pub mod worker_name;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PoolOrigin {
     SupportXTM,
     LuckyPool,
     Kryptex,
+    Custom(CustomPoolSettings),
 }
+
+impl PoolOrigin {
+    /// Separator the pool expects between the wallet address and the worker name.
+    /// SupportXTM does not support worker names in the login so none is used, custom pools
+    /// include the separator in their worker name template.
+    pub fn worker_name_separator(&self) -> Option<char> {
+        match self {
+            PoolOrigin::LuckyPool => Some('.'),
+            PoolOrigin::SupportXTM => None,
+            PoolOrigin::Kryptex => Some('/'),
+            PoolOrigin::Custom(_) => None,
+        }
+    }
+
+    /// Worker name appended to the wallet address, the format depends on the pool.
+    pub fn worker_name(&self, worker_name: &str) -> Option<String> {
+        match self {
+            PoolOrigin::Custom(settings) => settings.worker_name(worker_name),
+            _ => self
+                .worker_name_separator()
+                .map(|separator| format!("{separator}{worker_name}")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BasePoolData<T> {
     pub pool_name: String,
@@ -0,0 +1,158 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! Worker name identifying this installation on pool dashboards.
+//!
+//! The worker name is appended to the wallet address the miners log in with, so several rigs
+//! mining to the same address show up as separate workers. Each pool expects its own separator
+//! between the address and the worker name, see `PoolOrigin::worker_name`.
+
+use anyhow::anyhow;
+
+use crate::utils::rand_utils;
+
+/// Prefix of generated worker names, also used when a name has no single usable character
+pub const DEFAULT_WORKER_NAME: &str = "Tari-universe";
+pub const MAX_WORKER_NAME_LENGTH: usize = 32;
+const GENERATED_SUFFIX_LENGTH: usize = 6;
+
+/// Pools split the login on separators like `.`, `/` or `+`, so only characters no pool treats
+/// specially are allowed.
+fn is_allowed_character(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '-' || character == '_'
+}
+
+/// Checks that the worker name can be used with every pool.
+pub fn validate_worker_name(worker_name: &str) -> Result<(), anyhow::Error> {
+    if worker_name.is_empty() {
+        return Err(anyhow!("Worker name cannot be empty"));
+    }
+    if worker_name.len() > MAX_WORKER_NAME_LENGTH {
+        return Err(anyhow!(
+            "Worker name cannot be longer than {MAX_WORKER_NAME_LENGTH} characters"
+        ));
+    }
+    if let Some(character) = worker_name.chars().find(|c| !is_allowed_character(*c)) {
+        return Err(anyhow!(
+            "Worker name contains {character:?}, only letters, digits, '-' and '_' are allowed"
+        ));
+    }
+    Ok(())
+}
+
+/// Turns an arbitrary name into a valid worker name.
+///
+/// Disallowed characters become `-`, repeated and leading/trailing dashes are dropped and the
+/// result is cut to the maximum length.
+pub fn sanitize_worker_name(name: &str) -> String {
+    let mut sanitized = String::with_capacity(name.len());
+    for character in name.chars() {
+        let character = if is_allowed_character(character) {
+            character
+        } else {
+            '-'
+        };
+        if character == '-' && (sanitized.is_empty() || sanitized.ends_with('-')) {
+            continue;
+        }
+        sanitized.push(character);
+    }
+    sanitized.truncate(MAX_WORKER_NAME_LENGTH);
+    let sanitized = sanitized.trim_end_matches('-');
+
+    if sanitized.is_empty() {
+        DEFAULT_WORKER_NAME.to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+/// Worker name of a new installation.
+///
+/// Pool dashboards are public, so the name is random rather than derived from the machine.
+pub fn default_worker_name() -> String {
+    format!(
+        "{DEFAULT_WORKER_NAME}-{}",
+        rand_utils::get_rand_string(GENERATED_SUFFIX_LENGTH)
+    )
+}
+
+/// Worker name derived from the machine's host name, only used when the user opts in.
+pub fn hostname_worker_name() -> String {
+    let host_name = whoami::fallible::hostname().unwrap_or_default();
+    // Drop the domain of fully qualified host names, e.g. `rig-1.local`
+    let host_name = host_name.split('.').next().unwrap_or_default();
+    sanitize_worker_name(host_name)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    #[test]
+    fn test_sanitize_worker_name() {
+        assert_eq!(sanitize_worker_name("rig-01"), "rig-01");
+        assert_eq!(
+            sanitize_worker_name("Bob's MacBook Pro"),
+            "Bob-s-MacBook-Pro"
+        );
+        assert_eq!(sanitize_worker_name("--rig..1/"), "rig-1");
+        assert_eq!(sanitize_worker_name("żółć"), DEFAULT_WORKER_NAME);
+        assert_eq!(sanitize_worker_name(""), DEFAULT_WORKER_NAME);
+        assert_eq!(
+            sanitize_worker_name(&"a".repeat(40)).len(),
+            MAX_WORKER_NAME_LENGTH
+        );
+    }
+
+    #[test]
+    fn test_sanitized_names_are_valid() {
+        for name in [
+            "Bob's MacBook Pro",
+            "rig_1",
+            "DESKTOP-4F2K9J1",
+            "x/y.z+w",
+            "",
+        ] {
+            validate_worker_name(&sanitize_worker_name(name)).unwrap();
+        }
+        validate_worker_name(&default_worker_name()).unwrap();
+        validate_worker_name(&hostname_worker_name()).unwrap();
+    }
+
+    #[test]
+    fn test_default_worker_name_is_random() {
+        let worker_name = default_worker_name();
+        assert!(worker_name.starts_with(DEFAULT_WORKER_NAME));
+        assert_ne!(worker_name, default_worker_name());
+    }
+
+    #[test]
+    fn test_validate_worker_name() {
+        validate_worker_name("rig-01_gpu").unwrap();
+        assert!(validate_worker_name("").is_err());
+        assert!(validate_worker_name("rig.01").is_err());
+        assert!(validate_worker_name("rig/01").is_err());
+        assert!(validate_worker_name("rig 01").is_err());
+        assert!(validate_worker_name(&"a".repeat(MAX_WORKER_NAME_LENGTH + 1)).is_err());
+    }
+}
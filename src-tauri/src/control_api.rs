@@ -0,0 +1,480 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! Opt-in local REST API that lets operators drive a rig from scripts instead of the UI.
+//!
+//! Every request has to carry the token stored in `ConfigCore` as `Authorization: Bearer <token>`.
+//! Only a safe subset of the Tauri commands is exposed: status, starting and stopping mining,
+//! selecting the mining mode and pools, scheduler events and the wallet balance. Nothing that
+//! reveals seed words or moves funds is reachable through it.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::sync::{Arc, LazyLock};
+
+use anyhow::anyhow;
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post, put};
+use axum::{Json, Router};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::ipc::InvokeError;
+use tokio::sync::{watch, RwLock};
+
+use crate::commands;
+use crate::configs::config_core::ConfigCore;
+use crate::configs::config_mining::ConfigMining;
+use crate::configs::config_pools::ConfigPools;
+use crate::configs::pools::{cpu_pools::CpuPool, gpu_pools::GpuPool};
+use crate::configs::trait_config::ConfigImpl;
+use crate::event_scheduler::{ScheduledEventInfo, SchedulerEventTiming, SchedulerEventType};
+use crate::mining::cpu::CpuMinerStatus;
+use crate::mining::gpu::consts::GpuMinerStatus;
+use crate::mining::mode_selection;
+use crate::mining::pools::pool_selection::{select_cpu_pool, select_gpu_pool};
+use crate::node::node_adapter::BaseNodeStatus;
+use crate::utils::rand_utils;
+use crate::utils::server_utils::{bind_local_server, spawn_local_server, LocalServerHandle};
+use crate::wallet::wallet_types::{WalletBalance, WalletState};
+use crate::LOG_TARGET_APP_LOGIC;
+
+const API_PREFIX: &str = "/api/v1";
+const DEFAULT_CONTROL_API_PORT: u16 = 9465;
+const TOKEN_LENGTH: usize = 48;
+
+static INSTANCE: LazyLock<RwLock<ControlApi>> = LazyLock::new(|| RwLock::new(ControlApi::new()));
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ControlApiSettings {
+    pub enabled: bool,
+    pub bind_address: SocketAddr,
+    /// Generated the first time the API is enabled
+    pub token: String,
+}
+
+impl Default for ControlApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: SocketAddr::V4(SocketAddrV4::new(
+                Ipv4Addr::LOCALHOST,
+                DEFAULT_CONTROL_API_PORT,
+            )),
+            token: String::new(),
+        }
+    }
+}
+
+impl ControlApiSettings {
+    pub fn generate_token() -> String {
+        rand_utils::get_rand_string(TOKEN_LENGTH)
+    }
+}
+
+/// Channels the status endpoints read from
+#[derive(Clone)]
+pub struct ControlApiSources {
+    pub cpu_miner_status_rx: watch::Receiver<CpuMinerStatus>,
+    pub gpu_miner_status_rx: watch::Receiver<GpuMinerStatus>,
+    pub node_status_rx: watch::Receiver<BaseNodeStatus>,
+    pub wallet_state_rx: watch::Receiver<Option<WalletState>>,
+}
+
+#[derive(Clone)]
+struct ApiState {
+    token: Arc<String>,
+    sources: ControlApiSources,
+}
+
+pub struct ControlApi {
+    sources: Option<ControlApiSources>,
+    handle: Option<LocalServerHandle>,
+}
+
+impl ControlApi {
+    fn new() -> Self {
+        Self {
+            sources: None,
+            handle: None,
+        }
+    }
+
+    pub async fn initialize(sources: ControlApiSources) {
+        INSTANCE.write().await.sources = Some(sources);
+    }
+
+    /// Starts, restarts or stops the API so that it matches the given settings.
+    /// The running API is kept when the new one can not be started.
+    pub async fn apply_settings(settings: &ControlApiSettings) -> Result<(), anyhow::Error> {
+        let mut server = INSTANCE.write().await;
+        if !settings.enabled {
+            if let Some(handle) = server.handle.take() {
+                info!(target: LOG_TARGET_APP_LOGIC, "Stopping control API");
+                handle.stop().await;
+            }
+            return Ok(());
+        }
+        if settings.token.is_empty() {
+            return Err(anyhow!("Control API token is not set"));
+        }
+        let sources = server
+            .sources
+            .clone()
+            .ok_or_else(|| anyhow!("Control API sources are not initialized"))?;
+        if !settings.bind_address.ip().is_loopback() {
+            warn!(target: LOG_TARGET_APP_LOGIC, "Control API is bound to {}, it is reachable from other machines", settings.bind_address);
+        }
+
+        let app = router(ApiState {
+            token: Arc::new(settings.token.clone()),
+            sources,
+        });
+        // The same address can only be bound again once the running API released it
+        if let Some(handle) = server
+            .handle
+            .take_if(|handle| handle.bind_address() == settings.bind_address)
+        {
+            info!(target: LOG_TARGET_APP_LOGIC, "Stopping control API");
+            handle.stop().await;
+        }
+        let listener = bind_local_server("Control API", settings.bind_address).await?;
+        if let Some(handle) = server.handle.take() {
+            info!(target: LOG_TARGET_APP_LOGIC, "Stopping control API");
+            handle.stop().await;
+        }
+        let handle = spawn_local_server("Control API", listener, app).await?;
+        info!(target: LOG_TARGET_APP_LOGIC, "Control API listening on http://{}{}", settings.bind_address, API_PREFIX);
+        server.handle = Some(handle);
+        Ok(())
+    }
+}
+
+fn router(state: ApiState) -> Router {
+    let api = Router::new()
+        .route("/status", get(get_status))
+        .route("/wallet/balance", get(get_wallet_balance))
+        .route("/mining/cpu/start", post(start_cpu_mining))
+        .route("/mining/cpu/stop", post(stop_cpu_mining))
+        .route("/mining/gpu/start", post(start_gpu_mining))
+        .route("/mining/gpu/stop", post(stop_gpu_mining))
+        .route("/mining/mode", put(select_mining_mode))
+        .route("/pools/cpu", put(change_cpu_pool))
+        .route("/pools/gpu", put(change_gpu_pool))
+        .route(
+            "/scheduler/events",
+            get(list_scheduler_events).post(add_scheduler_event),
+        )
+        .route(
+            "/scheduler/events/:event_id",
+            delete(remove_scheduler_event),
+        )
+        .route(
+            "/scheduler/events/:event_id/pause",
+            post(pause_scheduler_event),
+        )
+        .route(
+            "/scheduler/events/:event_id/resume",
+            post(resume_scheduler_event),
+        )
+        .route_layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        .with_state(state);
+    Router::new().nest(API_PREFIX, api)
+}
+
+/// Compares in constant time, so the token cannot be guessed from response times
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    !expected.is_empty()
+        && provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn authenticate(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let is_authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| tokens_match(token, &state.token));
+    if is_authorized {
+        next.run(request).await
+    } else {
+        warn!(target: LOG_TARGET_APP_LOGIC, "Rejected unauthorized control API request to {}", request.uri().path());
+        ApiError {
+            status: StatusCode::UNAUTHORIZED,
+            message: "Missing or invalid token".to_string(),
+        }
+        .into_response()
+    }
+}
+
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message,
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::from(error.to_string())
+    }
+}
+
+impl From<InvokeError> for ApiError {
+    fn from(error: InvokeError) -> Self {
+        let message = match error.0 {
+            serde_json::Value::String(message) => message,
+            value => value.to_string(),
+        };
+        Self::from(message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(serde_json::json!({ "error": self.message })),
+        )
+            .into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+#[derive(Serialize)]
+struct StatusResponse {
+    cpu_miner: CpuMinerStatus,
+    gpu_miner: GpuMinerStatus,
+    node: BaseNodeStatus,
+    selected_mining_mode: String,
+    /// `None` when solo mining
+    cpu_pool: Option<String>,
+    gpu_pool: Option<String>,
+}
+
+async fn get_status(State(state): State<ApiState>) -> Json<StatusResponse> {
+    let selected_mining_mode = ConfigMining::content().await.selected_mining_mode().clone();
+    let pools_config = ConfigPools::content().await;
+    let cpu_pool = pools_config
+        .cpu_pool_enabled()
+        .then(|| pools_config.current_cpu_pool().pool_name);
+    let gpu_pool = pools_config
+        .gpu_pool_enabled()
+        .then(|| pools_config.current_gpu_pool().pool_name);
+    let sources = &state.sources;
+    Json(StatusResponse {
+        cpu_miner: sources.cpu_miner_status_rx.borrow().clone(),
+        gpu_miner: sources.gpu_miner_status_rx.borrow().clone(),
+        node: *sources.node_status_rx.borrow(),
+        selected_mining_mode,
+        cpu_pool,
+        gpu_pool,
+    })
+}
+
+async fn get_wallet_balance(State(state): State<ApiState>) -> Json<Option<WalletBalance>> {
+    let balance = state
+        .sources
+        .wallet_state_rx
+        .borrow()
+        .as_ref()
+        .and_then(|wallet_state| wallet_state.balance.clone());
+    Json(balance)
+}
+
+async fn start_cpu_mining() -> ApiResult<StatusCode> {
+    commands::start_cpu_mining().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn stop_cpu_mining() -> ApiResult<StatusCode> {
+    commands::stop_cpu_mining().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn start_gpu_mining() -> ApiResult<StatusCode> {
+    commands::start_gpu_mining().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn stop_gpu_mining() -> ApiResult<StatusCode> {
+    commands::stop_gpu_mining().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+struct MiningModeRequest {
+    mode: String,
+}
+
+async fn select_mining_mode(Json(request): Json<MiningModeRequest>) -> ApiResult<StatusCode> {
+    mode_selection::select_mining_mode(request.mode).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+struct PoolRequest {
+    pool: String,
+    /// Skips the reachability probe of the pool
+    #[serde(default)]
+    force: bool,
+}
+
+async fn change_cpu_pool(Json(request): Json<PoolRequest>) -> ApiResult<StatusCode> {
+    let cpu_pool = CpuPool::from_string(&request.pool)?;
+    select_cpu_pool(cpu_pool, request.force).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn change_gpu_pool(Json(request): Json<PoolRequest>) -> ApiResult<StatusCode> {
+    let gpu_pool = GpuPool::from_string(&request.pool)?;
+    select_gpu_pool(gpu_pool, request.force).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+struct SchedulerEventRequest {
+    event_id: String,
+    event_time: SchedulerEventTiming,
+    event_type: SchedulerEventType,
+    priority: Option<i32>,
+}
+
+async fn list_scheduler_events() -> Json<HashMap<String, ScheduledEventInfo>> {
+    Json(ConfigCore::content().await.scheduler_events().clone())
+}
+
+async fn add_scheduler_event(Json(request): Json<SchedulerEventRequest>) -> ApiResult<StatusCode> {
+    commands::add_scheduler_event(
+        request.event_id,
+        request.event_time,
+        request.event_type,
+        request.priority,
+    )
+    .await?;
+    Ok(StatusCode::CREATED)
+}
+
+async fn remove_scheduler_event(Path(event_id): Path<String>) -> ApiResult<StatusCode> {
+    commands::remove_scheduler_event(event_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn pause_scheduler_event(Path(event_id): Path<String>) -> ApiResult<StatusCode> {
+    commands::pause_scheduler_event(event_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn resume_scheduler_event(Path(event_id): Path<String>) -> ApiResult<StatusCode> {
+    commands::resume_scheduler_event(event_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::configs::config_mining::ConfigMiningContent;
+
+    #[test]
+    fn matches_only_the_exact_token() {
+        let token = ControlApiSettings::generate_token();
+        assert_eq!(token.len(), TOKEN_LENGTH);
+        assert!(tokens_match(&token, &token));
+        assert!(!tokens_match(&token[1..], &token));
+        assert!(!tokens_match(&format!("{token}x"), &token));
+        assert!(!tokens_match("", ""));
+    }
+
+    #[test]
+    fn command_errors_keep_their_message() {
+        let error = ApiError::from(InvokeError::from("Insufficient balance".to_string()));
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert_eq!(error.message, "Insufficient balance");
+    }
+
+    #[test]
+    fn rejects_unknown_mining_modes() {
+        let config = ConfigMiningContent::default();
+        assert!(mode_selection::ensure_mining_mode_exists(config.mining_modes(), "Turbo").is_ok());
+
+        let error = ApiError::from(
+            mode_selection::ensure_mining_mode_exists(config.mining_modes(), "Turbbo").unwrap_err(),
+        );
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert_eq!(error.message, "Unknown mining mode: Turbbo");
+    }
+
+    #[tokio::test]
+    async fn authenticates_every_request() {
+        let token = ControlApiSettings::generate_token();
+        let sources = ControlApiSources {
+            cpu_miner_status_rx: watch::channel(CpuMinerStatus::default()).1,
+            gpu_miner_status_rx: watch::channel(GpuMinerStatus::default()).1,
+            node_status_rx: watch::channel(BaseNodeStatus::default()).1,
+            wallet_state_rx: watch::channel(None).1,
+        };
+        let app = router(ApiState {
+            token: Arc::new(token.clone()),
+            sources,
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let url = format!("http://{address}{API_PREFIX}/wallet/balance");
+        let client = reqwest::Client::new();
+
+        let missing = client.get(&url).send().await.unwrap();
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+
+        let wrong = client.get(&url).bearer_auth("wrong").send().await.unwrap();
+        assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+
+        let not_bearer = client
+            .get(&url)
+            .basic_auth("user", Some(&token))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(not_bearer.status(), StatusCode::UNAUTHORIZED);
+
+        let valid = client.get(&url).bearer_auth(&token).send().await.unwrap();
+        assert_eq!(valid.status(), StatusCode::OK);
+        assert!(valid.json::<serde_json::Value>().await.unwrap().is_null());
+    }
+}
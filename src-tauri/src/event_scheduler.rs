@@ -28,13 +28,34 @@
 //! ## What it does
 //!
 //! The scheduler can handle two main types of events:
-//! - **One-time events**: Run something after a delay (like "start mining in 2 hours")
+//! - **One-time events**: Run something after a delay (like "start mining in 2 hours").
+//!   The absolute deadline is stored so pending events survive an application restart
 //! - **Recurring events**: Run something during specific time windows using cron expressions
-//!   (like "mine every day from 10 PM to 6 AM")
+//!   (like "mine every day from 10 PM to 6 AM"), optionally limited to certain days of the week
+//!   and combined into several windows per event (like "weekdays 10 PM - 7 AM, weekends all day")
+//! - **Condition events**: Run something while a machine state holds, like being idle for
+//!   30 minutes, running hotter than a temperature threshold or having a low battery
+//!
+//! Besides mining, events can run operational actions such as switching pools, the GPU miner,
+//! pool/solo mining or Tor. Recurring and condition events restore the previous value when
+//! they are exited.
 //!
 //! It also manages different mining modes and keeps your scheduled events even when you
 //! restart the application.
 //!
+//! ## Overlapping mining modes
+//!
+//! Several `Mine` events can be inside their windows at the same time. Every event carries a
+//! priority and the rule is:
+//! - The active `Mine` event with the highest priority decides the mining mode
+//! - Ties are broken by event ID, the lexicographically smallest ID wins
+//! - When the deciding event exits, the next active event takes over; mining is stopped only
+//!   when no other `Mine` event is active
+//! - One-time (`In`) events apply their mode directly and do not take part in the resolution
+//!
+//! `Action` events changing the same setting follow the same rule. They share the value
+//! captured by the first of them to enter, which is restored once the last of them exits.
+//!
 //! ## How it works
 //!
 //! The system uses message passing to stay thread-safe:
@@ -42,12 +63,19 @@
 //! - Each scheduled event runs in its own background task
 //! - All operations go through a central message loop
 //! - Events can be paused, resumed, or removed as needed
+//! - The loop watches for wall-clock jumps (suspend/resume, NTP corrections) and re-evaluates
+//!   every event against the current time, entering windows we are now inside of and exiting
+//!   the ones we left
 //!
 //! ## Key parts
 //!
 //! - `CronSchedule`: Handles recurring time windows with cron expressions
-//! - `SchedulerEventTiming`: Defines when events should trigger (In/Between patterns)
-//! - `SchedulerEventType`: Defines what actions to perform (ResumeMining/Mine)
+//! - `SchedulerEventTiming`: Defines when events should trigger (In/Between/BetweenWindows/Condition patterns)
+//! - `SchedulerCondition`: Machine states checked periodically by the message loop
+//! - `SchedulerTransition`: Preview of upcoming enter/exit transitions and resulting modes
+//! - `SchedulerHistory`: Persisted audit log of every enter/exit execution and its outcome
+//! - `SchedulerEventType`: Defines what actions to perform (ResumeMining/Mine/Action)
+//! - `SchedulerAction`: Configuration changes that can be scheduled, with their restore values
 //! - Persistent storage: Your recurring events are saved and restored automatically
 //!
 //! ## Basic usage
@@ -56,13 +84,13 @@
 //! // Schedule mining to start in 2 hours
 //! let timing = SchedulerEventTiming::parse_in_variant(2, TimeUnit::Hours)?;
 //! EventScheduler::instance()
-//!     .schedule_event(SchedulerEventType::ResumeMining, "start_mining".to_string(), timing)
+//!     .schedule_event(SchedulerEventType::ResumeMining, "start_mining".to_string(), timing, 0)
 //!     .await?;
 //!
 //! // Schedule mining between 10 PM and 6 AM daily
-//! let timing = SchedulerEventTiming::parse_between_variant(10, TimePeriod::PM, 6, TimePeriod::AM)?;
+//! let timing = SchedulerEventTiming::parse_between_variant(10, 0, TimePeriod::PM, 6, 0, TimePeriod::AM, vec![])?;
 //! EventScheduler::instance()
-//!     .schedule_event(SchedulerEventType::ResumeMining, "night_mining".to_string(), timing)
+//!     .schedule_event(SchedulerEventType::ResumeMining, "night_mining".to_string(), timing, 0)
 //!     .await?;
 //! ```
 //!
@@ -75,6 +103,7 @@ use chrono::{DateTime, Duration, Local};
 use croner::{self, parser::CronParser, Cron};
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 use std::{
     collections::HashMap,
     fmt::Display,
@@ -85,22 +114,32 @@ use std::{
 };
 use tokio::{
     sync::{mpsc, RwLock},
-    time::sleep,
+    time::{sleep, Instant},
 };
 
 use crate::{
     configs::{
         config_core::{ConfigCore, ConfigCoreContent},
-        config_mining::{ConfigMining, ConfigMiningContent},
         trait_config::ConfigImpl,
     },
-    events_emitter::EventsEmitter,
-    mining::{cpu::manager::CpuManager, gpu::manager::GpuManager},
+    mining::{
+        cpu::manager::CpuManager, gpu::manager::GpuManager, mode_selection::select_mining_mode,
+    },
+    scheduler_actions::SchedulerAction,
+    scheduler_conditions::{
+        ConditionVariantPayload, SchedulerCondition, SchedulerConditionsMonitor,
+    },
+    scheduler_history::{SchedulerExecutionOutcome, SchedulerExecutionRecord, SchedulerHistory},
     tasks_tracker::TasksTrackers,
+    utils::system_status::SystemStatus,
     LOG_TARGET_APP_LOGIC,
 };
 
 static ZERO_DURATION: std::time::Duration = std::time::Duration::from_secs(0);
+static CONDITIONS_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+static CLOCK_JUMP_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+/// Difference between wall-clock and monotonic time above which we treat it as a clock jump
+static CLOCK_JUMP_THRESHOLD: std::time::Duration = std::time::Duration::from_secs(60);
 
 static INSTANCE: LazyLock<EventScheduler> = LazyLock::new(EventScheduler::new);
 static EVENT_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
@@ -113,6 +152,7 @@ enum SchedulerMessage {
         event_type: SchedulerEventType,
         timing: SchedulerEventTiming,
         event_id: String,
+        priority: i32,
         response: tokio::sync::oneshot::Sender<Result<String, SchedulerError>>,
     },
     RemoveEvent {
@@ -129,13 +169,27 @@ enum SchedulerMessage {
     },
     TriggerEnterCallback {
         event_id: String,
+        /// Time the trigger was scheduled for, `None` if it was not planned ahead
+        planned_time: Option<DateTime<Local>>,
     },
     TriggerExitCallback {
         event_id: String,
+        planned_time: Option<DateTime<Local>>,
     },
     CleanupSchedule {
         event_id: String,
     },
+    GetNextTransitions {
+        count: usize,
+        response: tokio::sync::oneshot::Sender<Vec<SchedulerTransition>>,
+    },
+    GetHistory {
+        limit: Option<usize>,
+        response: tokio::sync::oneshot::Sender<Vec<SchedulerExecutionRecord>>,
+    },
+    ClearHistory {
+        response: tokio::sync::oneshot::Sender<()>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -306,6 +360,28 @@ impl CronSchedule {
                 .max(ZERO_DURATION)
         })
     }
+
+    /// Checks whether the given moment falls inside this schedule's window.
+    ///
+    /// The window is considered active when its most recent start is not older than its most
+    /// recent end, so a window that ends and restarts at the same minute stays active.
+    ///
+    /// ### Parameters
+    /// * `at` - The moment to check
+    ///
+    /// ### Returns
+    /// * `true` - The window started and has not ended yet
+    /// * `false` - The window is closed or never started
+    pub fn is_active_at(&self, at: DateTime<Local>) -> bool {
+        // Inclusive, so the window is active from its start minute and closed from its end minute
+        let last_start = self.start_time.find_previous_occurrence(&at, true).ok();
+        let last_end = self.end_time.find_previous_occurrence(&at, true).ok();
+        match (last_start, last_end) {
+            (Some(start), Some(end)) => start >= end,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -321,6 +397,46 @@ pub enum TimePeriod {
     PM,
 }
 
+/// Day of the week a `Between` window is allowed to start on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum DayOfWeek {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl DayOfWeek {
+    /// Index used in the day-of-week field of a cron expression (Sunday = 0).
+    fn cron_index(self) -> u8 {
+        match self {
+            DayOfWeek::Sunday => 0,
+            DayOfWeek::Monday => 1,
+            DayOfWeek::Tuesday => 2,
+            DayOfWeek::Wednesday => 3,
+            DayOfWeek::Thursday => 4,
+            DayOfWeek::Friday => 5,
+            DayOfWeek::Saturday => 6,
+        }
+    }
+
+    /// The day following this one, used when a window crosses midnight.
+    fn following_day(self) -> Self {
+        match self {
+            DayOfWeek::Monday => DayOfWeek::Tuesday,
+            DayOfWeek::Tuesday => DayOfWeek::Wednesday,
+            DayOfWeek::Wednesday => DayOfWeek::Thursday,
+            DayOfWeek::Thursday => DayOfWeek::Friday,
+            DayOfWeek::Friday => DayOfWeek::Saturday,
+            DayOfWeek::Saturday => DayOfWeek::Sunday,
+            DayOfWeek::Sunday => DayOfWeek::Monday,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BetweenTimeVariantPayload {
     pub start_hour: i64,
@@ -329,11 +445,17 @@ pub struct BetweenTimeVariantPayload {
     pub end_hour: i64,
     pub end_minute: i64,
     pub end_period: TimePeriod,
+    /// Days on which the window starts. Empty means every day.
+    #[serde(default)]
+    pub days_of_week: Vec<DayOfWeek>,
 }
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct InVariantPayload {
     pub time_value: i64,
     pub time_unit: TimeUnit,
+    /// Absolute time the event fires at, set when the event is scheduled
+    #[serde(default)]
+    pub deadline: Option<DateTime<Local>>,
 }
 
 impl InVariantPayload {
@@ -345,6 +467,27 @@ impl InVariantPayload {
     pub fn to_duration(&self) -> Result<Duration, SchedulerError> {
         SchedulerEventTiming::parse_duration_unit(self.time_value, self.time_unit.clone())
     }
+
+    /// How long to wait until the event fires.
+    ///
+    /// Uses the absolute deadline when known, so the remaining time is correct after
+    /// restarts and clock changes. A deadline in the past results in a zero wait.
+    ///
+    /// ### Parameters
+    /// * `from` - Time to calculate from
+    ///
+    /// ### Returns
+    /// * `Ok(Duration)` - Time to wait (at least 0)
+    /// * `Err(SchedulerError::InvalidTimingFormat)` - If value is out of range
+    pub fn find_wait_time(
+        &self,
+        from: DateTime<Local>,
+    ) -> Result<std::time::Duration, SchedulerError> {
+        match self.deadline {
+            Some(deadline) => Ok((deadline - from).to_std().unwrap_or(ZERO_DURATION)),
+            None => Ok(self.to_duration()?.to_std().unwrap_or_default()),
+        }
+    }
 }
 
 impl BetweenTimeVariantPayload {
@@ -352,22 +495,67 @@ impl BetweenTimeVariantPayload {
     ///
     /// ### Returns
     /// * `Ok(CronSchedule)` - Schedule created successfully
+    /// * `Err(SchedulerError::InvalidTimingFormat)` - If the window starts and ends at the same time
     /// * `Err(SchedulerError::InvalidCronPattern)` - If cron generation fails
     pub fn to_cron_schedule(&self) -> Result<CronSchedule, SchedulerError> {
-        let start_cron = SchedulerEventTiming::parse_cron(
-            self.start_hour,
-            self.start_minute,
-            self.start_period.clone(),
-        )?;
-        let end_cron = SchedulerEventTiming::parse_cron(
-            self.end_hour,
-            self.end_minute,
-            self.end_period.clone(),
-        )?;
+        let start_hour_24 =
+            SchedulerEventTiming::parse_hour_24(self.start_hour, self.start_period.clone())?;
+        let end_hour_24 =
+            SchedulerEventTiming::parse_hour_24(self.end_hour, self.end_period.clone())?;
+
+        // A window ending at its start time would be entered and left at the same moment
+        if (end_hour_24, self.end_minute) == (start_hour_24, self.start_minute) {
+            return Err(SchedulerError::InvalidTimingFormat(
+                "Start and end time of a window must differ".to_string(),
+            ));
+        }
+
+        // A window that ends before its start time finishes on the following day,
+        // e.g. Friday 10 PM - 7 AM has to end on Saturday morning.
+        let crosses_midnight = (end_hour_24, self.end_minute) < (start_hour_24, self.start_minute);
+        let end_days: Vec<DayOfWeek> = if crosses_midnight {
+            self.days_of_week
+                .iter()
+                .map(|day| day.following_day())
+                .collect()
+        } else {
+            self.days_of_week.clone()
+        };
+
+        let start_cron =
+            SchedulerEventTiming::parse_cron(start_hour_24, self.start_minute, &self.days_of_week)?;
+        let end_cron = SchedulerEventTiming::parse_cron(end_hour_24, self.end_minute, &end_days)?;
         CronSchedule::new(&start_cron, &end_cron)
     }
 }
 
+/// Several `Between` windows grouped under a single event,
+/// e.g. "weekdays 10 PM - 7 AM" together with "weekends all day".
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BetweenWindowsVariantPayload {
+    pub windows: Vec<BetweenTimeVariantPayload>,
+}
+
+impl BetweenWindowsVariantPayload {
+    /// Converts every window into a CronSchedule.
+    ///
+    /// ### Returns
+    /// * `Ok(Vec<CronSchedule>)` - One schedule per window
+    /// * `Err(SchedulerError::InvalidTimingFormat)` - If there are no windows
+    /// * `Err(SchedulerError::InvalidCronPattern)` - If cron generation fails for any window
+    pub fn to_cron_schedules(&self) -> Result<Vec<CronSchedule>, SchedulerError> {
+        if self.windows.is_empty() {
+            return Err(SchedulerError::InvalidTimingFormat(
+                "At least one time window is required".to_string(),
+            ));
+        }
+        self.windows
+            .iter()
+            .map(BetweenTimeVariantPayload::to_cron_schedule)
+            .collect()
+    }
+}
+
 /// When a scheduled event should trigger.
 /// Either run once after a delay, or repeatedly during certain time windows.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -378,6 +566,12 @@ pub enum SchedulerEventTiming {
     /// Run during recurring time windows (e.g., Between("0 22 * * *", "0 6 * * *") for 10PM to 6AM daily)
     /// The event keeps repeating according to the schedule.
     Between(BetweenTimeVariantPayload),
+    /// Run during several recurring time windows, each with its own days of the week.
+    /// The event keeps repeating according to all of the schedules.
+    BetweenWindows(BetweenWindowsVariantPayload),
+    /// Run while a machine condition holds (e.g., idle for 30 minutes, GPU above 80°C).
+    /// Entered when the condition starts to hold and exited when it stops.
+    Condition(ConditionVariantPayload),
 }
 
 impl SchedulerEventTiming {
@@ -437,6 +631,7 @@ impl SchedulerEventTiming {
         let payload = InVariantPayload {
             time_value: value,
             time_unit: unit,
+            deadline: None,
         };
         Ok(SchedulerEventTiming::In(payload))
     }
@@ -448,6 +643,7 @@ impl SchedulerEventTiming {
     /// * `start_period` - AM/PM for start time
     /// * `end_hour` - When to end (1-12)
     /// * `end_period` - AM/PM for end time
+    /// * `days_of_week` - Days the window starts on (empty for every day)
     ///
     /// ### Returns
     /// * `Ok(SchedulerEventTiming::Between)` - Timing created
@@ -456,9 +652,10 @@ impl SchedulerEventTiming {
     ///
     /// ### Example
     /// ```
-    /// // Run between 10 PM and 6 AM daily
+    /// // Run between 10 PM and 6 AM on weekdays
     /// let timing = SchedulerEventTiming::parse_between_variant(
-    ///     10, TimePeriod::PM, 6, TimePeriod::AM
+    ///     10, 0, TimePeriod::PM, 6, 0, TimePeriod::AM,
+    ///     vec![DayOfWeek::Monday, DayOfWeek::Tuesday, DayOfWeek::Wednesday, DayOfWeek::Thursday, DayOfWeek::Friday],
     /// )?;
     /// ```
     pub fn parse_between_variant(
@@ -468,6 +665,7 @@ impl SchedulerEventTiming {
         end_hour: i64,
         end_minute: i64,
         end_period: TimePeriod,
+        days_of_week: Vec<DayOfWeek>,
     ) -> Result<Self, SchedulerError> {
         let payload = BetweenTimeVariantPayload {
             start_hour,
@@ -476,23 +674,81 @@ impl SchedulerEventTiming {
             end_hour,
             end_minute,
             end_period,
+            days_of_week,
         };
         // Validate by trying to convert to CronSchedule
         payload.to_cron_schedule()?;
         Ok(SchedulerEventTiming::Between(payload))
     }
 
-    /// Converts 12-hour format time to a cron expression.
-    fn parse_cron(hour: i64, minute: i64, period: TimePeriod) -> Result<String, SchedulerError> {
-        if !(1..=12).contains(&hour) {
-            return Err(SchedulerError::InvalidTimingFormat(
-                "Hour must be between 1 and 12".to_string(),
-            ));
+    /// Create a timing made of several recurring time windows.
+    ///
+    /// ### Parameters
+    /// * `windows` - Time windows, each with its own days of the week
+    ///
+    /// ### Returns
+    /// * `Ok(SchedulerEventTiming::BetweenWindows)` - Timing created
+    /// * `Err(SchedulerError)` - No windows given or one of them is invalid
+    pub fn parse_between_windows_variant(
+        windows: Vec<BetweenTimeVariantPayload>,
+    ) -> Result<Self, SchedulerError> {
+        let payload = BetweenWindowsVariantPayload { windows };
+        // Validate by trying to convert to CronSchedules
+        payload.to_cron_schedules()?;
+        Ok(SchedulerEventTiming::BetweenWindows(payload))
+    }
+
+    /// Create a condition based timing.
+    ///
+    /// ### Parameters
+    /// * `condition` - Machine state that should drive the event
+    ///
+    /// ### Returns
+    /// * `Ok(SchedulerEventTiming::Condition)` - Timing created
+    /// * `Err(SchedulerError::InvalidTimingFormat)` - Threshold out of range
+    ///
+    /// ### Example
+    /// ```
+    /// // Run while the machine has been idle for 30 minutes
+    /// let timing = SchedulerEventTiming::parse_condition_variant(
+    ///     SchedulerCondition::IdleFor { minutes: 30 }
+    /// )?;
+    /// ```
+    pub fn parse_condition_variant(condition: SchedulerCondition) -> Result<Self, SchedulerError> {
+        let payload = ConditionVariantPayload { condition };
+        payload.validate()?;
+        Ok(SchedulerEventTiming::Condition(payload))
+    }
+
+    /// Returns the cron schedules of a recurring timing.
+    ///
+    /// ### Returns
+    /// * `Ok(Vec<CronSchedule>)` - Schedules of Between / BetweenWindows timings, empty otherwise
+    /// * `Err(SchedulerError)` - If any schedule is invalid
+    pub fn to_cron_schedules(&self) -> Result<Vec<CronSchedule>, SchedulerError> {
+        match self {
+            SchedulerEventTiming::In(_) | SchedulerEventTiming::Condition(_) => Ok(vec![]),
+            SchedulerEventTiming::Between(payload) => Ok(vec![payload.to_cron_schedule()?]),
+            SchedulerEventTiming::BetweenWindows(payload) => payload.to_cron_schedules(),
         }
+    }
 
-        if !(0..=59).contains(&minute) {
+    /// Checks whether any recurring window of this timing is active at the given moment.
+    ///
+    /// ### Returns
+    /// * `true` - At least one window is currently open
+    /// * `false` - No window is open, or this is an In timing
+    pub fn is_within_window(&self, at: DateTime<Local>) -> bool {
+        self.to_cron_schedules()
+            .map(|schedules| schedules.iter().any(|schedule| schedule.is_active_at(at)))
+            .unwrap_or(false)
+    }
+
+    /// Converts 12-hour format time to a 24-hour value.
+    fn parse_hour_24(hour: i64, period: TimePeriod) -> Result<i64, SchedulerError> {
+        if !(1..=12).contains(&hour) {
             return Err(SchedulerError::InvalidTimingFormat(
-                "Minute must be between 0 and 59".to_string(),
+                "Hour must be between 1 and 12".to_string(),
             ));
         }
 
@@ -512,17 +768,73 @@ impl SchedulerEventTiming {
                 }
             }
         };
-        // Cron pattern for every day at the specified hour and minute
-        Ok(format!("{} {} * * *", minute, hour_24))
+        Ok(hour_24)
+    }
+
+    /// Converts a 24-hour time and day-of-week mask to a cron expression.
+    fn parse_cron(
+        hour_24: i64,
+        minute: i64,
+        days_of_week: &[DayOfWeek],
+    ) -> Result<String, SchedulerError> {
+        if !(0..=59).contains(&minute) {
+            return Err(SchedulerError::InvalidTimingFormat(
+                "Minute must be between 0 and 59".to_string(),
+            ));
+        }
+
+        if days_of_week.is_empty() {
+            // Cron pattern for every day at the specified hour and minute
+            return Ok(format!("{} {} * * *", minute, hour_24));
+        }
+
+        let mut day_indexes: Vec<u8> = days_of_week.iter().map(|day| day.cron_index()).collect();
+        day_indexes.sort_unstable();
+        day_indexes.dedup();
+        let days = day_indexes
+            .iter()
+            .map(|day| day.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        Ok(format!("{} {} * * {}", minute, hour_24, days))
     }
 
     /// Checks if this timing represents a recurring event.
     ///
     /// ### Returns
-    /// * `true` - Between timing (survives app restarts)
+    /// * `true` - Between / BetweenWindows / Condition timing
     /// * `false` - In timing (gets removed after running)
+    pub fn is_recurring(&self) -> bool {
+        matches!(
+            self,
+            SchedulerEventTiming::Between { .. }
+                | SchedulerEventTiming::BetweenWindows { .. }
+                | SchedulerEventTiming::Condition { .. }
+        )
+    }
+
+    /// Checks if this timing is driven by cron time windows.
+    ///
+    /// ### Returns
+    /// * `true` - Between / BetweenWindows timing
+    /// * `false` - In / Condition timing
+    pub fn is_windowed(&self) -> bool {
+        matches!(
+            self,
+            SchedulerEventTiming::Between { .. } | SchedulerEventTiming::BetweenWindows { .. }
+        )
+    }
+
+    /// Checks if this timing should survive app restarts.
+    ///
+    /// ### Returns
+    /// * `true` - Recurring timings and In timings with a known deadline
+    /// * `false` - In timing without a deadline
     pub fn is_persistent(&self) -> bool {
-        matches!(self, SchedulerEventTiming::Between { .. })
+        match self {
+            SchedulerEventTiming::In(payload) => payload.deadline.is_some(),
+            _ => self.is_recurring(),
+        }
     }
 }
 
@@ -534,6 +846,28 @@ pub struct ScheduledEventInfo {
     pub event_type: SchedulerEventType,
     pub timing: SchedulerEventTiming,
     pub state: SchedulerEventState,
+    /// Higher priority events decide the mining mode when windows overlap
+    #[serde(default)]
+    pub priority: i32,
+    /// Value to restore when an entered Action event exits
+    #[serde(default)]
+    pub restore_action: Option<SchedulerAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchedulerTransitionKind {
+    Enter,
+    Exit,
+}
+
+/// A computed future change of the scheduler state.
+#[derive(Debug, Clone, Serialize)]
+pub struct SchedulerTransition {
+    pub time: DateTime<Local>,
+    pub event_id: String,
+    pub kind: SchedulerTransitionKind,
+    /// Mining mode in effect after the transition, `None` when no Mine event is active
+    pub resulting_mining_mode: Option<String>,
 }
 
 /// Defines the types of actions that can be scheduled.
@@ -550,6 +884,12 @@ pub enum SchedulerEventType {
         /// The specific mining mode configuration to use
         mining_mode: String,
     },
+    /// Change a setting while the event is active.
+    /// Recurring and condition events restore the previous value on exit.
+    Action {
+        /// The configuration change to apply
+        action: SchedulerAction,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -565,7 +905,7 @@ impl SchedulerEventType {
     ///
     /// ### Returns
     /// * `true` - ResumeMining events (only one allowed)
-    /// * `false` - Mine events (can have multiple with different modes)
+    /// * `false` - Mine and Action events (can have multiple)
     pub fn is_unique(&self) -> bool {
         matches!(self, SchedulerEventType::ResumeMining)
     }
@@ -578,6 +918,7 @@ impl Display for SchedulerEventType {
             SchedulerEventType::Mine { mining_mode } => {
                 write!(f, "Mine ({})", mining_mode)
             }
+            SchedulerEventType::Action { action } => write!(f, "Action ({})", action),
         }
     }
 }
@@ -591,6 +932,13 @@ struct ScheduledEvent {
     timing: SchedulerEventTiming,
     state: SchedulerEventState,
     task_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Last evaluated result of a Condition timing, used to detect enter/exit transitions
+    is_condition_met: bool,
+    priority: i32,
+    /// Whether the event was entered and has not exited yet
+    is_entered: bool,
+    /// Value captured before an Action event was applied, restored on exit
+    restore_action: Option<SchedulerAction>,
 }
 
 /// The main scheduler that handles all your scheduled events.
@@ -629,6 +977,7 @@ impl EventScheduler {
     /// * `event_type` - What to do (ResumeMining or Mine)
     /// * `event_id` - Unique name for this event
     /// * `timing` - When to run it (In or Between)
+    /// * `priority` - Decides the mining mode when windows of Mine events overlap
     ///
     /// ### Returns
     /// * `Ok(String)` - Event ID of the scheduled event
@@ -638,7 +987,7 @@ impl EventScheduler {
     /// ```
     /// let timing = SchedulerEventTiming::parse_in_variant(2, TimeUnit::Hours)?;
     /// let event_id = EventScheduler::instance()
-    ///     .schedule_event(SchedulerEventType::ResumeMining, "resume_mining".to_string(), timing)
+    ///     .schedule_event(SchedulerEventType::ResumeMining, "resume_mining".to_string(), timing, 0)
     ///     .await?;
     /// ```
     pub async fn schedule_event(
@@ -646,6 +995,7 @@ impl EventScheduler {
         event_type: SchedulerEventType,
         event_id: String,
         timing: SchedulerEventTiming,
+        priority: i32,
     ) -> Result<String, SchedulerError> {
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
 
@@ -654,6 +1004,7 @@ impl EventScheduler {
                 event_type,
                 timing,
                 event_id,
+                priority,
                 response: response_tx,
             })
             .map_err(|_| SchedulerError::SchedulerNotRunning)?;
@@ -711,10 +1062,77 @@ impl EventScheduler {
             .map_err(|_| SchedulerError::InternalError("Response channel closed".to_string()))?
     }
 
-    /// Saves persistent events (Between timing) to the application configuration.
+    /// Computes the next transitions of all active recurring events.
+    ///
+    /// ### Parameters
+    /// * `count` - Maximum number of transitions to return
+    ///
+    /// ### Returns
+    /// * `Ok(Vec<SchedulerTransition>)` - Upcoming transitions ordered by time
+    /// * `Err(SchedulerError)` - If the scheduler is not running
+    pub async fn get_next_transitions(
+        &self,
+        count: usize,
+    ) -> Result<Vec<SchedulerTransition>, SchedulerError> {
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+
+        self.message_sender
+            .send(SchedulerMessage::GetNextTransitions {
+                count,
+                response: response_tx,
+            })
+            .map_err(|_| SchedulerError::SchedulerNotRunning)?;
+
+        response_rx
+            .await
+            .map_err(|_| SchedulerError::InternalError("Response channel closed".to_string()))
+    }
+
+    /// Returns the execution history, newest first.
+    ///
+    /// ### Parameters
+    /// * `limit` - Maximum number of records to return, all of them when `None`
+    ///
+    /// ### Returns
+    /// * `Ok(Vec<SchedulerExecutionRecord>)` - Recorded enter/exit executions
+    /// * `Err(SchedulerError)` - If the scheduler is not running
+    pub async fn get_history(
+        &self,
+        limit: Option<usize>,
+    ) -> Result<Vec<SchedulerExecutionRecord>, SchedulerError> {
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+
+        self.message_sender
+            .send(SchedulerMessage::GetHistory {
+                limit,
+                response: response_tx,
+            })
+            .map_err(|_| SchedulerError::SchedulerNotRunning)?;
+
+        response_rx
+            .await
+            .map_err(|_| SchedulerError::InternalError("Response channel closed".to_string()))
+    }
+
+    /// Removes all records from the execution history.
+    pub async fn clear_history(&self) -> Result<(), SchedulerError> {
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+
+        self.message_sender
+            .send(SchedulerMessage::ClearHistory {
+                response: response_tx,
+            })
+            .map_err(|_| SchedulerError::SchedulerNotRunning)?;
+
+        response_rx
+            .await
+            .map_err(|_| SchedulerError::InternalError("Response channel closed".to_string()))
+    }
+
+    /// Saves persistent events to the application configuration.
     ///
-    /// This ensures that recurring events survive application restarts by storing
-    /// their configuration in the persistent config system.
+    /// This ensures that recurring events and pending one-time events survive application
+    /// restarts by storing their configuration in the persistent config system.
     ///
     /// ### Parameters
     /// * `scheduled_events` - Map of all current scheduled events
@@ -731,6 +1149,8 @@ impl EventScheduler {
                             event_type: event.event_type.clone(),
                             timing: event.timing.clone(),
                             state: event.state.clone(),
+                            priority: event.priority,
+                            restore_action: event.restore_action.clone(),
                         },
                     )
                 }),
@@ -745,8 +1165,9 @@ impl EventScheduler {
 
     /// Loads persistent events from the application configuration.
     ///
-    /// Called during scheduler startup to restore recurring events that were
-    /// saved before the previous application shutdown.
+    /// Called during scheduler startup to restore recurring and pending one-time events
+    /// that were saved before the previous application shutdown. One-time events whose
+    /// deadline passed while the app was closed fire right away.
     ///
     /// ### Returns
     /// * `Ok(HashMap)` - Map of restored scheduled events
@@ -763,6 +1184,10 @@ impl EventScheduler {
                         timing: info.timing,
                         state: SchedulerEventState::Active,
                         task_handle: None,
+                        is_condition_met: false,
+                        priority: info.priority,
+                        is_entered: false,
+                        restore_action: info.restore_action,
                     },
                 )
             }));
@@ -800,64 +1225,43 @@ impl EventScheduler {
         info!(target: LOG_TARGET_APP_LOGIC, "Starting Event Scheduler Listener");
 
         task_tracker.spawn(async move {
-            let mut internal_events: HashMap<String, ScheduledEvent> = HashMap::new();
-            for (id, event) in persistent_events_from_config {
-                Self::handle_add_event(&mut internal_events, event.event_type.clone(), id.clone(), event.timing.clone()).await.unwrap_or_else(|e| {
-                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to reschedule persistent event {:?}: {}", id, e);
-                    e.to_string()
-                });
-            }
+            let mut internal_events =
+                Self::handle_restore_persistent_events(persistent_events_from_config).await;
 
             let message_receiver = &mut *INSTANCE.message_receiver.write().await;
+            let mut conditions_interval = tokio::time::interval(CONDITIONS_CHECK_INTERVAL);
+            let mut clock_jump_interval = tokio::time::interval(CLOCK_JUMP_CHECK_INTERVAL);
+            let mut last_clock_check = (Instant::now(), Local::now());
+            let mut sleep_mode_watcher = SystemStatus::current().get_sleep_mode_watcher();
+            let mut history = SchedulerHistory::load();
 
             loop {
                 tokio::select! {
                     _ = shutdown_signal.wait() => {
                         info!(target: LOG_TARGET_APP_LOGIC, "Event Scheduler Listener received shutdown signal");
-
-                        Self::save_persistent_events_to_config(&internal_events).await;
-                        for (_, mut event) in internal_events.drain() {
-                            if let Some(handle) = event.task_handle.take() {
-                                handle.abort();
-                            }
-                        }
+                        Self::handle_shutdown(&mut internal_events).await;
                         break;
                     },
-                    message = message_receiver.recv() => {
-                        match message {
-                            Some(SchedulerMessage::AddEvent { event_type, timing, event_id,response }) => {
-                                let result = Self::handle_add_event(&mut internal_events, event_type, event_id, timing).await;
-                                let _unused = response.send(result);
-                            },
-                            Some(SchedulerMessage::RemoveEvent { event_id, response }) => {
-                                let result = Self::handle_remove_event(&mut internal_events, event_id);
-                                let _unused = response.send(result);
-                            },
-                            Some(SchedulerMessage::PauseEvent { event_id, response }) => {
-                                let result = Self::handle_pause_event(&mut internal_events, event_id);
-                                let _unused = response.send(result);
-                            },
-                            Some(SchedulerMessage::ResumeEvent { event_id, response }) => {
-                                let result = Self::handle_resume_event(&mut internal_events, event_id).await;
-                                let _unused = response.send(result);
-                            },
-                            Some(SchedulerMessage::TriggerEnterCallback { event_id }) => {
-                                Self::handle_enter_callback(&internal_events, event_id).await;
-
-                            },
-                            Some(SchedulerMessage::TriggerExitCallback { event_id }) => {
-                                Self::handle_exit_callback(&internal_events, event_id).await;
-
-                            }
-                            Some(SchedulerMessage::CleanupSchedule { event_id }) => {
-                                Self::handle_cleanup_schedule_events(&mut internal_events, event_id).await;
-                            }
-
-                            None => {
-                                warn!(target: LOG_TARGET_APP_LOGIC, "Message channel closed, stopping scheduler");
-                                break;
-                            }
+                    _ = conditions_interval.tick() => {
+                        Self::handle_conditions_check(&mut internal_events).await;
+                    },
+                    _ = clock_jump_interval.tick() => {
+                        Self::handle_clock_check(&mut internal_events, &mut last_clock_check).await;
+                    },
+                    Ok(_) = sleep_mode_watcher.changed() => {
+                        let is_sleeping = *sleep_mode_watcher.borrow();
+                        if !is_sleeping {
+                            info!(target: LOG_TARGET_APP_LOGIC, "System resumed, re-evaluating scheduled events");
+                            Self::handle_clock_jump(&mut internal_events).await;
+                            last_clock_check = (Instant::now(), Local::now());
                         }
+                    },
+                    message = message_receiver.recv() => {
+                        let Some(message) = message else {
+                            warn!(target: LOG_TARGET_APP_LOGIC, "Message channel closed, stopping scheduler");
+                            break;
+                        };
+                        Self::handle_message(&mut internal_events, &mut history, message).await;
                     }
                 }
             }
@@ -868,6 +1272,222 @@ impl EventScheduler {
         Ok(())
     }
 
+    /// Schedules the events loaded from the config when the listener starts.
+    ///
+    /// ### Parameters
+    /// * `persistent_events` - Events loaded from the config
+    ///
+    /// ### Returns
+    /// * `HashMap<String, ScheduledEvent>` - The scheduled events
+    async fn handle_restore_persistent_events(
+        persistent_events: HashMap<String, ScheduledEvent>,
+    ) -> HashMap<String, ScheduledEvent> {
+        let mut events: HashMap<String, ScheduledEvent> = HashMap::new();
+        for (id, event) in persistent_events {
+            Self::handle_add_event(&mut events, event.event_type.clone(), id.clone(), event.timing.clone(), event.priority).await.unwrap_or_else(|e| {
+                error!(target: LOG_TARGET_APP_LOGIC, "Failed to reschedule persistent event {:?}: {}", id, e);
+                e.to_string()
+            });
+            Self::restore_pending_action(&mut events, &id, event.restore_action);
+            Self::enter_if_window_open(&events, &id);
+        }
+        Self::save_persistent_events_to_config(&events).await;
+        events
+    }
+
+    /// Saves the events and stops their tasks when the app shuts down.
+    ///
+    /// ### Parameters
+    /// * `events` - Mutable reference to the events map
+    async fn handle_shutdown(events: &mut HashMap<String, ScheduledEvent>) {
+        Self::save_persistent_events_to_config(events).await;
+        for (_, mut event) in events.drain() {
+            if let Some(handle) = event.task_handle.take() {
+                handle.abort();
+            }
+        }
+    }
+
+    /// Compares the monotonic and the wall clock time since the last check and re-evaluates
+    /// the events when the wall clock jumped.
+    ///
+    /// ### Parameters
+    /// * `events` - Mutable reference to the events map
+    /// * `last_clock_check` - Monotonic and wall clock time of the last check, updated by the call
+    async fn handle_clock_check(
+        events: &mut HashMap<String, ScheduledEvent>,
+        last_clock_check: &mut (Instant, DateTime<Local>),
+    ) {
+        let (last_instant, last_local_time) = *last_clock_check;
+        let monotonic_elapsed =
+            Duration::from_std(last_instant.elapsed()).unwrap_or(Duration::zero());
+        let wall_clock_elapsed = Local::now() - last_local_time;
+        let drift = (wall_clock_elapsed - monotonic_elapsed).abs();
+        if drift.to_std().unwrap_or_default() > CLOCK_JUMP_THRESHOLD {
+            warn!(target: LOG_TARGET_APP_LOGIC, "Detected wall-clock jump of {} seconds", drift.num_seconds());
+            Self::handle_clock_jump(events).await;
+        }
+        *last_clock_check = (Instant::now(), Local::now());
+    }
+
+    /// Dispatches a message of the public API or of an event task to its handler.
+    ///
+    /// ### Parameters
+    /// * `events` - Mutable reference to the events map
+    /// * `history` - Execution history the enter and exit outcomes are recorded in
+    /// * `message` - The received message
+    async fn handle_message(
+        events: &mut HashMap<String, ScheduledEvent>,
+        history: &mut SchedulerHistory,
+        message: SchedulerMessage,
+    ) {
+        match message {
+            SchedulerMessage::AddEvent {
+                event_type,
+                timing,
+                event_id,
+                priority,
+                response,
+            } => {
+                let result =
+                    Self::handle_add_event(events, event_type, event_id, timing, priority).await;
+                if let Ok(event_id) = &result {
+                    Self::enter_if_window_open(events, event_id);
+                }
+                let _unused = response.send(result);
+            }
+            SchedulerMessage::RemoveEvent { event_id, response } => {
+                let result = Self::handle_remove_event(events, event_id).await;
+                Self::save_persistent_events_to_config(events).await;
+                let _unused = response.send(result);
+            }
+            SchedulerMessage::PauseEvent { event_id, response } => {
+                let result = Self::handle_pause_event(events, event_id).await;
+                Self::save_persistent_events_to_config(events).await;
+                let _unused = response.send(result);
+            }
+            SchedulerMessage::ResumeEvent { event_id, response } => {
+                let result = Self::handle_resume_event(events, event_id.clone()).await;
+                if result.is_ok() {
+                    Self::enter_if_window_open(events, &event_id);
+                }
+                let _unused = response.send(result);
+            }
+            SchedulerMessage::TriggerEnterCallback {
+                event_id,
+                planned_time,
+            } => {
+                let event_info = Self::get_event_info(events, &event_id);
+                let outcome = Self::handle_enter_callback(events, event_id).await;
+                Self::record_execution(
+                    history,
+                    event_info,
+                    SchedulerTransitionKind::Enter,
+                    planned_time,
+                    outcome,
+                )
+                .await;
+            }
+            SchedulerMessage::TriggerExitCallback {
+                event_id,
+                planned_time,
+            } => {
+                let event_info = Self::get_event_info(events, &event_id);
+                let outcome = Self::handle_exit_callback(events, event_id).await;
+                Self::record_execution(
+                    history,
+                    event_info,
+                    SchedulerTransitionKind::Exit,
+                    planned_time,
+                    outcome,
+                )
+                .await;
+            }
+            SchedulerMessage::CleanupSchedule { event_id } => {
+                Self::handle_cleanup_schedule_events(events, event_id).await;
+            }
+            SchedulerMessage::GetNextTransitions { count, response } => {
+                let result = Self::handle_get_next_transitions(events, count);
+                let _unused = response.send(result);
+            }
+            SchedulerMessage::GetHistory { limit, response } => {
+                let _unused = response.send(history.records(limit));
+            }
+            SchedulerMessage::ClearHistory { response } => {
+                history.clear().await;
+                let _unused = response.send(());
+            }
+        }
+    }
+
+    /// Enters an event whose window is already open when it is registered, loaded or resumed.
+    ///
+    /// The event's task only reacts to the next boundary of the window, so without this a
+    /// window open at that moment would only be entered when it starts the next time.
+    ///
+    /// ### Parameters
+    /// * `events` - Reference to the events map
+    /// * `event_id` - ID of the event to check
+    fn enter_if_window_open(events: &HashMap<String, ScheduledEvent>, event_id: &str) {
+        let Some(event) = events.get(event_id) else {
+            return;
+        };
+        if !Self::is_in_unentered_window(event, Local::now()) {
+            return;
+        }
+        info!(target: LOG_TARGET_APP_LOGIC, "Event {:?} is inside its window, entering", event_id);
+        let _unused = INSTANCE
+            .message_sender
+            .send(SchedulerMessage::TriggerEnterCallback {
+                event_id: event_id.to_string(),
+                planned_time: None,
+            });
+    }
+
+    /// Checks whether an active windowed event is inside its window without having entered it.
+    fn is_in_unentered_window(event: &ScheduledEvent, at: DateTime<Local>) -> bool {
+        event.state == SchedulerEventState::Active
+            && !event.is_entered
+            && event.timing.is_windowed()
+            && event.timing.is_within_window(at)
+    }
+
+    /// Brings back the restore value of an Action event that was applied before a restart.
+    ///
+    /// The action is still in effect, so the event counts as entered. If its window was
+    /// left while the app was closed the exit is triggered right away, condition events
+    /// exit on the next check when their condition no longer holds.
+    ///
+    /// ### Parameters
+    /// * `events` - Mutable reference to the events map
+    /// * `event_id` - ID of the restored event
+    /// * `restore_action` - Restore value loaded from the config
+    fn restore_pending_action(
+        events: &mut HashMap<String, ScheduledEvent>,
+        event_id: &str,
+        restore_action: Option<SchedulerAction>,
+    ) {
+        let Some(event) = events.get_mut(event_id) else {
+            return;
+        };
+        if restore_action.is_none() {
+            return;
+        }
+
+        event.restore_action = restore_action;
+        event.is_entered = true;
+        event.is_condition_met = true;
+        if event.timing.is_windowed() && !event.timing.is_within_window(Local::now()) {
+            info!(target: LOG_TARGET_APP_LOGIC, "Window of event {:?} ended while the app was closed, restoring previous value", event_id);
+            let _unused = INSTANCE
+                .message_sender
+                .send(SchedulerMessage::TriggerExitCallback {
+                    event_id: event_id.to_string(),
+                    planned_time: None,
+                });
+        }
+    }
+
     /// Internal handler for adding new events to the scheduler.
     ///
     /// Processes AddEvent messages from the public API, manages unique event types,
@@ -878,6 +1498,7 @@ impl EventScheduler {
     /// * `event_type` - Type of event to add
     /// * `event_id` - Unique identifier for the event
     /// * `timing` - When the event should trigger
+    /// * `priority` - Priority used to resolve overlapping Mine events
     ///
     /// ### Returns
     /// * `Ok(String)` - The event ID of the added event
@@ -887,6 +1508,7 @@ impl EventScheduler {
         event_type: SchedulerEventType,
         event_id: String,
         timing: SchedulerEventTiming,
+        priority: i32,
     ) -> Result<String, SchedulerError> {
         if event_type.is_unique() {
             info!(target: LOG_TARGET_APP_LOGIC, "Ensuring uniqueness for event type {:?}", event_type);
@@ -897,23 +1519,37 @@ impl EventScheduler {
                 .collect();
 
             for id in to_remove {
-                if let Err(e) = Self::handle_remove_event(events, id.clone()) {
+                if let Err(e) = Self::handle_remove_event(events, id.clone()).await {
                     warn!(target: LOG_TARGET_APP_LOGIC, "Failed to remove duplicate event {:?}: {}", id, e);
                 }
             }
         }
 
+        // Pin one-time events to an absolute deadline so they can be restored after a restart
+        let timing = match timing {
+            SchedulerEventTiming::In(mut payload) => {
+                if payload.deadline.is_none() {
+                    payload.deadline = Some(Local::now() + payload.to_duration()?);
+                }
+                SchedulerEventTiming::In(payload)
+            }
+            other => other,
+        };
+
         let mut scheduled_event = ScheduledEvent {
             id: event_id.clone(),
             event_type: event_type.clone(),
             timing: timing.clone(),
             state: SchedulerEventState::Active,
             task_handle: None,
+            is_condition_met: false,
+            priority,
+            is_entered: false,
+            restore_action: None,
         };
 
-        let task_handle =
+        scheduled_event.task_handle =
             Self::create_scheduling_task(event_id.clone(), event_type, timing).await?;
-        scheduled_event.task_handle = Some(task_handle);
         events.insert(event_id.clone(), scheduled_event);
         Self::save_persistent_events_to_config(events).await;
 
@@ -923,7 +1559,8 @@ impl EventScheduler {
     /// Internal handler for removing events from the scheduler.
     ///
     /// Processes RemoveEvent messages, cancels the event's execution task,
-    /// and removes it from the events map.
+    /// and removes it from the events map. An event removed inside its window
+    /// is exited first.
     ///
     /// ### Parameters
     /// * `events` - Mutable reference to the events map
@@ -932,11 +1569,12 @@ impl EventScheduler {
     /// ### Returns
     /// * `Ok(())` - Event successfully removed
     /// * `Err(SchedulerError::EventNotFound)` - No event with the given ID
-    fn handle_remove_event(
+    async fn handle_remove_event(
         events: &mut HashMap<String, ScheduledEvent>,
         event_id: String,
     ) -> Result<(), SchedulerError> {
         info!(target: LOG_TARGET_APP_LOGIC, "Removing event with ID {:?}", event_id);
+        Self::exit_entered_event(events, &event_id).await;
         if let Some(mut event) = events.remove(&event_id) {
             if let Some(handle) = event.task_handle.take() {
                 handle.abort();
@@ -952,6 +1590,7 @@ impl EventScheduler {
     ///
     /// Processes PauseEvent messages, changes the event state to Paused,
     /// and cancels the event's execution task while keeping the event data.
+    /// An event paused inside its window is exited first.
     ///
     /// ### Parameters
     /// * `events` - Mutable reference to the events map
@@ -960,15 +1599,19 @@ impl EventScheduler {
     /// ### Returns
     /// * `Ok(())` - Event successfully paused
     /// * `Err(SchedulerError)` - If pausing fails
-    fn handle_pause_event(
+    async fn handle_pause_event(
         events: &mut HashMap<String, ScheduledEvent>,
         event_id: String,
     ) -> Result<(), SchedulerError> {
+        if events
+            .get(&event_id)
+            .is_some_and(|event| event.state == SchedulerEventState::Paused)
+        {
+            return Err(SchedulerError::EventAlreadyPaused(event_id));
+        }
+
+        Self::exit_entered_event(events, &event_id).await;
         if let Some(event) = events.get_mut(&event_id) {
-            if event.state == SchedulerEventState::Paused {
-                return Err(SchedulerError::EventAlreadyPaused(event_id));
-            }
-
             event.state = SchedulerEventState::Paused;
             if let Some(handle) = event.task_handle.take() {
                 handle.abort();
@@ -981,6 +1624,40 @@ impl EventScheduler {
         }
     }
 
+    /// Undoes an entered event that is removed or paused inside its window.
+    ///
+    /// Aborting the event's task skips the end of its window, so the exit transition is run
+    /// here instead: an Action's previous value is restored and the mining mode falls back
+    /// to the next Mine event by priority.
+    ///
+    /// ### Parameters
+    /// * `events` - Mutable reference to the events map
+    /// * `event_id` - ID of the event that stops
+    ///
+    /// ### Returns
+    /// * `Some(SchedulerExecutionOutcome)` - Outcome of the exit transition
+    /// * `None` - The event was not entered, nothing to undo
+    async fn exit_entered_event(
+        events: &mut HashMap<String, ScheduledEvent>,
+        event_id: &str,
+    ) -> Option<SchedulerExecutionOutcome> {
+        let is_entered = events.get(event_id).is_some_and(|event| {
+            event.state == SchedulerEventState::Active
+                && event.is_entered
+                && event.timing.is_recurring()
+        });
+        if !is_entered {
+            return None;
+        }
+
+        info!(target: LOG_TARGET_APP_LOGIC, "Exiting event {:?} as it stops inside its window", event_id);
+        let outcome = Self::exit_event(events, event_id.to_string()).await;
+        if let SchedulerExecutionOutcome::Failed { errors } = &outcome {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to exit event {:?}: {}", event_id, errors.join(", "));
+        }
+        Some(outcome)
+    }
+
     /// Internal handler for resuming paused events.
     ///
     /// Processes ResumeEvent messages, changes the event state back to Active,
@@ -1003,16 +1680,16 @@ impl EventScheduler {
             }
 
             event.state = SchedulerEventState::Active;
+            // Condition is re-evaluated from scratch so a still holding condition enters again
+            event.is_condition_met = false;
 
-            let task_handle = Self::create_scheduling_task(
+            event.task_handle = Self::create_scheduling_task(
                 event_id.clone(),
                 event.event_type.clone(),
                 event.timing.clone(),
             )
             .await?;
 
-            event.task_handle = Some(task_handle);
-
             info!(target: LOG_TARGET_APP_LOGIC, "Resumed event with ID {:?}", event_id);
             Ok(())
         } else {
@@ -1020,83 +1697,563 @@ impl EventScheduler {
         }
     }
 
+    /// Finds the Mine event that currently decides the mining mode.
+    ///
+    /// Only active, entered recurring or condition events are considered. The highest priority
+    /// wins and ties are broken by the lexicographically smallest event ID.
+    ///
+    /// ### Parameters
+    /// * `events` - Reference to the events map
+    ///
+    /// ### Returns
+    /// * `Some((event_id, mining_mode))` - The deciding event and its mining mode
+    /// * `None` - No Mine event is currently active
+    fn resolve_active_mining_event(
+        events: &HashMap<String, ScheduledEvent>,
+    ) -> Option<(String, String)> {
+        events
+            .values()
+            .filter(|event| {
+                event.state == SchedulerEventState::Active
+                    && event.is_entered
+                    && event.timing.is_recurring()
+            })
+            .filter_map(|event| match &event.event_type {
+                SchedulerEventType::Mine { mining_mode } => {
+                    Some((event.priority, event.id.clone(), mining_mode.clone()))
+                }
+                SchedulerEventType::ResumeMining | SchedulerEventType::Action { .. } => None,
+            })
+            .max_by_key(|(priority, id, _)| (*priority, Reverse(id.clone())))
+            .map(|(_, id, mining_mode)| (id, mining_mode))
+    }
+
+    /// Finds the Action event that currently decides the value of the action's setting.
+    ///
+    /// Same rule as for Mine events: among active, entered recurring or condition events
+    /// changing the same setting the highest priority wins, ties go to the smallest event ID.
+    ///
+    /// ### Parameters
+    /// * `events` - Reference to the events map
+    /// * `action` - Action whose setting is resolved, its value is ignored
+    ///
+    /// ### Returns
+    /// * `Some((event_id, action))` - The deciding event and its action
+    /// * `None` - No event changing this setting is currently active
+    fn resolve_active_action_event(
+        events: &HashMap<String, ScheduledEvent>,
+        action: &SchedulerAction,
+    ) -> Option<(String, SchedulerAction)> {
+        events
+            .values()
+            .filter(|event| {
+                event.state == SchedulerEventState::Active
+                    && event.is_entered
+                    && event.timing.is_recurring()
+            })
+            .filter_map(|event| match &event.event_type {
+                SchedulerEventType::Action {
+                    action: event_action,
+                } if event_action.is_same_kind(action) => {
+                    Some((event.priority, event.id.clone(), event_action.clone()))
+                }
+                _ => None,
+            })
+            .max_by_key(|(priority, id, _)| (*priority, Reverse(id.clone())))
+            .map(|(_, id, event_action)| (id, event_action))
+    }
+
+    /// Captures the type and timing of an event before it is executed.
+    ///
+    /// One-time events are removed right after their execution, so this is read
+    /// up front to be able to record them in the history.
+    fn get_event_info(
+        events: &HashMap<String, ScheduledEvent>,
+        event_id: &str,
+    ) -> Option<(String, SchedulerEventType, SchedulerEventTiming)> {
+        events.get(event_id).map(|event| {
+            (
+                event.id.clone(),
+                event.event_type.clone(),
+                event.timing.clone(),
+            )
+        })
+    }
+
+    /// Adds an executed enter/exit callback to the history.
+    ///
+    /// ### Parameters
+    /// * `history` - History owned by the message loop
+    /// * `event_info` - ID, type and timing of the executed event, nothing is recorded when missing
+    /// * `kind` - Whether the event was entered or exited
+    /// * `planned_time` - Time the execution was scheduled for
+    /// * `outcome` - Result of the execution
+    async fn record_execution(
+        history: &mut SchedulerHistory,
+        event_info: Option<(String, SchedulerEventType, SchedulerEventTiming)>,
+        kind: SchedulerTransitionKind,
+        planned_time: Option<DateTime<Local>>,
+        outcome: SchedulerExecutionOutcome,
+    ) {
+        let Some((event_id, event_type, timing)) = event_info else {
+            return;
+        };
+
+        history
+            .push(SchedulerExecutionRecord {
+                event_id,
+                event_type,
+                timing,
+                kind,
+                planned_time,
+                actual_time: Local::now(),
+                outcome,
+            })
+            .await;
+    }
+
+    /// Logs a failed step of an execution and keeps its error for the history.
+    ///
+    /// ### Parameters
+    /// * `errors` - Errors collected during the current execution
+    /// * `event_id` - ID of the executed event, used for logging
+    /// * `context` - Description of the failed step
+    /// * `result` - Result of the step
+    fn collect_error(
+        errors: &mut Vec<String>,
+        event_id: &str,
+        context: &str,
+        result: Result<(), anyhow::Error>,
+    ) {
+        if let Err(e) = result {
+            error!(target: LOG_TARGET_APP_LOGIC, "{} during event {:?}: {}", context, event_id, e);
+            errors.push(format!("{}: {}", context, e));
+        }
+    }
+
+    /// Applies a scheduled action.
+    ///
+    /// ### Parameters
+    /// * `event_id` - ID of the event applying the action, used for logging
+    /// * `action` - Action to apply
+    ///
+    /// ### Returns
+    /// * `Vec<String>` - Errors of the failed steps, empty on success
+    async fn apply_action(event_id: &str, action: &SchedulerAction) -> Vec<String> {
+        let mut errors = Vec::new();
+        Self::collect_error(
+            &mut errors,
+            event_id,
+            &format!("Failed to execute action {}", action),
+            action.execute().await,
+        );
+        errors
+    }
+
+    /// Switches to the given mining mode and makes sure mining is running.
+    ///
+    /// A running GPU miner is restarted when the mode changes, the CPU miner is retuned
+    /// in place, so the new mode is applied right away.
+    ///
+    /// ### Parameters
+    /// * `event_id` - ID of the event applying the mode, used for logging
+    /// * `mining_mode` - Name of the mining mode to apply
+    ///
+    /// ### Returns
+    /// * `Vec<String>` - Errors of the failed steps, empty on success
+    async fn apply_mining_mode(event_id: &str, mining_mode: String) -> Vec<String> {
+        let mut errors = Vec::new();
+        info!(target: LOG_TARGET_APP_LOGIC, "Switching to mining mode {:?} for event {:?}", mining_mode, event_id);
+        Self::collect_error(
+            &mut errors,
+            event_id,
+            "Failed to apply mining mode",
+            select_mining_mode(mining_mode).await,
+        );
+
+        errors.extend(Self::start_mining(event_id).await);
+        errors
+    }
+
+    /// Starts GPU and CPU mining.
+    ///
+    /// ### Parameters
+    /// * `event_id` - ID of the event starting mining, used for logging
+    ///
+    /// ### Returns
+    /// * `Vec<String>` - Errors of the failed steps, empty on success
+    async fn start_mining(event_id: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        Self::collect_error(
+            &mut errors,
+            event_id,
+            "Failed to start GPU mining",
+            GpuManager::write().await.start_mining().await,
+        );
+        Self::collect_error(
+            &mut errors,
+            event_id,
+            "Failed to start CPU mining",
+            CpuManager::write().await.start_mining().await,
+        );
+        errors
+    }
+
+    /// Stops GPU and CPU mining.
+    ///
+    /// ### Parameters
+    /// * `event_id` - ID of the event stopping mining, used for logging
+    ///
+    /// ### Returns
+    /// * `Vec<String>` - Errors of the failed steps, empty on success
+    async fn stop_mining(event_id: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        Self::collect_error(
+            &mut errors,
+            event_id,
+            "Failed to stop GPU mining",
+            GpuManager::write().await.stop_mining().await,
+        );
+        Self::collect_error(
+            &mut errors,
+            event_id,
+            "Failed to stop CPU mining",
+            CpuManager::write().await.stop_mining().await,
+        );
+        errors
+    }
+
     /// Internal handler for event enter callbacks.
     ///
     /// Executes the actions associated with an event when it triggers.
-    /// This starts mining operations based on the event type.
+    /// This starts mining operations based on the event type. Recurring Mine events
+    /// only apply their mode when they win the priority resolution.
     ///
     /// ### Parameters
-    /// * `events` - Reference to the events map
+    /// * `events` - Mutable reference to the events map
     /// * `event_id` - ID of the event that triggered
     ///
     /// ### Returns
-    /// * `Ok(())` - Callback executed successfully
-    /// * `Err(SchedulerError)` - If execution fails
+    /// * `SchedulerExecutionOutcome` - What the execution did, recorded in the history
     async fn handle_enter_callback(
-        events: &HashMap<String, ScheduledEvent>,
+        events: &mut HashMap<String, ScheduledEvent>,
         event_id: String,
-    ) -> Result<(), SchedulerError> {
-        if let Some(event) = events.get(&event_id) {
-            if event.state == SchedulerEventState::Active {
-                match event.event_type.clone() {
-                    SchedulerEventType::ResumeMining => {
-                        GpuManager::write().await.start_mining().await.unwrap_or_else(|e| {
-                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to start GPU mining during PauseMining event {:?}: {}", event_id, e);
-                });
-                        CpuManager::write().await.start_mining().await.unwrap_or_else(|e| {
-                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to start CPU mining during PauseMining event {:?}: {}", event_id, e);
-                });
+    ) -> SchedulerExecutionOutcome {
+        let (event_type, is_recurring) = match events.get_mut(&event_id) {
+            Some(event) if event.state == SchedulerEventState::Active => {
+                event.is_entered = true;
+                (event.event_type.clone(), event.timing.is_recurring())
+            }
+            _ => {
+                return SchedulerExecutionOutcome::Skipped {
+                    reason: "Event is not active".to_string(),
+                }
+            }
+        };
+
+        let errors = match event_type {
+            SchedulerEventType::ResumeMining => Self::start_mining(&event_id).await,
+            SchedulerEventType::Mine { mining_mode } => {
+                if !is_recurring {
+                    return SchedulerExecutionOutcome::from_errors(
+                        Self::apply_mining_mode(&event_id, mining_mode).await,
+                    );
+                }
+                match Self::resolve_active_mining_event(events) {
+                    Some((deciding_event_id, deciding_mining_mode))
+                        if deciding_event_id == event_id =>
+                    {
+                        Self::apply_mining_mode(&event_id, deciding_mining_mode).await
                     }
-                    SchedulerEventType::Mine { mining_mode } => {
-                        ConfigMining::update_field(ConfigMiningContent::set_selected_mining_mode, mining_mode.clone()).await.unwrap_or_else(|e| {
-                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to set mining mode during Mine event {:?}: {}", event_id, e);
-                });
-                        // TODO: Replace with emiting specific value only
-                        EventsEmitter::emit_mining_config_loaded(&ConfigMining::content().await)
-                            .await;
-                        GpuManager::write().await.start_mining().await.unwrap_or_else(|e| {
-                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to start GPU mining during Mine event {:?}: {}", event_id, e);
-                });
-                        CpuManager::write().await.start_mining().await.unwrap_or_else(|e| {
-                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to start CPU mining during Mine event {:?}: {}", event_id, e);
-                });
+                    Some((deciding_event_id, _)) => {
+                        info!(target: LOG_TARGET_APP_LOGIC, "Event {:?} entered but {:?} has higher priority, keeping its mining mode", event_id, deciding_event_id);
+                        return SchedulerExecutionOutcome::Skipped {
+                            reason: format!("Event {} has higher priority", deciding_event_id),
+                        };
                     }
+                    None => Vec::new(),
                 }
             }
-        }
-        Ok(())
+            SchedulerEventType::Action { action } => {
+                if !is_recurring {
+                    return SchedulerExecutionOutcome::from_errors(
+                        Self::apply_action(&event_id, &action).await,
+                    );
+                }
+
+                // Events changing the same setting share the value from before the first of them entered
+                let restore_action = match events
+                    .values()
+                    .filter(|event| event.id != event_id && event.is_entered)
+                    .find_map(|event| match (&event.event_type, &event.restore_action) {
+                        (SchedulerEventType::Action { action: other }, Some(restore_action))
+                            if other.is_same_kind(&action) =>
+                        {
+                            Some(restore_action.clone())
+                        }
+                        _ => None,
+                    }) {
+                    Some(restore_action) => restore_action,
+                    None => match events
+                        .get(&event_id)
+                        .and_then(|event| event.restore_action.clone())
+                    {
+                        Some(restore_action) => restore_action,
+                        None => action.capture_current_value().await,
+                    },
+                };
+                if let Some(event) = events.get_mut(&event_id) {
+                    event.restore_action = Some(restore_action);
+                }
+                Self::save_persistent_events_to_config(events).await;
+
+                match Self::resolve_active_action_event(events, &action) {
+                    Some((deciding_event_id, deciding_action)) if deciding_event_id == event_id => {
+                        Self::apply_action(&event_id, &deciding_action).await
+                    }
+                    Some((deciding_event_id, _)) => {
+                        info!(target: LOG_TARGET_APP_LOGIC, "Event {:?} entered but {:?} has higher priority, keeping its action", event_id, deciding_event_id);
+                        return SchedulerExecutionOutcome::Skipped {
+                            reason: format!("Event {} has higher priority", deciding_event_id),
+                        };
+                    }
+                    None => Vec::new(),
+                }
+            }
+        };
+        SchedulerExecutionOutcome::from_errors(errors)
     }
 
     /// Internal handler for event exit callbacks.
     ///
     /// Executes cleanup actions when a time window ends (for Between events).
-    /// This typically stops mining operations.
+    /// For Mine events this falls back to the next active Mine event,
+    /// or stops mining when none is left.
     ///
     /// ### Parameters
-    /// * `events` - Reference to the events map
+    /// * `events` - Mutable reference to the events map
     /// * `event_id` - ID of the event that is ending
     ///
     /// ### Returns
-    /// * `Ok(())` - Callback executed successfully
-    /// * `Err(SchedulerError)` - If execution fails
+    /// * `SchedulerExecutionOutcome` - What the execution did, recorded in the history
     async fn handle_exit_callback(
-        events: &HashMap<String, ScheduledEvent>,
+        events: &mut HashMap<String, ScheduledEvent>,
         event_id: String,
-    ) -> Result<(), SchedulerError> {
-        if let Some(event) = events.get(&event_id) {
-            match event.event_type.clone() {
-                SchedulerEventType::ResumeMining => {}
-                SchedulerEventType::Mine { mining_mode } => {
-                    GpuManager::write().await.stop_mining().await.unwrap_or_else(|e| {
-                        error!(target: LOG_TARGET_APP_LOGIC, "Failed to stop mining during cleanup of event {:?}: {}", event_id, e);
-                    });
-                    CpuManager::write().await.stop_mining().await.unwrap_or_else(|e| {
-                        error!(target: LOG_TARGET_APP_LOGIC, "Failed to stop mining during cleanup of event {:?}: {}", event_id, e);
-                    });
+    ) -> SchedulerExecutionOutcome {
+        let Some(event) = events.get_mut(&event_id) else {
+            return SchedulerExecutionOutcome::Skipped {
+                reason: "Event not found".to_string(),
+            };
+        };
+
+        // Another window of the same event may still be open, e.g. a weekday night window
+        // ending on Saturday morning while the weekend window is already running.
+        if event.timing.is_within_window(Local::now()) {
+            info!(target: LOG_TARGET_APP_LOGIC, "Skipping exit of event {:?} as another of its windows is still active", event_id);
+            return SchedulerExecutionOutcome::Skipped {
+                reason: "Another window of the event is still active".to_string(),
+            };
+        }
+
+        Self::exit_event(events, event_id).await
+    }
+
+    /// Runs the exit transition of an event, regardless of its windows.
+    ///
+    /// Restores the previous value of an Action event or falls back to the next Mine event
+    /// by priority, stopping mining when none is left.
+    ///
+    /// ### Parameters
+    /// * `events` - Mutable reference to the events map
+    /// * `event_id` - ID of the event that is exiting
+    ///
+    /// ### Returns
+    /// * `SchedulerExecutionOutcome` - What the execution did, recorded in the history
+    async fn exit_event(
+        events: &mut HashMap<String, ScheduledEvent>,
+        event_id: String,
+    ) -> SchedulerExecutionOutcome {
+        let Some(event) = events.get_mut(&event_id) else {
+            return SchedulerExecutionOutcome::Skipped {
+                reason: "Event not found".to_string(),
+            };
+        };
+
+        // The exiting event counts as active even if it was never entered (e.g. the app was
+        // started inside its window), so a lone event still stops mining when its window ends.
+        event.is_entered = true;
+        let event_type = event.event_type.clone();
+        let was_deciding = match &event_type {
+            SchedulerEventType::Action { action } => {
+                Self::resolve_active_action_event(events, action)
+                    .is_some_and(|(deciding_event_id, _)| deciding_event_id == event_id)
+            }
+            _ => Self::resolve_active_mining_event(events)
+                .is_some_and(|(deciding_event_id, _)| deciding_event_id == event_id),
+        };
+        let mut restore_action = None;
+        if let Some(event) = events.get_mut(&event_id) {
+            event.is_entered = false;
+            restore_action = event.restore_action.take();
+        }
+
+        let errors = match event_type {
+            SchedulerEventType::ResumeMining => Vec::new(),
+            SchedulerEventType::Action { action } => {
+                Self::save_persistent_events_to_config(events).await;
+                match (
+                    Self::resolve_active_action_event(events, &action),
+                    restore_action,
+                ) {
+                    (Some((fallback_event_id, fallback_action)), _) if was_deciding => {
+                        info!(target: LOG_TARGET_APP_LOGIC, "Event {:?} exited, falling back to event {:?}", event_id, fallback_event_id);
+                        Self::apply_action(&fallback_event_id, &fallback_action).await
+                    }
+                    (Some((deciding_event_id, _)), _) => {
+                        return SchedulerExecutionOutcome::Skipped {
+                            reason: format!("Event {} has higher priority", deciding_event_id),
+                        };
+                    }
+                    (None, Some(restore_action)) => {
+                        info!(target: LOG_TARGET_APP_LOGIC, "Event {:?} exited, restoring {}", event_id, restore_action);
+                        Self::apply_action(&event_id, &restore_action).await
+                    }
+                    (None, None) => {
+                        return SchedulerExecutionOutcome::Skipped {
+                            reason: "No previous value to restore".to_string(),
+                        };
+                    }
+                }
+            }
+            SchedulerEventType::Mine { .. } => {
+                if !was_deciding {
+                    info!(target: LOG_TARGET_APP_LOGIC, "Event {:?} exited without deciding the mining mode, nothing to change", event_id);
+                    return SchedulerExecutionOutcome::Skipped {
+                        reason: "Event was not deciding the mining mode".to_string(),
+                    };
+                }
+                match Self::resolve_active_mining_event(events) {
+                    Some((fallback_event_id, fallback_mining_mode)) => {
+                        info!(target: LOG_TARGET_APP_LOGIC, "Event {:?} exited, falling back to event {:?}", event_id, fallback_event_id);
+                        Self::apply_mining_mode(&fallback_event_id, fallback_mining_mode).await
+                    }
+                    None => Self::stop_mining(&event_id).await,
                 }
             }
+        };
+        SchedulerExecutionOutcome::from_errors(errors)
+    }
+
+    /// Internal handler computing upcoming transitions of recurring events.
+    ///
+    /// Walks forward in time over the start and end boundaries of every active Between /
+    /// BetweenWindows event and records each change of an event's state together with the
+    /// mining mode resulting from the priority resolution. Condition events cannot be
+    /// predicted, they only contribute their current state.
+    ///
+    /// ### Parameters
+    /// * `events` - Reference to the events map
+    /// * `count` - Maximum number of transitions to return
+    ///
+    /// ### Returns
+    /// * `Vec<SchedulerTransition>` - Upcoming transitions ordered by time
+    fn handle_get_next_transitions(
+        events: &HashMap<String, ScheduledEvent>,
+        count: usize,
+    ) -> Vec<SchedulerTransition> {
+        const MAX_SIMULATION_STEPS: usize = 1000;
+
+        let now = Local::now();
+        let active_events: Vec<&ScheduledEvent> = events
+            .values()
+            .filter(|event| {
+                event.state == SchedulerEventState::Active && event.timing.is_recurring()
+            })
+            .collect();
+
+        let mut schedules: HashMap<String, Vec<CronSchedule>> = HashMap::new();
+        let mut simulated_state: HashMap<String, bool> = HashMap::new();
+        for event in &active_events {
+            let event_schedules = event.timing.to_cron_schedules().unwrap_or_default();
+            let is_entered = if event_schedules.is_empty() {
+                event.is_condition_met
+            } else {
+                event.timing.is_within_window(now)
+            };
+            simulated_state.insert(event.id.clone(), is_entered);
+            if !event_schedules.is_empty() {
+                schedules.insert(event.id.clone(), event_schedules);
+            }
         }
-        Ok(())
+
+        let resolve_mining_mode = |state: &HashMap<String, bool>| -> Option<String> {
+            active_events
+                .iter()
+                .filter(|event| state.get(&event.id).copied().unwrap_or(false))
+                .filter_map(|event| match &event.event_type {
+                    SchedulerEventType::Mine { mining_mode } => {
+                        Some((event.priority, event.id.clone(), mining_mode.clone()))
+                    }
+                    SchedulerEventType::ResumeMining | SchedulerEventType::Action { .. } => None,
+                })
+                .max_by_key(|(priority, id, _)| (*priority, Reverse(id.clone())))
+                .map(|(_, _, mining_mode)| mining_mode)
+        };
+
+        let mut transitions = Vec::new();
+        let mut cursor = now;
+        for _ in 0..MAX_SIMULATION_STEPS {
+            if transitions.len() >= count {
+                break;
+            }
+
+            let next_boundary = schedules
+                .values()
+                .flatten()
+                .flat_map(|schedule| {
+                    [
+                        schedule.find_next_start_time(cursor),
+                        schedule.find_next_end_time(cursor),
+                    ]
+                })
+                .flatten()
+                .min();
+            let Some(boundary) = next_boundary else {
+                break;
+            };
+
+            // Evaluate just after the boundary so windows starting at that moment count as open
+            let after_boundary = boundary + Duration::seconds(1);
+            let mut changed_events: Vec<(String, bool)> = schedules
+                .keys()
+                .filter_map(|event_id| {
+                    let event = events.get(event_id)?;
+                    let is_entered = event.timing.is_within_window(after_boundary);
+                    (simulated_state.get(event_id).copied() != Some(is_entered))
+                        .then(|| (event_id.clone(), is_entered))
+                })
+                .collect();
+            changed_events.sort();
+
+            for (event_id, is_entered) in changed_events {
+                simulated_state.insert(event_id.clone(), is_entered);
+                transitions.push(SchedulerTransition {
+                    time: boundary,
+                    event_id,
+                    kind: if is_entered {
+                        SchedulerTransitionKind::Enter
+                    } else {
+                        SchedulerTransitionKind::Exit
+                    },
+                    resulting_mining_mode: resolve_mining_mode(&simulated_state),
+                });
+            }
+
+            cursor = boundary;
+        }
+
+        transitions.truncate(count);
+        transitions
     }
 
     /// Internal handler for cleaning up completed one-time events.
@@ -1114,9 +2271,119 @@ impl EventScheduler {
         if let Some(event) = events.get(&event_id) {
             if let SchedulerEventTiming::In(_) = event.timing {
                 info!(target: LOG_TARGET_APP_LOGIC, "Cleaning up schedule for event ID {:?}", event_id);
-                if let Err(e) = Self::handle_remove_event(events, event_id.clone()) {
+                if let Err(e) = Self::handle_remove_event(events, event_id.clone()).await {
                     error!(target: LOG_TARGET_APP_LOGIC, "Failed to clean up scheduled event {:?}: {}", event_id, e);
                 }
+                Self::save_persistent_events_to_config(events).await;
+            }
+        }
+    }
+
+    /// Internal handler for wall-clock jumps and system resume.
+    ///
+    /// Timers are based on monotonic time, so after a suspend or a clock correction they
+    /// fire late or miss windows. This re-creates every active event's timers against the
+    /// current time and enters windows we are now inside of or exits the ones we left.
+    /// One-time events whose deadline passed fire right away.
+    ///
+    /// ### Parameters
+    /// * `events` - Mutable reference to the events map
+    async fn handle_clock_jump(events: &mut HashMap<String, ScheduledEvent>) {
+        let now = Local::now();
+        for (event_id, event) in events {
+            if event.state != SchedulerEventState::Active {
+                continue;
+            }
+
+            if let Some(handle) = event.task_handle.take() {
+                handle.abort();
+            }
+            match Self::create_scheduling_task(
+                event_id.clone(),
+                event.event_type.clone(),
+                event.timing.clone(),
+            )
+            .await
+            {
+                Ok(task_handle) => event.task_handle = task_handle,
+                Err(e) => {
+                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to reschedule event {:?} after clock jump: {}", event_id, e);
+                }
+            }
+
+            if !event.timing.is_windowed() {
+                continue;
+            }
+            let message = match (event.timing.is_within_window(now), event.is_entered) {
+                (true, false) => {
+                    info!(target: LOG_TARGET_APP_LOGIC, "Event {:?} is now inside its window, entering", event_id);
+                    SchedulerMessage::TriggerEnterCallback {
+                        event_id: event_id.clone(),
+                        planned_time: None,
+                    }
+                }
+                (false, true) => {
+                    info!(target: LOG_TARGET_APP_LOGIC, "Event {:?} is no longer inside its window, exiting", event_id);
+                    SchedulerMessage::TriggerExitCallback {
+                        event_id: event_id.clone(),
+                        planned_time: None,
+                    }
+                }
+                _ => continue,
+            };
+            let _unused = INSTANCE.message_sender.send(message);
+        }
+    }
+
+    /// Internal handler for the periodic condition check.
+    ///
+    /// Samples the machine state required by active Condition events and sends the
+    /// enter/exit triggers for every event whose condition changed since the last check.
+    ///
+    /// ### Parameters
+    /// * `events` - Mutable reference to the events map
+    async fn handle_conditions_check(events: &mut HashMap<String, ScheduledEvent>) {
+        let condition_events: Vec<(String, SchedulerCondition)> = events
+            .iter()
+            .filter(|(_, event)| event.state == SchedulerEventState::Active)
+            .filter_map(|(id, event)| match &event.timing {
+                SchedulerEventTiming::Condition(payload) => {
+                    Some((id.clone(), payload.condition.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        if condition_events.is_empty() {
+            return;
+        }
+
+        let conditions: Vec<SchedulerCondition> = condition_events
+            .iter()
+            .map(|(_, condition)| condition.clone())
+            .collect();
+        let snapshot = SchedulerConditionsMonitor::snapshot(&conditions).await;
+
+        for (event_id, condition) in condition_events {
+            let is_met = condition.is_met(&snapshot);
+            if let Some(event) = events.get_mut(&event_id) {
+                if event.is_condition_met == is_met {
+                    continue;
+                }
+                event.is_condition_met = is_met;
+                info!(target: LOG_TARGET_APP_LOGIC, "Condition {:?} of event {:?} changed to {}", condition, event_id, is_met);
+
+                let message = if is_met {
+                    SchedulerMessage::TriggerEnterCallback {
+                        event_id,
+                        planned_time: None,
+                    }
+                } else {
+                    SchedulerMessage::TriggerExitCallback {
+                        event_id,
+                        planned_time: None,
+                    }
+                };
+                let _unused = INSTANCE.message_sender.send(message);
             }
         }
     }
@@ -1126,6 +2393,7 @@ impl EventScheduler {
     /// This spawns the appropriate timing task based on the event's timing type:
     /// - "In" timing: Simple delay then trigger
     /// - "Between" timing: Complex cron-based recurring schedule
+    /// - "Condition" timing: No task, conditions are checked by the message loop
     ///
     /// ### Parameters
     /// * `event_id` - Unique identifier for the event
@@ -1133,85 +2401,342 @@ impl EventScheduler {
     /// * `timing` - When the event should be triggered
     ///
     /// ### Returns
-    /// * `Ok(JoinHandle)` - Handle to the spawned scheduling task
+    /// * `Ok(Some(JoinHandle))` - Handle to the spawned scheduling task
+    /// * `Ok(None)` - No task is needed for this timing
     /// * `Err(SchedulerError)` - If task creation fails
     async fn create_scheduling_task(
         event_id: String,
         _event_type: SchedulerEventType, // Not used in scheduling logic, only for identification
         timing: SchedulerEventTiming,
-    ) -> Result<tokio::task::JoinHandle<()>, SchedulerError> {
-        let handle = match timing {
-            SchedulerEventTiming::In(in_variant_payload) => tokio::spawn(async move {
-                let duration = in_variant_payload.to_duration();
-                if let Ok(duration) = duration {
-                    sleep(duration.to_std().unwrap_or_default()).await;
-
-                    let _unused = INSTANCE
-                        .message_sender
-                        .send(SchedulerMessage::TriggerEnterCallback { event_id });
-                } else {
-                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to parse duration for 'In' event {:?}", event_id);
-                }
-            }),
-
-            SchedulerEventTiming::Between(between_time_variant_payload) => {
-                info!(target: LOG_TARGET_APP_LOGIC, "Creating scheduling task for 'Between' event ID {:?}", event_id);
-                let cron_schedule =
-                    between_time_variant_payload
-                        .to_cron_schedule()
-                        .map_err(|e| {
-                            SchedulerError::InvalidTimingFormat(format!(
-                                "Failed to create cron schedule for Between timing: {}",
-                                e
-                            ))
-                        })?;
-
-                let copied_cron_schedule = cron_schedule.clone();
-                let copied_event_id = event_id.clone();
-
+    ) -> Result<Option<tokio::task::JoinHandle<()>>, SchedulerError> {
+        let handle = match &timing {
+            SchedulerEventTiming::In(in_variant_payload) => {
+                let in_variant_payload = in_variant_payload.clone();
                 tokio::spawn(async move {
-                    let cron_schedule = copied_cron_schedule.clone();
-                    let event_id = copied_event_id.clone();
-                    loop {
-                        let local_now = Local::now();
-
-                        if let Some(next_start_wait_time) =
-                            cron_schedule.find_next_start_wait_time(local_now)
-                        {
-                            sleep(next_start_wait_time).await;
-                        } else {
-                            warn!(target: LOG_TARGET_APP_LOGIC, "No next start time found for event with ID {:?}", event_id);
-                            break;
-                        }
+                    let wait_time = in_variant_payload.find_wait_time(Local::now());
+                    if let Ok(wait_time) = wait_time {
+                        sleep(wait_time).await;
 
                         let _unused =
                             INSTANCE
                                 .message_sender
                                 .send(SchedulerMessage::TriggerEnterCallback {
                                     event_id: event_id.clone(),
+                                    planned_time: in_variant_payload.deadline,
                                 });
+                        let _unused = INSTANCE
+                            .message_sender
+                            .send(SchedulerMessage::CleanupSchedule { event_id });
+                    } else {
+                        error!(target: LOG_TARGET_APP_LOGIC, "Failed to parse duration for 'In' event {:?}", event_id);
                     }
-                });
+                })
+            }
+
+            SchedulerEventTiming::Between(_) | SchedulerEventTiming::BetweenWindows(_) => {
+                info!(target: LOG_TARGET_APP_LOGIC, "Creating scheduling task for recurring event ID {:?}", event_id);
+                let cron_schedules = timing.to_cron_schedules().map_err(|e| {
+                    SchedulerError::InvalidTimingFormat(format!(
+                        "Failed to create cron schedule for recurring timing: {}",
+                        e
+                    ))
+                })?;
+
+                // All windows run inside a single task so aborting its handle stops every one of them
                 tokio::spawn(async move {
-                    let cron_schedule = cron_schedule.clone();
-                    loop {
-                        let local_now = Local::now();
-                        // Now wait until end time, eg. currently is 10AM and the range is 9AM - 11AM, then we wait until 11AM
-                        if let Some(next_end_wait_time) =
-                            cron_schedule.find_next_end_wait_time(local_now)
-                        {
-                            sleep(next_end_wait_time).await;
-                            let _unused = INSTANCE.message_sender.send(
-                                SchedulerMessage::TriggerExitCallback {
-                                    event_id: event_id.clone(),
-                                },
-                            );
-                        }
-                    }
+                    futures::future::join_all(cron_schedules.into_iter().map(|cron_schedule| {
+                        Self::run_cron_window(event_id.clone(), cron_schedule)
+                    }))
+                    .await;
                 })
             }
+
+            SchedulerEventTiming::Condition(payload) => {
+                payload.validate()?;
+                return Ok(None);
+            }
         };
 
-        Ok(handle)
+        Ok(Some(handle))
+    }
+
+    /// Drives a single recurring time window.
+    ///
+    /// Sleeps until whichever comes first, the next start or the next end of the window,
+    /// and sends the matching enter/exit trigger. If the next end comes before the next start
+    /// we are currently inside the window (or just past its start), so the exit is sent first.
+    ///
+    /// ### Parameters
+    /// * `event_id` - ID of the event the window belongs to
+    /// * `cron_schedule` - Start and end of the window
+    async fn run_cron_window(event_id: String, cron_schedule: CronSchedule) {
+        let mut last_boundary: Option<DateTime<Local>> = None;
+        loop {
+            let local_now = Local::now();
+            // Searching after the boundary just handled, waking up slightly before it
+            // cannot trigger it a second time
+            let from = last_boundary.map_or(local_now, |boundary| boundary.max(local_now));
+
+            let Some(boundary) = next_window_boundary(&cron_schedule, from) else {
+                warn!(target: LOG_TARGET_APP_LOGIC, "No next start time found for event with ID {:?}", event_id);
+                break;
+            };
+            let boundary_time = boundary.time();
+            sleep((boundary_time - local_now).to_std().unwrap_or_default()).await;
+            last_boundary = Some(boundary_time);
+
+            let message = match boundary {
+                WindowBoundary::Start(start) => SchedulerMessage::TriggerEnterCallback {
+                    event_id: event_id.clone(),
+                    planned_time: Some(start),
+                },
+                WindowBoundary::End(end) => SchedulerMessage::TriggerExitCallback {
+                    event_id: event_id.clone(),
+                    planned_time: Some(end),
+                },
+            };
+            let _unused = INSTANCE.message_sender.send(message);
+        }
+    }
+}
+
+/// Next start or end of a recurring window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WindowBoundary {
+    Start(DateTime<Local>),
+    End(DateTime<Local>),
+}
+
+impl WindowBoundary {
+    fn time(self) -> DateTime<Local> {
+        match self {
+            WindowBoundary::Start(time) | WindowBoundary::End(time) => time,
+        }
+    }
+}
+
+/// Finds the first start or end of the window after `from`.
+///
+/// ### Returns
+/// * `Some(WindowBoundary)` - The end when it comes before the next start, the next start otherwise
+/// * `None` - If the window never starts again
+fn next_window_boundary(
+    cron_schedule: &CronSchedule,
+    from: DateTime<Local>,
+) -> Option<WindowBoundary> {
+    let next_start = cron_schedule.find_next_start_time(from)?;
+    match cron_schedule.find_next_end_time(from) {
+        Some(next_end) if next_end < next_start => Some(WindowBoundary::End(next_end)),
+        _ => Some(WindowBoundary::Start(next_start)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn window(
+        start: (i64, TimePeriod),
+        end: (i64, TimePeriod),
+        days_of_week: Vec<DayOfWeek>,
+    ) -> BetweenTimeVariantPayload {
+        BetweenTimeVariantPayload {
+            start_hour: start.0,
+            start_minute: 0,
+            start_period: start.1,
+            end_hour: end.0,
+            end_minute: 0,
+            end_period: end.1,
+            days_of_week,
+        }
+    }
+
+    /// 2025-01-03 is a Friday
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2025, 1, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rejects_window_starting_and_ending_at_the_same_time() {
+        let payload = window((10, TimePeriod::PM), (10, TimePeriod::PM), vec![]);
+        assert!(matches!(
+            payload.to_cron_schedule(),
+            Err(SchedulerError::InvalidTimingFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_same_day_window() {
+        let schedule = window((9, TimePeriod::AM), (5, TimePeriod::PM), vec![])
+            .to_cron_schedule()
+            .unwrap();
+        assert!(!schedule.is_active_at(at(3, 8, 59)));
+        assert!(schedule.is_active_at(at(3, 9, 0)));
+        assert!(schedule.is_active_at(at(3, 12, 0)));
+        assert!(!schedule.is_active_at(at(3, 17, 0)));
+        assert!(!schedule.is_active_at(at(3, 22, 0)));
+    }
+
+    #[test]
+    fn test_window_crossing_midnight_ends_the_next_day() {
+        let schedule = window(
+            (10, TimePeriod::PM),
+            (7, TimePeriod::AM),
+            vec![DayOfWeek::Friday],
+        )
+        .to_cron_schedule()
+        .unwrap();
+        // Thursday night is not part of the window
+        assert!(!schedule.is_active_at(at(2, 23, 0)));
+        assert!(!schedule.is_active_at(at(3, 21, 59)));
+        assert!(schedule.is_active_at(at(3, 22, 0)));
+        assert!(schedule.is_active_at(at(3, 23, 30)));
+        // Saturday morning still belongs to Friday's window
+        assert!(schedule.is_active_at(at(4, 0, 30)));
+        assert!(schedule.is_active_at(at(4, 6, 59)));
+        assert!(!schedule.is_active_at(at(4, 7, 0)));
+        assert!(!schedule.is_active_at(at(4, 22, 30)));
+
+        assert_eq!(
+            schedule.find_next_start_time(at(3, 12, 0)),
+            Some(at(3, 22, 0))
+        );
+        assert_eq!(schedule.find_next_end_time(at(3, 22, 0)), Some(at(4, 7, 0)));
+    }
+
+    #[test]
+    fn test_next_boundary_after_a_handled_boundary() {
+        let schedule = window(
+            (10, TimePeriod::PM),
+            (7, TimePeriod::AM),
+            vec![DayOfWeek::Friday],
+        )
+        .to_cron_schedule()
+        .unwrap();
+        assert_eq!(
+            next_window_boundary(&schedule, at(3, 12, 0)),
+            Some(WindowBoundary::Start(at(3, 22, 0)))
+        );
+        // Searching from the start that was just entered moves on to the end
+        assert_eq!(
+            next_window_boundary(&schedule, at(3, 22, 0)),
+            Some(WindowBoundary::End(at(4, 7, 0)))
+        );
+        // Inside the window the end comes first
+        assert_eq!(
+            next_window_boundary(&schedule, at(4, 3, 0)),
+            Some(WindowBoundary::End(at(4, 7, 0)))
+        );
+        assert_eq!(
+            next_window_boundary(&schedule, at(4, 7, 0)),
+            Some(WindowBoundary::Start(at(10, 22, 0)))
+        );
+    }
+
+    fn entered_mine_event(id: &str, priority: i32) -> ScheduledEvent {
+        ScheduledEvent {
+            id: id.to_string(),
+            event_type: SchedulerEventType::Mine {
+                mining_mode: id.to_string(),
+            },
+            timing: SchedulerEventTiming::Between(window(
+                (9, TimePeriod::AM),
+                (5, TimePeriod::PM),
+                vec![],
+            )),
+            state: SchedulerEventState::Active,
+            task_handle: None,
+            is_condition_met: false,
+            priority,
+            is_entered: true,
+            restore_action: None,
+        }
+    }
+
+    fn entered_mine_events() -> HashMap<String, ScheduledEvent> {
+        HashMap::from([
+            ("low".to_string(), entered_mine_event("low", 0)),
+            ("high".to_string(), entered_mine_event("high", 1)),
+        ])
+    }
+
+    #[test]
+    fn test_enters_window_open_at_registration() {
+        let mut event = entered_mine_event("night", 0);
+        event.timing = SchedulerEventTiming::Between(window(
+            (10, TimePeriod::PM),
+            (6, TimePeriod::AM),
+            vec![],
+        ));
+        event.is_entered = false;
+        // Launched at 23:00, inside the 22:00 - 06:00 window
+        assert!(EventScheduler::is_in_unentered_window(&event, at(3, 23, 0)));
+        assert!(!EventScheduler::is_in_unentered_window(
+            &event,
+            at(3, 12, 0)
+        ));
+
+        event.is_entered = true;
+        assert!(!EventScheduler::is_in_unentered_window(
+            &event,
+            at(3, 23, 0)
+        ));
+
+        event.is_entered = false;
+        event.state = SchedulerEventState::Paused;
+        assert!(!EventScheduler::is_in_unentered_window(
+            &event,
+            at(3, 23, 0)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pausing_an_entered_event_exits_it() {
+        let mut events = entered_mine_events();
+        EventScheduler::handle_pause_event(&mut events, "low".to_string())
+            .await
+            .unwrap();
+
+        let paused = &events["low"];
+        assert_eq!(paused.state, SchedulerEventState::Paused);
+        assert!(!paused.is_entered);
+        assert_eq!(
+            EventScheduler::resolve_active_mining_event(&events),
+            Some(("high".to_string(), "high".to_string()))
+        );
+        assert!(matches!(
+            EventScheduler::handle_pause_event(&mut events, "low".to_string()).await,
+            Err(SchedulerError::EventAlreadyPaused(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_removing_an_entered_event_runs_its_exit() {
+        let mut events = entered_mine_events();
+        assert_eq!(
+            EventScheduler::exit_entered_event(&mut events, "low").await,
+            Some(SchedulerExecutionOutcome::Skipped {
+                reason: "Event was not deciding the mining mode".to_string(),
+            })
+        );
+        // Already exited, nothing left to undo
+        assert_eq!(
+            EventScheduler::exit_entered_event(&mut events, "low").await,
+            None
+        );
+
+        EventScheduler::handle_remove_event(&mut events, "low".to_string())
+            .await
+            .unwrap();
+        assert!(!events.contains_key("low"));
+        assert_eq!(
+            EventScheduler::resolve_active_mining_event(&events),
+            Some(("high".to_string(), "high".to_string()))
+        );
     }
 }
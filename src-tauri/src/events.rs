@@ -87,6 +87,14 @@ pub enum EventType {
     OpenSettings,
     ShowEcoAlert,
     SetShowBatteryAlert,
+    SchedulerExecution,
+    PoolFailover,
+    GpuBenchmarkProgress,
+    GpuWatchdogIntervention,
+    GpuEnginePreferencesIgnored,
+    ProcessCrashReport,
+    ProcessOutput,
+    UnrelatedProcessWarning,
     // Shutdown
     ShutdownModeSelectionRequested,
     FeedbackSurveyRequested,
@@ -134,6 +142,13 @@ pub struct DetectedAvailableGpuEnginesPayload {
     pub selected_engine: String,
 }
 
+/// Devices mined with another engine than the one they prefer
+#[derive(Clone, Debug, Serialize)]
+pub struct GpuEnginePreferencesIgnoredPayload {
+    pub engine: String,
+    pub device_indexes: Vec<u32>,
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct DetectedDevicesPayload {
     pub devices: Vec<GpuCommonInformation>,
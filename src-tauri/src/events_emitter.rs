@@ -28,10 +28,17 @@ use crate::events::{
 };
 use crate::internal_wallet::TariAddressType;
 use crate::mining::cpu::CpuMinerStatus;
+use crate::mining::gpu::benchmark::GpuBenchmarkProgressPayload;
 use crate::mining::gpu::consts::{GpuMiner, GpuMinerStatus, GpuMinerType};
 use crate::mining::gpu::miners::GpuCommonInformation;
+use crate::mining::gpu::watchdog::GpuWatchdogIntervention;
+use crate::mining::pools::pool_failover::PoolFailoverPayload;
 use crate::mining::pools::PoolStatus;
 use crate::mining::MinerControlsState;
+use crate::process_identity::UnrelatedProcessWarning;
+use crate::process_output::ProcessOutputLine;
+use crate::process_restart_policy::ProcessCrashReport;
+use crate::scheduler_history::SchedulerExecutionRecord;
 #[cfg(target_os = "windows")]
 use crate::system_dependencies::UniversalSystemDependency;
 use crate::wallet::wallet_types::{TransactionInfo, WalletBalance};
@@ -42,8 +49,9 @@ use crate::{
     },
     events::{
         DetectedAvailableGpuEnginesPayload, DetectedDevicesPayload, Event, EventType,
-        NetworkStatusPayload, NewBlockHeightPayload, NodeTypeUpdatePayload,
-        ProgressTrackerUpdatePayload, ShowReleaseNotesPayload, TariAddressUpdatePayload,
+        GpuEnginePreferencesIgnoredPayload, NetworkStatusPayload, NewBlockHeightPayload,
+        NodeTypeUpdatePayload, ProgressTrackerUpdatePayload, ShowReleaseNotesPayload,
+        TariAddressUpdatePayload,
     },
     hardware::hardware_status_monitor::PublicDeviceGpuProperties,
     setup::setup_manager::SetupPhase,
@@ -842,4 +850,108 @@ impl EventsEmitter {
             error!(target: LOG_TARGET_APP_LOGIC, "Failed to emit ShowBatteryAlert event: {e:?}");
         }
     }
+
+    pub async fn emit_scheduler_execution(payload: SchedulerExecutionRecord) {
+        let _ = FrontendReadyChannel::current().wait_for_ready().await;
+        if let Err(e) = Self::get_app_handle().await.emit(
+            BACKEND_STATE_UPDATE,
+            Event {
+                event_type: EventType::SchedulerExecution,
+                payload,
+            },
+        ) {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to emit SchedulerExecution event: {e:?}");
+        }
+    }
+
+    pub async fn emit_pool_failover(payload: PoolFailoverPayload) {
+        let _ = FrontendReadyChannel::current().wait_for_ready().await;
+        if let Err(e) = Self::get_app_handle().await.emit(
+            BACKEND_STATE_UPDATE,
+            Event {
+                event_type: EventType::PoolFailover,
+                payload,
+            },
+        ) {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to emit PoolFailover event: {e:?}");
+        }
+    }
+
+    pub async fn emit_gpu_benchmark_progress(payload: GpuBenchmarkProgressPayload) {
+        let _ = FrontendReadyChannel::current().wait_for_ready().await;
+        if let Err(e) = Self::get_app_handle().await.emit(
+            BACKEND_STATE_UPDATE,
+            Event {
+                event_type: EventType::GpuBenchmarkProgress,
+                payload,
+            },
+        ) {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to emit GpuBenchmarkProgress event: {e:?}");
+        }
+    }
+
+    pub async fn emit_gpu_watchdog_intervention(payload: GpuWatchdogIntervention) {
+        let _ = FrontendReadyChannel::current().wait_for_ready().await;
+        if let Err(e) = Self::get_app_handle().await.emit(
+            BACKEND_STATE_UPDATE,
+            Event {
+                event_type: EventType::GpuWatchdogIntervention,
+                payload,
+            },
+        ) {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to emit GpuWatchdogIntervention event: {e:?}");
+        }
+    }
+
+    pub async fn emit_gpu_engine_preferences_ignored(payload: GpuEnginePreferencesIgnoredPayload) {
+        let _ = FrontendReadyChannel::current().wait_for_ready().await;
+        if let Err(e) = Self::get_app_handle().await.emit(
+            BACKEND_STATE_UPDATE,
+            Event {
+                event_type: EventType::GpuEnginePreferencesIgnored,
+                payload,
+            },
+        ) {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to emit GpuEnginePreferencesIgnored event: {e:?}");
+        }
+    }
+
+    pub async fn emit_process_crash_report(payload: ProcessCrashReport) {
+        let _ = FrontendReadyChannel::current().wait_for_ready().await;
+        if let Err(e) = Self::get_app_handle().await.emit(
+            BACKEND_STATE_UPDATE,
+            Event {
+                event_type: EventType::ProcessCrashReport,
+                payload,
+            },
+        ) {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to emit ProcessCrashReport event: {e:?}");
+        }
+    }
+
+    pub async fn emit_process_output(payload: Vec<ProcessOutputLine>) {
+        let _ = FrontendReadyChannel::current().wait_for_ready().await;
+        if let Err(e) = Self::get_app_handle().await.emit(
+            BACKEND_STATE_UPDATE,
+            Event {
+                event_type: EventType::ProcessOutput,
+                payload,
+            },
+        ) {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to emit ProcessOutput event: {e:?}");
+        }
+    }
+
+    pub async fn emit_unrelated_process_warning(payload: UnrelatedProcessWarning) {
+        let _ = FrontendReadyChannel::current().wait_for_ready().await;
+        if let Err(e) = Self::get_app_handle().await.emit(
+            BACKEND_STATE_UPDATE,
+            Event {
+                event_type: EventType::UnrelatedProcessWarning,
+                payload,
+            },
+        ) {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to emit UnrelatedProcessWarning event: {e:?}");
+        }
+    }
 }
@@ -36,9 +36,11 @@ use zip::ZipWriter;
 use crate::app_in_memory_config::AppInMemoryConfig;
 use crate::configs::config_core::ConfigCore;
 use crate::configs::trait_config::ConfigImpl;
+use crate::process_output::ProcessOutputBuffers;
 use crate::utils::file_utils::{make_relative_path, path_as_string};
 use crate::LOG_TARGET_APP_LOGIC;
 const MAX_FILE_SIZE: u64 = 100 * 1024 * 1024; // 100MB in bytes
+const PROCESS_OUTPUT_LINES: usize = 200; // Last lines of each process added to the archive
 pub struct Feedback {
     in_memory_config: Arc<RwLock<AppInMemoryConfig>>,
 }
@@ -54,6 +56,7 @@ impl Feedback {
         directories_and_filters: &[(PathBuf, Regex, String)],
     ) -> Result<zip::result::ZipResult<File>, Error> {
         let file_options = SimpleFileOptions::default();
+        let process_outputs = ProcessOutputBuffers::snapshot(PROCESS_OUTPUT_LINES).await;
 
         let _zip_file_name = archive_file
             .file_name()
@@ -121,6 +124,16 @@ impl Feedback {
             }
         }
 
+        for (binary, lines) in process_outputs {
+            zip.start_file(
+                format!("process_output/{}.log", binary.name()),
+                file_options,
+            )?;
+            for line in lines {
+                writeln!(zip, "{line}")?;
+            }
+        }
+
         Ok(zip.finish())
     }
 
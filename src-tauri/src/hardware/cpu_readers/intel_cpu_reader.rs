@@ -95,6 +95,7 @@ impl CpuParametersReader for IntelCpuParametersReader {
             max_temperature: old_device_parameters.map_or(avarage_temperature, |old| {
                 old.max_temperature.max(avarage_temperature)
             }),
+            power_usage_watts: None,
         };
 
         Ok(device_parameters)
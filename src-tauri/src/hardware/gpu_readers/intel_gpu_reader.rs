@@ -56,6 +56,7 @@ impl GpuParametersReader for IntelGpuReader {
             usage_percentage: 0.0,
             current_temperature: 0.0,
             max_temperature: 0.0,
+            power_usage_watts: None,
         };
         Ok(device_parameters)
     }
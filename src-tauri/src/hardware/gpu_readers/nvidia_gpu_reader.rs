@@ -33,10 +33,13 @@ use crate::{
 use super::GpuParametersReader;
 
 #[derive(Clone)]
-pub struct NvidiaGpuReader {}
+pub struct NvidiaGpuReader {
+    /// Index of the device among the Nvidia devices, as numbered by NVML
+    device_index: u32,
+}
 impl NvidiaGpuReader {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(device_index: u32) -> Self {
+        Self { device_index }
     }
 
     pub fn init_nvml(&self) -> Option<Nvml> {
@@ -69,9 +72,8 @@ impl GpuParametersReader for NvidiaGpuReader {
         let nvml = self
             .init_nvml()
             .ok_or(anyhow!("Failed to initialize Nvidia GPU reader"))?;
-        // TODO ADD support for multiple GPUs
         let main_device = nvml
-            .device_by_index(0)
+            .device_by_index(self.device_index)
             .map_err(|e| anyhow!("Failed to get Nvidia GPU device: {}", e))?;
         let usage_percentage = main_device
             .utilization_rates()
@@ -81,6 +83,11 @@ impl GpuParametersReader for NvidiaGpuReader {
             .temperature(TemperatureSensor::Gpu)
             .map_err(|e| anyhow!("Failed to get Nvidia GPU temperature: {}", e))?
             as f32;
+        // Not every card reports its power draw, e.g. most laptop GPUs
+        let power_usage_watts = main_device
+            .power_usage()
+            .ok()
+            .map(|milliwatts| milliwatts as f32 / 1000.0);
 
         let device_parameters = DeviceParameters {
             usage_percentage,
@@ -88,6 +95,7 @@ impl GpuParametersReader for NvidiaGpuReader {
             max_temperature: old_device_parameters.map_or(current_temperature, |old| {
                 old.max_temperature.max(current_temperature)
             }),
+            power_usage_watts,
         };
         Ok(device_parameters)
     }
@@ -117,6 +117,8 @@ pub struct DeviceParameters {
     pub usage_percentage: f32,
     pub current_temperature: f32,
     pub max_temperature: f32,
+    /// Current power draw, only available for readers with power reporting
+    pub power_usage_watts: Option<f32>,
 }
 #[derive(Debug, Serialize, Clone, Default)]
 pub struct DeviceStatus {
@@ -194,9 +196,10 @@ impl HardwareStatusMonitor {
     async fn select_reader_for_gpu_device(
         &self,
         vendor: HardwareVendor,
+        vendor_device_index: u32,
     ) -> Box<dyn GpuParametersReader> {
         match vendor {
-            HardwareVendor::Nvidia => Box::new(NvidiaGpuReader::new()),
+            HardwareVendor::Nvidia => Box::new(NvidiaGpuReader::new(vendor_device_index)),
             HardwareVendor::Amd => Box::new(AmdGpuReader::new()),
             HardwareVendor::Intel => Box::new(IntelGpuReader::new()),
             HardwareVendor::Apple => Box::new(AppleGpuReader::new()),
@@ -213,11 +216,28 @@ impl HardwareStatusMonitor {
             .join(APPLICATION_FOLDER_ID);
         let gpu_status_file_content = self.load_gpu_devices_from_status_file(config_dir).await?;
         let mut platform_devices = Vec::new();
+        // Vendor libraries like NVML number only their own devices
+        let mut vendor_device_counts: Vec<(HardwareVendor, u32)> = Vec::new();
 
         for gpu_device in &gpu_status_file_content.devices {
             debug!(target: LOG_TARGET_APP_LOGIC, "GPU device name: {:?}", gpu_device.name);
             let vendor = HardwareVendor::from_string(&gpu_device.name);
-            let device_reader = self.select_reader_for_gpu_device(vendor.clone()).await;
+            let vendor_device_index = match vendor_device_counts
+                .iter_mut()
+                .find(|(counted_vendor, _)| counted_vendor == &vendor)
+            {
+                Some((_, count)) => {
+                    *count += 1;
+                    *count - 1
+                }
+                None => {
+                    vendor_device_counts.push((vendor.clone(), 1));
+                    0
+                }
+            };
+            let device_reader = self
+                .select_reader_for_gpu_device(vendor.clone(), vendor_device_index)
+                .await;
             let platform_device = GpuDeviceProperties {
                 private_properties: PrivateGpuDeviceProperties {
                     device_reader: device_reader.clone(),
@@ -339,6 +359,29 @@ impl HardwareStatusMonitor {
         Ok(platform_devices)
     }
 
+    /// Current parameters of the GPU with the given device index, `None` when its reader is not implemented.
+    /// Devices are ordered as in the gpu status file written during detection.
+    pub async fn get_gpu_device_parameters(&self, device_index: u32) -> Option<DeviceParameters> {
+        let gpu_devices = self.gpu_devices.read().await;
+        let device = gpu_devices.get(usize::try_from(device_index).ok()?)?;
+        if !device.public_properties.status.is_reader_implemented {
+            return None;
+        }
+        device
+            .private_properties
+            .device_reader
+            .get_device_parameters(None)
+            .await
+            .ok()
+    }
+
+    /// Current power draw of the GPU with the given device index, `None` when its reader cannot report it.
+    pub async fn get_gpu_power_usage(&self, device_index: u32) -> Option<f32> {
+        self.get_gpu_device_parameters(device_index)
+            .await?
+            .power_usage_watts
+    }
+
     pub async fn get_cpu_public_properties(&self) -> Result<Vec<PublicDeviceCpuProperties>, Error> {
         let cpu_devices = self.cpu_devices.read().await;
 
@@ -63,7 +63,9 @@ use app_in_memory_config::EXCHANGE_ID;
 
 use telemetry_manager::TelemetryManager;
 
+use crate::control_api::{ControlApi, ControlApiSources};
 use crate::feedback::Feedback;
+use crate::metrics_server::{MetricsServer, MetricsSources};
 use crate::mining::cpu::manager::CpuManager;
 use crate::mining::cpu::CpuMinerStatus;
 use crate::mining::gpu::consts::GpuMinerStatus;
@@ -84,6 +86,7 @@ mod binaries;
 mod commands;
 mod configs;
 mod consts;
+mod control_api;
 mod credential_manager;
 mod download_utils;
 mod event_scheduler;
@@ -93,6 +96,7 @@ mod events_manager;
 mod feedback;
 mod hardware;
 mod internal_wallet;
+mod metrics_server;
 mod mining;
 mod mining_status_manager;
 mod mm_proxy_adapter;
@@ -103,13 +107,20 @@ mod pin;
 mod port_allocator;
 mod process_adapter;
 mod process_adapter_utils;
+mod process_identity;
+mod process_isolation;
 mod process_killer;
+mod process_output;
+mod process_restart_policy;
 mod process_stats_collector;
 mod process_utils;
 mod process_watcher;
 mod progress_trackers;
 mod release_notes;
 mod requests;
+mod scheduler_actions;
+mod scheduler_conditions;
+mod scheduler_history;
 mod setup;
 mod shutdown_manager;
 mod system_dependencies;
@@ -258,6 +269,7 @@ fn main() {
 
     block_on(GpuManager::initialize(
         stats_collector.take_gpu_miner(),
+        stats_collector.take_additional_gpu_miners(),
         gpu_status_tx.clone(),
         Some(base_node_watch_rx.clone()),
     ));
@@ -272,6 +284,22 @@ fn main() {
     let tor_manager = TorManager::new(tor_watch_tx, &mut stats_collector);
     let mm_proxy_manager = MmProxyManager::new(&mut stats_collector);
 
+    let process_stats_collector = stats_collector.build();
+    block_on(MetricsServer::initialize(MetricsSources {
+        cpu_miner_status_rx: cpu_miner_status_watch_rx.clone(),
+        gpu_miner_status_rx: gpu_status_rx.clone(),
+        node_status_rx: base_node_watch_rx.clone(),
+        wallet_state_rx: wallet_state_watch_rx.clone(),
+        process_stats: process_stats_collector.clone(),
+    }));
+
+    block_on(ControlApi::initialize(ControlApiSources {
+        cpu_miner_status_rx: cpu_miner_status_watch_rx.clone(),
+        gpu_miner_status_rx: gpu_status_rx.clone(),
+        node_status_rx: base_node_watch_rx.clone(),
+        wallet_state_rx: wallet_state_watch_rx.clone(),
+    }));
+
     let telemetry_manager: TelemetryManager = TelemetryManager::new(
         cpu_miner_status_watch_rx.clone(),
         app_in_memory_config.clone(),
@@ -279,7 +307,7 @@ fn main() {
         gpu_status_rx.clone(),
         base_node_watch_rx.clone(),
         tor_watch_rx.clone(),
-        stats_collector.build(),
+        process_stats_collector,
         node_manager.clone(),
     );
 
@@ -572,16 +600,46 @@ fn main() {
             commands::forgot_pin,
             commands::set_seed_backed_up,
             commands::select_mining_mode,
+            commands::get_cpu_miner_stats,
             commands::update_custom_mining_mode,
+            commands::update_mode_cpu_tuning,
             commands::encode_payment_id_to_address,
             commands::save_wxtm_address,
             commands::set_security_warning_dismissed,
             commands::change_cpu_pool,
             commands::change_gpu_pool,
+            commands::set_cpu_fallback_pools,
+            commands::set_gpu_fallback_pools,
+            commands::set_pool_failover_timeout,
             commands::update_selected_gpu_pool_config,
             commands::update_selected_cpu_pool_config,
             commands::reset_gpu_pool_config,
             commands::reset_cpu_pool_config,
+            commands::probe_pool,
+            commands::set_pool_worker_name,
+            commands::use_hostname_as_pool_worker_name,
+            commands::set_gpu_device_intensity,
+            commands::set_gpu_device_engine,
+            commands::set_gpu_device_miner,
+            commands::start_gpu_benchmark,
+            commands::cancel_gpu_benchmark,
+            commands::apply_gpu_benchmark_recommendations,
+            commands::set_gpu_device_limits,
+            commands::set_gpu_watchdog_action,
+            commands::get_gpu_watchdog_interventions,
+            commands::get_process_logs,
+            commands::search_process_logs,
+            commands::update_custom_mode_process_isolation,
+            commands::update_metrics_server_settings,
+            commands::update_control_api_settings,
+            commands::regenerate_control_api_token,
+            commands::create_custom_pool,
+            commands::update_custom_pool,
+            commands::delete_custom_pool,
+            commands::get_pool_earnings_aggregates,
+            commands::get_pool_payouts,
+            commands::export_pool_earnings,
+            commands::clear_pool_earnings_history,
             commands::restart_phases,
             commands::list_connected_peers,
             commands::switch_gpu_miner,
@@ -597,6 +655,9 @@ fn main() {
             commands::remove_scheduler_event,
             commands::pause_scheduler_event,
             commands::resume_scheduler_event,
+            commands::get_scheduler_next_transitions,
+            commands::get_scheduler_history,
+            commands::clear_scheduler_history,
         ])
         .build(tauri::generate_context!())
         .inspect_err(|e| {
@@ -0,0 +1,579 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! Opt-in HTTP endpoint exposing mining, node, wallet, process and hardware stats in the
+//! Prometheus text exposition format, so that rigs can be scraped by an existing monitoring stack.
+//!
+//! The endpoint is disabled by default and binds to localhost unless another address is
+//! configured in `ConfigCore`. Every scrape reads the latest values from the watch channels
+//! shared with the rest of the app, nothing is polled in the background.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::sync::LazyLock;
+
+use anyhow::anyhow;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{watch, RwLock};
+
+use crate::hardware::hardware_status_monitor::{
+    DeviceParameters, HardwareStatusMonitor, PublicDeviceCpuProperties, PublicDeviceGpuProperties,
+};
+use crate::mining::cpu::CpuMinerStatus;
+use crate::mining::gpu::consts::GpuMinerStatus;
+use crate::mining::pools::cpu_pool_manager::CpuPoolManager;
+use crate::mining::pools::gpu_pool_manager::GpuPoolManager;
+use crate::mining::pools::{PoolManagerInterfaceTrait, PoolStatus};
+use crate::node::node_adapter::BaseNodeStatus;
+use crate::process_stats_collector::ProcessStatsCollector;
+use crate::process_watcher::ProcessWatcherStats;
+use crate::utils::server_utils::{bind_local_server, spawn_local_server, LocalServerHandle};
+use crate::wallet::wallet_types::{WalletBalance, WalletState};
+use crate::LOG_TARGET_APP_LOGIC;
+
+const METRICS_PATH: &str = "/metrics";
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const DEFAULT_METRICS_PORT: u16 = 9464;
+
+static INSTANCE: LazyLock<RwLock<MetricsServer>> =
+    LazyLock::new(|| RwLock::new(MetricsServer::new()));
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct MetricsServerSettings {
+    pub enabled: bool,
+    pub bind_address: SocketAddr,
+}
+
+impl Default for MetricsServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: SocketAddr::V4(SocketAddrV4::new(
+                Ipv4Addr::LOCALHOST,
+                DEFAULT_METRICS_PORT,
+            )),
+        }
+    }
+}
+
+/// Channels the exported values are read from on every scrape
+#[derive(Clone)]
+pub struct MetricsSources {
+    pub cpu_miner_status_rx: watch::Receiver<CpuMinerStatus>,
+    pub gpu_miner_status_rx: watch::Receiver<GpuMinerStatus>,
+    pub node_status_rx: watch::Receiver<BaseNodeStatus>,
+    pub wallet_state_rx: watch::Receiver<Option<WalletState>>,
+    pub process_stats: ProcessStatsCollector,
+}
+
+pub struct MetricsServer {
+    sources: Option<MetricsSources>,
+    handle: Option<LocalServerHandle>,
+}
+
+impl MetricsServer {
+    fn new() -> Self {
+        Self {
+            sources: None,
+            handle: None,
+        }
+    }
+
+    pub async fn initialize(sources: MetricsSources) {
+        INSTANCE.write().await.sources = Some(sources);
+    }
+
+    /// Starts, restarts or stops the endpoint so that it matches the given settings.
+    /// The running endpoint is kept when the new one can not be started.
+    pub async fn apply_settings(settings: MetricsServerSettings) -> Result<(), anyhow::Error> {
+        let mut server = INSTANCE.write().await;
+        if !settings.enabled {
+            if let Some(handle) = server.handle.take() {
+                info!(target: LOG_TARGET_APP_LOGIC, "Stopping metrics endpoint");
+                handle.stop().await;
+            }
+            return Ok(());
+        }
+        let sources = server
+            .sources
+            .clone()
+            .ok_or_else(|| anyhow!("Metrics sources are not initialized"))?;
+        if !settings.bind_address.ip().is_loopback() {
+            warn!(target: LOG_TARGET_APP_LOGIC, "Metrics endpoint is bound to {}, it is reachable from other machines", settings.bind_address);
+        }
+
+        let app = Router::new()
+            .route(METRICS_PATH, get(metrics_handler))
+            .with_state(sources);
+        // The same address can only be bound again once the running endpoint released it
+        if let Some(handle) = server
+            .handle
+            .take_if(|handle| handle.bind_address() == settings.bind_address)
+        {
+            info!(target: LOG_TARGET_APP_LOGIC, "Stopping metrics endpoint");
+            handle.stop().await;
+        }
+        let listener = bind_local_server("Metrics endpoint", settings.bind_address).await?;
+        if let Some(handle) = server.handle.take() {
+            info!(target: LOG_TARGET_APP_LOGIC, "Stopping metrics endpoint");
+            handle.stop().await;
+        }
+        let handle = spawn_local_server("Metrics endpoint", listener, app).await?;
+        info!(target: LOG_TARGET_APP_LOGIC, "Metrics endpoint listening on http://{}{}", settings.bind_address, METRICS_PATH);
+        server.handle = Some(handle);
+        Ok(())
+    }
+}
+
+async fn metrics_handler(State(sources): State<MetricsSources>) -> impl IntoResponse {
+    let snapshot = MetricsSnapshot::collect(&sources).await;
+    (
+        [(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)],
+        snapshot.render(),
+    )
+}
+
+/// Values of a single scrape
+#[derive(Default)]
+struct MetricsSnapshot {
+    cpu_miner: CpuMinerStatus,
+    gpu_miner: GpuMinerStatus,
+    cpu_pools: HashMap<String, PoolStatus>,
+    gpu_pools: HashMap<String, PoolStatus>,
+    node: BaseNodeStatus,
+    wallet_balance: Option<WalletBalance>,
+    processes: Vec<(String, ProcessWatcherStats)>,
+    cpu_devices: Vec<PublicDeviceCpuProperties>,
+    gpu_devices: Vec<PublicDeviceGpuProperties>,
+}
+
+impl MetricsSnapshot {
+    async fn collect(sources: &MetricsSources) -> Self {
+        let hardware = HardwareStatusMonitor::current();
+        let cpu_pools = CpuPoolManager::get_pool_statuses().await;
+        let gpu_pools = GpuPoolManager::get_pool_statuses().await;
+        let cpu_devices = hardware
+            .get_cpu_public_properties()
+            .await
+            .unwrap_or_default();
+        let gpu_devices = hardware
+            .get_gpu_public_properties()
+            .await
+            .unwrap_or_default();
+        // Channel guards are not `Send`, so they are only taken once nothing is awaited anymore
+        let wallet_balance = sources
+            .wallet_state_rx
+            .borrow()
+            .as_ref()
+            .and_then(|state| state.balance.clone());
+        let stats = &sources.process_stats;
+        let mut processes = vec![
+            ("cpu_miner".to_string(), stats.get_cpu_miner_stats()),
+            ("gpu_miner".to_string(), stats.get_gpu_miner_stats()),
+            ("mm_proxy".to_string(), stats.get_mm_proxy_stats()),
+            ("node".to_string(), stats.get_minotari_node_stats()),
+            ("tor".to_string(), stats.get_tor_stats()),
+            ("wallet".to_string(), stats.get_wallet_stats()),
+        ];
+        processes.extend(stats.get_additional_gpu_miners_stats().into_iter().map(
+            |(miner_type, stats)| {
+                let process = format!("gpu_miner_{}", miner_type.to_string().to_lowercase());
+                (process, stats)
+            },
+        ));
+        Self {
+            cpu_miner: sources.cpu_miner_status_rx.borrow().clone(),
+            gpu_miner: sources.gpu_miner_status_rx.borrow().clone(),
+            cpu_pools,
+            gpu_pools,
+            node: *sources.node_status_rx.borrow(),
+            wallet_balance,
+            processes,
+            cpu_devices,
+            gpu_devices,
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut writer = MetricsWriter::default();
+        self.render_mining(&mut writer);
+        self.render_pools(&mut writer);
+        self.render_node_and_wallet(&mut writer);
+        self.render_processes(&mut writer);
+        self.render_hardware(&mut writer);
+        writer.finish()
+    }
+
+    fn render_mining(&self, writer: &mut MetricsWriter) {
+        let cpu = &self.cpu_miner;
+        writer.gauge(
+            "universe_cpu_mining",
+            "Whether the CPU miner is running",
+            &[(vec![], bool_value(cpu.is_mining))],
+        );
+        writer.gauge(
+            "universe_cpu_hashrate",
+            "CPU hashrate in hashes per second",
+            &[(vec![], cpu.hash_rate)],
+        );
+        writer.counter(
+            "universe_cpu_shares_total",
+            "Shares submitted by the CPU miner",
+            &[
+                (vec![("result", "accepted")], cpu.shares.accepted as f64),
+                (vec![("result", "rejected")], cpu.shares.rejected as f64),
+                (vec![("result", "invalid")], cpu.shares.invalid as f64),
+            ],
+        );
+
+        let gpu = &self.gpu_miner;
+        writer.gauge(
+            "universe_gpu_mining",
+            "Whether the GPU miner is running",
+            &[(vec![], bool_value(gpu.is_mining))],
+        );
+        let mut gpu_hash_rates: Vec<(String, f64)> = gpu
+            .algorithm_hash_rates
+            .iter()
+            .map(|(algorithm, hash_rate)| (format!("{algorithm:?}"), *hash_rate))
+            .collect();
+        if gpu_hash_rates.is_empty() {
+            gpu_hash_rates.push((format!("{:?}", gpu.algorithm), gpu.hash_rate));
+        }
+        gpu_hash_rates.sort_by(|a, b| a.0.cmp(&b.0));
+        writer.gauge(
+            "universe_gpu_hashrate",
+            "GPU hashrate in hashes per second",
+            &gpu_hash_rates
+                .iter()
+                .map(|(algorithm, hash_rate)| (vec![("algorithm", algorithm.as_str())], *hash_rate))
+                .collect::<Vec<_>>(),
+        );
+        if let Some(telemetry) = &gpu.telemetry {
+            writer.counter(
+                "universe_gpu_shares_total",
+                "Shares submitted by the GPU miner",
+                &[
+                    (
+                        vec![("result", "accepted")],
+                        telemetry.accepted_shares as f64,
+                    ),
+                    (
+                        vec![("result", "rejected")],
+                        telemetry.rejected_shares as f64,
+                    ),
+                ],
+            );
+        }
+    }
+
+    fn render_pools(&self, writer: &mut MetricsWriter) {
+        let mut pools: Vec<(&str, &String, &PoolStatus)> = self
+            .cpu_pools
+            .iter()
+            .map(|(name, status)| ("cpu", name, status))
+            .chain(
+                self.gpu_pools
+                    .iter()
+                    .map(|(name, status)| ("gpu", name, status)),
+            )
+            .collect();
+        pools.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        let samples = |read: fn(&PoolStatus) -> f64| {
+            pools
+                .iter()
+                .map(|(kind, name, status)| {
+                    (vec![("kind", *kind), ("pool", name.as_str())], read(status))
+                })
+                .collect::<Vec<_>>()
+        };
+        writer.gauge(
+            "universe_pool_unpaid",
+            "Unpaid balance reported by the pool",
+            &samples(|status| status.unpaid),
+        );
+        writer.gauge(
+            "universe_pool_balance",
+            "Balance reported by the pool",
+            &samples(|status| status.balance),
+        );
+        writer.gauge(
+            "universe_pool_accepted_shares",
+            "Accepted shares reported by the pool",
+            &samples(|status| status.accepted_shares as f64),
+        );
+    }
+
+    fn render_node_and_wallet(&self, writer: &mut MetricsWriter) {
+        writer.gauge(
+            "universe_node_block_height",
+            "Block height of the base node",
+            &[(vec![], self.node.block_height as f64)],
+        );
+        writer.gauge(
+            "universe_node_synced",
+            "Whether the base node is synced",
+            &[(vec![], bool_value(self.node.is_synced))],
+        );
+        writer.gauge(
+            "universe_node_connections",
+            "Number of peers connected to the base node",
+            &[(vec![], self.node.num_connections as f64)],
+        );
+        if let Some(balance) = &self.wallet_balance {
+            writer.gauge(
+                "universe_wallet_balance_microminotari",
+                "Wallet balance in microMinotari",
+                &[
+                    (
+                        vec![("state", "available")],
+                        balance.available_balance.as_u64() as f64,
+                    ),
+                    (
+                        vec![("state", "timelocked")],
+                        balance.timelocked_balance.as_u64() as f64,
+                    ),
+                    (
+                        vec![("state", "pending_incoming")],
+                        balance.pending_incoming_balance.as_u64() as f64,
+                    ),
+                    (
+                        vec![("state", "pending_outgoing")],
+                        balance.pending_outgoing_balance.as_u64() as f64,
+                    ),
+                ],
+            );
+        }
+    }
+
+    fn render_processes(&self, writer: &mut MetricsWriter) {
+        let samples = |read: fn(&ProcessWatcherStats) -> f64| {
+            self.processes
+                .iter()
+                .map(|(process, stats)| (vec![("process", process.as_str())], read(stats)))
+                .collect::<Vec<_>>()
+        };
+        writer.gauge(
+            "universe_process_uptime_seconds",
+            "Time since the process was last started",
+            &samples(|stats| stats.current_uptime.as_secs_f64()),
+        );
+        writer.counter(
+            "universe_process_restarts_total",
+            "Restarts of the process by its watcher",
+            &samples(|stats| stats.num_restarts as f64),
+        );
+        writer.counter(
+            "universe_process_health_checks_total",
+            "Health checks run against the process",
+            &samples(|stats| stats.total_health_checks as f64),
+        );
+        writer.counter(
+            "universe_process_health_check_failures_total",
+            "Health checks that reported the process as unhealthy",
+            &samples(|stats| stats.num_failures as f64),
+        );
+        writer.counter(
+            "universe_process_health_check_duration_seconds_total",
+            "Time spent running health checks",
+            &samples(|stats| stats.total_health_check_duration.as_secs_f64()),
+        );
+        writer.gauge(
+            "universe_process_health_check_duration_seconds_max",
+            "Longest health check of the process",
+            &samples(|stats| stats.max_health_check_duration.as_secs_f64()),
+        );
+    }
+
+    fn render_hardware(&self, writer: &mut MetricsWriter) {
+        let devices: Vec<(&str, String, &str)> = self
+            .cpu_devices
+            .iter()
+            .enumerate()
+            .map(|(index, device)| ("cpu", index.to_string(), device.name.as_str()))
+            .chain(
+                self.gpu_devices
+                    .iter()
+                    .enumerate()
+                    .map(|(index, device)| ("gpu", index.to_string(), device.name.as_str())),
+            )
+            .collect();
+        let parameters: Vec<_> = self
+            .cpu_devices
+            .iter()
+            .map(|device| device.parameters.as_ref())
+            .chain(
+                self.gpu_devices
+                    .iter()
+                    .map(|device| device.parameters.as_ref()),
+            )
+            .collect();
+        let samples = |read: fn(&DeviceParameters) -> Option<f32>| {
+            devices
+                .iter()
+                .zip(&parameters)
+                .filter_map(|((kind, index, name), parameters)| {
+                    let value = read((*parameters)?)?;
+                    Some((
+                        vec![("kind", *kind), ("index", index.as_str()), ("name", *name)],
+                        f64::from(value),
+                    ))
+                })
+                .collect::<Vec<_>>()
+        };
+        writer.gauge(
+            "universe_device_temperature_celsius",
+            "Current temperature of the device",
+            &samples(|parameters| Some(parameters.current_temperature)),
+        );
+        writer.gauge(
+            "universe_device_usage_percent",
+            "Current usage of the device",
+            &samples(|parameters| Some(parameters.usage_percentage)),
+        );
+        writer.gauge(
+            "universe_device_power_watts",
+            "Current power draw of the device",
+            &samples(|parameters| parameters.power_usage_watts),
+        );
+    }
+}
+
+fn bool_value(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Writes metric families in the Prometheus text exposition format
+#[derive(Default)]
+struct MetricsWriter {
+    output: String,
+}
+
+impl MetricsWriter {
+    fn gauge(&mut self, name: &str, help: &str, samples: &[(Vec<(&str, &str)>, f64)]) {
+        self.family(name, help, "gauge", samples);
+    }
+
+    fn counter(&mut self, name: &str, help: &str, samples: &[(Vec<(&str, &str)>, f64)]) {
+        self.family(name, help, "counter", samples);
+    }
+
+    fn family(&mut self, name: &str, help: &str, kind: &str, samples: &[(Vec<(&str, &str)>, f64)]) {
+        if samples.is_empty() {
+            return;
+        }
+        let _unused = writeln!(self.output, "# HELP {name} {help}");
+        let _unused = writeln!(self.output, "# TYPE {name} {kind}");
+        for (labels, value) in samples {
+            self.output.push_str(name);
+            if !labels.is_empty() {
+                let labels: Vec<String> = labels
+                    .iter()
+                    .map(|(key, value)| format!("{key}=\"{}\"", escape_label_value(value)))
+                    .collect();
+                let _unused = write!(self.output, "{{{}}}", labels.join(","));
+            }
+            let _unused = writeln!(self.output, " {}", format_value(*value));
+        }
+    }
+
+    fn finish(self) -> String {
+        self.output
+    }
+}
+
+/// Infinite values are spelled differently than Rust formats them
+fn format_value(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_families_in_text_format() {
+        let mut writer = MetricsWriter::default();
+        writer.gauge("up", "Whether it is up", &[(vec![], 1.0)]);
+        writer.counter(
+            "shares_total",
+            "Shares",
+            &[(vec![("result", "accepted"), ("pool", "a\"b\\c\nd")], 12.0)],
+        );
+        writer.gauge("empty", "Not written", &[]);
+        assert_eq!(
+            writer.finish(),
+            "# HELP up Whether it is up\n# TYPE up gauge\nup 1\n\
+             # HELP shares_total Shares\n# TYPE shares_total counter\n\
+             shares_total{result=\"accepted\",pool=\"a\\\"b\\\\c\\nd\"} 12\n"
+        );
+    }
+
+    #[test]
+    fn renders_snapshot() {
+        let mut snapshot = MetricsSnapshot::default();
+        snapshot.cpu_miner.is_mining = true;
+        snapshot.cpu_miner.hash_rate = 1500.5;
+        snapshot.cpu_miner.shares.accepted = 7;
+        snapshot.node.block_height = 42;
+        snapshot.cpu_pools.insert(
+            "LuckyPool".to_string(),
+            PoolStatus {
+                unpaid: 0.25,
+                ..Default::default()
+            },
+        );
+        let output = snapshot.render();
+        assert!(output.contains("universe_cpu_mining 1\n"));
+        assert!(output.contains("universe_cpu_hashrate 1500.5\n"));
+        assert!(output.contains("universe_cpu_shares_total{result=\"accepted\"} 7\n"));
+        assert!(output.contains("universe_gpu_hashrate{algorithm=\"C29\"} 0\n"));
+        assert!(output.contains("universe_pool_unpaid{kind=\"cpu\",pool=\"LuckyPool\"} 0.25\n"));
+        assert!(output.contains("universe_node_block_height 42\n"));
+        assert!(!output.contains("universe_wallet_balance_microminotari"));
+        assert!(!output.contains("universe_device_temperature_celsius"));
+    }
+}
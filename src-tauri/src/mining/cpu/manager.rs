@@ -20,9 +20,12 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{sync::LazyLock, thread};
+use std::{
+    sync::{atomic::Ordering, LazyLock},
+    thread,
+};
 
-use log::{error, info};
+use log::{error, info, warn};
 use tari_shutdown::Shutdown;
 use tauri::{AppHandle, Manager};
 use tauri_plugin_sentry::sentry;
@@ -39,15 +42,22 @@ use crate::{
         config_mining::ConfigMining,
         config_pools::ConfigPools,
         config_wallet::ConfigWallet,
-        pools::{cpu_pools::CpuPool, PoolOrigin},
+        pools::{cpu_pools::CpuPool, BasePoolData},
         trait_config::ConfigImpl,
     },
     events_emitter::EventsEmitter,
     internal_wallet::InternalWallet,
     mining::{
-        cpu::{miners::xmrig::XmrigAdapter, CpuMinerStatus},
-        pools::{cpu_pool_manager::CpuPoolManager, PoolManagerInterfaceTrait},
-        CpuConnectionType, MinerControlsState,
+        cpu::{
+            miners::{xmrig::XmrigAdapter, xmrig_config::XmrigConfig},
+            CpuMinerStatus,
+        },
+        pools::{
+            cpu_pool_manager::CpuPoolManager,
+            pool_failover::{PoolFailoverKind, PoolFailoverMiner, PoolFailoverPayload},
+            PoolManagerInterfaceTrait,
+        },
+        CpuConnectionType, MinerControlsState, PoolEndpoint,
     },
     node::node_adapter::BaseNodeStatus,
     process_adapter::ProcessAdapter,
@@ -78,6 +88,10 @@ pub struct CpuManager {
     pool: Option<CpuPool>,
     #[allow(dead_code)]
     intensity_percentage: Option<u32>,
+    /// Config the running xmrig was started with or last retuned to
+    running_config: Option<XmrigConfig>,
+    /// Pools the running xmrig was given, in failover order
+    failover_pools: Vec<BasePoolData<CpuPool>>,
 }
 
 impl CpuManager {
@@ -100,6 +114,8 @@ impl CpuManager {
             tari_address: None,
             pool: None,
             intensity_percentage: None,
+            running_config: None,
+            failover_pools: Vec::new(),
         }
     }
 
@@ -158,6 +174,24 @@ impl CpuManager {
             return Err(anyhow::anyhow!("CPU mining is disabled"));
         }
 
+        if self.process_watcher.is_running() && self.is_paused() {
+            match self.process_watcher.adapter.api_client().resume().await {
+                Ok(_) => {
+                    info!(target: LOG_TARGET_APP_LOGIC, "Resumed paused cpu miner");
+                    self.process_watcher
+                        .adapter
+                        .is_paused
+                        .store(false, Ordering::SeqCst);
+                    // The mining mode may have changed while paused
+                    return Box::pin(self.apply_selected_mining_mode()).await;
+                }
+                Err(e) => {
+                    warn!(target: LOG_TARGET_APP_LOGIC, "Could not resume paused cpu miner, restarting it: {e}");
+                    self.stop_mining().await?;
+                }
+            }
+        }
+
         if self.process_watcher.is_running() {
             info!(target: LOG_TARGET_APP_LOGIC, "CPU miner is already running");
             return Ok(());
@@ -191,28 +225,26 @@ impl CpuManager {
             let mmproxy_manager = &app_state.mm_proxy_manager;
 
             if *ConfigPools::content().await.cpu_pool_enabled() {
-                let pool_url = ConfigPools::content()
-                    .await
-                    .current_cpu_pool()
-                    .pool_url
-                    .clone();
+                let pools_config = ConfigPools::content().await;
                 let tari_address = InternalWallet::tari_address().await;
 
-                // Worker name format depends on the pool
-                // LuckyPool: .Tari-Universe
-                // Kryptex: /Tari-Universe
-                // SupportXTM: Not specified so we use None
-                let worker_name = match ConfigPools::content().await.current_cpu_pool().pool_origin
-                {
-                    PoolOrigin::LuckyPool => Some(".Tari-universe"),
-                    PoolOrigin::SupportXTM => None,
-                    PoolOrigin::Kryptex => Some("/Tari-universe"),
-                };
+                // xmrig handles the failover itself, the current pool goes first
+                self.failover_pools = pools_config.cpu_failover_pools();
+                let mut endpoints = self.failover_pools.iter().map(|pool| PoolEndpoint {
+                    pool_url: pool.pool_url.clone(),
+                    worker_name: pool.pool_origin.worker_name(pools_config.worker_name()),
+                });
+                let primary = endpoints
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("No cpu pool configured"))?;
 
                 self.process_watcher.adapter.connection_type = CpuConnectionType::Pool {
-                    pool_url,
-                    worker_name: worker_name.map(|s| s.to_string()),
+                    pool_url: primary.pool_url,
+                    worker_name: primary.worker_name,
+                    fallback_pools: endpoints.collect(),
                 };
+                self.process_watcher.adapter.pool_failover_timeout_secs =
+                    Some(*pools_config.pool_failover_timeout_secs());
                 self.process_watcher.adapter.address = tari_address.to_base58();
             } else {
                 let host_name = "127.0.0.1".to_string();
@@ -222,27 +254,18 @@ impl CpuManager {
 
                 self.process_watcher.adapter.connection_type =
                     CpuConnectionType::LocalMMProxy { local_proxy_url };
+                self.failover_pools = Vec::new();
                 self.process_watcher.adapter.address = monero_address.to_string();
             }
 
             let binary = crate::binaries::Binaries::Xmrig;
 
-            let cpu_usage_percentage = ConfigMining::content()
-                .await
-                .get_selected_cpu_usage_percentage();
-
-            if cpu_usage_percentage <= 1 {
-                self.process_watcher.adapter.cpu_threads =
-                    Some(Self::determine_number_of_cores_to_use(10).await);
-
-                self.process_watcher.adapter.extra_options =
-                    vec!["--randomx-mode=light".to_string()]
-            } else {
-                self.process_watcher.adapter.cpu_threads =
-                    Some(Self::determine_number_of_cores_to_use(cpu_usage_percentage).await);
-
-                self.process_watcher.adapter.extra_options = vec!["--randomx-mode=fast".to_string()]
-            }
+            self.load_selected_tuning().await;
+            self.process_watcher
+                .adapter
+                .is_paused
+                .store(false, Ordering::SeqCst);
+            let running_config = self.process_watcher.adapter.build_config(&log_path);
 
             self.process_watcher
                 .start(
@@ -254,6 +277,7 @@ impl CpuManager {
                     task_tracker,
                 )
                 .await?;
+            self.running_config = Some(running_config);
 
             if self.connection_type.is_pool() {
                 CpuPoolManager::start_stats_watcher().await;
@@ -269,6 +293,145 @@ impl CpuManager {
         self.process_watcher.is_running()
     }
 
+    /// Latest CPU miner statistics, as sent on the CPU status channel.
+    pub fn status(&self) -> CpuMinerStatus {
+        self.cpu_external_status_channel.borrow().clone()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.process_watcher
+            .adapter
+            .is_paused
+            .load(Ordering::SeqCst)
+    }
+
+    /// Sets the thread count and tuning profile of the selected mining mode on the adapter.
+    async fn load_selected_tuning(&mut self) {
+        let mining_config = ConfigMining::content().await;
+        let cpu_usage_percentage = mining_config.get_selected_cpu_usage_percentage();
+
+        // Light RandomX mode on low usage, the tuning profile of the mode selects it as well
+        self.process_watcher.adapter.cpu_threads = if cpu_usage_percentage <= 1 {
+            Some(Self::determine_number_of_cores_to_use(10).await)
+        } else {
+            Some(Self::determine_number_of_cores_to_use(cpu_usage_percentage).await)
+        };
+        self.process_watcher.adapter.tuning = mining_config.get_selected_cpu_tuning_profile();
+        self.process_watcher.adapter.isolation = mining_config.get_selected_process_isolation();
+    }
+
+    /// Pauses hashing through the xmrig HTTP API, keeping the RandomX dataset in memory.
+    /// Falls back to stopping the miner when the API request fails.
+    pub async fn pause_mining(&mut self) -> Result<(), anyhow::Error> {
+        if !self.is_running() || self.is_paused() {
+            return Ok(());
+        }
+
+        info!(target: LOG_TARGET_APP_LOGIC, "Pausing cpu miner");
+        match self.process_watcher.adapter.api_client().pause().await {
+            Ok(_) => {
+                self.process_watcher
+                    .adapter
+                    .is_paused
+                    .store(true, Ordering::SeqCst);
+                EventsEmitter::emit_update_cpu_miner_state(MinerControlsState::Stopped).await;
+                SystemTrayManager::send_event(SystemTrayEvents::CpuMiningActivity(false)).await;
+                info!(target: LOG_TARGET_APP_LOGIC, "Paused cpu miner");
+                Ok(())
+            }
+            Err(e) => {
+                warn!(target: LOG_TARGET_APP_LOGIC, "Could not pause cpu miner through HTTP API, stopping it instead: {e}");
+                self.stop_mining().await
+            }
+        }
+    }
+
+    /// Resumes hashing paused with [`CpuManager::pause_mining`].
+    /// Falls back to restarting the miner when the API request fails.
+    pub async fn resume_mining(&mut self) -> Result<(), anyhow::Error> {
+        if !self.is_paused() {
+            return Ok(());
+        }
+
+        info!(target: LOG_TARGET_APP_LOGIC, "Resuming cpu miner");
+        match self.process_watcher.adapter.api_client().resume().await {
+            Ok(_) => {
+                self.process_watcher
+                    .adapter
+                    .is_paused
+                    .store(false, Ordering::SeqCst);
+                EventsEmitter::emit_update_cpu_miner_state(MinerControlsState::Started).await;
+                SystemTrayManager::send_event(SystemTrayEvents::CpuMiningActivity(true)).await;
+                info!(target: LOG_TARGET_APP_LOGIC, "Resumed cpu miner");
+                // The mining mode may have changed while paused
+                self.apply_selected_mining_mode().await
+            }
+            Err(e) => {
+                warn!(target: LOG_TARGET_APP_LOGIC, "Could not resume cpu miner through HTTP API, restarting it instead: {e}");
+                self.stop_mining().await?;
+                self.start_mining().await
+            }
+        }
+    }
+
+    /// Applies the thread count and tuning of the selected mining mode to the running miner.
+    ///
+    /// Changes xmrig can apply in place are sent through its HTTP API, the miner is only
+    /// restarted when the change requires it or the API request fails. Does nothing when
+    /// the miner is not running, the next start picks up the selected mode. A paused miner
+    /// is left untouched as both a config update and a restart would resume hashing,
+    /// the selected mode is applied once it resumes.
+    pub async fn apply_selected_mining_mode(&mut self) -> Result<(), anyhow::Error> {
+        if !self.is_running() {
+            return Ok(());
+        }
+        if self.is_paused() {
+            info!(target: LOG_TARGET_APP_LOGIC, "Cpu miner is paused, the selected tuning is applied when it resumes");
+            return Ok(());
+        }
+        let Some(log_path) = self
+            .app_handle
+            .as_ref()
+            .and_then(|app_handle| app_handle.path().app_log_dir().ok())
+        else {
+            return Err(anyhow::anyhow!("Could not get log dir"));
+        };
+
+        self.load_selected_tuning().await;
+        let new_config = self.process_watcher.adapter.build_config(&log_path);
+        let requires_restart = match &self.running_config {
+            Some(running_config) if *running_config == new_config => {
+                info!(target: LOG_TARGET_APP_LOGIC, "Cpu miner already runs with the selected tuning");
+                return Ok(());
+            }
+            Some(running_config) => running_config.requires_restart(&new_config),
+            None => true,
+        };
+
+        if !requires_restart {
+            match self
+                .process_watcher
+                .adapter
+                .api_client()
+                .update_config(&new_config)
+                .await
+            {
+                Ok(_) => {
+                    info!(target: LOG_TARGET_APP_LOGIC, "Retuned cpu miner without restart");
+                    self.running_config = Some(new_config);
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!(target: LOG_TARGET_APP_LOGIC, "Could not retune cpu miner through HTTP API, restarting it instead: {e}");
+                }
+            }
+        }
+
+        info!(target: LOG_TARGET_APP_LOGIC, "Restarting cpu miner to apply the selected tuning");
+        self.stop_mining().await?;
+        self.start_mining().await
+    }
+
     async fn determine_number_of_cores_to_use(cpu_usage_percentage: u32) -> u32 {
         let max_cpu_available = thread::available_parallelism();
         let max_cpu_available = match max_cpu_available {
@@ -297,6 +460,11 @@ impl CpuManager {
         {
             self.process_watcher.status_monitor = None;
             self.process_watcher.stop().await?;
+            self.process_watcher
+                .adapter
+                .is_paused
+                .store(false, Ordering::SeqCst);
+            self.running_config = None;
             self.status_thread_shutdown.trigger();
             let _res = self
                 .cpu_external_status_channel
@@ -334,6 +502,8 @@ impl CpuManager {
         let cpu_external_status_channel = self.cpu_external_status_channel.clone();
         let node_status_channel = self.node_status_channel.clone();
         let connection_type = self.connection_type.clone();
+        let failover_pools = self.failover_pools.clone();
+        let mut last_pool_address: Option<String> = None;
 
         let mut internal_shutdown_signal = self.status_thread_shutdown.to_signal();
         let mut global_shutdown_signal =
@@ -367,6 +537,15 @@ impl CpuManager {
                             let _res = cpu_external_status_channel.send(paresd_status.clone());
                             EventsEmitter::emit_cpu_mining_update(paresd_status.clone()).await;
 
+                            if let Some(pool_address) = paresd_status.connection.pool_address.clone() {
+                                if let Some(previous_pool_address) = last_pool_address
+                                    .replace(pool_address.clone())
+                                    .filter(|previous| *previous != pool_address)
+                                {
+                                    Self::handle_pool_switch(&failover_pools, previous_pool_address, pool_address).await;
+                                }
+                            }
+
                             SystemTrayManager::send_event(SystemTrayEvents::CpuHashrate(paresd_status.hash_rate)).await;
                         } else {
                             break;
@@ -377,6 +556,37 @@ impl CpuManager {
         });
     }
 
+    /// Reports a pool switch done by xmrig, which moves between its pools on its own.
+    async fn handle_pool_switch(
+        failover_pools: &[BasePoolData<CpuPool>],
+        from_pool_address: String,
+        to_pool_address: String,
+    ) {
+        let pool_name = |pool_address: &String| {
+            failover_pools
+                .iter()
+                .find(|pool| pool.pool_url == *pool_address)
+                .map_or_else(|| pool_address.clone(), |pool| pool.pool_name.clone())
+        };
+        let kind = if failover_pools
+            .first()
+            .is_some_and(|primary| primary.pool_url == to_pool_address)
+        {
+            PoolFailoverKind::SwitchBack
+        } else {
+            PoolFailoverKind::Failover
+        };
+
+        let payload = PoolFailoverPayload {
+            miner: PoolFailoverMiner::Cpu,
+            kind,
+            from_pool: pool_name(&from_pool_address),
+            to_pool: pool_name(&to_pool_address),
+        };
+        info!(target: LOG_TARGET_APP_LOGIC, "Cpu miner switched pools: {payload:?}");
+        EventsEmitter::emit_pool_failover(payload).await;
+    }
+
     async fn handle_local_mm_proxy_connection_type_status_change(
         gpu_status: CpuMinerStatus,
         node_status_reciever: Option<Receiver<BaseNodeStatus>>,
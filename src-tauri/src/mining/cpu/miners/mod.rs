@@ -21,3 +21,5 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 pub mod xmrig;
+pub mod xmrig_api;
+pub mod xmrig_config;
@@ -24,20 +24,29 @@ use anyhow::Error;
 use async_trait::async_trait;
 use log::{info, warn};
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use tari_shutdown::Shutdown;
 use tokio::sync::watch::Sender;
 use uuid::Uuid;
 
-use crate::mining::cpu::{CpuMinerConnectionStatus, CpuMinerStatus};
+use crate::binaries::Binaries;
+use crate::mining::cpu::miners::xmrig_api::XmrigApiClient;
+use crate::mining::cpu::miners::xmrig_config::{XmrigConfig, XmrigPoolConfig};
+use crate::mining::cpu::tuning::CpuTuningProfile;
+use crate::mining::cpu::{
+    CpuMinerConnectionStatus, CpuMinerHashRates, CpuMinerHugePages, CpuMinerShares, CpuMinerStatus,
+};
 use crate::mining::CpuConnectionType;
 use crate::port_allocator::PortAllocator;
 use crate::process_adapter::{
     HandleUnhealthyResult, HealthStatus, ProcessAdapter, ProcessInstance, ProcessStartupSpec,
     StatusMonitor,
 };
+use crate::process_isolation::ProcessIsolation;
+use crate::process_restart_policy::StderrTail;
 use crate::setup::setup_manager::SetupManager;
 use crate::{LOG_TARGET_APP_LOGIC, LOG_TARGET_STATUSES};
 
@@ -47,8 +56,14 @@ pub struct XmrigAdapter {
     pub http_api_token: String,
     pub http_api_port: u16,
     pub cpu_threads: Option<u32>,
-    pub extra_options: Vec<String>,
+    pub tuning: CpuTuningProfile,
+    /// Scheduling and resource limits of the selected mining mode, applied when xmrig starts
+    pub isolation: ProcessIsolation,
+    /// Seconds the current pool has to be unreachable before xmrig moves on to a fallback pool
+    pub pool_failover_timeout_secs: Option<u64>,
     pub summary_broadcast: Sender<CpuMinerStatus>,
+    /// Set while hashing is paused through the HTTP API, the zero hashrate is expected then
+    pub is_paused: Arc<AtomicBool>,
 }
 
 impl XmrigAdapter {
@@ -61,8 +76,44 @@ impl XmrigAdapter {
             http_api_token: http_api_token.clone(),
             http_api_port,
             cpu_threads: None,
-            extra_options: Vec::new(),
+            tuning: CpuTuningProfile::default(),
+            isolation: ProcessIsolation::default(),
+            pool_failover_timeout_secs: None,
             summary_broadcast,
+            is_paused: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn api_client(&self) -> XmrigApiClient {
+        XmrigApiClient::new(self.http_api_port, self.http_api_token.clone())
+    }
+
+    /// Builds the xmrig config for the current connection and tuning.
+    ///
+    /// ### Parameters
+    /// * `log_dir` - Application log directory, the xmrig log file is placed in its `xmrig` subfolder
+    pub fn build_config(&self, log_dir: &Path) -> XmrigConfig {
+        let xmrig_log_file = log_dir.join("xmrig").join("xmrig.log");
+        let log_file = match xmrig_log_file.to_str() {
+            Some(log_file) => Some(log_file.to_string()),
+            None => {
+                warn!(target: LOG_TARGET_APP_LOGIC, "Could not convert xmrig log file path to string");
+                warn!(target: LOG_TARGET_APP_LOGIC, "Log file will not be added to xmrig config");
+                None
+            }
+        };
+
+        let config = XmrigConfig::build(
+            &self.tuning,
+            self.cpu_threads,
+            XmrigPoolConfig::from_connection_type(&self.connection_type, &self.address),
+            self.http_api_port,
+            &self.http_api_token,
+            log_file,
+        );
+        match self.pool_failover_timeout_secs {
+            Some(timeout_secs) => config.with_failover_timeout(timeout_secs),
+            None => config,
         }
     }
 }
@@ -74,87 +125,32 @@ impl ProcessAdapter for XmrigAdapter {
     fn spawn_inner(
         &self,
         data_dir: PathBuf,
-        _config_dir: PathBuf,
+        config_dir: PathBuf,
         log_dir: PathBuf,
         binary_version_path: PathBuf,
         _is_first_start: bool,
     ) -> Result<(ProcessInstance, Self::StatusMonitor), anyhow::Error> {
         let xmrig_shutdown = Shutdown::new();
 
-        let mut args = vec![];
-
-        let connection_type_args = match &self.connection_type {
-            CpuConnectionType::LocalMMProxy { local_proxy_url } => {
-                let extra_args = vec![
-                    "--user".to_string(),
-                    self.address.to_string(),
-                    "--daemon".to_string(),
-                    "--retry-pause=1".to_string(),
-                    format!("--url={}", local_proxy_url),
-                    "--coin=monero".to_string(), // is it needed? if yes then is it needed for pool mining?
-                ];
-                extra_args
-            }
-            CpuConnectionType::Pool {
-                pool_url,
-                worker_name,
-            } => {
-                let mut extra_args = vec![];
-                let extended_user_address = match worker_name {
-                    Some(worker_name) => format!("{}{}", self.address, worker_name),
-                    None => self.address.to_string(),
-                };
-                extra_args.push(format!("--url={}", pool_url));
-                extra_args.push("--user".to_string());
-                extra_args.push(extended_user_address);
-                extra_args
-            }
-        };
-
-        args.extend(connection_type_args);
+        std::fs::create_dir_all(log_dir.join("xmrig"))?;
 
-        let xmrig_log_file = log_dir.join("xmrig").join("xmrig.log");
-        std::fs::create_dir_all(
-            xmrig_log_file
-                .parent()
-                .expect("Could not get xmrig root log dir"),
-        )?;
-
-        let xmrig_log_file_parent = xmrig_log_file
-            .parent()
-            .ok_or_else(|| anyhow::anyhow!("Could not get parent directory of xmrig log file"))?;
-
-        match xmrig_log_file.to_str() {
-            Some(log_file) => {
-                args.push(format!("--log-file={}", &log_file));
-            }
-            None => {
-                warn!(target: LOG_TARGET_APP_LOGIC, "Could not convert xmrig log file path to string");
-                warn!(target: LOG_TARGET_APP_LOGIC, "Logs argument will not be added to xmrig");
-            }
-        };
-
-        std::fs::create_dir_all(xmrig_log_file_parent).unwrap_or_else(|error| {
-            warn!(target: LOG_TARGET_APP_LOGIC, "Could not create xmrig log file parent directory - {error}");
-        });
+        let xmrig_config = self.build_config(&log_dir);
+        let xmrig_config_file = config_dir.join("xmrig").join("config.json");
+        xmrig_config.write_to_file(&xmrig_config_file)?;
+        info!(target: LOG_TARGET_APP_LOGIC, "Xmrig config written to {:?} with tuning {:?}", xmrig_config_file, self.tuning);
 
-        args.push(format!("--http-port={}", self.http_api_port));
-        args.push(format!("--http-access-token={}", self.http_api_token));
-        args.push("--donate-level=1".to_string());
-
-        // don't specify threads for ludicrous mode
-        if let Some(cpu_threads) = self.cpu_threads {
-            args.push(format!("--threads={cpu_threads}"));
-        }
-        args.push("--verbose".to_string());
-        for extra_option in &self.extra_options {
-            args.push(extra_option.clone());
-        }
+        let args = vec![format!(
+            "--config={}",
+            xmrig_config_file
+                .to_str()
+                .ok_or_else(|| anyhow::anyhow!("Could not convert xmrig config path to string"))?
+        )];
 
         Ok((
             ProcessInstance {
                 shutdown: xmrig_shutdown,
                 handle: None,
+                stderr_tail: StderrTail::default(),
                 startup_spec: ProcessStartupSpec {
                     file_path: binary_version_path,
                     envs: None,
@@ -162,12 +158,15 @@ impl ProcessAdapter for XmrigAdapter {
                     data_dir,
                     pid_file_name: self.pid_file_name().to_string(),
                     name: self.name().to_string(),
+                    binary: Binaries::Xmrig,
+                    isolation: self.isolation.clone(),
                 },
             },
             XmrigStatusMonitor {
                 summary_broadcast: self.summary_broadcast.clone(),
                 access_token: self.http_api_token.clone(),
                 http_api_port: self.http_api_port.to_string(),
+                is_paused: self.is_paused.clone(),
             },
         ))
     }
@@ -190,6 +189,7 @@ pub struct XmrigStatusMonitor {
     http_api_port: String,
     access_token: String,
     summary_broadcast: Sender<CpuMinerStatus>,
+    is_paused: Arc<AtomicBool>,
 }
 
 #[async_trait]
@@ -228,6 +228,10 @@ impl StatusMonitor for XmrigStatusMonitor {
                 Ok(status) => {
                     let _result = self.summary_broadcast.send(status.clone());
 
+                    if self.is_paused.load(Ordering::SeqCst) {
+                        return HealthStatus::Healthy;
+                    }
+
                     if status.hash_rate.le(&0.0) {
                         warn!(target: LOG_TARGET_STATUSES, "Xmrig hash rate is 0");
                         return HealthStatus::Unhealthy;
@@ -252,18 +256,124 @@ impl StatusMonitor for XmrigStatusMonitor {
 
 #[derive(Deserialize, Debug, Clone)]
 pub(crate) struct Summary {
+    #[serde(default)]
+    pub(crate) uptime: u64,
+    pub(crate) algo: Option<String>,
     pub(crate) connection: Connection,
     pub(crate) hashrate: Hashrate,
+    #[serde(default)]
+    pub(crate) results: Results,
+    pub(crate) hugepages: Option<HugePages>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Connection {
     pub(crate) uptime: u64,
+    pub(crate) pool: Option<String>,
+    pub(crate) ping: Option<u64>,
+    #[serde(default)]
+    pub(crate) diff: u64,
+    #[serde(default)]
+    pub(crate) accepted: u64,
+    #[serde(default)]
+    pub(crate) rejected: u64,
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Hashrate {
     pub(crate) total: Vec<Option<f64>>,
+    pub(crate) highest: Option<f64>,
+    #[serde(default)]
+    pub(crate) threads: Vec<Vec<Option<f64>>>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Results {
+    #[serde(default)]
+    pub(crate) diff_current: u64,
+    #[serde(default)]
+    pub(crate) shares_total: u64,
+}
+
+/// Older xmrig versions only report whether huge pages are used at all
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum HugePages {
+    Pages([u64; 2]),
+    Enabled(bool),
+}
+
+impl Hashrate {
+    // Every hashrate vector holds the averages over:
+    // index 0: 10 seconds avarage hashrate
+    // index 1: 60 seconds avarage hashrate
+    // index 2: 15 minutes avarage hashrate
+    fn to_hash_rates(values: &[Option<f64>]) -> CpuMinerHashRates {
+        CpuMinerHashRates {
+            ten_seconds: values.first().and_then(|v| *v),
+            sixty_seconds: values.get(1).and_then(|v| *v),
+            fifteen_minutes: values.get(2).and_then(|v| *v),
+        }
+    }
+}
+
+impl From<Summary> for CpuMinerStatus {
+    fn from(summary: Summary) -> Self {
+        let hash_rates = Hashrate::to_hash_rates(&summary.hashrate.total);
+        let avarage_hash_rate = hash_rates
+            .fifteen_minutes
+            .or(hash_rates.sixty_seconds)
+            .or(hash_rates.ten_seconds);
+
+        let accepted = summary.connection.accepted;
+        let rejected = summary.connection.rejected;
+        let difficulty = if summary.connection.diff > 0 {
+            summary.connection.diff
+        } else {
+            summary.results.diff_current
+        };
+        let huge_pages = match summary.hugepages {
+            Some(HugePages::Pages([allocated, total])) => CpuMinerHugePages { allocated, total },
+            Some(HugePages::Enabled(true)) => CpuMinerHugePages {
+                allocated: 1,
+                total: 1,
+            },
+            Some(HugePages::Enabled(false)) | None => CpuMinerHugePages::default(),
+        };
+
+        CpuMinerStatus {
+            is_mining: true,
+            estimated_earnings: 0,
+            hash_rate: avarage_hash_rate.unwrap_or(0.0),
+            connection: CpuMinerConnectionStatus {
+                is_connected: summary.connection.uptime > 0,
+                pool_address: summary.connection.pool,
+                ping: summary.connection.ping,
+                uptime: summary.connection.uptime,
+            },
+            hash_rates,
+            thread_hash_rates: summary
+                .hashrate
+                .threads
+                .iter()
+                .map(|thread| Hashrate::to_hash_rates(thread))
+                .collect(),
+            highest_hash_rate: summary.hashrate.highest,
+            shares: CpuMinerShares {
+                accepted,
+                rejected,
+                invalid: summary
+                    .results
+                    .shares_total
+                    .saturating_sub(accepted)
+                    .saturating_sub(rejected),
+            },
+            difficulty,
+            algorithm: summary.algo,
+            uptime: summary.uptime,
+            huge_pages,
+        }
+    }
 }
 
 impl XmrigStatusMonitor {
@@ -295,27 +405,100 @@ impl XmrigStatusMonitor {
 
         info!(target: LOG_TARGET_STATUSES, "Xmrig status: {:?}", body);
 
-        // body.hashrate.total is a vector of Option<f64> which elements are:
-        // index 0: 10 seconds avarage hashrate
-        // index 1: 60 seconds avarage hashrate
-        // index 2: 15 minutes avarage hashrate
+        Ok(CpuMinerStatus::from(body))
+    }
+}
 
-        let (ten_second_hash_rate, sixty_second_hash_rate, fifteen_minute_hash_rate) = (
-            body.hashrate.total.first().and_then(|v| *v),
-            body.hashrate.total.get(1).and_then(|v| *v),
-            body.hashrate.total.get(2).and_then(|v| *v),
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    const SUMMARY: &str = r#"{
+        "id": "6e2a7e9b0a1c4d5f",
+        "uptime": 3600,
+        "algo": "rx/0",
+        "hugepages": [1168, 1168],
+        "results": {
+            "diff_current": 120001,
+            "shares_good": 40,
+            "shares_total": 45,
+            "avg_time": 80
+        },
+        "connection": {
+            "pool": "pool.example.com:3333",
+            "ip": "203.0.113.10",
+            "uptime": 3500,
+            "ping": 42,
+            "failures": 0,
+            "algo": "rx/0",
+            "diff": 120000,
+            "accepted": 40,
+            "rejected": 3
+        },
+        "hashrate": {
+            "total": [2100.5, 2050.25, null],
+            "highest": 2200.0,
+            "threads": [[1050.0, 1025.5, null], [1050.5, null, null]]
+        }
+    }"#;
+
+    #[test]
+    fn test_full_summary_is_parsed() {
+        let summary: Summary = serde_json::from_str(SUMMARY).unwrap();
+        let status = CpuMinerStatus::from(summary);
+
+        assert!(status.is_mining);
+        assert_eq!(status.hash_rate, 2050.25);
+        assert_eq!(status.hash_rates.ten_seconds, Some(2100.5));
+        assert_eq!(status.hash_rates.fifteen_minutes, None);
+        assert_eq!(status.highest_hash_rate, Some(2200.0));
+        assert_eq!(status.thread_hash_rates.len(), 2);
+        assert_eq!(status.thread_hash_rates[1].ten_seconds, Some(1050.5));
+        assert_eq!(status.thread_hash_rates[1].sixty_seconds, None);
+        assert_eq!(
+            status.shares,
+            CpuMinerShares {
+                accepted: 40,
+                rejected: 3,
+                invalid: 2,
+            }
+        );
+        assert_eq!(status.difficulty, 120000);
+        assert_eq!(status.algorithm.as_deref(), Some("rx/0"));
+        assert_eq!(status.uptime, 3600);
+        assert!(status.connection.is_connected);
+        assert_eq!(
+            status.connection.pool_address.as_deref(),
+            Some("pool.example.com:3333")
         );
-        let avarage_hash_rate = fifteen_minute_hash_rate
-            .or(sixty_second_hash_rate)
-            .or(ten_second_hash_rate);
+        assert_eq!(status.connection.ping, Some(42));
+        assert_eq!(
+            status.huge_pages,
+            CpuMinerHugePages {
+                allocated: 1168,
+                total: 1168,
+            }
+        );
+    }
 
-        Ok(CpuMinerStatus {
-            is_mining: true,
-            estimated_earnings: 0,
-            hash_rate: avarage_hash_rate.unwrap_or(0.0),
-            connection: CpuMinerConnectionStatus {
-                is_connected: body.connection.uptime > 0,
-            },
-        })
+    #[test]
+    fn test_minimal_summary_is_parsed() {
+        let summary: Summary = serde_json::from_str(
+            r#"{
+                "hugepages": false,
+                "connection": { "uptime": 0 },
+                "hashrate": { "total": [null, null, null] }
+            }"#,
+        )
+        .unwrap();
+        let status = CpuMinerStatus::from(summary);
+
+        assert_eq!(status.hash_rate, 0.0);
+        assert!(!status.connection.is_connected);
+        assert_eq!(status.shares, CpuMinerShares::default());
+        assert_eq!(status.huge_pages, CpuMinerHugePages::default());
+        assert!(status.thread_hash_rates.is_empty());
     }
 }
@@ -0,0 +1,111 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Client for the control endpoints of the xmrig HTTP API.
+//!
+//! Used to pause, resume and retune a running miner without restarting the process, which
+//! would throw away the initialised RandomX dataset. The endpoints are only available when
+//! the HTTP API is started in unrestricted mode.
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use log::info;
+use serde_json::json;
+
+use crate::mining::cpu::miners::xmrig_config::XmrigConfig;
+use crate::LOG_TARGET_APP_LOGIC;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug)]
+pub struct XmrigApiClient {
+    http_api_port: u16,
+    access_token: String,
+}
+
+impl XmrigApiClient {
+    pub fn new(http_api_port: u16, access_token: String) -> Self {
+        Self {
+            http_api_port,
+            access_token,
+        }
+    }
+
+    /// Stops hashing while keeping the process, pool connection and RandomX dataset alive.
+    pub async fn pause(&self) -> Result<(), anyhow::Error> {
+        self.json_rpc("pause").await
+    }
+
+    /// Resumes hashing after [`XmrigApiClient::pause`].
+    pub async fn resume(&self) -> Result<(), anyhow::Error> {
+        self.json_rpc("resume").await
+    }
+
+    /// Replaces the config of the running miner.
+    /// xmrig applies the new config in place and only restarts the parts that changed.
+    pub async fn update_config(&self, config: &XmrigConfig) -> Result<(), anyhow::Error> {
+        let response = reqwest::Client::new()
+            .put(format!("http://127.0.0.1:{}/1/config", self.http_api_port))
+            .bearer_auth(&self.access_token)
+            .timeout(REQUEST_TIMEOUT)
+            .json(config)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Xmrig rejected config update with status {}",
+                response.status()
+            ));
+        }
+        info!(target: LOG_TARGET_APP_LOGIC, "Xmrig config updated through HTTP API");
+        Ok(())
+    }
+
+    async fn json_rpc(&self, method: &str) -> Result<(), anyhow::Error> {
+        let response = reqwest::Client::new()
+            .post(format!("http://127.0.0.1:{}/json_rpc", self.http_api_port))
+            .bearer_auth(&self.access_token)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Xmrig {method} request failed with status {}",
+                response.status()
+            ));
+        }
+        let body: serde_json::Value = response.json().await?;
+        if let Some(error) = body.get("error").filter(|error| !error.is_null()) {
+            return Err(anyhow!("Xmrig {method} request failed: {error}"));
+        }
+        info!(target: LOG_TARGET_APP_LOGIC, "Xmrig {method} request succeeded");
+        Ok(())
+    }
+}
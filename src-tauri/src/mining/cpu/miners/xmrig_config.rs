@@ -0,0 +1,471 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Generation of the xmrig `config.json`.
+//!
+//! xmrig is started with `--config` pointing to a file written before every start, so all
+//! tuning options end up in one place instead of a long list of command line arguments.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::mining::cpu::tuning::{AsmMode, CpuTuningProfile, RandomXMode};
+use crate::mining::{CpuConnectionType, PoolEndpoint};
+
+const DONATE_LEVEL: u32 = 1;
+/// Seconds to wait before reconnecting, kept short as we only mine against known endpoints
+const RETRY_PAUSE: u32 = 1;
+/// Failed connection attempts before xmrig switches to the next pool, xmrig's own default
+const DEFAULT_RETRIES: u32 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct XmrigConfig {
+    /// Must stay disabled, otherwise xmrig rewrites the generated file
+    pub autosave: bool,
+    pub background: bool,
+    pub colors: bool,
+    pub donate_level: u32,
+    pub log_file: Option<String>,
+    pub retries: u32,
+    pub retry_pause: u32,
+    pub verbose: u32,
+    pub http: XmrigHttpConfig,
+    pub cpu: XmrigCpuConfig,
+    pub randomx: XmrigRandomXConfig,
+    pub pools: Vec<XmrigPoolConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct XmrigHttpConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub access_token: String,
+    pub restricted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct XmrigCpuConfig {
+    pub enabled: bool,
+    pub huge_pages: bool,
+    pub huge_pages_jit: bool,
+    pub priority: Option<u8>,
+    #[serde(rename = "yield")]
+    pub yield_between_rounds: bool,
+    pub max_threads_hint: u8,
+    /// `true`/`false` or the name of the optimisation variant
+    pub asm: Value,
+    /// Thread profile used for every algorithm, omitted to let xmrig configure the threads
+    #[serde(rename = "*", skip_serializing_if = "Option::is_none")]
+    pub threads_profile: Option<XmrigThreadsProfile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct XmrigThreadsProfile {
+    pub intensity: u32,
+    pub threads: u32,
+    /// CPU mask the threads are pinned to, -1 disables pinning
+    pub affinity: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct XmrigRandomXConfig {
+    /// Dataset initialisation threads, -1 uses all of them
+    pub init: i64,
+    pub mode: String,
+    #[serde(rename = "1gb-pages")]
+    pub one_gb_pages: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct XmrigPoolConfig {
+    pub url: String,
+    pub user: String,
+    pub pass: String,
+    pub coin: Option<String>,
+    pub daemon: bool,
+    pub keepalive: bool,
+    pub tls: bool,
+}
+
+impl XmrigPoolConfig {
+    /// Builds the pool entries for the given connection type, in failover order.
+    ///
+    /// ### Parameters
+    /// * `connection_type` - Local merge mining proxy or remote pool with its fallbacks
+    /// * `address` - Wallet address used as the pool user
+    pub fn from_connection_type(connection_type: &CpuConnectionType, address: &str) -> Vec<Self> {
+        match connection_type {
+            CpuConnectionType::LocalMMProxy { local_proxy_url } => vec![Self {
+                url: local_proxy_url.clone(),
+                user: address.to_string(),
+                pass: "x".to_string(),
+                coin: Some("monero".to_string()),
+                daemon: true,
+                keepalive: false,
+                tls: false,
+            }],
+            CpuConnectionType::Pool {
+                pool_url,
+                worker_name,
+                fallback_pools,
+            } => std::iter::once(PoolEndpoint {
+                pool_url: pool_url.clone(),
+                worker_name: worker_name.clone(),
+            })
+            .chain(fallback_pools.iter().cloned())
+            .map(|endpoint| Self {
+                url: endpoint.pool_url,
+                user: match endpoint.worker_name {
+                    Some(worker_name) => format!("{address}{worker_name}"),
+                    None => address.to_string(),
+                },
+                pass: "x".to_string(),
+                coin: None,
+                daemon: false,
+                keepalive: true,
+                tls: false,
+            })
+            .collect(),
+        }
+    }
+}
+
+impl XmrigConfig {
+    /// Builds the config for a single xmrig start.
+    ///
+    /// ### Parameters
+    /// * `tuning` - Tuning profile of the selected mining mode
+    /// * `cpu_threads` - Number of mining threads, ignored when the profile sets a max threads hint
+    /// * `pools` - Pools to connect to, in order of preference
+    /// * `http_api_port` - Port of the xmrig HTTP API
+    /// * `http_api_token` - Bearer token protecting the HTTP API
+    /// * `log_file` - Path of the xmrig log file
+    pub fn build(
+        tuning: &CpuTuningProfile,
+        cpu_threads: Option<u32>,
+        pools: Vec<XmrigPoolConfig>,
+        http_api_port: u16,
+        http_api_token: &str,
+        log_file: Option<String>,
+    ) -> Self {
+        let threads_profile = match (tuning.max_threads_hint, cpu_threads) {
+            (None, Some(threads)) => Some(XmrigThreadsProfile {
+                intensity: 1,
+                threads,
+                affinity: tuning
+                    .affinity_mask
+                    .and_then(|mask| i64::try_from(mask).ok())
+                    .unwrap_or(-1),
+            }),
+            _ => None,
+        };
+
+        Self {
+            autosave: false,
+            background: false,
+            colors: false,
+            donate_level: DONATE_LEVEL,
+            log_file,
+            retries: DEFAULT_RETRIES,
+            retry_pause: RETRY_PAUSE,
+            verbose: 1,
+            http: XmrigHttpConfig {
+                enabled: true,
+                host: "127.0.0.1".to_string(),
+                port: http_api_port,
+                access_token: http_api_token.to_string(),
+                // Pause, resume and config updates are rejected by a restricted API
+                restricted: false,
+            },
+            cpu: XmrigCpuConfig {
+                enabled: true,
+                huge_pages: tuning.huge_pages,
+                huge_pages_jit: tuning.huge_pages_jit,
+                priority: tuning.priority,
+                yield_between_rounds: tuning.yield_between_rounds,
+                max_threads_hint: tuning.max_threads_hint.unwrap_or(100),
+                asm: match tuning.asm {
+                    AsmMode::Auto => Value::Bool(true),
+                    AsmMode::Disabled => Value::Bool(false),
+                    AsmMode::Intel => Value::String("intel".to_string()),
+                    AsmMode::Ryzen => Value::String("ryzen".to_string()),
+                    AsmMode::Bulldozer => Value::String("bulldozer".to_string()),
+                },
+                threads_profile,
+            },
+            randomx: XmrigRandomXConfig {
+                init: tuning.randomx_init_threads.map(i64::from).unwrap_or(-1),
+                mode: match tuning.randomx_mode {
+                    RandomXMode::Auto => "auto".to_string(),
+                    RandomXMode::Fast => "fast".to_string(),
+                    RandomXMode::Light => "light".to_string(),
+                },
+                one_gb_pages: tuning.one_gb_pages,
+            },
+            pools,
+        }
+    }
+
+    /// Makes xmrig move on to the next pool once the current one was unreachable for about
+    /// the given time. xmrig keeps reconnecting to the first pool in the background and
+    /// switches back on its own once it recovers.
+    pub fn with_failover_timeout(mut self, timeout_secs: u64) -> Self {
+        self.retries = u32::try_from(timeout_secs / u64::from(RETRY_PAUSE))
+            .unwrap_or(u32::MAX)
+            .max(1);
+        self
+    }
+
+    /// Checks if moving from this config to `other` needs a miner restart.
+    ///
+    /// Thread, priority and yield changes are applied by xmrig in place. Changing pools, the HTTP
+    /// API or anything affecting how the RandomX dataset is allocated needs a new process.
+    pub fn requires_restart(&self, other: &XmrigConfig) -> bool {
+        self.pools != other.pools
+            || self.retries != other.retries
+            || self.http != other.http
+            || self.randomx != other.randomx
+            || self.log_file != other.log_file
+            || self.cpu.huge_pages != other.cpu.huge_pages
+            || self.cpu.huge_pages_jit != other.cpu.huge_pages_jit
+    }
+
+    /// Writes the config as JSON, creating the parent directory if needed.
+    pub fn write_to_file(&self, path: &Path) -> Result<(), anyhow::Error> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    const ADDRESS: &str = "tari_address";
+    const TOKEN: &str = "token";
+
+    fn pool_connection() -> CpuConnectionType {
+        CpuConnectionType::Pool {
+            pool_url: "pool.example.com:3333".to_string(),
+            worker_name: Some(".Tari-universe".to_string()),
+            fallback_pools: vec![PoolEndpoint {
+                pool_url: "backup.example.com:7038".to_string(),
+                worker_name: Some("/Tari-universe".to_string()),
+            }],
+        }
+    }
+
+    fn build(tuning: &CpuTuningProfile, cpu_threads: Option<u32>) -> Value {
+        let config = XmrigConfig::build(
+            tuning,
+            cpu_threads,
+            XmrigPoolConfig::from_connection_type(&pool_connection(), ADDRESS),
+            18080,
+            TOKEN,
+            Some("/tmp/xmrig.log".to_string()),
+        );
+        serde_json::to_value(config).unwrap()
+    }
+
+    #[test]
+    fn test_generated_config_uses_xmrig_keys() {
+        let config = build(&CpuTuningProfile::turbo(), Some(4));
+
+        assert_eq!(config["autosave"], false);
+        assert_eq!(config["donate-level"], 1);
+        assert_eq!(config["log-file"], "/tmp/xmrig.log");
+        assert_eq!(config["http"]["port"], 18080);
+        assert_eq!(config["http"]["access-token"], TOKEN);
+        assert_eq!(config["cpu"]["huge-pages"], true);
+        assert_eq!(config["cpu"]["yield"], true);
+        assert_eq!(config["cpu"]["priority"], 1);
+        assert_eq!(config["cpu"]["asm"], true);
+        assert_eq!(config["randomx"]["mode"], "fast");
+        assert_eq!(config["randomx"]["init"], -1);
+        assert_eq!(config["randomx"]["1gb-pages"], false);
+    }
+
+    #[test]
+    fn test_thread_count_and_affinity_go_into_wildcard_profile() {
+        let tuning = CpuTuningProfile {
+            affinity_mask: Some(0b1010),
+            ..CpuTuningProfile::turbo()
+        };
+        let config = build(&tuning, Some(2));
+
+        assert_eq!(config["cpu"]["*"]["threads"], 2);
+        assert_eq!(config["cpu"]["*"]["affinity"], 10);
+        assert_eq!(config["cpu"]["max-threads-hint"], 100);
+    }
+
+    #[test]
+    fn test_max_threads_hint_leaves_threads_to_xmrig() {
+        let tuning = CpuTuningProfile {
+            max_threads_hint: Some(50),
+            ..CpuTuningProfile::turbo()
+        };
+        let config = build(&tuning, Some(2));
+
+        assert!(config["cpu"].get("*").is_none());
+        assert_eq!(config["cpu"]["max-threads-hint"], 50);
+    }
+
+    #[test]
+    fn test_presets_map_to_expected_options() {
+        let eco = build(&CpuTuningProfile::eco(), Some(1));
+        assert_eq!(eco["randomx"]["mode"], "light");
+        assert_eq!(eco["cpu"]["priority"], 0);
+
+        let ludicrous = build(&CpuTuningProfile::ludicrous(), None);
+        assert_eq!(ludicrous["randomx"]["1gb-pages"], true);
+        assert_eq!(ludicrous["cpu"]["huge-pages-jit"], true);
+        assert_eq!(ludicrous["cpu"]["yield"], false);
+        assert!(ludicrous["cpu"]["priority"].is_null());
+        assert!(ludicrous["cpu"].get("*").is_none());
+    }
+
+    #[test]
+    fn test_asm_variants() {
+        let tuning = CpuTuningProfile {
+            asm: AsmMode::Ryzen,
+            ..CpuTuningProfile::turbo()
+        };
+        assert_eq!(build(&tuning, None)["cpu"]["asm"], "ryzen");
+
+        let tuning = CpuTuningProfile {
+            asm: AsmMode::Disabled,
+            ..CpuTuningProfile::turbo()
+        };
+        assert_eq!(build(&tuning, None)["cpu"]["asm"], false);
+    }
+
+    #[test]
+    fn test_pool_entries() {
+        let pools = XmrigPoolConfig::from_connection_type(&pool_connection(), ADDRESS);
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[0].url, "pool.example.com:3333");
+        assert_eq!(pools[0].user, "tari_address.Tari-universe");
+        assert!(!pools[0].daemon);
+        assert_eq!(pools[1].url, "backup.example.com:7038");
+        assert_eq!(pools[1].user, "tari_address/Tari-universe");
+
+        let local = XmrigPoolConfig::from_connection_type(
+            &CpuConnectionType::LocalMMProxy {
+                local_proxy_url: "127.0.0.1:18081".to_string(),
+            },
+            "monero_address",
+        );
+        assert_eq!(local.len(), 1);
+        assert_eq!(local[0].url, "127.0.0.1:18081");
+        assert_eq!(local[0].user, "monero_address");
+        assert!(local[0].daemon);
+        assert_eq!(local[0].coin.as_deref(), Some("monero"));
+    }
+
+    #[test]
+    fn test_failover_timeout_sets_retries() {
+        let config = serde_json::to_value(
+            XmrigConfig::build(
+                &CpuTuningProfile::turbo(),
+                None,
+                XmrigPoolConfig::from_connection_type(&pool_connection(), ADDRESS),
+                18080,
+                TOKEN,
+                None,
+            )
+            .with_failover_timeout(90),
+        )
+        .unwrap();
+
+        assert_eq!(config["retries"], 90);
+        assert_eq!(config["retry-pause"], 1);
+        assert_eq!(config["pools"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_thread_and_priority_changes_apply_in_place() {
+        let build_config = |tuning: &CpuTuningProfile, cpu_threads| {
+            XmrigConfig::build(
+                tuning,
+                cpu_threads,
+                XmrigPoolConfig::from_connection_type(&pool_connection(), ADDRESS),
+                18080,
+                TOKEN,
+                None,
+            )
+        };
+        let current = build_config(&CpuTuningProfile::turbo(), Some(4));
+
+        let more_threads = build_config(&CpuTuningProfile::turbo(), Some(8));
+        assert!(!current.requires_restart(&more_threads));
+
+        let other_priority = build_config(
+            &CpuTuningProfile {
+                priority: Some(3),
+                yield_between_rounds: false,
+                ..CpuTuningProfile::turbo()
+            },
+            Some(4),
+        );
+        assert!(!current.requires_restart(&other_priority));
+
+        let light_mode = build_config(&CpuTuningProfile::eco(), Some(1));
+        assert!(current.requires_restart(&light_mode));
+
+        let one_gb_pages = build_config(&CpuTuningProfile::ludicrous(), Some(4));
+        assert!(current.requires_restart(&one_gb_pages));
+    }
+
+    #[test]
+    fn test_written_file_round_trips() {
+        let config = XmrigConfig::build(
+            &CpuTuningProfile::ludicrous(),
+            Some(8),
+            XmrigPoolConfig::from_connection_type(&pool_connection(), ADDRESS),
+            18080,
+            TOKEN,
+            None,
+        );
+        let path = std::env::temp_dir()
+            .join("xmrig_config_test")
+            .join("config.json");
+
+        config.write_to_file(&path).unwrap();
+        let loaded: XmrigConfig =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(loaded, config);
+    }
+}
@@ -24,6 +24,7 @@ use serde::Serialize;
 
 pub mod manager;
 pub mod miners;
+pub mod tuning;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct CpuMinerStatus {
@@ -31,6 +32,17 @@ pub struct CpuMinerStatus {
     pub hash_rate: f64,
     pub estimated_earnings: u64,
     pub connection: CpuMinerConnectionStatus,
+    pub hash_rates: CpuMinerHashRates,
+    /// Hashrates of every mining thread, in the order xmrig reports them
+    pub thread_hash_rates: Vec<CpuMinerHashRates>,
+    pub highest_hash_rate: Option<f64>,
+    pub shares: CpuMinerShares,
+    /// Difficulty of the current job
+    pub difficulty: u64,
+    pub algorithm: Option<String>,
+    /// Seconds since the miner was started
+    pub uptime: u64,
+    pub huge_pages: CpuMinerHugePages,
 }
 
 impl Default for CpuMinerStatus {
@@ -41,11 +53,50 @@ impl Default for CpuMinerStatus {
             estimated_earnings: 0,
             connection: CpuMinerConnectionStatus {
                 is_connected: false,
+                pool_address: None,
+                ping: None,
+                uptime: 0,
             },
+            hash_rates: CpuMinerHashRates::default(),
+            thread_hash_rates: Vec::new(),
+            highest_hash_rate: None,
+            shares: CpuMinerShares::default(),
+            difficulty: 0,
+            algorithm: None,
+            uptime: 0,
+            huge_pages: CpuMinerHugePages::default(),
         }
     }
 }
 #[derive(Debug, Serialize, Clone, Default)]
 pub struct CpuMinerConnectionStatus {
     pub is_connected: bool,
+    pub pool_address: Option<String>,
+    /// Round trip time to the pool in milliseconds
+    pub ping: Option<u64>,
+    /// Seconds since the connection to the pool was established
+    pub uptime: u64,
+}
+
+/// Average hashrates over the windows reported by xmrig, `None` until a window is filled.
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct CpuMinerHashRates {
+    pub ten_seconds: Option<f64>,
+    pub sixty_seconds: Option<f64>,
+    pub fifteen_minutes: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct CpuMinerShares {
+    pub accepted: u64,
+    pub rejected: u64,
+    /// Results that were found but never accepted nor rejected by the pool,
+    /// e.g. stale results dropped on reconnect
+    pub invalid: u64,
+}
+
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct CpuMinerHugePages {
+    pub allocated: u64,
+    pub total: u64,
 }
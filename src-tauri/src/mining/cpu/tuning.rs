@@ -0,0 +1,296 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use serde::{Deserialize, Serialize};
+
+use crate::configs::config_mining::{MiningMode, MiningModeType};
+
+/// Highest process priority supported by xmrig
+const MAX_XMRIG_PRIORITY: u8 = 5;
+
+/// RandomX dataset mode.
+/// Light mode only allocates the 256 MB cache and is much slower but gentle on memory.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RandomXMode {
+    Auto,
+    Fast,
+    Light,
+}
+
+/// Assembly optimisations used for the RandomX JIT.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AsmMode {
+    /// Let xmrig pick the best variant for the detected CPU
+    Auto,
+    Disabled,
+    Intel,
+    Ryzen,
+    Bulldozer,
+}
+
+/// Full set of CPU miner tuning options applied when xmrig is started.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CpuTuningProfile {
+    pub huge_pages: bool,
+    pub huge_pages_jit: bool,
+    /// 1GB pages for the RandomX dataset, only supported on Linux
+    pub one_gb_pages: bool,
+    /// Bit mask of the logical CPUs the mining threads are pinned to, `None` lets the OS decide
+    pub affinity_mask: Option<u64>,
+    /// xmrig process priority from 0 (idle) to 5 (highest), `None` keeps the default
+    pub priority: Option<u8>,
+    /// Percentage of threads xmrig uses when it configures the threads itself.
+    /// When set, the thread count derived from the mining mode is not passed to xmrig.
+    pub max_threads_hint: Option<u8>,
+    /// Threads used to initialise the RandomX dataset, `None` uses all of them
+    pub randomx_init_threads: Option<u32>,
+    pub randomx_mode: RandomXMode,
+    /// Yield the CPU between hashing rounds so other applications stay responsive
+    pub yield_between_rounds: bool,
+    pub asm: AsmMode,
+}
+
+/// Tuning values set by the user on Custom and User mining modes.
+/// Every value left empty falls back to the preset the mode is based on.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CpuTuningOverrides {
+    pub huge_pages: Option<bool>,
+    pub huge_pages_jit: Option<bool>,
+    pub one_gb_pages: Option<bool>,
+    pub affinity_mask: Option<u64>,
+    pub priority: Option<u8>,
+    pub max_threads_hint: Option<u8>,
+    pub randomx_init_threads: Option<u32>,
+    pub randomx_mode: Option<RandomXMode>,
+    pub yield_between_rounds: Option<bool>,
+    pub asm: Option<AsmMode>,
+}
+
+impl CpuTuningOverrides {
+    /// Checks that the overridden values are accepted by xmrig.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if self.affinity_mask == Some(0) {
+            return Err(anyhow::anyhow!(
+                "Affinity mask must select at least one CPU"
+            ));
+        }
+        if self
+            .priority
+            .is_some_and(|priority| priority > MAX_XMRIG_PRIORITY)
+        {
+            return Err(anyhow::anyhow!(
+                "Priority must be between 0 and {MAX_XMRIG_PRIORITY}"
+            ));
+        }
+        if self
+            .max_threads_hint
+            .is_some_and(|hint| !(1..=100).contains(&hint))
+        {
+            return Err(anyhow::anyhow!(
+                "Max threads hint must be between 1 and 100"
+            ));
+        }
+        if self.randomx_init_threads == Some(0) {
+            return Err(anyhow::anyhow!("RandomX init threads must be at least 1"));
+        }
+        Ok(())
+    }
+}
+
+impl CpuTuningProfile {
+    /// Low impact preset, mines in the background with idle priority and the light dataset.
+    pub fn eco() -> Self {
+        Self {
+            huge_pages: true,
+            huge_pages_jit: false,
+            one_gb_pages: false,
+            affinity_mask: None,
+            priority: Some(0),
+            max_threads_hint: None,
+            randomx_init_threads: None,
+            randomx_mode: RandomXMode::Light,
+            yield_between_rounds: true,
+            asm: AsmMode::Auto,
+        }
+    }
+
+    /// Balanced preset with the full dataset but a lowered priority.
+    pub fn turbo() -> Self {
+        Self {
+            priority: Some(1),
+            randomx_mode: RandomXMode::Fast,
+            ..Self::eco()
+        }
+    }
+
+    /// Maximum hash rate preset, the machine is expected to be dedicated to mining.
+    pub fn ludicrous() -> Self {
+        Self {
+            huge_pages_jit: true,
+            one_gb_pages: true,
+            priority: None,
+            yield_between_rounds: false,
+            ..Self::turbo()
+        }
+    }
+
+    /// Resolves the tuning profile of a mining mode.
+    ///
+    /// Eco, Turbo and Ludicrous use their presets. Custom and User modes start from the Eco
+    /// preset when mining with the lowest CPU usage and from Turbo otherwise, then apply
+    /// the overrides stored on the mode.
+    pub fn for_mining_mode(mining_mode: &MiningMode) -> Self {
+        match mining_mode.mode_type {
+            MiningModeType::Eco => Self::eco(),
+            MiningModeType::Turbo => Self::turbo(),
+            MiningModeType::Ludicrous => Self::ludicrous(),
+            MiningModeType::Custom | MiningModeType::User => {
+                let preset = if mining_mode.cpu_usage_percentage <= 1 {
+                    Self::eco()
+                } else {
+                    Self::turbo()
+                };
+                preset.with_overrides(&mining_mode.cpu_tuning)
+            }
+        }
+    }
+
+    /// Returns the profile with every set override applied.
+    pub fn with_overrides(self, overrides: &CpuTuningOverrides) -> Self {
+        Self {
+            huge_pages: overrides.huge_pages.unwrap_or(self.huge_pages),
+            huge_pages_jit: overrides.huge_pages_jit.unwrap_or(self.huge_pages_jit),
+            one_gb_pages: overrides.one_gb_pages.unwrap_or(self.one_gb_pages),
+            affinity_mask: overrides.affinity_mask.or(self.affinity_mask),
+            priority: overrides.priority.or(self.priority),
+            max_threads_hint: overrides.max_threads_hint.or(self.max_threads_hint),
+            randomx_init_threads: overrides.randomx_init_threads.or(self.randomx_init_threads),
+            randomx_mode: overrides.randomx_mode.unwrap_or(self.randomx_mode),
+            yield_between_rounds: overrides
+                .yield_between_rounds
+                .unwrap_or(self.yield_between_rounds),
+            asm: overrides.asm.unwrap_or(self.asm),
+        }
+    }
+}
+
+impl Default for CpuTuningProfile {
+    fn default() -> Self {
+        Self::eco()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    fn mining_mode(mode_type: MiningModeType, cpu_usage_percentage: u32) -> MiningMode {
+        MiningMode {
+            mode_type,
+            mode_name: mode_type.to_string(),
+            cpu_usage_percentage,
+            gpu_usage_percentage: 50,
+            cpu_tuning: CpuTuningOverrides {
+                priority: Some(4),
+                asm: Some(AsmMode::Ryzen),
+                ..CpuTuningOverrides::default()
+            },
+            process_isolation: None,
+        }
+    }
+
+    #[test]
+    fn test_presets_ignore_overrides() {
+        assert_eq!(
+            CpuTuningProfile::for_mining_mode(&mining_mode(MiningModeType::Eco, 1)),
+            CpuTuningProfile::eco()
+        );
+        assert_eq!(
+            CpuTuningProfile::for_mining_mode(&mining_mode(MiningModeType::Turbo, 10)),
+            CpuTuningProfile::turbo()
+        );
+        assert_eq!(
+            CpuTuningProfile::for_mining_mode(&mining_mode(MiningModeType::Ludicrous, 85)),
+            CpuTuningProfile::ludicrous()
+        );
+    }
+
+    #[test]
+    fn test_custom_mode_applies_overrides_on_top_of_preset() {
+        let profile = CpuTuningProfile::for_mining_mode(&mining_mode(MiningModeType::Custom, 75));
+
+        assert_eq!(profile.priority, Some(4));
+        assert_eq!(profile.asm, AsmMode::Ryzen);
+        assert_eq!(profile.randomx_mode, RandomXMode::Fast);
+        assert_eq!(profile.huge_pages, CpuTuningProfile::turbo().huge_pages);
+    }
+
+    #[test]
+    fn test_custom_mode_with_lowest_usage_is_based_on_eco() {
+        let profile = CpuTuningProfile::for_mining_mode(&mining_mode(MiningModeType::User, 1));
+
+        assert_eq!(profile.randomx_mode, RandomXMode::Light);
+        assert_eq!(profile.priority, Some(4));
+    }
+
+    #[test]
+    fn test_validate_overrides() {
+        assert!(CpuTuningOverrides::default().validate().is_ok());
+        assert!(CpuTuningOverrides {
+            priority: Some(6),
+            ..CpuTuningOverrides::default()
+        }
+        .validate()
+        .is_err());
+        assert!(CpuTuningOverrides {
+            affinity_mask: Some(0),
+            ..CpuTuningOverrides::default()
+        }
+        .validate()
+        .is_err());
+        assert!(CpuTuningOverrides {
+            max_threads_hint: Some(0),
+            ..CpuTuningOverrides::default()
+        }
+        .validate()
+        .is_err());
+        assert!(CpuTuningOverrides {
+            randomx_init_threads: Some(0),
+            ..CpuTuningOverrides::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_overrides_deserialize_from_partial_json() {
+        let overrides: CpuTuningOverrides =
+            serde_json::from_str(r#"{"huge_pages": false, "randomx_mode": "Light"}"#).unwrap();
+
+        assert_eq!(overrides.huge_pages, Some(false));
+        assert_eq!(overrides.randomx_mode, Some(RandomXMode::Light));
+        assert_eq!(overrides.priority, None);
+    }
+}
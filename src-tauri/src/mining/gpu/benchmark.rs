@@ -0,0 +1,827 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Benchmark of the GPU miners used to recommend a miner, engine and intensity per device.
+//!
+//! Every device is mined on alone with each available miner and engine of the pool's algorithm
+//! for a bounded time, sampling the reported hashrate and the power draw where the hardware
+//! reader reports it. The best combination of a device is then run at reduced intensities, as
+//! an intensity keeping almost the full hashrate leaves the machine more responsive and uses
+//! less power.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        LazyLock,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Error;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tari_shutdown::{Shutdown, ShutdownSignal};
+use tokio::{
+    select,
+    sync::{watch::Sender, RwLock},
+};
+
+use crate::{
+    configs::{
+        config_mining::{ConfigMining, ConfigMiningContent},
+        config_pools::ConfigPools,
+        trait_config::ConfigImpl,
+    },
+    events_emitter::EventsEmitter,
+    hardware::hardware_status_monitor::HardwareStatusMonitor,
+    mining::gpu::{
+        consts::{EngineType, GpuMinerFeature, GpuMinerStatus, GpuMinerType, GpuMiningAlgorithm},
+        interface::GpuMinerInterfaceTrait,
+        manager::GpuManager,
+        miners::GpuCommonInformation,
+    },
+    process_watcher::ProcessWatcherStats,
+    tasks_tracker::TasksTrackers,
+};
+
+const LOG_TARGET: &str = "tari::universe::gpu_benchmark";
+
+pub const DEFAULT_RUN_DURATION: Duration = Duration::from_secs(60);
+const MIN_RUN_DURATION: Duration = Duration::from_secs(30);
+/// Miners fall back to another miner after 3 minutes without hashrate, runs have to end before that
+const MAX_RUN_DURATION: Duration = Duration::from_secs(150);
+/// Time given to a miner to start and ramp up before its hashrate is sampled
+const WARMUP_DURATION: Duration = Duration::from_secs(15);
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+const FULL_INTENSITY: u32 = 100;
+const REDUCED_INTENSITIES: [u32; 2] = [75, 50];
+/// Share of the full intensity hashrate a reduced intensity has to keep to be recommended
+const MIN_HASH_RATE_RATIO: f64 = 0.95;
+/// Share of samples that have to report a hashrate for a run to be recommended
+const MIN_STABILITY: f64 = 0.8;
+
+static INSTANCE: LazyLock<RwLock<GpuBenchmark>> =
+    LazyLock::new(|| RwLock::new(GpuBenchmark::new()));
+/// Set while benchmark runs own the GPU miner binaries and pid files
+static IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuBenchmarkRun {
+    pub device_id: u32,
+    pub miner: GpuMinerType,
+    pub engine: EngineType,
+    pub intensity_percentage: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuBenchmarkResult {
+    #[serde(flatten)]
+    pub run: GpuBenchmarkRun,
+    pub algorithm: GpuMiningAlgorithm,
+    pub average_hash_rate: f64,
+    /// Share of samples with a reported hashrate, from 0.0 to 1.0
+    pub stability: f64,
+    pub average_power_watts: Option<f64>,
+    pub error: Option<String>,
+}
+
+impl GpuBenchmarkResult {
+    fn is_stable(&self) -> bool {
+        self.error.is_none() && self.average_hash_rate > 0.0 && self.stability >= MIN_STABILITY
+    }
+
+    fn hash_rate_per_watt(&self) -> Option<f64> {
+        self.average_power_watts
+            .filter(|watts| *watts > 0.0)
+            .map(|watts| self.average_hash_rate / watts)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuDeviceRecommendation {
+    pub device_id: u32,
+    pub miner: GpuMinerType,
+    pub engine: EngineType,
+    pub intensity_percentage: u32,
+    pub average_hash_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuBenchmarkReport {
+    /// Unix timestamp in seconds
+    pub finished_at: u64,
+    pub algorithm: GpuMiningAlgorithm,
+    pub results: Vec<GpuBenchmarkResult>,
+    pub recommendations: Vec<GpuDeviceRecommendation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum GpuBenchmarkState {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuBenchmarkProgressPayload {
+    pub state: GpuBenchmarkState,
+    pub completed_runs: usize,
+    pub total_runs: usize,
+    pub current_run: Option<GpuBenchmarkRun>,
+    pub error: Option<String>,
+}
+
+struct BenchmarkProgress {
+    completed_runs: usize,
+    total_runs: usize,
+}
+
+#[derive(Debug, Clone)]
+struct BenchmarkSample {
+    hash_rate: f64,
+    power_watts: Option<f32>,
+}
+
+/// Averages the samples of a run into its hashrate, stability and power draw.
+/// The power draw is only reported when every sample has a reading.
+fn summarize_samples(samples: &[BenchmarkSample]) -> (f64, f64, Option<f64>) {
+    if samples.is_empty() {
+        return (0.0, 0.0, None);
+    }
+    let count = samples.len() as f64;
+    let average_hash_rate = samples.iter().map(|sample| sample.hash_rate).sum::<f64>() / count;
+    let stability = samples
+        .iter()
+        .filter(|sample| sample.hash_rate > 0.0)
+        .count() as f64
+        / count;
+    let average_power_watts = samples
+        .iter()
+        .map(|sample| sample.power_watts.map(f64::from))
+        .sum::<Option<f64>>()
+        .map(|total| total / count);
+    (average_hash_rate, stability, average_power_watts)
+}
+
+/// Picks the stable full intensity run with the highest hashrate of a device.
+fn best_combination(results: &[GpuBenchmarkResult], device_id: u32) -> Option<&GpuBenchmarkResult> {
+    results
+        .iter()
+        .filter(|result| {
+            result.run.device_id == device_id
+                && result.run.intensity_percentage == FULL_INTENSITY
+                && result.is_stable()
+        })
+        .max_by(|a, b| a.average_hash_rate.total_cmp(&b.average_hash_rate))
+}
+
+/// Recommends the best combination of a device at the intensity keeping at least
+/// `MIN_HASH_RATE_RATIO` of its full intensity hashrate. With power readings for every candidate
+/// the most efficient one wins, otherwise the lowest intensity.
+fn recommend_device(
+    results: &[GpuBenchmarkResult],
+    device_id: u32,
+) -> Option<GpuDeviceRecommendation> {
+    let best = best_combination(results, device_id)?;
+    let candidates: Vec<&GpuBenchmarkResult> = results
+        .iter()
+        .filter(|result| {
+            result.run.device_id == device_id
+                && result.run.miner == best.run.miner
+                && result.run.engine == best.run.engine
+                && result.is_stable()
+                && result.average_hash_rate >= best.average_hash_rate * MIN_HASH_RATE_RATIO
+        })
+        .collect();
+
+    let efficiencies: Option<Vec<f64>> = candidates
+        .iter()
+        .map(|result| result.hash_rate_per_watt())
+        .collect();
+    let recommended = match efficiencies {
+        Some(efficiencies) => candidates
+            .iter()
+            .zip(efficiencies)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(result, _)| *result),
+        None => candidates
+            .iter()
+            .min_by_key(|result| result.run.intensity_percentage)
+            .copied(),
+    }
+    .unwrap_or(best);
+
+    Some(GpuDeviceRecommendation {
+        device_id,
+        miner: recommended.run.miner.clone(),
+        engine: recommended.run.engine.clone(),
+        intensity_percentage: recommended.run.intensity_percentage,
+        average_hash_rate: recommended.average_hash_rate,
+    })
+}
+
+/// Miner recommended for most devices, ties are broken by the miners priority.
+fn most_recommended_miner(recommendations: &[GpuDeviceRecommendation]) -> Option<GpuMinerType> {
+    let mut counts: HashMap<&GpuMinerType, usize> = HashMap::new();
+    for recommendation in recommendations {
+        *counts.entry(&recommendation.miner).or_default() += 1;
+    }
+    let max_count = counts.values().copied().max()?;
+    crate::mining::gpu::consts::MINERS_PRIORITY
+        .iter()
+        .find(|miner| counts.get(miner) == Some(&max_count))
+        .cloned()
+}
+
+pub struct GpuBenchmark {
+    cancel_shutdown: Option<Shutdown>,
+}
+
+impl GpuBenchmark {
+    fn new() -> Self {
+        Self {
+            cancel_shutdown: None,
+        }
+    }
+
+    /// Starts the benchmark in the background. GPU mining is stopped while it runs and started
+    /// again afterwards when it was running.
+    ///
+    /// ### Parameters
+    /// * `run_duration` - Time every miner, engine and intensity is run on a device, clamped to a safe range
+    /// * `apply_recommendations` - Whether the recommendations are applied once the benchmark completes
+    pub async fn start(run_duration: Duration, apply_recommendations: bool) -> Result<(), Error> {
+        let mut instance = INSTANCE.write().await;
+        if instance
+            .cancel_shutdown
+            .as_ref()
+            .is_some_and(|shutdown| !shutdown.is_triggered())
+        {
+            return Err(anyhow::anyhow!("GPU benchmark is already running"));
+        }
+        let cancel_shutdown = Shutdown::new();
+        let cancel_signal = cancel_shutdown.to_signal();
+        instance.cancel_shutdown = Some(cancel_shutdown);
+        drop(instance);
+
+        let run_duration = run_duration.clamp(MIN_RUN_DURATION, MAX_RUN_DURATION);
+        info!(target: LOG_TARGET, "Starting GPU benchmark with {}s per run", run_duration.as_secs());
+        TasksTrackers::current()
+            .gpu_mining_phase
+            .get_task_tracker()
+            .await
+            .spawn(async move {
+                Self::run(run_duration, apply_recommendations, cancel_signal).await;
+                if let Some(shutdown) = INSTANCE.write().await.cancel_shutdown.as_mut() {
+                    shutdown.trigger();
+                }
+            });
+        Ok(())
+    }
+
+    /// Checks if benchmark runs are using the GPU miners, regular mining must not start meanwhile.
+    pub fn is_in_progress() -> bool {
+        IN_PROGRESS.load(Ordering::SeqCst)
+    }
+
+    /// Cancels the running benchmark, the run in progress is stopped right away.
+    pub async fn cancel() -> Result<(), Error> {
+        match INSTANCE.write().await.cancel_shutdown.as_mut() {
+            Some(shutdown) if !shutdown.is_triggered() => {
+                info!(target: LOG_TARGET, "Cancelling GPU benchmark");
+                shutdown.trigger();
+                Ok(())
+            }
+            _ => Err(anyhow::anyhow!("GPU benchmark is not running")),
+        }
+    }
+
+    /// Applies the recommendations of a benchmark to the devices settings. The miner recommended
+    /// for most devices becomes the selected miner, the other miners run next to it.
+    pub async fn apply_recommendations(
+        recommendations: &[GpuDeviceRecommendation],
+    ) -> Result<(), Error> {
+        let selected_miner = most_recommended_miner(recommendations)
+            .ok_or_else(|| anyhow::anyhow!("No GPU benchmark recommendations to apply"))?;
+
+        for recommendation in recommendations {
+            let device_id = recommendation.device_id;
+            let miner =
+                (recommendation.miner != selected_miner).then(|| recommendation.miner.clone());
+            let engine = recommendation
+                .miner
+                .supports_feature(&GpuMinerFeature::PerDeviceEngineSelection)
+                .then(|| recommendation.engine.clone());
+            let intensity_percentage = (recommendation.intensity_percentage < FULL_INTENSITY)
+                .then_some(recommendation.intensity_percentage);
+
+            ConfigMining::update_field(
+                ConfigMiningContent::set_gpu_device_miner,
+                (device_id, miner),
+            )
+            .await?;
+            ConfigMining::update_field(
+                ConfigMiningContent::set_gpu_device_engine,
+                (device_id, engine),
+            )
+            .await?;
+            ConfigMining::update_field(
+                ConfigMiningContent::set_gpu_device_intensity,
+                (device_id, intensity_percentage),
+            )
+            .await?;
+        }
+        EventsEmitter::emit_update_gpu_devices_settings(
+            ConfigMining::content().await.gpu_devices_settings().clone(),
+        )
+        .await;
+
+        if ConfigMining::content().await.gpu_miner_type() != &selected_miner {
+            GpuManager::write()
+                .await
+                .switch_miner(selected_miner)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn run(
+        run_duration: Duration,
+        apply_recommendations: bool,
+        cancel_signal: ShutdownSignal,
+    ) {
+        IN_PROGRESS.store(true, Ordering::SeqCst);
+        let was_mining = GpuManager::read().await.is_running();
+        if was_mining {
+            if let Err(e) = GpuManager::write().await.stop_mining().await {
+                error!(target: LOG_TARGET, "Failed to stop GPU mining for the benchmark: {e}");
+            }
+        }
+
+        let report = Self::run_benchmarks(run_duration, cancel_signal).await;
+        IN_PROGRESS.store(false, Ordering::SeqCst);
+        match report {
+            Ok(Some(report)) => {
+                info!(target: LOG_TARGET, "GPU benchmark completed with {} recommendations", report.recommendations.len());
+                let recommendations = report.recommendations.clone();
+                if let Err(e) = ConfigMining::update_field(
+                    ConfigMiningContent::set_gpu_benchmark_report,
+                    Some(report),
+                )
+                .await
+                {
+                    error!(target: LOG_TARGET, "Failed to save GPU benchmark report: {e}");
+                }
+                EventsEmitter::emit_mining_config_loaded(&ConfigMining::content().await).await;
+
+                if apply_recommendations && !recommendations.is_empty() {
+                    if let Err(e) = Self::apply_recommendations(&recommendations).await {
+                        error!(target: LOG_TARGET, "Failed to apply GPU benchmark recommendations: {e}");
+                    }
+                }
+                Self::emit_final_state(GpuBenchmarkState::Completed, None).await;
+            }
+            Ok(None) => {
+                info!(target: LOG_TARGET, "GPU benchmark cancelled");
+                Self::emit_final_state(GpuBenchmarkState::Cancelled, None).await;
+            }
+            Err(e) => {
+                error!(target: LOG_TARGET, "GPU benchmark failed: {e}");
+                Self::emit_final_state(GpuBenchmarkState::Failed, Some(e.to_string())).await;
+            }
+        }
+
+        if was_mining {
+            if let Err(e) = GpuManager::write().await.start_mining().await {
+                error!(target: LOG_TARGET, "Failed to restart GPU mining after the benchmark: {e}");
+            }
+        }
+    }
+
+    async fn emit_final_state(state: GpuBenchmarkState, error: Option<String>) {
+        EventsEmitter::emit_gpu_benchmark_progress(GpuBenchmarkProgressPayload {
+            state,
+            completed_runs: 0,
+            total_runs: 0,
+            current_run: None,
+            error,
+        })
+        .await;
+    }
+
+    /// Miners and engines to benchmark, the miners mining the algorithm of the current pool,
+    /// or of solo mining when pool mining is disabled.
+    async fn resolve_combinations(
+    ) -> Result<(GpuMiningAlgorithm, Vec<(GpuMinerType, EngineType)>), Error> {
+        let pools_config = ConfigPools::content().await;
+        let algorithm = if *pools_config.gpu_pool_enabled() {
+            pools_config.current_gpu_pool().pool_type.algorithm()
+        } else {
+            GpuMiningAlgorithm::SHA3X
+        };
+        let selected_engine = ConfigMining::content().await.gpu_engine().clone();
+        let miners = GpuManager::read().await.healthy_miners();
+
+        let mut combinations = Vec::new();
+        for miner in miners
+            .into_iter()
+            .filter(|miner| miner.main_algorithm() == algorithm)
+        {
+            let mut adapter = GpuManager::create_miner_interface(
+                &miner,
+                Sender::new(GpuMinerStatus::default_with_algorithm(algorithm.clone())),
+            );
+            if let Err(e) = adapter.detect_devices().await {
+                warn!(target: LOG_TARGET, "Skipping {miner} in the benchmark, device detection failed: {e}");
+                continue;
+            }
+            let mut engines = if miner.supports_feature(&GpuMinerFeature::EngineSelection) {
+                adapter.available_engines()
+            } else {
+                Vec::new()
+            };
+            if engines.is_empty() {
+                engines.push(selected_engine.clone());
+            }
+            combinations.extend(engines.into_iter().map(|engine| (miner.clone(), engine)));
+        }
+        Ok((algorithm, combinations))
+    }
+
+    /// Runs every combination on every included device.
+    ///
+    /// ### Returns
+    /// * `Ok(Some(GpuBenchmarkReport))` - Benchmark completed
+    /// * `Ok(None)` - Benchmark cancelled
+    /// * `Err(anyhow::Error)` - Nothing to benchmark
+    async fn run_benchmarks(
+        run_duration: Duration,
+        mut cancel_signal: ShutdownSignal,
+    ) -> Result<Option<GpuBenchmarkReport>, Error> {
+        let (algorithm, combinations) = Self::resolve_combinations().await?;
+        let device_ids = ConfigMining::content().await.get_included_gpu_devices();
+        if combinations.is_empty() {
+            return Err(anyhow::anyhow!("No GPU miner available to benchmark"));
+        }
+        if device_ids.is_empty() {
+            return Err(anyhow::anyhow!("No GPU devices to benchmark"));
+        }
+
+        let mut progress = BenchmarkProgress {
+            completed_runs: 0,
+            total_runs: device_ids.len() * (combinations.len() + REDUCED_INTENSITIES.len()),
+        };
+        let mut results: Vec<GpuBenchmarkResult> = Vec::new();
+        for &device_id in &device_ids {
+            let full_intensity_runs = combinations
+                .iter()
+                .map(|(miner, engine)| GpuBenchmarkRun {
+                    device_id,
+                    miner: miner.clone(),
+                    engine: engine.clone(),
+                    intensity_percentage: FULL_INTENSITY,
+                })
+                .collect();
+            if !Self::execute_runs(
+                full_intensity_runs,
+                run_duration,
+                &mut cancel_signal,
+                &mut progress,
+                &mut results,
+            )
+            .await
+            {
+                return Ok(None);
+            }
+
+            let Some(best_run) = best_combination(&results, device_id).map(|best| best.run.clone())
+            else {
+                warn!(target: LOG_TARGET, "No stable miner found for device {device_id}");
+                progress.completed_runs += REDUCED_INTENSITIES.len();
+                continue;
+            };
+            let reduced_intensity_runs = REDUCED_INTENSITIES
+                .iter()
+                .map(|&intensity_percentage| GpuBenchmarkRun {
+                    intensity_percentage,
+                    ..best_run.clone()
+                })
+                .collect();
+            if !Self::execute_runs(
+                reduced_intensity_runs,
+                run_duration,
+                &mut cancel_signal,
+                &mut progress,
+                &mut results,
+            )
+            .await
+            {
+                return Ok(None);
+            }
+        }
+
+        let recommendations = device_ids
+            .iter()
+            .filter_map(|&device_id| recommend_device(&results, device_id))
+            .collect();
+        Ok(Some(GpuBenchmarkReport {
+            finished_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            algorithm,
+            results,
+            recommendations,
+        }))
+    }
+
+    /// Runs the benchmark runs one after another, emitting the progress before each of them.
+    ///
+    /// ### Returns
+    /// * `true` - All runs completed
+    /// * `false` - Benchmark cancelled
+    async fn execute_runs(
+        runs: Vec<GpuBenchmarkRun>,
+        run_duration: Duration,
+        cancel_signal: &mut ShutdownSignal,
+        progress: &mut BenchmarkProgress,
+        results: &mut Vec<GpuBenchmarkResult>,
+    ) -> bool {
+        for run in runs {
+            if cancel_signal.is_triggered() {
+                return false;
+            }
+            EventsEmitter::emit_gpu_benchmark_progress(GpuBenchmarkProgressPayload {
+                state: GpuBenchmarkState::Running,
+                completed_runs: progress.completed_runs,
+                total_runs: progress.total_runs,
+                current_run: Some(run.clone()),
+                error: None,
+            })
+            .await;
+            let result = Self::benchmark(run, run_duration, cancel_signal).await;
+            if cancel_signal.is_triggered() {
+                return false;
+            }
+            info!(target: LOG_TARGET, "Benchmarked {:?}: {} H/s, stability {:.2}, power {:?} W", result.run, result.average_hash_rate, result.stability, result.average_power_watts);
+            results.push(result);
+            progress.completed_runs += 1;
+        }
+        true
+    }
+
+    async fn benchmark(
+        run: GpuBenchmarkRun,
+        run_duration: Duration,
+        cancel_signal: &mut ShutdownSignal,
+    ) -> GpuBenchmarkResult {
+        let algorithm = run.miner.main_algorithm();
+        match Self::sample_run(&run, run_duration, cancel_signal).await {
+            Ok(samples) => {
+                let (average_hash_rate, stability, average_power_watts) =
+                    summarize_samples(&samples);
+                GpuBenchmarkResult {
+                    run,
+                    algorithm,
+                    average_hash_rate,
+                    stability,
+                    average_power_watts,
+                    error: None,
+                }
+            }
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Benchmark run {run:?} failed: {e}");
+                GpuBenchmarkResult {
+                    run,
+                    algorithm,
+                    average_hash_rate: 0.0,
+                    stability: 0.0,
+                    average_power_watts: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        }
+    }
+
+    /// Mines on the device of the run alone and samples the hashrate until the run duration is over.
+    async fn sample_run(
+        run: &GpuBenchmarkRun,
+        run_duration: Duration,
+        cancel_signal: &mut ShutdownSignal,
+    ) -> Result<Vec<BenchmarkSample>, Error> {
+        let status_channel = Sender::new(GpuMinerStatus::default_with_algorithm(
+            run.miner.main_algorithm(),
+        ));
+        let status_receiver = status_channel.subscribe();
+
+        let mut process_watcher = {
+            let gpu_manager = GpuManager::read().await;
+            let mut adapter = gpu_manager
+                .prepare_miner_interface(&run.miner, status_channel)
+                .await?;
+            // The run's device id is the selected miner's, the miner of the run may number it differently
+            let run_device_id = gpu_manager
+                .map_config_device_ids(&adapter)
+                .get(&run.device_id)
+                .copied()
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Device {} not detected by {}", run.device_id, run.miner)
+                })?;
+            let excluded_devices: Vec<u32> = adapter
+                .gpu_devices()
+                .iter()
+                .map(GpuCommonInformation::device_id)
+                .filter(|device_id| *device_id != run_device_id)
+                .collect();
+            adapter.load_gpu_engine(run.engine.clone()).await?;
+            adapter
+                .load_intensity_percentage(run.intensity_percentage)
+                .await?;
+            adapter.load_excluded_devices(excluded_devices).await?;
+            gpu_manager
+                .start_miner_watcher(
+                    &run.miner,
+                    adapter,
+                    Sender::new(ProcessWatcherStats::default()),
+                )
+                .await?
+        };
+
+        let started_at = Instant::now();
+        let mut samples = Vec::new();
+        let mut sample_interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + WARMUP_DURATION,
+            SAMPLE_INTERVAL,
+        );
+        loop {
+            select! {
+                _ = cancel_signal.wait() => break,
+                _ = sample_interval.tick() => {
+                    if started_at.elapsed() >= run_duration {
+                        break;
+                    }
+                    let hash_rate = if process_watcher.is_running() {
+                        status_receiver.borrow().hash_rate
+                    } else {
+                        0.0
+                    };
+                    let power_watts = HardwareStatusMonitor::current()
+                        .get_gpu_power_usage(run.device_id)
+                        .await;
+                    samples.push(BenchmarkSample { hash_rate, power_watts });
+                }
+            }
+        }
+
+        process_watcher.stop().await?;
+        Ok(samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn result(
+        miner: GpuMinerType,
+        engine: EngineType,
+        intensity_percentage: u32,
+        average_hash_rate: f64,
+        average_power_watts: Option<f64>,
+    ) -> GpuBenchmarkResult {
+        GpuBenchmarkResult {
+            run: GpuBenchmarkRun {
+                device_id: 0,
+                miner,
+                engine,
+                intensity_percentage,
+            },
+            algorithm: GpuMiningAlgorithm::SHA3X,
+            average_hash_rate,
+            stability: 1.0,
+            average_power_watts,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize_samples() {
+        let samples = [
+            BenchmarkSample {
+                hash_rate: 0.0,
+                power_watts: Some(100.0),
+            },
+            BenchmarkSample {
+                hash_rate: 300.0,
+                power_watts: Some(200.0),
+            },
+            BenchmarkSample {
+                hash_rate: 300.0,
+                power_watts: None,
+            },
+            BenchmarkSample {
+                hash_rate: 200.0,
+                power_watts: Some(200.0),
+            },
+        ];
+        let (average_hash_rate, stability, average_power_watts) = summarize_samples(&samples);
+        assert_eq!(average_hash_rate, 200.0);
+        assert_eq!(stability, 0.75);
+        assert_eq!(average_power_watts, None);
+
+        let (_, _, average_power_watts) = summarize_samples(&samples[..2]);
+        assert_eq!(average_power_watts, Some(150.0));
+        assert_eq!(summarize_samples(&[]), (0.0, 0.0, None));
+    }
+
+    #[test]
+    fn test_recommends_lowest_intensity_keeping_hashrate() {
+        let mut unstable = result(GpuMinerType::Graxil, EngineType::OpenCL, 100, 2000.0, None);
+        unstable.stability = 0.5;
+        let results = vec![
+            unstable,
+            result(GpuMinerType::Glytex, EngineType::OpenCL, 100, 900.0, None),
+            result(GpuMinerType::Glytex, EngineType::Cuda, 100, 1000.0, None),
+            result(GpuMinerType::Glytex, EngineType::Cuda, 75, 960.0, None),
+            result(GpuMinerType::Glytex, EngineType::Cuda, 50, 700.0, None),
+        ];
+
+        let recommendation = recommend_device(&results, 0).unwrap();
+        assert_eq!(recommendation.miner, GpuMinerType::Glytex);
+        assert_eq!(recommendation.engine, EngineType::Cuda);
+        assert_eq!(recommendation.intensity_percentage, 75);
+        assert!(recommend_device(&results, 1).is_none());
+    }
+
+    #[test]
+    fn test_recommends_most_efficient_intensity_with_power_readings() {
+        let results = vec![
+            result(
+                GpuMinerType::Graxil,
+                EngineType::OpenCL,
+                100,
+                1000.0,
+                Some(200.0),
+            ),
+            result(
+                GpuMinerType::Graxil,
+                EngineType::OpenCL,
+                75,
+                990.0,
+                Some(150.0),
+            ),
+            result(
+                GpuMinerType::Graxil,
+                EngineType::OpenCL,
+                50,
+                960.0,
+                Some(180.0),
+            ),
+        ];
+        let recommendation = recommend_device(&results, 0).unwrap();
+        assert_eq!(recommendation.intensity_percentage, 75);
+    }
+
+    #[test]
+    fn test_most_recommended_miner() {
+        let recommendation = |device_id, miner| GpuDeviceRecommendation {
+            device_id,
+            miner,
+            engine: EngineType::OpenCL,
+            intensity_percentage: 100,
+            average_hash_rate: 1.0,
+        };
+        assert_eq!(
+            most_recommended_miner(&[
+                recommendation(0, GpuMinerType::Graxil),
+                recommendation(1, GpuMinerType::Glytex),
+                recommendation(2, GpuMinerType::Graxil),
+            ]),
+            Some(GpuMinerType::Graxil)
+        );
+        assert_eq!(most_recommended_miner(&[]), None);
+    }
+}
@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
 
 use serde::{Deserialize, Serialize};
 
@@ -64,6 +64,30 @@ pub(crate) struct GpuMinerStatus {
     pub hash_rate: f64,
     pub estimated_earnings: u64, // Only for node connections
     pub algorithm: GpuMiningAlgorithm,
+    /// Hashrate of every algorithm mined, including the ones of additional miners.
+    /// Only filled in the status aggregated by the `GpuManager`.
+    pub algorithm_hash_rates: HashMap<GpuMiningAlgorithm, f64>,
+    /// Detailed stats, only reported by miners with a telemetry feed
+    pub telemetry: Option<GpuMinerTelemetry>,
+}
+
+/// Share, pool and device stats reported by the miner itself.
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct GpuMinerTelemetry {
+    pub session_average_hash_rate: f64,
+    pub accepted_shares: u64,
+    pub rejected_shares: u64,
+    pub submitted_shares: u64,
+    /// Percentage of submitted shares accepted by the pool
+    pub acceptance_rate: f64,
+    pub average_luck: f64,
+    pub thread_hash_rates: Vec<u64>,
+    pub is_pool_connected: bool,
+    pub pool_latency_ms: Option<u64>,
+    pub gpu_temperature: Option<f32>,
+    pub gpu_power_usage: Option<f32>,
+    pub gpu_utilization: Option<f32>,
+    pub uptime_secs: u64,
 }
 
 impl GpuMinerStatus {
@@ -73,8 +97,40 @@ impl GpuMinerStatus {
             hash_rate: 0.0,
             estimated_earnings: 0,
             algorithm,
+            algorithm_hash_rates: HashMap::new(),
+            telemetry: None,
         }
     }
+
+    /// Combines the status of the selected miner with the statuses of the additional miners.
+    ///
+    /// The hashrate, algorithm and earnings stay the ones of the selected miner as hashrates
+    /// of different algorithms cannot be added up, they are reported per algorithm instead.
+    pub fn aggregate<'a>(
+        &self,
+        additional_statuses: impl IntoIterator<Item = &'a GpuMinerStatus>,
+    ) -> GpuMinerStatus {
+        let mut aggregated = GpuMinerStatus {
+            algorithm_hash_rates: HashMap::new(),
+            ..self.clone()
+        };
+        aggregated.add_algorithm_hash_rate(self);
+        for status in additional_statuses {
+            aggregated.add_algorithm_hash_rate(status);
+        }
+        aggregated
+    }
+
+    fn add_algorithm_hash_rate(&mut self, status: &GpuMinerStatus) {
+        if !status.is_mining {
+            return;
+        }
+        self.is_mining = true;
+        *self
+            .algorithm_hash_rates
+            .entry(status.algorithm.clone())
+            .or_default() += status.hash_rate;
+    }
 }
 
 #[derive(Eq, Hash, PartialEq, Clone, Deserialize, Serialize, Debug)]
@@ -92,13 +148,19 @@ impl GpuMinerType {
                 GpuMinerFeature::DeviceExclusion,
                 GpuMinerFeature::MiningIntensity,
                 GpuMinerFeature::EngineSelection,
+                GpuMinerFeature::PerDeviceIntensity,
+                GpuMinerFeature::PerDeviceEngineSelection,
             ],
             GpuMinerType::Graxil => vec![
                 GpuMinerFeature::PoolMining,
                 GpuMinerFeature::DeviceExclusion,
                 GpuMinerFeature::MiningIntensity,
+                GpuMinerFeature::PerDeviceIntensity,
+            ],
+            GpuMinerType::LolMiner => vec![
+                GpuMinerFeature::PoolMining,
+                GpuMinerFeature::DeviceExclusion,
             ],
-            GpuMinerType::LolMiner => vec![GpuMinerFeature::PoolMining],
         }
     }
 
@@ -148,8 +210,14 @@ impl GpuMinerType {
         }
     }
 
+    /// Custom pools are supported by every pool miner mining their algorithm.
     pub fn is_pool_supported(&self, pool: &GpuPool) -> bool {
-        self.supported_pools().contains(pool)
+        match pool {
+            GpuPool::Custom { algorithm, .. } => {
+                self.is_pool_mining_supported() && self.supported_algorithms().contains(algorithm)
+            }
+            _ => self.supported_pools().contains(pool),
+        }
     }
 
     pub fn default_pool(&self) -> Option<GpuPool> {
@@ -168,6 +236,9 @@ impl GpuMinerType {
         self.get_expected_features()
             .contains(&GpuMinerFeature::PoolMining)
     }
+    pub fn supports_feature(&self, feature: &GpuMinerFeature) -> bool {
+        self.get_expected_features().contains(feature)
+    }
     pub fn is_solo_mining_supported(&self) -> bool {
         self.get_expected_features()
             .contains(&GpuMinerFeature::SoloMining)
@@ -205,6 +276,10 @@ pub enum GpuMinerFeature {
     MiningIntensity,
     /// Select mining engine ( OPENCL, CUDA, etc )
     EngineSelection,
+    /// Control mining intensity of each GPU device separately
+    PerDeviceIntensity,
+    /// Prefer a mining engine for specific GPU devices
+    PerDeviceEngineSelection,
 }
 
 #[derive(Clone, Serialize)]
@@ -236,3 +311,50 @@ pub const MINERS_PRIORITY: &[GpuMinerType] = &[
     GpuMinerType::Graxil,
     GpuMinerType::Glytex,
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(algorithm: GpuMiningAlgorithm, hash_rate: f64) -> GpuMinerStatus {
+        GpuMinerStatus {
+            is_mining: hash_rate > 0.0,
+            hash_rate,
+            algorithm,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_aggregate_reports_hashrate_per_algorithm() {
+        let selected = status(GpuMiningAlgorithm::SHA3X, 1_000_000.0);
+        let additional = [
+            status(GpuMiningAlgorithm::C29, 4.5),
+            status(GpuMiningAlgorithm::SHA3X, 500_000.0),
+        ];
+
+        let aggregated = selected.aggregate(&additional);
+        assert!(aggregated.is_mining);
+        assert_eq!(aggregated.hash_rate, 1_000_000.0);
+        assert_eq!(aggregated.algorithm, GpuMiningAlgorithm::SHA3X);
+        assert_eq!(
+            aggregated.algorithm_hash_rates,
+            HashMap::from([
+                (GpuMiningAlgorithm::SHA3X, 1_500_000.0),
+                (GpuMiningAlgorithm::C29, 4.5)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_aggregate_is_mining_when_only_additional_miner_hashes() {
+        let selected = status(GpuMiningAlgorithm::C29, 0.0);
+        let aggregated = selected.aggregate(&[status(GpuMiningAlgorithm::SHA3X, 10.0)]);
+        assert!(aggregated.is_mining);
+        assert_eq!(aggregated.hash_rate, 0.0);
+        assert_eq!(
+            aggregated.algorithm_hash_rates,
+            HashMap::from([(GpuMiningAlgorithm::SHA3X, 10.0)])
+        );
+    }
+}
@@ -20,25 +20,26 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use axum::async_trait;
 
 use crate::{
     mining::{
         gpu::{
-            consts::EngineType,
+            consts::{EngineType, GpuMinerType},
+            manager::GpuManager,
             miners::{
                 glytex::{GlytexGpuMiner, GlytexGpuMinerStatusMonitor},
                 graxil::{GraxilGpuMiner, GraxilGpuMinerStatusMonitor},
                 lolminer::{LolMinerGpuMiner, LolMinerGpuMinerStatusMonitor},
+                GpuCommonInformation,
             },
         },
         GpuConnectionType,
     },
-    process_adapter::{
-        HandleUnhealthyResult, HealthStatus, ProcessAdapter, ProcessInstance, StatusMonitor,
-    },
+    process_adapter::{HealthStatus, ProcessAdapter, ProcessInstance, StatusMonitor},
+    process_restart_policy::{GiveUpAction, RestartPolicy},
 };
 
 pub trait GpuMinerInterfaceTrait: Send + Sync {
@@ -53,6 +54,8 @@ pub trait GpuMinerInterfaceTrait: Send + Sync {
         connection_type: GpuConnectionType,
     ) -> Result<(), anyhow::Error>;
     async fn detect_devices(&mut self) -> Result<(), anyhow::Error>;
+    /// Devices found while detecting devices, numbered the way this miner numbers them.
+    fn gpu_devices(&self) -> Vec<GpuCommonInformation>;
     async fn load_gpu_engine(&mut self, _engine: EngineType) -> Result<(), anyhow::Error> {
         Ok(())
     }
@@ -62,6 +65,26 @@ pub trait GpuMinerInterfaceTrait: Send + Sync {
     ) -> Result<(), anyhow::Error> {
         Ok(())
     }
+    /// Intensity per device id, devices without an entry use the intensity percentage.
+    /// Loaded before the excluded devices.
+    async fn load_device_intensities(
+        &mut self,
+        _device_intensities: HashMap<u32, u32>,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+    /// Engine per device id, devices without an entry use the selected engine.
+    /// Loaded before the excluded devices.
+    async fn load_device_engines(
+        &mut self,
+        _device_engines: HashMap<u32, EngineType>,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+    /// Engines found while detecting devices, empty for miners without engine selection.
+    fn available_engines(&self) -> Vec<EngineType> {
+        Vec::new()
+    }
 }
 
 pub enum GpuMinerInterface {
@@ -128,6 +151,14 @@ impl GpuMinerInterfaceTrait for GpuMinerInterface {
         }
     }
 
+    fn gpu_devices(&self) -> Vec<GpuCommonInformation> {
+        match self {
+            GpuMinerInterface::LolMiner(miner) => miner.gpu_devices(),
+            GpuMinerInterface::Graxil(miner) => miner.gpu_devices(),
+            GpuMinerInterface::Glytex(miner) => miner.gpu_devices(),
+        }
+    }
+
     async fn load_excluded_devices(
         &mut self,
         excluded_devices: Vec<u32>,
@@ -140,6 +171,42 @@ impl GpuMinerInterfaceTrait for GpuMinerInterface {
             GpuMinerInterface::Glytex(miner) => miner.load_excluded_devices(excluded_devices).await,
         }
     }
+
+    async fn load_device_intensities(
+        &mut self,
+        device_intensities: HashMap<u32, u32>,
+    ) -> Result<(), anyhow::Error> {
+        match self {
+            GpuMinerInterface::LolMiner(miner) => {
+                miner.load_device_intensities(device_intensities).await
+            }
+            GpuMinerInterface::Graxil(miner) => {
+                miner.load_device_intensities(device_intensities).await
+            }
+            GpuMinerInterface::Glytex(miner) => {
+                miner.load_device_intensities(device_intensities).await
+            }
+        }
+    }
+
+    async fn load_device_engines(
+        &mut self,
+        device_engines: HashMap<u32, EngineType>,
+    ) -> Result<(), anyhow::Error> {
+        match self {
+            GpuMinerInterface::LolMiner(miner) => miner.load_device_engines(device_engines).await,
+            GpuMinerInterface::Graxil(miner) => miner.load_device_engines(device_engines).await,
+            GpuMinerInterface::Glytex(miner) => miner.load_device_engines(device_engines).await,
+        }
+    }
+
+    fn available_engines(&self) -> Vec<EngineType> {
+        match self {
+            GpuMinerInterface::LolMiner(miner) => miner.available_engines(),
+            GpuMinerInterface::Graxil(miner) => miner.available_engines(),
+            GpuMinerInterface::Glytex(miner) => miner.available_engines(),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -151,27 +218,18 @@ pub enum GpuMinerStatusInterface {
 
 #[async_trait]
 impl StatusMonitor for GpuMinerStatusInterface {
-    async fn handle_unhealthy(
-        &self,
-        duration_since_last_healthy_status: Duration,
-    ) -> Result<HandleUnhealthyResult, anyhow::Error> {
-        match self {
-            GpuMinerStatusInterface::LolMiner(monitor) => {
-                monitor
-                    .handle_unhealthy(duration_since_last_healthy_status)
-                    .await
-            }
-            GpuMinerStatusInterface::Graxil(monitor) => {
-                monitor
-                    .handle_unhealthy(duration_since_last_healthy_status)
-                    .await
-            }
-            GpuMinerStatusInterface::Glytex(monitor) => {
-                monitor
-                    .handle_unhealthy(duration_since_last_healthy_status)
-                    .await
-            }
-        }
+    /// Marks the miner as unhealthy and switches to the next healthy miner by priority
+    async fn handle_fallback(&self) -> Result<Option<String>, anyhow::Error> {
+        let miner_type = match self {
+            GpuMinerStatusInterface::LolMiner(_) => GpuMinerType::LolMiner,
+            GpuMinerStatusInterface::Graxil(_) => GpuMinerType::Graxil,
+            GpuMinerStatusInterface::Glytex(_) => GpuMinerType::Glytex,
+        };
+        let fallback_miner = GpuManager::write()
+            .await
+            .handle_unhealthy_miner(&miner_type)
+            .await?;
+        Ok(fallback_miner.map(|miner| miner.to_string()))
     }
     async fn check_health(&self, uptime: Duration, timeout_duration: Duration) -> HealthStatus {
         match self {
@@ -237,4 +295,13 @@ impl ProcessAdapter for GpuMinerInterface {
             GpuMinerInterface::Glytex(miner) => miner.pid_file_name(),
         }
     }
+    fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy {
+            max_restarts: 3,
+            window: Duration::from_secs(5 * 60),
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(30),
+            give_up_action: GiveUpAction::Fallback,
+        }
+    }
 }
@@ -20,8 +20,12 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use log::{error, info};
-use std::{collections::HashMap, sync::LazyLock};
+use log::{error, info, warn};
+use std::{
+    collections::HashMap,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
 use tari_shutdown::Shutdown;
 use tauri::{AppHandle, Manager};
 use tauri_plugin_sentry::sentry;
@@ -39,18 +43,32 @@ use crate::{
     configs::{
         config_mining::{ConfigMining, ConfigMiningContent},
         config_pools::ConfigPools,
-        pools::{gpu_pools::GpuPool, PoolOrigin},
+        pools::{gpu_pools::GpuPool, BasePoolData},
         trait_config::ConfigImpl,
     },
     events_emitter::EventsEmitter,
     internal_wallet::InternalWallet,
     mining::{
         gpu::{
-            consts::{EngineType, GpuMiner, GpuMinerStatus, GpuMinerType, MINERS_PRIORITY},
+            benchmark::GpuBenchmark,
+            consts::{
+                EngineType, GpuMiner, GpuMinerFeature, GpuMinerStatus, GpuMinerType,
+                MINERS_PRIORITY,
+            },
             interface::{GpuMinerInterface, GpuMinerInterfaceTrait},
-            miners::{glytex::GlytexGpuMiner, graxil::GraxilGpuMiner, lolminer::LolMinerGpuMiner},
+            miners::{
+                glytex::GlytexGpuMiner, graxil::GraxilGpuMiner, lolminer::LolMinerGpuMiner,
+                GpuCommonInformation,
+            },
+        },
+        pools::{
+            gpu_pool_manager::GpuPoolManager,
+            pool_failover::{
+                is_pool_reachable, next_failover_pool, DisconnectionTracker, PoolFailoverKind,
+                PoolFailoverMiner, PoolFailoverPayload, SWITCH_BACK_CHECK_INTERVAL,
+            },
+            PoolManagerInterfaceTrait,
         },
-        pools::{gpu_pool_manager::GpuPoolManager, PoolManagerInterfaceTrait},
         GpuConnectionType, MinerControlsState,
     },
     node::node_adapter::BaseNodeStatus,
@@ -64,11 +82,19 @@ use crate::{
 
 static INSTANCE: LazyLock<RwLock<GpuManager>> = LazyLock::new(|| RwLock::new(GpuManager::new()));
 
+/// Miner running next to the selected one on the devices assigned to it
+struct AdditionalGpuMiner {
+    process_watcher: ProcessWatcher<GpuMinerInterface>,
+    status_channel: Sender<GpuMinerStatus>,
+}
+
 pub struct GpuManager {
     app_handle: Option<AppHandle>,
     // ======= Miner config =======
     selected_miner: GpuMinerType,
     available_miners: HashMap<GpuMinerType, GpuMiner>,
+    /// Devices each miner found during the last detection, numbered the way that miner numbers them
+    detected_devices: HashMap<GpuMinerType, Vec<GpuCommonInformation>>,
     // ======= Process watcher =======
     process_watcher: ProcessWatcher<GpuMinerInterface>,
     // ======= Parameters tracking =======
@@ -77,6 +103,10 @@ pub struct GpuManager {
     gpu_internal_status_channel: Sender<GpuMinerStatus>,
     gpu_external_status_channel: Sender<GpuMinerStatus>,
     node_status_channel: Option<Receiver<BaseNodeStatus>>, // Optional, only if connected to a node
+    // ======= Additional miners =======
+    additional_miners: HashMap<GpuMinerType, AdditionalGpuMiner>,
+    additional_statuses_channel: Sender<HashMap<GpuMinerType, GpuMinerStatus>>,
+    additional_process_stats_collector: Sender<HashMap<GpuMinerType, ProcessWatcherStats>>,
     // ======= Cached config =======
     connection_type: GpuConnectionType,
     #[allow(dead_code)]
@@ -89,6 +119,8 @@ pub struct GpuManager {
     worker_name: Option<String>,
     #[allow(dead_code)]
     selected_engine: Option<EngineType>,
+    /// Fallback pool the miner was moved to after the current pool became unreachable
+    failover_pool: Option<GpuPool>,
 }
 
 impl GpuManager {
@@ -98,6 +130,7 @@ impl GpuManager {
             // ======= Miner config =======
             selected_miner: GpuMinerType::LolMiner,
             available_miners: HashMap::new(),
+            detected_devices: HashMap::new(),
             // ======= Process watcher =======
             process_watcher: ProcessWatcher::new(
                 GpuMinerInterface::LolMiner(LolMinerGpuMiner::default()),
@@ -113,6 +146,10 @@ impl GpuManager {
                 GpuMinerType::LolMiner.main_algorithm(),
             )),
             node_status_channel: None,
+            // ======= Additional miners =======
+            additional_miners: HashMap::new(),
+            additional_statuses_channel: Sender::new(HashMap::new()),
+            additional_process_stats_collector: Sender::new(HashMap::new()),
             // ======= Cached config =======
             connection_type: GpuConnectionType::default(),
             tari_address: None,
@@ -120,6 +157,7 @@ impl GpuManager {
             intensity_percentage: None,
             worker_name: None,
             selected_engine: None,
+            failover_pool: None,
         }
     }
 
@@ -147,6 +185,7 @@ impl GpuManager {
 
     pub async fn initialize(
         process_stats_collector: Sender<ProcessWatcherStats>,
+        additional_process_stats_collector: Sender<HashMap<GpuMinerType, ProcessWatcherStats>>,
         status_channel: Sender<GpuMinerStatus>,
         node_status_channel: Option<Receiver<BaseNodeStatus>>,
     ) {
@@ -154,6 +193,7 @@ impl GpuManager {
         let mut instance = INSTANCE.write().await;
 
         instance.process_stats_collector = process_stats_collector;
+        instance.additional_process_stats_collector = additional_process_stats_collector;
         instance.gpu_external_status_channel = status_channel;
         instance.node_status_channel = node_status_channel;
         instance.selected_engine = Some(selected_engine);
@@ -191,6 +231,92 @@ impl GpuManager {
             .insert(miner.miner_type.clone(), miner);
     }
 
+    /// GPU pools in failover order the selected miner can connect to, starting with the current pool.
+    async fn failover_pools(&self) -> Vec<BasePoolData<GpuPool>> {
+        ConfigPools::content()
+            .await
+            .gpu_failover_pools()
+            .into_iter()
+            .enumerate()
+            .filter(|(index, pool)| {
+                *index == 0 || self.selected_miner.is_pool_supported(&pool.pool_type)
+            })
+            .map(|(_, pool)| pool)
+            .collect()
+    }
+
+    /// Pool the miner connects to, the fallback pool after a failover or the current pool otherwise.
+    async fn active_pool(&self) -> BasePoolData<GpuPool> {
+        let mut failover_pools = self.failover_pools().await.into_iter();
+        let primary_pool = failover_pools
+            .next()
+            .unwrap_or_else(|| GpuPool::default().default_content());
+        match &self.failover_pool {
+            Some(failover_pool) => failover_pools
+                .find(|pool| &pool.pool_type == failover_pool)
+                .unwrap_or(primary_pool),
+            None => primary_pool,
+        }
+    }
+
+    /// Restarts the miner on the pool following the active one in the failover order.
+    /// After the last fallback pool the primary pool is tried again.
+    pub async fn fail_over_to_next_pool(&mut self) -> Result<(), anyhow::Error> {
+        let failover_pools = self.failover_pools().await;
+        let active_pool = self.active_pool().await;
+        let pool_types: Vec<GpuPool> = failover_pools
+            .iter()
+            .map(|pool| pool.pool_type.clone())
+            .collect();
+        let Some(next_pool) = next_failover_pool(&pool_types, &active_pool.pool_type) else {
+            info!(target: LOG_TARGET_APP_LOGIC, "No fallback gpu pool configured, staying on {}", active_pool.pool_name);
+            return Ok(());
+        };
+        self.switch_pool(next_pool, PoolFailoverKind::Failover)
+            .await
+    }
+
+    /// Forgets the fallback pool the miner was moved to, it connects to the current pool on its next start.
+    pub fn clear_failover_pool(&mut self) {
+        self.failover_pool = None;
+    }
+
+    /// Restarts the miner on the current pool after running on a fallback pool.
+    pub async fn switch_back_to_primary_pool(&mut self) -> Result<(), anyhow::Error> {
+        if self.failover_pool.is_none() {
+            return Ok(());
+        }
+        let primary_pool = ConfigPools::content().await.current_gpu_pool().pool_type;
+        self.switch_pool(primary_pool, PoolFailoverKind::SwitchBack)
+            .await
+    }
+
+    async fn switch_pool(
+        &mut self,
+        target_pool: GpuPool,
+        kind: PoolFailoverKind,
+    ) -> Result<(), anyhow::Error> {
+        let from_pool = self.active_pool().await;
+        let primary_pool = ConfigPools::content().await.current_gpu_pool().pool_type;
+        self.failover_pool = (target_pool != primary_pool).then_some(target_pool);
+        let to_pool = self.active_pool().await;
+        info!(target: LOG_TARGET_APP_LOGIC, "Switching gpu pool from {} to {} ({kind:?})", from_pool.pool_name, to_pool.pool_name);
+
+        if self.is_running() {
+            self.stop_mining().await?;
+            self.start_mining().await?;
+        }
+
+        EventsEmitter::emit_pool_failover(PoolFailoverPayload {
+            miner: PoolFailoverMiner::Gpu,
+            kind,
+            from_pool: from_pool.pool_name,
+            to_pool: to_pool.pool_name,
+        })
+        .await;
+        Ok(())
+    }
+
     /// Handles loading the pool connection for the selected miner.
     /// If the selected miner does not support pool mining, it attempts to switch to a fallback miner that does.
     /// If no suitable miner is found, an error is returned.
@@ -201,10 +327,11 @@ impl GpuManager {
                 .selected_miner
                 .is_pool_supported(&current_pool_data.pool_type)
         {
+            let active_pool_data = self.active_pool().await;
             self.process_watcher
                 .adapter
                 .load_connection_type(GpuConnectionType::Pool {
-                    pool_url: current_pool_data.pool_url,
+                    pool_url: active_pool_data.pool_url,
                 })
                 .await?;
         } else {
@@ -228,12 +355,12 @@ impl GpuManager {
             if let Some(fallback_miner) = fallback_miner {
                 info!(target: LOG_TARGET_APP_LOGIC, "Selected gpu miner does not support pool mining, switching to fallback miner: {fallback_miner}");
                 self.switch_miner(fallback_miner).await?;
-                let current_pool_data = ConfigPools::content().await.current_gpu_pool().clone();
+                let active_pool_data = self.active_pool().await;
 
                 self.process_watcher
                     .adapter
                     .load_connection_type(GpuConnectionType::Pool {
-                        pool_url: current_pool_data.pool_url,
+                        pool_url: active_pool_data.pool_url,
                     })
                     .await?;
             } else {
@@ -293,6 +420,12 @@ impl GpuManager {
 
     #[allow(clippy::too_many_arguments)]
     pub async fn start_mining(&mut self) -> Result<(), anyhow::Error> {
+        if GpuBenchmark::is_in_progress() {
+            warn!(target: LOG_TARGET_APP_LOGIC, "Not starting gpu miner, GPU benchmark is in progress");
+            return Err(anyhow::anyhow!(
+                "GPU mining cannot start while the GPU benchmark is running"
+            ));
+        }
         info!(target: LOG_TARGET_APP_LOGIC, "Starting gpu miner: {}", self.selected_miner);
         info!(target: LOG_TARGET_APP_LOGIC, "Adapter miner type: {}", self.process_watcher.adapter.name());
 
@@ -365,23 +498,13 @@ impl GpuManager {
                 self.handle_node_connection_load(grpc_node_address).await?;
             }
 
-            let binary = match self.selected_miner {
-                GpuMinerType::Graxil => Binaries::Graxil,
-                GpuMinerType::LolMiner => Binaries::LolMiner,
-                GpuMinerType::Glytex => Binaries::Glytex,
-            };
-
-            // Worker name format depends on the pool
-            // LuckyPool: .Tari-Universe
-            // Kryptex: /Tari-Universe
-            // SupportXTM: Not specified so we use None
-            let worker_name = match ConfigPools::content().await.current_gpu_pool().pool_origin {
-                PoolOrigin::LuckyPool => Some(".Tari-universe"),
-                PoolOrigin::SupportXTM => None,
-                PoolOrigin::Kryptex => Some("/Tari-universe"),
-            };
+            let binary = Self::miner_binary(&self.selected_miner);
 
-            let excluded_devices = ConfigMining::content().await.get_excluded_devices();
+            let worker_name = self
+                .active_pool()
+                .await
+                .pool_origin
+                .worker_name(ConfigPools::content().await.worker_name());
 
             self.process_watcher
                 .adapter
@@ -389,20 +512,19 @@ impl GpuManager {
                 .await?;
             self.process_watcher
                 .adapter
-                .load_worker_name(worker_name)
+                .load_worker_name(worker_name.as_deref())
                 .await?;
             self.process_watcher
                 .adapter
                 .load_intensity_percentage(gpu_usage_percentage)
                 .await?;
-            self.process_watcher
-                .adapter
-                .load_gpu_engine(selected_engine)
-                .await?;
-            self.process_watcher
-                .adapter
-                .load_excluded_devices(excluded_devices)
-                .await?;
+            Self::load_device_settings(
+                &mut self.process_watcher.adapter,
+                &self.selected_miner,
+                selected_engine,
+                None,
+            )
+            .await?;
 
             info!(target: LOG_TARGET_APP_LOGIC, "Starting gpu miner process watcher with binary: {:?}", binary);
 
@@ -424,6 +546,7 @@ impl GpuManager {
                 info!(target: LOG_TARGET_APP_LOGIC, "Started gpu miner pool watcher");
             }
             self.status_thread_shutdown = Shutdown::new();
+            self.start_additional_miners().await;
             self.initialize_status_updates().await;
             info!(target: LOG_TARGET_APP_LOGIC, "Initialized gpu miner status updates");
         } else {
@@ -433,6 +556,325 @@ impl GpuManager {
         Ok(())
     }
 
+    /// Loads the engine and per-device settings of the given miner into its adapter.
+    /// Only the devices assigned to the miner are left included.
+    /// The settings are keyed by the selected miner's device ids, `device_id_map` translates them
+    /// for a miner numbering devices differently. Its devices without a match are excluded.
+    async fn load_device_settings(
+        adapter: &mut GpuMinerInterface,
+        miner_type: &GpuMinerType,
+        selected_engine: EngineType,
+        device_id_map: Option<HashMap<u32, u32>>,
+    ) -> Result<(), anyhow::Error> {
+        let config = ConfigMining::content().await;
+        let mut excluded_devices = config.get_excluded_devices_for_miner(miner_type);
+        let mut device_intensities = config.get_gpu_device_intensities();
+        let mut device_engines = config.get_gpu_device_engines();
+
+        if let Some(device_id_map) = device_id_map {
+            let included_devices: Vec<u32> = device_id_map
+                .iter()
+                .filter(|(device_id, _)| !excluded_devices.contains(device_id))
+                .map(|(_, miner_device_id)| *miner_device_id)
+                .collect();
+            excluded_devices = adapter
+                .gpu_devices()
+                .iter()
+                .map(GpuCommonInformation::device_id)
+                .filter(|device_id| !included_devices.contains(device_id))
+                .collect();
+            device_intensities = Self::map_device_settings(device_intensities, &device_id_map);
+            device_engines = Self::map_device_settings(device_engines, &device_id_map);
+        }
+
+        if !device_intensities.is_empty()
+            && !miner_type.supports_feature(&GpuMinerFeature::PerDeviceIntensity)
+        {
+            warn!(target: LOG_TARGET_APP_LOGIC, "{miner_type} does not support per-device intensity, using the mining mode's intensity for all devices");
+            device_intensities.clear();
+        }
+        if !device_engines.is_empty()
+            && !miner_type.supports_feature(&GpuMinerFeature::PerDeviceEngineSelection)
+        {
+            warn!(target: LOG_TARGET_APP_LOGIC, "{miner_type} does not support per-device engines, using {selected_engine} for all devices");
+            device_engines.clear();
+        }
+
+        adapter.load_gpu_engine(selected_engine).await?;
+        adapter.load_device_intensities(device_intensities).await?;
+        adapter.load_device_engines(device_engines).await?;
+        adapter.load_excluded_devices(excluded_devices).await?;
+        Ok(())
+    }
+
+    fn map_device_settings<T>(
+        device_settings: HashMap<u32, T>,
+        device_id_map: &HashMap<u32, u32>,
+    ) -> HashMap<u32, T> {
+        device_settings
+            .into_iter()
+            .filter_map(|(device_id, setting)| {
+                device_id_map
+                    .get(&device_id)
+                    .map(|miner_device_id| (*miner_device_id, setting))
+            })
+            .collect()
+    }
+
+    /// Maps the device ids of the mining config, numbered by the selected miner, to the ids
+    /// the given miner uses for the same devices.
+    pub(crate) fn map_config_device_ids(&self, adapter: &GpuMinerInterface) -> HashMap<u32, u32> {
+        let selected_miner_devices = self
+            .detected_devices
+            .get(&self.selected_miner)
+            .cloned()
+            .unwrap_or_else(|| self.process_watcher.adapter.gpu_devices());
+        let device_id_map =
+            GpuCommonInformation::map_device_ids(&selected_miner_devices, &adapter.gpu_devices());
+        if device_id_map.len() < selected_miner_devices.len() {
+            warn!(target: LOG_TARGET_APP_LOGIC, "Matched {} of {} devices of {} with {}, the other devices are left out", device_id_map.len(), selected_miner_devices.len(), self.selected_miner, adapter.name());
+        }
+        device_id_map
+    }
+
+    /// Connection of a miner running apart from the selected one. With pool mining enabled it connects
+    /// to the pool of its algorithm: the current pool if it matches, the first fallback pool or the miner's default pool.
+    async fn miner_connection(
+        &self,
+        miner_type: &GpuMinerType,
+    ) -> Result<(GpuConnectionType, Option<BasePoolData<GpuPool>>), anyhow::Error> {
+        if *ConfigPools::content().await.gpu_pool_enabled() {
+            let pool = ConfigPools::content()
+                .await
+                .gpu_pool_for_algorithm(&miner_type.main_algorithm())
+                .or_else(|| miner_type.default_pool().map(|pool| pool.default_content()))
+                .filter(|pool| miner_type.is_pool_supported(&pool.pool_type))
+                .ok_or_else(|| anyhow::anyhow!("No pool configured for {miner_type}"))?;
+            Ok((
+                GpuConnectionType::Pool {
+                    pool_url: pool.pool_url.clone(),
+                },
+                Some(pool),
+            ))
+        } else if miner_type.is_solo_mining_supported() {
+            let app_handle = self
+                .app_handle
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("App handle is not set"))?;
+            let app_state = app_handle.state::<UniverseAppState>();
+            let node_grpc_address = app_state.node_manager.get_grpc_address().await?;
+            Ok((GpuConnectionType::Node { node_grpc_address }, None))
+        } else {
+            Err(anyhow::anyhow!("{miner_type} does not support solo mining"))
+        }
+    }
+
+    /// Starts every healthy miner other than the selected one that has devices assigned to it.
+    /// Failing to start an additional miner does not affect the selected one.
+    async fn start_additional_miners(&mut self) {
+        let additional_miner_types = ConfigMining::content().await.get_additional_gpu_miners();
+        for miner_type in additional_miner_types {
+            if miner_type == self.selected_miner {
+                continue;
+            }
+            if !self
+                .available_miners
+                .get(&miner_type)
+                .is_some_and(|miner| miner.is_healthy)
+            {
+                warn!(target: LOG_TARGET_APP_LOGIC, "Devices are assigned to {miner_type} but it is not available, they will stay idle");
+                continue;
+            }
+            match self.start_additional_miner(&miner_type).await {
+                Ok(_) => {
+                    info!(target: LOG_TARGET_APP_LOGIC, "Started additional gpu miner: {miner_type}");
+                }
+                Err(e) => {
+                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to start additional gpu miner {miner_type}: {e}");
+                }
+            }
+        }
+    }
+
+    /// Creates the interface of a miner running apart from the selected one, with its devices detected
+    /// and the connection, address, worker name and mining mode intensity loaded.
+    pub(crate) async fn prepare_miner_interface(
+        &self,
+        miner_type: &GpuMinerType,
+        status_channel: Sender<GpuMinerStatus>,
+    ) -> Result<GpuMinerInterface, anyhow::Error> {
+        let mut adapter = Self::create_miner_interface(miner_type, status_channel);
+        adapter.detect_devices().await?;
+
+        let (connection_type, pool) = self.miner_connection(miner_type).await?;
+        let configured_worker_name = ConfigPools::content().await.worker_name().clone();
+        let worker_name =
+            pool.and_then(|pool| pool.pool_origin.worker_name(&configured_worker_name));
+        let tari_address = InternalWallet::tari_address().await;
+        let gpu_usage_percentage = ConfigMining::content()
+            .await
+            .get_selected_gpu_usage_percentage();
+
+        adapter.load_connection_type(connection_type).await?;
+        adapter.load_tari_address(&tari_address.to_base58()).await?;
+        adapter.load_worker_name(worker_name.as_deref()).await?;
+        adapter
+            .load_intensity_percentage(gpu_usage_percentage)
+            .await?;
+        Ok(adapter)
+    }
+
+    /// Starts a process watcher for a miner running apart from the selected one.
+    pub(crate) async fn start_miner_watcher(
+        &self,
+        miner_type: &GpuMinerType,
+        adapter: GpuMinerInterface,
+        stats_channel: Sender<ProcessWatcherStats>,
+    ) -> Result<ProcessWatcher<GpuMinerInterface>, anyhow::Error> {
+        let app_handle = self
+            .app_handle
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("App handle is not set"))?;
+        let mut process_watcher = ProcessWatcher::new(adapter, stats_channel);
+        process_watcher
+            .start(
+                app_handle.path().app_local_data_dir()?,
+                app_handle.path().app_config_dir()?,
+                app_handle.path().app_log_dir()?,
+                Self::miner_binary(miner_type),
+                TasksTrackers::current().gpu_mining_phase.get_signal().await,
+                TasksTrackers::current()
+                    .gpu_mining_phase
+                    .get_task_tracker()
+                    .await,
+            )
+            .await?;
+        Ok(process_watcher)
+    }
+
+    async fn start_additional_miner(
+        &mut self,
+        miner_type: &GpuMinerType,
+    ) -> Result<(), anyhow::Error> {
+        let status_channel = Sender::new(GpuMinerStatus::default_with_algorithm(
+            miner_type.main_algorithm(),
+        ));
+        let mut adapter = self
+            .prepare_miner_interface(miner_type, status_channel.clone())
+            .await?;
+        let selected_engine = ConfigMining::content().await.gpu_engine().clone();
+        let device_id_map = self.map_config_device_ids(&adapter);
+        Self::load_device_settings(
+            &mut adapter,
+            miner_type,
+            selected_engine,
+            Some(device_id_map),
+        )
+        .await?;
+        let stats_channel = Sender::new(ProcessWatcherStats::default());
+        let process_watcher = self
+            .start_miner_watcher(miner_type, adapter, stats_channel.clone())
+            .await?;
+
+        self.forward_additional_miner_updates(
+            miner_type.clone(),
+            status_channel.subscribe(),
+            stats_channel.subscribe(),
+        )
+        .await;
+        self.additional_miners.insert(
+            miner_type.clone(),
+            AdditionalGpuMiner {
+                process_watcher,
+                status_channel,
+            },
+        );
+        Ok(())
+    }
+
+    /// Forwards the status and process stats of an additional miner into the combined channels
+    /// of all additional miners until the status updates are shut down.
+    async fn forward_additional_miner_updates(
+        &self,
+        miner_type: GpuMinerType,
+        mut status_receiver: Receiver<GpuMinerStatus>,
+        mut stats_receiver: Receiver<ProcessWatcherStats>,
+    ) {
+        let additional_statuses_channel = self.additional_statuses_channel.clone();
+        let additional_process_stats_collector = self.additional_process_stats_collector.clone();
+        let mut internal_shutdown_signal = self.status_thread_shutdown.to_signal();
+        let mut global_shutdown_signal =
+            TasksTrackers::current().gpu_mining_phase.get_signal().await;
+        TasksTrackers::current()
+            .gpu_mining_phase
+            .get_task_tracker()
+            .await
+            .spawn(async move {
+                loop {
+                    select! {
+                        _ = internal_shutdown_signal.wait() => break,
+                        _ = global_shutdown_signal.wait() => break,
+                        updated_status = status_receiver.changed() => {
+                            if updated_status.is_err() {
+                                break;
+                            }
+                            let status = status_receiver.borrow().clone();
+                            additional_statuses_channel.send_modify(|statuses| {
+                                statuses.insert(miner_type.clone(), status);
+                            });
+                        }
+                        updated_stats = stats_receiver.changed() => {
+                            if updated_stats.is_err() {
+                                break;
+                            }
+                            let stats = stats_receiver.borrow().clone();
+                            additional_process_stats_collector.send_modify(|process_stats| {
+                                process_stats.insert(miner_type.clone(), stats);
+                            });
+                        }
+                    }
+                }
+                additional_statuses_channel.send_modify(|statuses| {
+                    statuses.remove(&miner_type);
+                });
+                additional_process_stats_collector.send_modify(|process_stats| {
+                    process_stats.remove(&miner_type);
+                });
+            });
+    }
+
+    async fn stop_additional_miners(&mut self) {
+        for (miner_type, mut additional_miner) in self.additional_miners.drain() {
+            let _res =
+                additional_miner
+                    .status_channel
+                    .send(GpuMinerStatus::default_with_algorithm(
+                        miner_type.main_algorithm(),
+                    ));
+            additional_miner.process_watcher.status_monitor = None;
+            match additional_miner.process_watcher.stop().await {
+                Ok(_) => {
+                    info!(target: LOG_TARGET_APP_LOGIC, "Stopped additional gpu miner: {miner_type}");
+                }
+                Err(e) => {
+                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to stop additional gpu miner {miner_type}: {e}");
+                }
+            }
+        }
+        self.additional_statuses_channel
+            .send_replace(HashMap::new());
+        self.additional_process_stats_collector
+            .send_replace(HashMap::new());
+    }
+
+    fn miner_binary(miner_type: &GpuMinerType) -> Binaries {
+        match miner_type {
+            GpuMinerType::Graxil => Binaries::Graxil,
+            GpuMinerType::LolMiner => Binaries::LolMiner,
+            GpuMinerType::Glytex => Binaries::Glytex,
+        }
+    }
+
     pub fn is_running(&self) -> bool {
         self.process_watcher.is_running()
     }
@@ -449,6 +891,7 @@ impl GpuManager {
             self.process_watcher.stop().await?;
             self.status_thread_shutdown.trigger();
         }
+        self.stop_additional_miners().await;
 
         info!(target: LOG_TARGET_APP_LOGIC, "Stopped gpu miner process");
         // Mark mining as stopped in pool manager
@@ -462,6 +905,11 @@ impl GpuManager {
     }
 
     pub async fn switch_miner(&mut self, new_miner: GpuMinerType) -> Result<(), anyhow::Error> {
+        if GpuBenchmark::is_in_progress() {
+            return Err(anyhow::anyhow!(
+                "GPU miner cannot be switched while the GPU benchmark is running"
+            ));
+        }
         info!(target: LOG_TARGET_APP_LOGIC, "Switching gpu miner to: {new_miner}");
         if let Some(miner) = self.available_miners.get(&new_miner) {
             info!(target: LOG_TARGET_APP_LOGIC, "Found selected gpu miner in available miners");
@@ -469,6 +917,8 @@ impl GpuManager {
             let mut adapter = self.resolve_miner_interface(&miner_type);
             adapter.detect_devices().await?;
             let miner_cloned = miner.clone();
+            self.detected_devices
+                .insert(miner_type.clone(), adapter.gpu_devices());
             info!(target: LOG_TARGET_APP_LOGIC, "Resolved selected gpu miner interface");
 
             self.stop_mining().await.ok();
@@ -489,14 +939,23 @@ impl GpuManager {
 
     /// Will need to mark current seleceted miner as unhealthy and switch to another one based on priority
     /// If no other miners are available, we will just mark the current one as unhealthy and emit the status
-    pub async fn handle_unhealthy_miner(&mut self) -> Result<(), anyhow::Error> {
-        info!(target: LOG_TARGET_APP_LOGIC, "Handling unhealthy gpu miner");
+    /// An unhealthy additional miner is only marked as unhealthy, its devices stay idle
+    /// Returns the miner we are switching to, if any
+    pub async fn handle_unhealthy_miner(
+        &mut self,
+        miner_type: &GpuMinerType,
+    ) -> Result<Option<GpuMinerType>, anyhow::Error> {
+        info!(target: LOG_TARGET_APP_LOGIC, "Handling unhealthy gpu miner: {miner_type}");
+
+        // mark the miner as unhealthy
+        if let Some(miner) = self.available_miners.get_mut(miner_type) {
+            miner.is_healthy = false;
+            miner.last_error = Some("Miner process crashed or became unresponsive".to_string());
+        }
 
-        // mark current miner as unhealthy
-        if let Some(current_miner) = self.available_miners.get_mut(&self.selected_miner) {
-            current_miner.is_healthy = false;
-            current_miner.last_error =
-                Some("Miner process crashed or became unresponsive".to_string());
+        if miner_type != &self.selected_miner {
+            EventsEmitter::emit_available_gpu_miners(self.available_miners.clone()).await;
+            return Ok(None);
         }
 
         let fallback_miner = MINERS_PRIORITY
@@ -512,7 +971,7 @@ impl GpuManager {
             })
             .cloned();
 
-        if let Some(fallback_miner) = fallback_miner {
+        if let Some(fallback_miner) = fallback_miner.clone() {
             info!(target: LOG_TARGET_APP_LOGIC, "Switching to fallback gpu miner: {fallback_miner}");
             TasksTrackers::current()
                 .gpu_mining_phase
@@ -535,27 +994,44 @@ impl GpuManager {
 
         EventsEmitter::emit_available_gpu_miners(self.available_miners.clone()).await;
 
-        Ok(())
+        Ok(fallback_miner)
+    }
+
+    pub fn healthy_miners(&self) -> Vec<GpuMinerType> {
+        MINERS_PRIORITY
+            .iter()
+            .filter(|miner_type| {
+                self.available_miners
+                    .get(miner_type)
+                    .is_some_and(|miner| miner.is_healthy)
+            })
+            .cloned()
+            .collect()
     }
 
-    pub async fn is_current_miner_healthy(&self) -> bool {
+    pub async fn is_miner_healthy(&self, miner_type: &GpuMinerType) -> bool {
         self.available_miners
-            .get(&self.selected_miner)
+            .get(miner_type)
             .map(|m| m.is_healthy)
             .unwrap_or(false)
     }
 
-    /// Will need to mark current seleceted miner as healthy if it was unhealthy before
+    /// Will need to mark the miner as healthy if it was unhealthy before
     /// If the miner was healthy before, we do nothing
     /// Mainly for cases when the miner was unhealthy and user want to try again and this time it works
-    pub async fn handle_healthy_miner(&mut self) -> Result<(), anyhow::Error> {
-        // mark current miner as healthy
-        if let Some(current_miner) = self.available_miners.get_mut(&self.selected_miner) {
-            if !current_miner.is_healthy {
-                current_miner.is_healthy = true;
-                current_miner.last_error = None;
+    pub async fn handle_healthy_miner(
+        &mut self,
+        miner_type: &GpuMinerType,
+    ) -> Result<(), anyhow::Error> {
+        // mark the miner as healthy
+        if let Some(miner) = self.available_miners.get_mut(miner_type) {
+            if !miner.is_healthy {
+                miner.is_healthy = true;
+                miner.last_error = None;
             }
-            EventsEmitter::emit_update_selected_gpu_miner(current_miner.miner_type.clone()).await;
+        }
+        if miner_type == &self.selected_miner {
+            EventsEmitter::emit_update_selected_gpu_miner(miner_type.clone()).await;
         }
 
         EventsEmitter::emit_available_gpu_miners(self.available_miners.clone()).await;
@@ -570,6 +1046,7 @@ impl GpuManager {
     pub async fn detect_devices(&mut self) -> Result<(), anyhow::Error> {
         let mut successful_detection = false;
         let mut unhealthy_miners = vec![];
+        let mut detected_devices = HashMap::new();
 
         for miner_type in self.available_miners.keys() {
             let mut adapter = self.resolve_miner_interface(miner_type);
@@ -577,6 +1054,7 @@ impl GpuManager {
             match detection_result {
                 Ok(_) => {
                     successful_detection = true;
+                    detected_devices.insert(miner_type.clone(), adapter.gpu_devices());
                     info!(target: LOG_TARGET_APP_LOGIC, "Devices detected with miner: {miner_type}");
                 }
                 Err(e) => {
@@ -586,6 +1064,7 @@ impl GpuManager {
             }
         }
 
+        self.detected_devices.extend(detected_devices);
         for miner_type in unhealthy_miners {
             if let Some(miner) = self.available_miners.get_mut(&miner_type) {
                 miner.is_healthy = false;
@@ -603,16 +1082,19 @@ impl GpuManager {
     }
 
     fn resolve_miner_interface(&self, miner_type: &GpuMinerType) -> GpuMinerInterface {
+        Self::create_miner_interface(miner_type, self.gpu_internal_status_channel.clone())
+    }
+
+    pub(crate) fn create_miner_interface(
+        miner_type: &GpuMinerType,
+        status_channel: Sender<GpuMinerStatus>,
+    ) -> GpuMinerInterface {
         match miner_type {
-            GpuMinerType::Graxil => GpuMinerInterface::Graxil(GraxilGpuMiner::new(
-                self.gpu_internal_status_channel.clone(),
-            )),
-            GpuMinerType::LolMiner => GpuMinerInterface::LolMiner(LolMinerGpuMiner::new(
-                self.gpu_internal_status_channel.clone(),
-            )),
-            GpuMinerType::Glytex => GpuMinerInterface::Glytex(GlytexGpuMiner::new(
-                self.gpu_internal_status_channel.clone(),
-            )),
+            GpuMinerType::Graxil => GpuMinerInterface::Graxil(GraxilGpuMiner::new(status_channel)),
+            GpuMinerType::LolMiner => {
+                GpuMinerInterface::LolMiner(LolMinerGpuMiner::new(status_channel))
+            }
+            GpuMinerType::Glytex => GpuMinerInterface::Glytex(GlytexGpuMiner::new(status_channel)),
         }
     }
 
@@ -622,6 +1104,23 @@ impl GpuManager {
         let node_status_channel = self.node_status_channel.clone();
         let connection_type = self.connection_type.clone();
         let mut last_known_status = self.gpu_internal_status_channel.borrow().clone();
+        let mut additional_statuses_receiver = self.additional_statuses_channel.subscribe();
+
+        // Failover is only watched for pool mining with fallback pools configured
+        let pools_config = ConfigPools::content().await;
+        let can_fail_over =
+            *pools_config.gpu_pool_enabled() && self.failover_pools().await.len() > 1;
+        let mut disconnection_tracker = DisconnectionTracker::new(Duration::from_secs(
+            *pools_config.pool_failover_timeout_secs(),
+        ));
+        let primary_pool_url = self
+            .failover_pool
+            .is_some()
+            .then(|| pools_config.current_gpu_pool().pool_url);
+        let mut switch_back_interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + SWITCH_BACK_CHECK_INTERVAL,
+            SWITCH_BACK_CHECK_INTERVAL,
+        );
 
         let mut internal_shutdown_signal = self.status_thread_shutdown.to_signal();
         let mut global_shutdown_signal =
@@ -631,6 +1130,7 @@ impl GpuManager {
             .get_task_tracker()
             .await;
 
+        let failover_task_tracker = task_tracker.clone();
         task_tracker.spawn(async move {
             loop {
                 select! {
@@ -655,13 +1155,44 @@ impl GpuManager {
                             };
                             let _res = gpu_external_status_channel.send(paresd_status.clone());
                             last_known_status = paresd_status.clone();
-                            EventsEmitter::emit_gpu_mining_update(paresd_status.clone()).await;
+                            let additional_statuses = additional_statuses_receiver.borrow().clone();
+                            EventsEmitter::emit_gpu_mining_update(paresd_status.aggregate(additional_statuses.values())).await;
 
                             info!(target: LOG_TARGET_STATUSES, "Gpu hashrate: {}", paresd_status.hash_rate);
                             SystemTrayManager::send_event(SystemTrayEvents::GpuHashrate(paresd_status.hash_rate)).await;
+
+                            // GPU miners do not report their pool connection, no hashrate means no work from the pool
+                            if can_fail_over && disconnection_tracker.update(paresd_status.hash_rate > 0.0, Instant::now()) {
+                                disconnection_tracker.reset();
+                                info!(target: LOG_TARGET_APP_LOGIC, "Gpu miner has not been hashing for too long, failing over to the next pool");
+                                failover_task_tracker.spawn(async move {
+                                    GpuManager::write().await.fail_over_to_next_pool().await.unwrap_or_else(|e| {
+                                        error!(target: LOG_TARGET_APP_LOGIC, "Failed to fail over to the next gpu pool: {e}");
+                                    });
+                                });
+                            }
                         } else {
                             break;
                         }
+                    },
+                    updated_statuses = additional_statuses_receiver.changed() => {
+                        if updated_statuses.is_ok() {
+                            let additional_statuses = additional_statuses_receiver.borrow().clone();
+                            EventsEmitter::emit_gpu_mining_update(last_known_status.aggregate(additional_statuses.values())).await;
+                        }
+                    },
+                    _ = switch_back_interval.tick(), if primary_pool_url.is_some() => {
+                        let Some(primary_pool_url) = primary_pool_url.as_deref() else {
+                            continue;
+                        };
+                        if is_pool_reachable(primary_pool_url).await {
+                            info!(target: LOG_TARGET_APP_LOGIC, "Primary gpu pool is reachable again, switching back");
+                            failover_task_tracker.spawn(async move {
+                                GpuManager::write().await.switch_back_to_primary_pool().await.unwrap_or_else(|e| {
+                                    error!(target: LOG_TARGET_APP_LOGIC, "Failed to switch back to the primary gpu pool: {e}");
+                                });
+                            });
+                        }
                     }
                 }
             }
@@ -696,17 +1227,19 @@ impl GpuManager {
     }
 
     pub async fn on_app_exit(&self) {
-        match self
-            .process_watcher
-            .adapter
-            .ensure_no_hanging_processes_are_running()
-            .await
-        {
-            Ok(_) => {
-                info!(target: LOG_TARGET_APP_LOGIC, "GpuManager::on_app_exit completed successfully");
-            }
-            Err(e) => {
-                error!(target: LOG_TARGET_APP_LOGIC, "GpuManager::on_app_exit failed: {}", e);
+        let adapters = std::iter::once(&self.process_watcher.adapter).chain(
+            self.additional_miners
+                .values()
+                .map(|additional_miner| &additional_miner.process_watcher.adapter),
+        );
+        for adapter in adapters {
+            match adapter.ensure_no_hanging_processes_are_running().await {
+                Ok(_) => {
+                    info!(target: LOG_TARGET_APP_LOGIC, "GpuManager::on_app_exit completed successfully for {}", adapter.name());
+                }
+                Err(e) => {
+                    error!(target: LOG_TARGET_APP_LOGIC, "GpuManager::on_app_exit failed for {}: {}", adapter.name(), e);
+                }
             }
         }
     }
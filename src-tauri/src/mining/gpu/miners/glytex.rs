@@ -20,11 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{
-    fs::read_dir,
-    sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
-};
+use std::{collections::HashMap, fs::read_dir, time::Duration};
 
 use axum::async_trait;
 use log::{info, warn};
@@ -38,7 +34,11 @@ use crate::utils::windows_setup_utils::add_firewall_rule;
 
 use crate::{
     binaries::{Binaries, BinaryResolver},
-    configs::{config_mining::ConfigMining, trait_config::ConfigImpl},
+    configs::{
+        config_mining::{ConfigMining, ConfigMiningContent},
+        trait_config::ConfigImpl,
+    },
+    events::GpuEnginePreferencesIgnoredPayload,
     events_emitter::EventsEmitter,
     mining::{
         gpu::{
@@ -50,9 +50,10 @@ use crate::{
         GpuConnectionType,
     },
     process_adapter::{
-        HandleUnhealthyResult, HealthStatus, ProcessAdapter, ProcessInstance, ProcessStartupSpec,
-        StatusMonitor,
+        HealthStatus, ProcessAdapter, ProcessInstance, ProcessStartupSpec, StatusMonitor,
     },
+    process_isolation::ProcessIsolation,
+    process_restart_policy::StderrTail,
     process_utils, APPLICATION_FOLDER_ID, LOG_TARGET_APP_LOGIC, LOG_TARGET_STATUSES,
 };
 
@@ -83,6 +84,8 @@ impl Default for GlytexGpuSettings {
 pub struct GlytexGpuDevice {
     pub device_name: String,
     pub device_index: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pci_bus_id: Option<u32>,
     pub status: GlytexGpuStatus,
     pub settings: GlytexGpuSettings,
 }
@@ -100,6 +103,12 @@ pub struct GlytexGpuMiner {
     pub gpu_status_sender: Sender<GpuMinerStatus>,
     pub gpu_devices: Vec<GpuCommonInformation>,
     pub excluded_devices: Vec<u32>,
+    pub device_intensities: HashMap<u32, u32>,
+    pub device_engines: HashMap<u32, EngineType>,
+    /// Grid size of each device in device index order, `None` to use the default for all devices
+    pub device_grid_sizes: Option<Vec<u32>>,
+    /// Engines with a status file written during detection
+    pub available_engines: Vec<EngineType>,
 }
 
 impl GlytexGpuMiner {
@@ -113,7 +122,85 @@ impl GlytexGpuMiner {
             gpu_status_sender,
             gpu_devices: vec![],
             excluded_devices: vec![],
+            device_intensities: HashMap::new(),
+            device_engines: HashMap::new(),
+            device_grid_sizes: None,
+            available_engines: vec![],
+        }
+    }
+
+    /// Grid size of every device, scaled down for devices with their own intensity.
+    /// Intensities scale the default grid size, so a device at 100% matches devices without one.
+    fn resolve_device_grid_sizes(
+        devices: &[GlytexGpuDevice],
+        device_intensities: &HashMap<u32, u32>,
+    ) -> Option<Vec<u32>> {
+        if device_intensities.is_empty() {
+            return None;
+        }
+        let mut devices: Vec<&GlytexGpuDevice> = devices.iter().collect();
+        devices.sort_by_key(|device| device.device_index);
+        Some(
+            devices
+                .iter()
+                .map(
+                    |device| match device_intensities.get(&device.device_index) {
+                        Some(intensity) => {
+                            (DEFAULT_GPU_THREADS.saturating_mul((*intensity).min(100)) / 100).max(1)
+                        }
+                        None => DEFAULT_GPU_THREADS,
+                    },
+                )
+                .collect(),
+        )
+    }
+
+    /// Engine the glytex process runs on. A single process runs on one engine, so included devices
+    /// vote with their preferred engine and the most preferred available engine wins. Devices
+    /// without a preference do not vote and ties go to the selected engine.
+    fn resolve_engine(
+        selected_engine: &EngineType,
+        device_engines: &HashMap<u32, EngineType>,
+        excluded_devices: &[u32],
+        available_engines: &[EngineType],
+    ) -> EngineType {
+        let mut votes: Vec<(EngineType, usize)> = Vec::new();
+        for (device_index, engine) in device_engines {
+            if excluded_devices.contains(device_index)
+                || !(available_engines.is_empty() || available_engines.contains(engine))
+            {
+                continue;
+            }
+            match votes
+                .iter_mut()
+                .find(|(voted_engine, _)| voted_engine == engine)
+            {
+                Some((_, count)) => *count += 1,
+                None => votes.push((engine.clone(), 1)),
+            }
         }
+        votes
+            .into_iter()
+            .max_by_key(|(engine, count)| (*count, engine == selected_engine, engine.to_string()))
+            .map_or_else(|| selected_engine.clone(), |(engine, _)| engine)
+    }
+
+    /// Included devices preferring another engine than the one the process runs on, they are
+    /// mined with that engine instead.
+    fn devices_with_ignored_engine(
+        engine: &EngineType,
+        device_engines: &HashMap<u32, EngineType>,
+        excluded_devices: &[u32],
+    ) -> Vec<u32> {
+        let mut device_indexes: Vec<u32> = device_engines
+            .iter()
+            .filter(|(device_index, preferred_engine)| {
+                *preferred_engine != engine && !excluded_devices.contains(*device_index)
+            })
+            .map(|(device_index, _)| *device_index)
+            .collect();
+        device_indexes.sort_unstable();
+        device_indexes
     }
 }
 
@@ -124,7 +211,33 @@ impl GpuMinerInterfaceTrait for GlytexGpuMiner {
     ) -> Result<(), anyhow::Error> {
         self.excluded_devices = excluded_devices;
 
-        let selected_engine = self.selected_engine.clone().unwrap_or(EngineType::OpenCL);
+        let configured_engine = self.selected_engine.clone().unwrap_or(EngineType::OpenCL);
+        let selected_engine = Self::resolve_engine(
+            &configured_engine,
+            &self.device_engines,
+            &self.excluded_devices,
+            &self.available_engines,
+        );
+        if selected_engine != configured_engine {
+            info!(target: LOG_TARGET_APP_LOGIC, "Running glytex on {selected_engine} as preferred by its devices instead of {configured_engine}");
+            self.selected_engine = Some(selected_engine.clone());
+        }
+        let ignored_devices = Self::devices_with_ignored_engine(
+            &selected_engine,
+            &self.device_engines,
+            &self.excluded_devices,
+        );
+        if !ignored_devices.is_empty() {
+            warn!(target: LOG_TARGET_APP_LOGIC, "Devices {ignored_devices:?} prefer another engine than {selected_engine}, they are mined with {selected_engine}");
+            EventsEmitter::emit_gpu_engine_preferences_ignored(
+                GpuEnginePreferencesIgnoredPayload {
+                    engine: selected_engine.to_string(),
+                    device_indexes: ignored_devices,
+                },
+            )
+            .await;
+        }
+
         let config_path =
             dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Failed to get config directory"))?;
 
@@ -141,9 +254,32 @@ impl GpuMinerInterfaceTrait for GlytexGpuMiner {
         }
         save_file_content::<GlytexGpuDevices>(&gpu_status_file_path, &gpu_status_file).await?;
 
+        self.device_grid_sizes =
+            Self::resolve_device_grid_sizes(&gpu_status_file.gpu_devices, &self.device_intensities);
+
         Ok(())
     }
 
+    async fn load_device_intensities(
+        &mut self,
+        device_intensities: HashMap<u32, u32>,
+    ) -> Result<(), anyhow::Error> {
+        self.device_intensities = device_intensities;
+        Ok(())
+    }
+
+    async fn load_device_engines(
+        &mut self,
+        device_engines: HashMap<u32, EngineType>,
+    ) -> Result<(), anyhow::Error> {
+        self.device_engines = device_engines;
+        Ok(())
+    }
+
+    fn available_engines(&self) -> Vec<EngineType> {
+        self.available_engines.clone()
+    }
+
     async fn load_tari_address(&mut self, tari_address: &str) -> Result<(), anyhow::Error> {
         self.tari_address = Some(tari_address.to_string());
         Ok(())
@@ -166,6 +302,10 @@ impl GpuMinerInterfaceTrait for GlytexGpuMiner {
         self.connection_type = Some(connection_type);
         Ok(())
     }
+    fn gpu_devices(&self) -> Vec<GpuCommonInformation> {
+        self.gpu_devices.clone()
+    }
+
     async fn detect_devices(&mut self) -> Result<(), anyhow::Error> {
         let selected_engine = self.selected_engine.clone().unwrap_or(EngineType::OpenCL);
         let config_path =
@@ -197,8 +337,14 @@ impl GpuMinerInterfaceTrait for GlytexGpuMiner {
             .await?;
 
         crate::download_utils::set_permissions(&gpuminer_bin).await?;
-        let child =
-            process_utils::launch_child_process(&gpuminer_bin, &config_dir, None, &args, false)?;
+        let child = process_utils::launch_child_process(
+            &gpuminer_bin,
+            &config_dir,
+            None,
+            &args,
+            false,
+            &ProcessIsolation::default(),
+        )?;
         let output = child.wait_with_output().await?;
 
         match output.status.code() {
@@ -217,12 +363,24 @@ impl GpuMinerInterfaceTrait for GlytexGpuMiner {
                 self.gpu_devices = common_gpu_devices.clone();
                 EventsEmitter::emit_detected_devices(common_gpu_devices).await;
 
+                let devices_indexes: Vec<u32> = gpu_status_file
+                    .gpu_devices
+                    .iter()
+                    .map(|device| device.device_index)
+                    .collect();
+                ConfigMining::update_field(
+                    ConfigMiningContent::populate_gpu_devices_settings,
+                    devices_indexes,
+                )
+                .await?;
+
                 EventsEmitter::emit_update_gpu_devices_settings(
                     ConfigMining::content().await.gpu_devices_settings().clone(),
                 )
                 .await;
 
                 let mut available_engines: Vec<String> = vec![];
+                self.available_engines.clear();
 
                 for entry in read_dir(gpu_engine_statuses_path)? {
                     info!(target: LOG_TARGET_APP_LOGIC, "Reading engine status file");
@@ -244,6 +402,7 @@ impl GpuMinerInterfaceTrait for GlytexGpuMiner {
                     match engine_type {
                         Ok(engine) => {
                             available_engines.push(engine.to_string());
+                            self.available_engines.push(engine);
                         }
                         Err(_) => {
                             info!(target: LOG_TARGET_APP_LOGIC, "Invalid engine type: {sanitized_file_name:?}");
@@ -314,7 +473,14 @@ impl ProcessAdapter for GlytexGpuMiner {
             .to_string_lossy()
             .to_string();
 
-        let grid_size = format!("{DEFAULT_GPU_THREADS}");
+        let grid_size = match &self.device_grid_sizes {
+            Some(grid_sizes) => grid_sizes
+                .iter()
+                .map(|size| size.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+            None => format!("{DEFAULT_GPU_THREADS}"),
+        };
 
         let selected_engine = self.selected_engine.clone().unwrap_or_default();
 
@@ -391,8 +557,11 @@ impl ProcessAdapter for GlytexGpuMiner {
                     data_dir: base_folder,
                     pid_file_name: self.pid_file_name().to_string(),
                     name: self.name().to_string(),
+                    binary: Binaries::Glytex,
+                    isolation: ProcessIsolation::gpu_miner(),
                 },
                 handle: None,
+                stderr_tail: StderrTail::default(),
             },
             GpuMinerStatusInterface::Glytex(GlytexGpuMinerStatusMonitor {
                 http_api_port,
@@ -416,36 +585,8 @@ pub struct GlytexGpuMinerStatusMonitor {
     gpu_status_sender: Sender<GpuMinerStatus>,
 }
 
-// This is a flag to indicate if the fallback to other miner has already been triggered
-// We want to avoid triggering it multiple times per session
-static WAS_FALLBACK_TO_OTHER_MINER_TRIGGERED: AtomicBool = AtomicBool::new(false);
-
 #[async_trait]
 impl StatusMonitor for GlytexGpuMinerStatusMonitor {
-    async fn handle_unhealthy(
-        &self,
-        duration_since_last_healthy_status: Duration,
-    ) -> Result<HandleUnhealthyResult, anyhow::Error> {
-        info!(target: LOG_TARGET_STATUSES, "Handling unhealthy status for GpuMinerShaAdapter | Duration since last healthy status: {:?}", duration_since_last_healthy_status.as_secs());
-        if duration_since_last_healthy_status.as_secs().gt(&(60 * 3)) // Fallback after 3 minutes of unhealthiness
-            && !WAS_FALLBACK_TO_OTHER_MINER_TRIGGERED.load(Ordering::SeqCst)
-        {
-            match GpuManager::write().await.handle_unhealthy_miner().await {
-                Ok(_) => {
-                    info!(target: LOG_TARGET_STATUSES, "GpuMinerShaAdapter: GPU Pool feature turned off due to prolonged unhealthiness.");
-                    WAS_FALLBACK_TO_OTHER_MINER_TRIGGERED.store(true, Ordering::SeqCst);
-                    return Ok(HandleUnhealthyResult::Stop);
-                }
-                Err(error) => {
-                    warn!(target: LOG_TARGET_STATUSES, "GpuMinerShaAdapter: Failed to turn off GPU Pool feature: {error} | Continuing to monitor.");
-                    return Ok(HandleUnhealthyResult::Continue);
-                }
-            }
-        } else {
-            return Ok(HandleUnhealthyResult::Continue);
-        }
-    }
-
     async fn check_health(&self, _uptime: Duration, timeout_duration: Duration) -> HealthStatus {
         let status = match tokio::time::timeout(timeout_duration, self.status()).await {
             Ok(inner) => inner,
@@ -464,9 +605,16 @@ impl StatusMonitor for GlytexGpuMinerStatusMonitor {
             Ok(status) => {
                 let _ = self.gpu_status_sender.send(status.clone());
                 if status.hash_rate > 0.0 {
-                    if !GpuManager::read().await.is_current_miner_healthy().await {
+                    if !GpuManager::read()
+                        .await
+                        .is_miner_healthy(&GpuMinerType::Glytex)
+                        .await
+                    {
                         info!(target: LOG_TARGET_STATUSES, "Marking current miner as healthy again");
-                        let _unused = GpuManager::write().await.handle_healthy_miner().await;
+                        let _unused = GpuManager::write()
+                            .await
+                            .handle_healthy_miner(&GpuMinerType::Glytex)
+                            .await;
                     }
                     HealthStatus::Healthy
                 } else {
@@ -503,6 +651,7 @@ impl GlytexGpuMinerStatusMonitor {
                         hash_rate: 0.0,
                         estimated_earnings: 0,
                         algorithm: GpuMinerType::Glytex.main_algorithm(),
+                        ..Default::default()
                     });
                 }
                 return Ok(GpuMinerStatus {
@@ -510,6 +659,7 @@ impl GlytexGpuMinerStatusMonitor {
                     hash_rate: 0.0,
                     estimated_earnings: 0,
                     algorithm: GpuMinerType::Glytex.main_algorithm(),
+                    ..Default::default()
                 });
             }
         };
@@ -523,6 +673,7 @@ impl GlytexGpuMinerStatusMonitor {
                     hash_rate: 0.0,
                     estimated_earnings: 0,
                     algorithm: GpuMinerType::Glytex.main_algorithm(),
+                    ..Default::default()
                 });
             }
         };
@@ -532,6 +683,7 @@ impl GlytexGpuMinerStatusMonitor {
             estimated_earnings: 0,
             hash_rate: body.total_hashrate.ten_seconds.unwrap_or(0.0),
             algorithm: GpuMinerType::Glytex.main_algorithm(),
+            ..Default::default()
         })
     }
 }
@@ -546,3 +698,101 @@ struct XtrGpuminerHttpApiStatus {
 pub(crate) struct AverageHashrate {
     ten_seconds: Option<f64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(device_index: u32, max_grid_size: u32) -> GlytexGpuDevice {
+        GlytexGpuDevice {
+            device_name: format!("GPU {device_index}"),
+            device_index,
+            pci_bus_id: None,
+            status: GlytexGpuStatus {
+                recommended_grid_size: 0,
+                recommended_block_size: 0,
+                max_grid_size,
+            },
+            settings: GlytexGpuSettings::default(),
+        }
+    }
+
+    #[test]
+    fn test_grid_sizes_default_without_device_intensities() {
+        let devices = vec![device(0, 65536), device(1, 4096)];
+        assert_eq!(
+            GlytexGpuMiner::resolve_device_grid_sizes(&devices, &HashMap::new()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_grid_sizes_scale_devices_with_own_intensity_from_the_default() {
+        let devices = vec![device(1, 4096), device(0, 65536), device(2, 0)];
+        let intensities = HashMap::from([(1, 50), (2, 10)]);
+        assert_eq!(
+            GlytexGpuMiner::resolve_device_grid_sizes(&devices, &intensities),
+            Some(vec![
+                DEFAULT_GPU_THREADS,
+                DEFAULT_GPU_THREADS / 2,
+                DEFAULT_GPU_THREADS / 10
+            ])
+        );
+    }
+
+    #[test]
+    fn test_engine_follows_device_preferences() {
+        let available_engines = vec![EngineType::OpenCL, EngineType::Cuda];
+        let device_engines = HashMap::from([(0, EngineType::Cuda), (1, EngineType::Cuda)]);
+        let engine = GlytexGpuMiner::resolve_engine(
+            &EngineType::OpenCL,
+            &device_engines,
+            &[],
+            &available_engines,
+        );
+        assert_eq!(engine, EngineType::Cuda);
+        assert!(
+            GlytexGpuMiner::devices_with_ignored_engine(&engine, &device_engines, &[]).is_empty()
+        );
+
+        // Unavailable engines are not applied
+        assert_eq!(
+            GlytexGpuMiner::resolve_engine(
+                &EngineType::OpenCL,
+                &device_engines,
+                &[],
+                &[EngineType::OpenCL],
+            ),
+            EngineType::OpenCL
+        );
+    }
+
+    #[test]
+    fn test_conflicting_preferences_keep_the_selected_engine() {
+        let available_engines = vec![EngineType::OpenCL, EngineType::Cuda];
+        let device_engines = HashMap::from([(0, EngineType::Cuda), (1, EngineType::OpenCL)]);
+        let engine = GlytexGpuMiner::resolve_engine(
+            &EngineType::OpenCL,
+            &device_engines,
+            &[],
+            &available_engines,
+        );
+        assert_eq!(engine, EngineType::OpenCL);
+        assert_eq!(
+            GlytexGpuMiner::devices_with_ignored_engine(&engine, &device_engines, &[]),
+            vec![0]
+        );
+
+        // Excluded devices neither vote nor get reported
+        let engine = GlytexGpuMiner::resolve_engine(
+            &EngineType::OpenCL,
+            &device_engines,
+            &[1],
+            &available_engines,
+        );
+        assert_eq!(engine, EngineType::Cuda);
+        assert!(
+            GlytexGpuMiner::devices_with_ignored_engine(&engine, &device_engines, &[1]).is_empty()
+        );
+    }
+}
@@ -20,10 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{
-    sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
-};
+use std::{collections::HashMap, time::Duration};
 
 #[cfg(target_os = "windows")]
 use crate::utils::windows_setup_utils::add_firewall_rule;
@@ -43,7 +40,7 @@ use crate::{
     events_emitter::EventsEmitter,
     mining::{
         gpu::{
-            consts::{GpuMinerStatus, GpuMinerType},
+            consts::{GpuMinerStatus, GpuMinerTelemetry, GpuMinerType},
             interface::{GpuMinerInterfaceTrait, GpuMinerStatusInterface},
             manager::GpuManager,
             miners::{load_file_content, GpuCommonInformation, GpuDeviceType, GpuVendor},
@@ -53,9 +50,10 @@ use crate::{
     },
     port_allocator::PortAllocator,
     process_adapter::{
-        HandleUnhealthyResult, HealthStatus, ProcessAdapter, ProcessInstance, ProcessStartupSpec,
-        StatusMonitor,
+        HealthStatus, ProcessAdapter, ProcessInstance, ProcessStartupSpec, StatusMonitor,
     },
+    process_isolation::ProcessIsolation,
+    process_restart_policy::StderrTail,
     process_utils, APPLICATION_FOLDER_ID, LOG_TARGET_APP_LOGIC, LOG_TARGET_STATUSES,
 };
 
@@ -69,6 +67,8 @@ pub struct GraxilGpuDeviceInformation {
     pub max_compute_units: u32,
     pub global_mem_size: u64,
     pub device_type: GpuDeviceType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pci_bus_id: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +86,7 @@ pub struct GraxilGpuMiner {
     pub gpu_devices: Vec<GpuCommonInformation>,
     pub raw_gpu_devices: Vec<GraxilGpuDeviceInformation>,
     pub excluded_devices: Vec<u32>,
+    pub device_intensities: HashMap<u32, u32>,
 }
 
 impl GraxilGpuMiner {
@@ -99,7 +100,38 @@ impl GraxilGpuMiner {
             gpu_devices: vec![],
             raw_gpu_devices: vec![],
             excluded_devices: vec![],
+            device_intensities: HashMap::new(),
+        }
+    }
+
+    /// Value of the `--gpu-intensity` argument, a comma separated list with one value per
+    /// detected device when any device has its own intensity.
+    fn resolve_intensity_argument(&self) -> Option<String> {
+        if self.device_intensities.is_empty() || self.raw_gpu_devices.is_empty() {
+            return self
+                .intensity_percentage
+                .map(|intensity| intensity.to_string());
         }
+        let default_intensity = self.intensity_percentage.unwrap_or(100);
+        let mut device_ids: Vec<u32> = self
+            .raw_gpu_devices
+            .iter()
+            .map(|device| device.device_id)
+            .collect();
+        device_ids.sort_unstable();
+        Some(
+            device_ids
+                .iter()
+                .map(|device_id| {
+                    self.device_intensities
+                        .get(device_id)
+                        .copied()
+                        .unwrap_or(default_intensity)
+                        .to_string()
+                })
+                .collect::<Vec<String>>()
+                .join(","),
+        )
     }
 
     pub fn get_raw_gpu_devices(&self) -> Vec<GraxilGpuDeviceInformation> {
@@ -116,6 +148,14 @@ impl GpuMinerInterfaceTrait for GraxilGpuMiner {
         Ok(())
     }
 
+    async fn load_device_intensities(
+        &mut self,
+        device_intensities: HashMap<u32, u32>,
+    ) -> Result<(), anyhow::Error> {
+        self.device_intensities = device_intensities;
+        Ok(())
+    }
+
     async fn load_tari_address(&mut self, tari_address: &str) -> Result<(), anyhow::Error> {
         self.tari_address = Some(tari_address.to_string());
         Ok(())
@@ -138,6 +178,10 @@ impl GpuMinerInterfaceTrait for GraxilGpuMiner {
         self.connection_type = Some(connection_type);
         Ok(())
     }
+    fn gpu_devices(&self) -> Vec<GpuCommonInformation> {
+        self.gpu_devices.clone()
+    }
+
     async fn detect_devices(&mut self) -> Result<(), anyhow::Error> {
         let config_path =
             dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Failed to get config directory"))?;
@@ -168,6 +212,7 @@ impl GpuMinerInterfaceTrait for GraxilGpuMiner {
             None,
             &args,
             false,
+            &ProcessIsolation::default(),
         )?;
         let output = child.wait_with_output().await?;
         info!(target: LOG_TARGET_APP_LOGIC, "Gpu detect exit code: {:?}", output.status.code().unwrap_or_default());
@@ -267,9 +312,9 @@ impl ProcessAdapter for GraxilGpuMiner {
             ));
         }
 
-        if let Some(intensity) = self.intensity_percentage {
+        if let Some(intensity) = self.resolve_intensity_argument() {
             args.push("--gpu-intensity".to_string());
-            args.push(intensity.to_string());
+            args.push(intensity);
         }
 
         args.push("--log-dir".to_string());
@@ -297,8 +342,11 @@ impl ProcessAdapter for GraxilGpuMiner {
                     data_dir: base_folder,
                     pid_file_name: self.pid_file_name().to_string(),
                     name: self.name().to_string(),
+                    binary: Binaries::Graxil,
+                    isolation: ProcessIsolation::gpu_miner(),
                 },
                 handle: None,
+                stderr_tail: StderrTail::default(),
             },
             GpuMinerStatusInterface::Graxil(GraxilGpuMinerStatusMonitor {
                 gpu_status_sender: self.gpu_status_sender.clone(),
@@ -316,10 +364,6 @@ impl ProcessAdapter for GraxilGpuMiner {
     }
 }
 
-// This is a flag to indicate if the fallback to solo mining has been triggered
-// We want to avoid triggering it multiple times per session
-static WAS_FALLBACK_TO_OTHER_MINER_TRIGGERED: AtomicBool = AtomicBool::new(false);
-
 #[derive(Clone)]
 pub struct GraxilGpuMinerStatusMonitor {
     gpu_status_sender: Sender<GpuMinerStatus>,
@@ -328,30 +372,6 @@ pub struct GraxilGpuMinerStatusMonitor {
 
 #[async_trait]
 impl StatusMonitor for GraxilGpuMinerStatusMonitor {
-    async fn handle_unhealthy(
-        &self,
-        duration_since_last_healthy_status: Duration,
-    ) -> Result<HandleUnhealthyResult, anyhow::Error> {
-        info!(target: LOG_TARGET_STATUSES, "Handling unhealthy status for GpuMinerShaAdapter | Duration since last healthy status: {:?}", duration_since_last_healthy_status.as_secs());
-        if duration_since_last_healthy_status.as_secs().gt(&(60 * 3)) // Fallback after 3 minutes of unhealthiness
-            && !WAS_FALLBACK_TO_OTHER_MINER_TRIGGERED.load(Ordering::SeqCst)
-        {
-            match GpuManager::write().await.handle_unhealthy_miner().await {
-                Ok(_) => {
-                    info!(target: LOG_TARGET_STATUSES, "GpuMinerShaAdapter: GPU Pool feature turned off due to prolonged unhealthiness.");
-                    WAS_FALLBACK_TO_OTHER_MINER_TRIGGERED.store(true, Ordering::SeqCst);
-                    return Ok(HandleUnhealthyResult::Stop);
-                }
-                Err(error) => {
-                    warn!(target: LOG_TARGET_STATUSES, "GpuMinerShaAdapter: Failed to turn off GPU Pool feature: {error} | Continuing to monitor.");
-                    return Ok(HandleUnhealthyResult::Continue);
-                }
-            }
-        } else {
-            return Ok(HandleUnhealthyResult::Continue);
-        }
-    }
-
     async fn check_health(&self, _uptime: Duration, timeout_duration: Duration) -> HealthStatus {
         info!(target: LOG_TARGET_STATUSES, "Checking health of ShaMiner");
         let status = match tokio::time::timeout(timeout_duration, self.status()).await {
@@ -372,9 +392,16 @@ impl StatusMonitor for GraxilGpuMinerStatusMonitor {
                 info!(target: LOG_TARGET_STATUSES, "ShaMiner status: {status:?}");
                 let _ = self.gpu_status_sender.send(status.clone());
                 if status.hash_rate > 0.0 {
-                    if !GpuManager::read().await.is_current_miner_healthy().await {
+                    if !GpuManager::read()
+                        .await
+                        .is_miner_healthy(&GpuMinerType::Graxil)
+                        .await
+                    {
                         info!(target: LOG_TARGET_STATUSES, "Marking current miner as healthy again");
-                        let _unused = GpuManager::write().await.handle_healthy_miner().await;
+                        let _unused = GpuManager::write()
+                            .await
+                            .handle_healthy_miner(&GpuMinerType::Graxil)
+                            .await;
                     }
                     HealthStatus::Healthy
                 } else {
@@ -395,7 +422,7 @@ impl StatusMonitor for GraxilGpuMinerStatusMonitor {
 
 impl GraxilGpuMinerStatusMonitor {
     pub async fn status(&self) -> Result<GpuMinerStatus, anyhow::Error> {
-        self.websocket_listener.clone().connect().await;
+        self.websocket_listener.connect().await;
         let last_status = self.websocket_listener.get_last_message().await;
 
         if let Some(status) = last_status {
@@ -404,6 +431,8 @@ impl GraxilGpuMinerStatusMonitor {
                 estimated_earnings: 0,
                 hash_rate: status.current_hashrate as f64,
                 algorithm: GpuMinerType::Graxil.main_algorithm(),
+                telemetry: Some(GpuMinerTelemetry::from(&status)),
+                ..Default::default()
             });
         }
 
@@ -412,6 +441,49 @@ impl GraxilGpuMinerStatusMonitor {
             estimated_earnings: 0,
             hash_rate: 0.0,
             algorithm: GpuMinerType::Graxil.main_algorithm(),
+            ..Default::default()
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn miner_with_devices(device_ids: &[u32]) -> GraxilGpuMiner {
+        let (gpu_status_sender, _) = tokio::sync::watch::channel(GpuMinerStatus::default());
+        let mut miner = GraxilGpuMiner::new(gpu_status_sender);
+        miner.raw_gpu_devices = device_ids
+            .iter()
+            .map(|device_id| GraxilGpuDeviceInformation {
+                name: format!("GPU {device_id}"),
+                device_id: *device_id,
+                platform_name: "OpenCL".to_string(),
+                vendor: GpuVendor::NVIDIA,
+                max_work_group_size: 1024,
+                max_compute_units: 128,
+                global_mem_size: 0,
+                device_type: GpuDeviceType::Dedicated,
+                pci_bus_id: None,
+            })
+            .collect();
+        miner.intensity_percentage = Some(75);
+        miner
+    }
+
+    #[test]
+    fn test_intensity_argument_without_device_intensities() {
+        let miner = miner_with_devices(&[0, 1]);
+        assert_eq!(miner.resolve_intensity_argument(), Some("75".to_string()));
+    }
+
+    #[test]
+    fn test_intensity_argument_lists_every_device() {
+        let mut miner = miner_with_devices(&[1, 0, 2]);
+        miner.device_intensities = HashMap::from([(1, 20)]);
+        assert_eq!(
+            miner.resolve_intensity_argument(),
+            Some("75,20,75".to_string())
+        );
+    }
+}
@@ -19,10 +19,7 @@
 // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
-use std::{
-    sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
-};
+use std::time::Duration;
 
 use axum::async_trait;
 use log::{info, warn};
@@ -52,9 +49,10 @@ use crate::{
     },
     port_allocator::PortAllocator,
     process_adapter::{
-        HandleUnhealthyResult, HealthStatus, ProcessAdapter, ProcessInstance, ProcessStartupSpec,
-        StatusMonitor,
+        HealthStatus, ProcessAdapter, ProcessInstance, ProcessStartupSpec, StatusMonitor,
     },
+    process_isolation::ProcessIsolation,
+    process_restart_policy::StderrTail,
     process_utils::launch_child_process,
     APPLICATION_FOLDER_ID, LOG_TARGET_APP_LOGIC, LOG_TARGET_STATUSES,
 };
@@ -67,6 +65,7 @@ pub struct LolMinerGpuMiner {
     pub connection_type: Option<GpuConnectionType>,
     pub gpu_status_sender: Sender<GpuMinerStatus>,
     pub gpu_devices: Vec<GpuCommonInformation>,
+    pub excluded_devices: Vec<u32>,
 }
 
 impl LolMinerGpuMiner {
@@ -78,7 +77,26 @@ impl LolMinerGpuMiner {
             connection_type: None,
             gpu_status_sender,
             gpu_devices: vec![],
+            excluded_devices: vec![],
+        }
+    }
+
+    /// Value of the `--devices` argument, `None` to mine on all devices.
+    fn resolve_devices_argument(&self) -> Result<Option<String>, anyhow::Error> {
+        if self.excluded_devices.is_empty() {
+            return Ok(None);
         }
+        let included_devices: Vec<String> = self
+            .gpu_devices
+            .iter()
+            .map(GpuCommonInformation::device_id)
+            .filter(|device_id| !self.excluded_devices.contains(device_id))
+            .map(|device_id| device_id.to_string())
+            .collect();
+        if included_devices.is_empty() {
+            return Err(anyhow::anyhow!("All devices are excluded from LolMiner"));
+        }
+        Ok(Some(included_devices.join(",")))
     }
 }
 
@@ -105,6 +123,17 @@ impl GpuMinerInterfaceTrait for LolMinerGpuMiner {
         self.connection_type = Some(connection_type);
         Ok(())
     }
+    async fn load_excluded_devices(
+        &mut self,
+        excluded_devices: Vec<u32>,
+    ) -> Result<(), anyhow::Error> {
+        self.excluded_devices = excluded_devices;
+        Ok(())
+    }
+
+    fn gpu_devices(&self) -> Vec<GpuCommonInformation> {
+        self.gpu_devices.clone()
+    }
 
     async fn detect_devices(&mut self) -> Result<(), anyhow::Error> {
         let config_path =
@@ -119,7 +148,14 @@ impl GpuMinerInterfaceTrait for LolMinerGpuMiner {
         let args = vec!["--list-devices".to_string()];
 
         crate::download_utils::set_permissions(&gpu_miner_binary).await?;
-        let result = launch_child_process(&gpu_miner_binary, &config_dir, None, &args, true)?;
+        let result = launch_child_process(
+            &gpu_miner_binary,
+            &config_dir,
+            None,
+            &args,
+            true,
+            &ProcessIsolation::default(),
+        )?;
 
         let mut gpu_devices: Vec<GpuCommonInformation> = vec![];
 
@@ -131,13 +167,14 @@ impl GpuMinerInterfaceTrait for LolMinerGpuMiner {
             return Err(anyhow::anyhow!("No supported GPU devices found"));
         }
 
-        for device_name in extract_device_names(&output_str) {
+        for (device_name, pci_bus) in extract_devices(&output_str) {
             info!(target: LOG_TARGET_APP_LOGIC,"Lolminer detected device name: {device_name}");
             #[allow(clippy::cast_possible_truncation)]
             let device_id = gpu_devices.len() as u32;
             gpu_devices.push(GpuCommonInformation {
-                name: device_name.trim().to_string(),
+                name: device_name,
                 device_id,
+                pci_bus,
             });
         }
 
@@ -212,6 +249,11 @@ impl ProcessAdapter for LolMinerGpuMiner {
             ));
         }
 
+        if let Some(devices) = self.resolve_devices_argument()? {
+            args.push("--devices".to_string());
+            args.push(devices);
+        }
+
         info!(
             target: LOG_TARGET_APP_LOGIC,
             "Lol miner logs destination: {}",
@@ -242,8 +284,11 @@ impl ProcessAdapter for LolMinerGpuMiner {
                     data_dir: base_folder,
                     pid_file_name: self.pid_file_name().to_string(),
                     name: self.name().to_string(),
+                    binary: Binaries::LolMiner,
+                    isolation: ProcessIsolation::gpu_miner(),
                 },
                 handle: None,
+                stderr_tail: StderrTail::default(),
             },
             GpuMinerStatusInterface::LolMiner(LolMinerGpuMinerStatusMonitor {
                 http_api_port: api_port,
@@ -267,36 +312,8 @@ pub struct LolMinerGpuMinerStatusMonitor {
     gpu_status_sender: Sender<GpuMinerStatus>,
 }
 
-// This is a flag to indicate if the fallback to other miner has already been triggered
-// We want to avoid triggering it multiple times per session
-static WAS_FALLBACK_TO_OTHER_MINER_TRIGGERED: AtomicBool = AtomicBool::new(false);
-
 #[async_trait]
 impl StatusMonitor for LolMinerGpuMinerStatusMonitor {
-    async fn handle_unhealthy(
-        &self,
-        duration_since_last_healthy_status: Duration,
-    ) -> Result<HandleUnhealthyResult, anyhow::Error> {
-        info!(target: LOG_TARGET_STATUSES, "Handling unhealthy status for GpuMinerShaAdapter | Duration since last healthy status: {:?}", duration_since_last_healthy_status.as_secs());
-        if duration_since_last_healthy_status.as_secs().gt(&(60 * 3)) // Fallback after 3 minutes of unhealthiness
-            && !WAS_FALLBACK_TO_OTHER_MINER_TRIGGERED.load(Ordering::SeqCst)
-        {
-            match GpuManager::write().await.handle_unhealthy_miner().await {
-                Ok(_) => {
-                    info!(target: LOG_TARGET_STATUSES, "GpuMinerShaAdapter: GPU Pool feature turned off due to prolonged unhealthiness.");
-                    WAS_FALLBACK_TO_OTHER_MINER_TRIGGERED.store(true, Ordering::SeqCst);
-                    return Ok(HandleUnhealthyResult::Stop);
-                }
-                Err(error) => {
-                    warn!(target: LOG_TARGET_STATUSES, "GpuMinerShaAdapter: Failed to turn off GPU Pool feature: {error} | Continuing to monitor.");
-                    return Ok(HandleUnhealthyResult::Continue);
-                }
-            }
-        } else {
-            return Ok(HandleUnhealthyResult::Continue);
-        }
-    }
-
     async fn check_health(&self, _uptime: Duration, timeout_duration: Duration) -> HealthStatus {
         let status = match tokio::time::timeout(timeout_duration, self.status()).await {
             Ok(inner) => inner,
@@ -315,9 +332,16 @@ impl StatusMonitor for LolMinerGpuMinerStatusMonitor {
             Ok(status) => {
                 let _ = self.gpu_status_sender.send(status.clone());
                 if status.hash_rate > 0.0 {
-                    if !GpuManager::read().await.is_current_miner_healthy().await {
+                    if !GpuManager::read()
+                        .await
+                        .is_miner_healthy(&GpuMinerType::LolMiner)
+                        .await
+                    {
                         info!(target: LOG_TARGET_STATUSES, "Marking current miner as healthy again");
-                        let _unused = GpuManager::write().await.handle_healthy_miner().await;
+                        let _unused = GpuManager::write()
+                            .await
+                            .handle_healthy_miner(&GpuMinerType::LolMiner)
+                            .await;
                     }
                     HealthStatus::Healthy
                 } else {
@@ -351,6 +375,7 @@ impl LolMinerGpuMinerStatusMonitor {
                         hash_rate: 0.0,
                         estimated_earnings: 0,
                         algorithm: GpuMinerType::LolMiner.main_algorithm(),
+                        ..Default::default()
                     });
                 }
                 return Ok(GpuMinerStatus {
@@ -358,6 +383,7 @@ impl LolMinerGpuMinerStatusMonitor {
                     hash_rate: 0.0,
                     estimated_earnings: 0,
                     algorithm: GpuMinerType::LolMiner.main_algorithm(),
+                    ..Default::default()
                 });
             }
         };
@@ -371,6 +397,7 @@ impl LolMinerGpuMinerStatusMonitor {
                     hash_rate: 0.0,
                     estimated_earnings: 0,
                     algorithm: GpuMinerType::LolMiner.main_algorithm(),
+                    ..Default::default()
                 });
             }
         };
@@ -387,6 +414,7 @@ impl LolMinerGpuMinerStatusMonitor {
                 .round()
                 / 100.0),
             algorithm: GpuMinerType::LolMiner.main_algorithm(),
+            ..Default::default()
         })
     }
 }
@@ -403,33 +431,72 @@ struct Algorithm {
     total_performance: f64,
 }
 
-fn extract_device_names(output_str: &str) -> Vec<String> {
-    let lines: Vec<&str> = output_str.lines().collect();
-    let mut device_names = Vec::new();
+/// Name and PCI bus of every device listed by `--list-devices`
+fn extract_devices(output_str: &str) -> Vec<(String, Option<u32>)> {
+    let mut devices: Vec<(String, Option<u32>)> = Vec::new();
     let mut found_device = false;
 
+    // Regex to match ANSI escape codes
     let regex =
         Regex::new(r"\x1b\[[0-9;]*m").expect("Failed to create regex for lolminer devices names");
 
-    for line in lines {
-        let trimmed = line.trim();
+    for line in output_str.lines() {
+        let plain_line = regex.replace_all(line, "");
+        let trimmed = plain_line.trim();
         // Check for any device marker (Device 0:, Device 1:, etc.)
         if trimmed.starts_with("Device ") && trimmed.ends_with(':') {
             found_device = true;
             continue;
         }
-        if found_device && trimmed.starts_with("Name:") {
-            // Regex to match ANSI escape codes
-
-            // Extract the name after "Name:    "
-            let parts: Vec<&str> = trimmed.splitn(2, ':').collect();
-            if parts.len() == 2 {
-                let name = parts[1].trim().to_string();
-                let plain_name = regex.replace_all(&name, "").to_string();
-                device_names.push(plain_name);
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "Name" if found_device => {
+                devices.push((value.trim().to_string(), None));
+                found_device = false; // Reset for next device
+            }
+            // Listed after the name as `bus:device` or `domain:bus:device.function`
+            "Address" if !found_device => {
+                let pci_bus = value
+                    .trim()
+                    .rsplit(':')
+                    .nth(1)
+                    .and_then(|bus| u32::from_str_radix(bus, 16).ok());
+                if let Some(device) = devices.last_mut() {
+                    device.1 = pci_bus;
+                }
             }
-            found_device = false; // Reset for next device
+            _ => {}
         }
     }
-    device_names
+    devices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_devices_with_pci_bus() {
+        let output = "Detected GPUs:\n\
+            Device 0:\n\
+            \x20   Name:    \x1b[1mNVIDIA GeForce RTX 3070\x1b[0m\n\
+            \x20   Address: 1:0\n\
+            \x20   Vendor:  NVIDIA Corporation\n\
+            Device 1:\n\
+            \x20   Name:    AMD Radeon RX 7900 XTX\n\
+            \x20   Address: 0000:0a:00.0\n\
+            Device 2:\n\
+            \x20   Name:    Intel Arc A770\n";
+
+        assert_eq!(
+            extract_devices(output),
+            vec![
+                ("NVIDIA GeForce RTX 3070".to_string(), Some(1)),
+                ("AMD Radeon RX 7900 XTX".to_string(), Some(10)),
+                ("Intel Arc A770".to_string(), None),
+            ]
+        );
+    }
 }
@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 use tokio::{
@@ -38,6 +38,9 @@ pub mod lolminer;
 pub struct GpuCommonInformation {
     name: String,
     device_id: u32,
+    /// PCI bus of the device, the only identifier shared by all miners when they report it
+    #[serde(default)]
+    pci_bus: Option<u32>,
 }
 
 impl GpuCommonInformation {
@@ -45,14 +48,64 @@ impl GpuCommonInformation {
         Self {
             name: device.device_name,
             device_id: device.device_index,
+            pci_bus: device.pci_bus_id,
         }
     }
+    pub fn device_id(&self) -> u32 {
+        self.device_id
+    }
     pub fn from_graxil_devices(devices: GraxilGpuDeviceInformation) -> Self {
         Self {
             name: devices.name,
             device_id: devices.device_id,
+            pci_bus: devices.pci_bus_id,
         }
     }
+
+    /// Maps the device ids of one miner to the ids another miner uses for the same devices,
+    /// every miner numbers devices its own way.
+    /// Devices are matched by PCI bus when both miners report it, otherwise by name and
+    /// their order among devices with the same name. Devices without a match are left out.
+    pub fn map_device_ids(
+        from_devices: &[GpuCommonInformation],
+        to_devices: &[GpuCommonInformation],
+    ) -> HashMap<u32, u32> {
+        let from_name_keys = Self::name_keys(from_devices);
+        let to_name_keys = Self::name_keys(to_devices);
+        let to_reports_pci_bus = to_devices.iter().any(|device| device.pci_bus.is_some());
+
+        from_devices
+            .iter()
+            .zip(&from_name_keys)
+            .filter_map(|(from_device, name_key)| {
+                let to_device = match from_device.pci_bus {
+                    Some(pci_bus) if to_reports_pci_bus => to_devices
+                        .iter()
+                        .find(|device| device.pci_bus == Some(pci_bus)),
+                    _ => to_devices
+                        .iter()
+                        .zip(&to_name_keys)
+                        .find(|(_, to_name_key)| *to_name_key == name_key)
+                        .map(|(device, _)| device),
+                }?;
+                Some((from_device.device_id, to_device.device_id))
+            })
+            .collect()
+    }
+
+    /// Name of each device with its order among the devices with the same name
+    fn name_keys(devices: &[GpuCommonInformation]) -> Vec<(String, usize)> {
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        devices
+            .iter()
+            .map(|device| {
+                let name = device.name.trim().to_lowercase();
+                let occurrence = occurrences.entry(name.clone()).or_default();
+                *occurrence += 1;
+                (name, *occurrence)
+            })
+            .collect()
+    }
 }
 
 #[allow(clippy::upper_case_acronyms)]
@@ -99,3 +152,51 @@ where
     file.write_all(serialized.as_bytes()).await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(name: &str, device_id: u32, pci_bus: Option<u32>) -> GpuCommonInformation {
+        GpuCommonInformation {
+            name: name.to_string(),
+            device_id,
+            pci_bus,
+        }
+    }
+
+    #[test]
+    fn test_map_device_ids_by_pci_bus() {
+        let glytex_devices = vec![
+            device("NVIDIA GeForce RTX 4090", 0, Some(1)),
+            device("NVIDIA GeForce RTX 3070", 1, Some(3)),
+        ];
+        let lolminer_devices = vec![
+            device("NVIDIA GeForce RTX 3070", 0, Some(3)),
+            device("NVIDIA GeForce RTX 4090", 1, Some(1)),
+        ];
+
+        let device_ids = GpuCommonInformation::map_device_ids(&glytex_devices, &lolminer_devices);
+
+        assert_eq!(device_ids, HashMap::from([(0, 1), (1, 0)]));
+    }
+
+    #[test]
+    fn test_map_device_ids_by_name_without_pci_bus() {
+        let graxil_devices = vec![
+            device("AMD Radeon RX 7900 XTX", 0, None),
+            device("NVIDIA GeForce RTX 3070", 1, None),
+            device("NVIDIA GeForce RTX 3070", 2, None),
+            device("Intel Arc A770", 3, None),
+        ];
+        let lolminer_devices = vec![
+            device("NVIDIA GeForce RTX 3070", 0, Some(3)),
+            device("NVIDIA GeForce RTX 3070", 1, Some(4)),
+            device("AMD Radeon RX 7900 XTX ", 2, Some(8)),
+        ];
+
+        let device_ids = GpuCommonInformation::map_device_ids(&graxil_devices, &lolminer_devices);
+
+        assert_eq!(device_ids, HashMap::from([(0, 2), (1, 0), (2, 1)]));
+    }
+}
@@ -20,13 +20,28 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Weak,
+};
+
+use anyhow::Error;
+use futures::StreamExt;
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use tungstenite::{connect, Message};
+use tokio::{select, sync::RwLock, time::sleep};
+use tokio_tungstenite::connect_async;
+use tungstenite::Message;
+
+use crate::{
+    mining::gpu::{consts::GpuMinerTelemetry, miners::GpuVendor},
+    requests::utils::create_exponential_timeout,
+    tasks_tracker::TasksTrackers,
+    LOG_TARGET_STATUSES,
+};
 
-use crate::{mining::gpu::miners::GpuVendor, tasks_tracker::TasksTrackers, LOG_TARGET_STATUSES};
+/// Reconnect delays stop growing after this many failed attempts
+const MAX_BACKOFF_ATTEMPT: u32 = 5;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 
@@ -117,96 +132,185 @@ pub struct WebSocketGpuMinerResponse {
     pub gpu_info: GpuInfo,
 }
 
+impl From<&WebSocketGpuMinerResponse> for GpuMinerTelemetry {
+    fn from(response: &WebSocketGpuMinerResponse) -> Self {
+        Self {
+            session_average_hash_rate: response.session_average as f64,
+            accepted_shares: response.accepted_shares,
+            rejected_shares: response.rejected_shares,
+            submitted_shares: response.submitted_shares,
+            acceptance_rate: response.acceptance_rate,
+            average_luck: response.average_luck,
+            thread_hash_rates: response.thread_hashrates.clone(),
+            is_pool_connected: response.pool_info.is_connected,
+            pool_latency_ms: response.pool_info.latency_ms,
+            gpu_temperature: response.gpu_info.temperature,
+            gpu_power_usage: response.gpu_info.power_usage,
+            gpu_utilization: response.gpu_info.utilization,
+            uptime_secs: response.uptime,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct WebSocketState {
+    last_message: RwLock<Option<WebSocketGpuMinerResponse>>,
+    is_listening: AtomicBool,
+}
+
+/// Consumer of the websocket feed of the SHA3X GPU miner.
+///
+/// The listener runs in the background and reconnects with a backoff, as the miner opens the
+/// socket a while after starting and closes it when restarting. It stops once every handle of
+/// the websocket is dropped or the GPU mining phase shuts down.
 #[derive(Debug, Clone)]
 pub struct GpuMinerShaWebSocket {
-    socket_listener_thread: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
-    last_message: Arc<Mutex<Option<WebSocketGpuMinerResponse>>>,
+    state: Arc<WebSocketState>,
     port: u16,
 }
 
 impl GpuMinerShaWebSocket {
     pub fn new(port: u16) -> Self {
         Self {
-            socket_listener_thread: Arc::new(Mutex::new(None)),
-            last_message: Arc::new(Mutex::new(None)),
+            state: Arc::new(WebSocketState::default()),
             port,
         }
     }
 
+    /// Last message received, `None` while disconnected.
     pub async fn get_last_message(&self) -> Option<WebSocketGpuMinerResponse> {
-        self.last_message.lock().await.clone()
+        self.state.last_message.read().await.clone()
     }
 
-    pub async fn connect(self) {
-        if self.socket_listener_thread.lock().await.is_some() {
-            debug!(target: LOG_TARGET_STATUSES, "WebSocket listener is already running");
+    /// Starts listening in the background, does nothing when already listening.
+    pub async fn connect(&self) {
+        if self.state.is_listening.swap(true, Ordering::SeqCst) {
             return;
         }
 
-        if let Ok((mut socket, response)) = connect(format!("ws://localhost:{}/ws", self.port)) {
-            info!(target: LOG_TARGET_STATUSES, "Connected to WebSocket server: {response:?}" );
-
-            let shutdown_signal = TasksTrackers::current().gpu_mining_phase.get_signal().await;
-
-            let last_message = Arc::clone(&self.last_message);
-            let socket_listener_thread = Arc::clone(&self.socket_listener_thread);
-
-            let thread = TasksTrackers::current()
-                .gpu_mining_phase
-                .get_task_tracker()
-                .await
-                .spawn(async move {
-                    loop {
-                        if shutdown_signal.is_triggered() {
+        let state = Arc::downgrade(&self.state);
+        let url = format!("ws://localhost:{}/ws", self.port);
+        let mut shutdown_signal = TasksTrackers::current().gpu_mining_phase.get_signal().await;
+        TasksTrackers::current()
+            .gpu_mining_phase
+            .get_task_tracker()
+            .await
+            .spawn(async move {
+                let mut attempt = 0;
+                loop {
+                    select! {
+                        _ = shutdown_signal.wait() => {
                             info!(target: LOG_TARGET_STATUSES, "Shutdown signal received, stopping WebSocket listener");
                             break;
                         }
-
-                        match socket.read() {
-                            Ok(msg) => {
-                                match msg {
-                                    Message::Text(text) => {
-                                        let parsed_message: Result<
-                                            WebSocketGpuMinerResponse,
-                                            serde_json::Error,
-                                        > = serde_json::from_str(&text);
-
-                                        match parsed_message {
-                                            Ok(response) => {
-                                                *last_message.lock().await = Some(response.clone());
-
-                                            }
-                                            Err(e) => {
-                                            if !last_message.lock().await.is_none() {
-                                                info!(target: LOG_TARGET_STATUSES, "Received message: {text}");
-                                            }
-                                                warn!(target: LOG_TARGET_STATUSES, "Failed to parse message: {e}");
-                                                *last_message.lock().await = None;
-                                            }
-                                        }
-                                    }
-                                    Message::Close(_) => {
-                                        println!("Connection closed by the server");
-                                        *last_message.lock().await = None;
-                                        break;
-                                    }
-                                    _ => {}
-                                }
-                            }
-                            Err(e) => {
-                                warn!(target: LOG_TARGET_STATUSES, "Error reading message: {e}");
-                                *last_message.lock().await = None;
-                                break;
+                        result = Self::listen(&url, &state, &mut attempt) => {
+                            if let Err(e) = result {
+                                debug!(target: LOG_TARGET_STATUSES, "WebSocket connection to {url} failed: {e}");
                             }
                         }
                     }
 
-                    *socket_listener_thread.lock().await = None;
-                });
+                    let Some(current_state) = state.upgrade() else {
+                        debug!(target: LOG_TARGET_STATUSES, "WebSocket listener is no longer used, stopping it");
+                        return;
+                    };
+                    *current_state.last_message.write().await = None;
+                    drop(current_state);
 
-            *self.socket_listener_thread.lock().await = Some(thread);
-        } else {
-            warn!(target: LOG_TARGET_STATUSES, "Failed to connect to WebSocket server at ws://localhost:{}/ws", self.port);
+                    let delay = create_exponential_timeout(attempt.min(MAX_BACKOFF_ATTEMPT));
+                    attempt += 1;
+                    select! {
+                        _ = shutdown_signal.wait() => break,
+                        _ = sleep(delay) => {}
+                    }
+                }
+
+                if let Some(state) = state.upgrade() {
+                    *state.last_message.write().await = None;
+                    state.is_listening.store(false, Ordering::SeqCst);
+                }
+            });
+    }
+
+    /// Reads messages until the connection closes.
+    ///
+    /// ### Returns
+    /// * `Ok(())` - Connection closed by the server or the websocket is no longer used
+    /// * `Err(anyhow::Error)` - Connection failed or broke
+    async fn listen(
+        url: &str,
+        state: &Weak<WebSocketState>,
+        attempt: &mut u32,
+    ) -> Result<(), Error> {
+        let (mut stream, response) = connect_async(url).await?;
+        info!(target: LOG_TARGET_STATUSES, "Connected to WebSocket server: {response:?}");
+        *attempt = 0;
+
+        while let Some(message) = stream.next().await {
+            let Some(state) = state.upgrade() else {
+                return Ok(());
+            };
+            match message? {
+                Message::Text(text) => {
+                    match serde_json::from_str::<WebSocketGpuMinerResponse>(&text) {
+                        Ok(response) => {
+                            *state.last_message.write().await = Some(response);
+                        }
+                        Err(e) => {
+                            warn!(target: LOG_TARGET_STATUSES, "Failed to parse message: {e}");
+                            *state.last_message.write().await = None;
+                        }
+                    }
+                }
+                Message::Close(_) => {
+                    info!(target: LOG_TARGET_STATUSES, "Connection closed by the server");
+                    return Ok(());
+                }
+                _ => {}
+            }
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    const MESSAGE: &str = r#"{
+        "current_hashrate": 1200000000, "session_average": 1100000000,
+        "accepted_shares": 40, "submitted_shares": 42, "rejected_shares": 2,
+        "work_efficiency": 98.5, "average_luck": 1.02, "uptime": 3600,
+        "thread_hashrates": [600000000, 600000000], "algorithm": "sha3x",
+        "active_threads": 2, "share_rate": 0.7, "total_work": 123456,
+        "current_difficulty": 1000000,
+        "current_job": {"job_id": "a1", "block_height": 10, "difficulty": 1000000, "timestamp": 1},
+        "recent_jobs": [], "session_time": 3600, "time_since_last_share": 12,
+        "avg_share_time": 85.7, "acceptance_rate": 95.2, "recent_shares": [], "top_shares": [],
+        "system_info": {"cpu_usage": 12.5, "cpu_cores": 8, "cpu_name": "CPU", "memory_total": 16, "memory_used": 8,
+            "memory_usage": 50.0, "os_name": null, "kernel_version": null, "hostname": null,
+            "cpu_temperature": null, "max_temperature": null},
+        "pool_info": {"pool_address": "pool:4000", "is_connected": true, "latency_ms": 35,
+            "connection_attempts": 1, "uptime_seconds": 3590},
+        "gpu_info": {"detected": true, "name": "GPU", "driver_version": null, "temperature": 71.0,
+            "power_usage": 180.5, "memory_used": null, "memory_total": null, "utilization": 99.0,
+            "count": 1, "vendor": "NVIDIA", "error_message": null}
+    }"#;
+
+    #[test]
+    fn test_telemetry_from_websocket_message() {
+        let response: WebSocketGpuMinerResponse = serde_json::from_str(MESSAGE).unwrap();
+        let telemetry = GpuMinerTelemetry::from(&response);
+
+        assert_eq!(telemetry.session_average_hash_rate, 1_100_000_000.0);
+        assert_eq!(telemetry.accepted_shares, 40);
+        assert_eq!(telemetry.rejected_shares, 2);
+        assert_eq!(telemetry.thread_hash_rates, vec![600_000_000, 600_000_000]);
+        assert!(telemetry.is_pool_connected);
+        assert_eq!(telemetry.pool_latency_ms, Some(35));
+        assert_eq!(telemetry.gpu_temperature, Some(71.0));
+        assert_eq!(telemetry.gpu_power_usage, Some(180.5));
+        assert_eq!(telemetry.uptime_secs, 3600);
     }
 }
@@ -0,0 +1,724 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Thermal and power watchdog of the GPU devices.
+//!
+//! Devices with a temperature or power limit are checked while GPU mining runs. A limit exceeded
+//! for a sustained period triggers the configured intervention: the intensity of the device is
+//! stepped down, the device is excluded or GPU mining is paused. The changed settings are restored
+//! once the device stays below its limits with a margin for a while, so a device hovering around
+//! its limit is not toggled on every check. The values to restore are saved with the device
+//! settings, so an intervention cut short by quitting the app is undone on the next start.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use anyhow::Error;
+use chrono::{DateTime, Local};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::{select, sync::RwLock};
+
+use crate::{
+    configs::{
+        config_mining::{ConfigMining, ConfigMiningContent},
+        trait_config::ConfigImpl,
+    },
+    events_emitter::EventsEmitter,
+    hardware::hardware_status_monitor::{DeviceParameters, HardwareStatusMonitor},
+    mining::gpu::manager::GpuManager,
+    systemtray_manager::{SystemTrayEvents, SystemTrayManager},
+    tasks_tracker::TasksTrackers,
+};
+
+const LOG_TARGET: &str = "tari::universe::gpu_watchdog";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Time a limit has to stay exceeded before the watchdog intervenes
+const SUSTAINED_DURATION: Duration = Duration::from_secs(30);
+/// Time a device has to stay below its limits, with the margins below, before its settings are restored
+const RESUME_DURATION: Duration = Duration::from_secs(60);
+/// Degrees Celsius a device has to cool down below its temperature limit to count as recovered
+const TEMPERATURE_HYSTERESIS: f32 = 5.0;
+/// Share of the power limit a device has to drop below to count as recovered
+const POWER_HYSTERESIS_RATIO: f32 = 0.9;
+const INTENSITY_STEP: u32 = 25;
+/// Devices are excluded instead of stepped down below this intensity
+const MIN_INTENSITY: u32 = 10;
+/// Oldest interventions are dropped once the history grows above this size
+const MAX_HISTORY_RECORDS: usize = 100;
+
+static HISTORY: LazyLock<RwLock<VecDeque<GpuWatchdogIntervention>>> =
+    LazyLock::new(|| RwLock::new(VecDeque::new()));
+
+/// What the watchdog does when a device exceeds its limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum GpuWatchdogAction {
+    /// Lower the intensity of the device step by step, excluding it once the intensity gets too low
+    #[default]
+    ReduceIntensity,
+    /// Stop mining on the device
+    ExcludeDevice,
+    /// Stop GPU mining altogether
+    PauseMining,
+}
+
+/// Temperature and power limits of a GPU device, both optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GpuDeviceLimits {
+    /// Degrees Celsius
+    pub max_temperature: Option<u32>,
+    /// Only enforced on devices reporting their power draw, currently Nvidia devices through NVML
+    pub max_power_watts: Option<u32>,
+}
+
+impl GpuDeviceLimits {
+    pub fn is_set(&self) -> bool {
+        self.max_temperature.is_some() || self.max_power_watts.is_some()
+    }
+
+    fn exceeded(&self, parameters: &DeviceParameters) -> Option<GpuWatchdogReason> {
+        if let Some(limit) = self.max_temperature {
+            if parameters.current_temperature > limit as f32 {
+                return Some(GpuWatchdogReason::Temperature {
+                    celsius: parameters.current_temperature,
+                    limit,
+                });
+            }
+        }
+        match (self.max_power_watts, parameters.power_usage_watts) {
+            (Some(limit), Some(watts)) if watts > limit as f32 => {
+                Some(GpuWatchdogReason::Power { watts, limit })
+            }
+            _ => None,
+        }
+    }
+
+    fn recovered(&self, parameters: &DeviceParameters) -> bool {
+        let temperature_recovered = self.max_temperature.is_none_or(|limit| {
+            parameters.current_temperature <= limit as f32 - TEMPERATURE_HYSTERESIS
+        });
+        let power_recovered = match (self.max_power_watts, parameters.power_usage_watts) {
+            (Some(limit), Some(watts)) => watts <= limit as f32 * POWER_HYSTERESIS_RATIO,
+            _ => true,
+        };
+        temperature_recovered && power_recovered
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum GpuWatchdogReason {
+    Temperature { celsius: f32, limit: u32 },
+    Power { watts: f32, limit: u32 },
+}
+
+impl Display for GpuWatchdogReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GpuWatchdogReason::Temperature { celsius, limit } => {
+                write!(f, "{celsius:.0}°C over the {limit}°C limit")
+            }
+            GpuWatchdogReason::Power { watts, limit } => {
+                write!(f, "{watts:.0} W over the {limit} W limit")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum GpuWatchdogStep {
+    ReducedIntensity {
+        from: u32,
+        to: u32,
+    },
+    ExcludedDevice,
+    PausedMining,
+    /// Settings changed by the watchdog were restored
+    Resumed,
+}
+
+impl Display for GpuWatchdogStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GpuWatchdogStep::ReducedIntensity { from, to } => {
+                write!(f, "intensity lowered from {from}% to {to}%")
+            }
+            GpuWatchdogStep::ExcludedDevice => write!(f, "device excluded"),
+            GpuWatchdogStep::PausedMining => write!(f, "mining paused"),
+            GpuWatchdogStep::Resumed => write!(f, "settings restored"),
+        }
+    }
+}
+
+/// A single entry of the watchdog history.
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuWatchdogIntervention {
+    pub device_id: u32,
+    pub step: GpuWatchdogStep,
+    /// Limit that was exceeded, `None` when resuming
+    pub reason: Option<GpuWatchdogReason>,
+    pub time: DateTime<Local>,
+    /// Set when the settings could not be changed
+    pub error: Option<String>,
+}
+
+enum WatchdogDecision {
+    Intervene(GpuWatchdogReason),
+    Resume,
+}
+
+/// Device settings changed by the watchdog, restored once the device recovers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuWatchdogRestore {
+    /// Intensity override of the device before the first intervention
+    intensity_percentage: Option<u32>,
+    excluded: bool,
+    paused_mining: bool,
+}
+
+#[derive(Debug, Default)]
+struct DeviceWatchdogState {
+    exceeded_since: Option<Instant>,
+    recovered_since: Option<Instant>,
+    restore: Option<GpuWatchdogRestore>,
+}
+
+impl DeviceWatchdogState {
+    fn evaluate(
+        &mut self,
+        limits: &GpuDeviceLimits,
+        parameters: &DeviceParameters,
+        now: Instant,
+    ) -> Option<WatchdogDecision> {
+        if let Some(reason) = limits.exceeded(parameters) {
+            self.recovered_since = None;
+            let exceeded_since = *self.exceeded_since.get_or_insert(now);
+            if now.duration_since(exceeded_since) < SUSTAINED_DURATION {
+                return None;
+            }
+            // The next intervention needs another sustained period, giving the last one time to take effect
+            self.exceeded_since = None;
+            return Some(WatchdogDecision::Intervene(reason));
+        }
+
+        self.exceeded_since = None;
+        if self.restore.is_none() || !limits.recovered(parameters) {
+            self.recovered_since = None;
+            return None;
+        }
+        let recovered_since = *self.recovered_since.get_or_insert(now);
+        if now.duration_since(recovered_since) < RESUME_DURATION {
+            return None;
+        }
+        self.recovered_since = None;
+        Some(WatchdogDecision::Resume)
+    }
+
+    fn is_mining_paused(&self) -> bool {
+        self.restore
+            .as_ref()
+            .is_some_and(|restore| restore.paused_mining)
+    }
+}
+
+/// Picks the intervention for a device exceeding its limits.
+///
+/// ### Parameters
+/// * `action` - Intervention configured by the user
+/// * `current_intensity` - Intensity the device is mined with
+/// * `is_last_included_device` - Excluding the last device would leave the miners without devices, mining is paused instead
+fn plan_step(
+    action: GpuWatchdogAction,
+    current_intensity: u32,
+    is_last_included_device: bool,
+) -> GpuWatchdogStep {
+    let reduced_intensity = current_intensity.saturating_sub(INTENSITY_STEP);
+    match action {
+        GpuWatchdogAction::ReduceIntensity if reduced_intensity >= MIN_INTENSITY => {
+            GpuWatchdogStep::ReducedIntensity {
+                from: current_intensity,
+                to: reduced_intensity,
+            }
+        }
+        GpuWatchdogAction::ReduceIntensity | GpuWatchdogAction::ExcludeDevice
+            if !is_last_included_device =>
+        {
+            GpuWatchdogStep::ExcludedDevice
+        }
+        _ => GpuWatchdogStep::PausedMining,
+    }
+}
+
+pub struct GpuWatchdog;
+
+impl GpuWatchdog {
+    /// Starts checking the devices in the background until the GPU mining phase shuts down.
+    ///
+    /// Settings left changed by interventions of a previous run are restored first, the devices
+    /// are checked from scratch.
+    pub async fn start() {
+        Self::restore_previous_interventions().await;
+        let mut shutdown_signal = TasksTrackers::current().gpu_mining_phase.get_signal().await;
+        TasksTrackers::current()
+            .gpu_mining_phase
+            .get_task_tracker()
+            .await
+            .spawn(async move {
+                info!(target: LOG_TARGET, "Starting GPU watchdog");
+                let mut devices: HashMap<u32, DeviceWatchdogState> = HashMap::new();
+                let mut interval = tokio::time::interval(CHECK_INTERVAL);
+                loop {
+                    select! {
+                        _ = shutdown_signal.wait() => {
+                            info!(target: LOG_TARGET, "Stopping GPU watchdog");
+                            break;
+                        }
+                        _ = interval.tick() => Self::check_devices(&mut devices).await,
+                    }
+                }
+            });
+    }
+
+    /// Returns the recorded interventions, newest first.
+    pub async fn history() -> Vec<GpuWatchdogIntervention> {
+        HISTORY.read().await.iter().rev().cloned().collect()
+    }
+
+    async fn check_devices(devices: &mut HashMap<u32, DeviceWatchdogState>) {
+        let config = ConfigMining::content().await;
+        let limits = config.get_gpu_device_limits();
+        let included_devices = config.get_included_gpu_devices();
+        let is_mining_paused = devices.values().any(DeviceWatchdogState::is_mining_paused);
+        if !is_mining_paused && !GpuManager::read().await.is_running() {
+            // Only running miners heat the devices up, the timers start over once mining starts again
+            devices.retain(|_, state| state.restore.is_some());
+            for state in devices.values_mut() {
+                state.exceeded_since = None;
+                state.recovered_since = None;
+            }
+            return;
+        }
+
+        let mut device_ids: Vec<u32> = limits.keys().chain(devices.keys()).copied().collect();
+        device_ids.sort_unstable();
+        device_ids.dedup();
+
+        let now = Instant::now();
+        let mut needs_restart = false;
+        for device_id in device_ids {
+            let has_restore = devices
+                .get(&device_id)
+                .is_some_and(|state| state.restore.is_some());
+            let decision = match limits.get(&device_id) {
+                // Devices the user excluded are not mined on, the watchdog leaves them alone
+                Some(_) if !has_restore && !included_devices.contains(&device_id) => None,
+                Some(device_limits) => {
+                    let Some(parameters) = HardwareStatusMonitor::current()
+                        .get_gpu_device_parameters(device_id)
+                        .await
+                    else {
+                        continue;
+                    };
+                    devices
+                        .entry(device_id)
+                        .or_default()
+                        .evaluate(device_limits, &parameters, now)
+                }
+                // Limits were removed while an intervention was active
+                None if has_restore => Some(WatchdogDecision::Resume),
+                None => None,
+            };
+
+            match decision {
+                Some(WatchdogDecision::Intervene(reason)) => {
+                    let state = devices.entry(device_id).or_default();
+                    needs_restart |= Self::intervene(device_id, state, reason).await;
+                }
+                Some(WatchdogDecision::Resume) => {
+                    let is_mining_paused_by_others = devices
+                        .iter()
+                        .any(|(id, state)| *id != device_id && state.is_mining_paused());
+                    if let Some(restore) = devices
+                        .get_mut(&device_id)
+                        .and_then(|state| state.restore.take())
+                    {
+                        needs_restart |=
+                            Self::resume(device_id, restore, is_mining_paused_by_others).await;
+                    }
+                    if !devices.values().any(|state| state.restore.is_some()) {
+                        SystemTrayManager::send_event(SystemTrayEvents::GpuWatchdog(None)).await;
+                    }
+                }
+                None => {}
+            }
+            if !limits.contains_key(&device_id) {
+                devices.remove(&device_id);
+            }
+        }
+
+        if needs_restart {
+            EventsEmitter::emit_update_gpu_devices_settings(
+                ConfigMining::content().await.gpu_devices_settings().clone(),
+            )
+            .await;
+            if let Err(e) = Self::restart_gpu_mining().await {
+                error!(target: LOG_TARGET, "Failed to restart GPU mining with the watchdog settings: {e}");
+            }
+        }
+    }
+
+    /// Applies the next intervention to a device exceeding its limits.
+    ///
+    /// ### Returns
+    /// * `bool` - Whether the device settings changed and GPU mining has to restart to apply them
+    async fn intervene(
+        device_id: u32,
+        state: &mut DeviceWatchdogState,
+        reason: GpuWatchdogReason,
+    ) -> bool {
+        let config = ConfigMining::content().await;
+        let intensity_override = config.get_gpu_device_intensities().get(&device_id).copied();
+        let current_intensity =
+            intensity_override.unwrap_or_else(|| config.get_selected_gpu_usage_percentage());
+        let included_devices = config.get_included_gpu_devices();
+        let step = plan_step(
+            *config.gpu_watchdog_action(),
+            current_intensity,
+            included_devices == [device_id],
+        );
+
+        let restore = state.restore.get_or_insert(GpuWatchdogRestore {
+            intensity_percentage: intensity_override,
+            excluded: false,
+            paused_mining: false,
+        });
+        match &step {
+            GpuWatchdogStep::ExcludedDevice => restore.excluded = true,
+            GpuWatchdogStep::PausedMining => restore.paused_mining = true,
+            GpuWatchdogStep::ReducedIntensity { .. } | GpuWatchdogStep::Resumed => {}
+        }
+        // Saved before the settings change, so they are restored even if the app exits meanwhile
+        let result = match ConfigMining::update_field(
+            ConfigMiningContent::set_gpu_device_watchdog_restore,
+            (device_id, Some(restore.clone())),
+        )
+        .await
+        {
+            Ok(()) => Self::apply_step(device_id, &step).await,
+            Err(e) => Err(e),
+        };
+
+        warn!(target: LOG_TARGET, "GPU {device_id} is {reason}, {step}");
+        let needs_restart = result.is_ok() && step != GpuWatchdogStep::PausedMining;
+        Self::record(device_id, step, Some(reason), result.err()).await;
+        needs_restart
+    }
+
+    async fn apply_step(device_id: u32, step: &GpuWatchdogStep) -> Result<(), Error> {
+        match step {
+            GpuWatchdogStep::ReducedIntensity { to, .. } => {
+                ConfigMining::update_field(
+                    ConfigMiningContent::set_gpu_device_intensity,
+                    (device_id, Some(*to)),
+                )
+                .await
+            }
+            GpuWatchdogStep::ExcludedDevice => {
+                ConfigMining::update_field(
+                    ConfigMiningContent::enable_gpu_device_exclusion,
+                    device_id,
+                )
+                .await
+            }
+            GpuWatchdogStep::PausedMining => GpuManager::write().await.stop_mining().await,
+            GpuWatchdogStep::Resumed => Ok(()),
+        }
+    }
+
+    /// Restores the settings changed by the watchdog on a recovered device.
+    ///
+    /// ### Returns
+    /// * `bool` - Whether the device settings changed and GPU mining has to restart to apply them
+    async fn resume(
+        device_id: u32,
+        restore: GpuWatchdogRestore,
+        is_mining_paused_by_others: bool,
+    ) -> bool {
+        info!(target: LOG_TARGET, "GPU {device_id} is back within its limits, restoring its settings");
+        let mut errors = Self::restore_device_settings(device_id, &restore).await;
+        if restore.paused_mining && !is_mining_paused_by_others {
+            if let Err(e) = GpuManager::write().await.start_mining().await {
+                errors.push(e.to_string());
+            }
+        }
+
+        let error = (!errors.is_empty()).then(|| errors.join(", "));
+        Self::record(
+            device_id,
+            GpuWatchdogStep::Resumed,
+            None,
+            error.map(Error::msg),
+        )
+        .await;
+        !restore.paused_mining
+    }
+
+    /// Restores the intensity and exclusion of a device and forgets the saved restore record.
+    ///
+    /// ### Returns
+    /// * `Vec<String>` - Errors of the failed steps, empty on success
+    async fn restore_device_settings(device_id: u32, restore: &GpuWatchdogRestore) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Err(e) = ConfigMining::update_field(
+            ConfigMiningContent::set_gpu_device_intensity,
+            (device_id, restore.intensity_percentage),
+        )
+        .await
+        {
+            errors.push(e.to_string());
+        }
+        if restore.excluded {
+            if let Err(e) = ConfigMining::update_field(
+                ConfigMiningContent::disable_gpu_device_exclusion,
+                device_id,
+            )
+            .await
+            {
+                errors.push(e.to_string());
+            }
+        }
+        if errors.is_empty() {
+            if let Err(e) = ConfigMining::update_field(
+                ConfigMiningContent::set_gpu_device_watchdog_restore,
+                (device_id, None),
+            )
+            .await
+            {
+                errors.push(e.to_string());
+            }
+        }
+        errors
+    }
+
+    /// Undoes interventions that were still active when the watchdog stopped, e.g. because the
+    /// app was closed while a device was over its limits.
+    async fn restore_previous_interventions() {
+        let restores = ConfigMining::content()
+            .await
+            .get_gpu_device_watchdog_restores();
+        if restores.is_empty() {
+            return;
+        }
+        for (device_id, restore) in restores {
+            info!(target: LOG_TARGET, "Restoring settings of GPU {device_id} changed by the watchdog in a previous run");
+            let errors = Self::restore_device_settings(device_id, &restore).await;
+            if !errors.is_empty() {
+                error!(target: LOG_TARGET, "Failed to restore settings of GPU {device_id}: {}", errors.join(", "));
+            }
+        }
+        EventsEmitter::emit_update_gpu_devices_settings(
+            ConfigMining::content().await.gpu_devices_settings().clone(),
+        )
+        .await;
+    }
+
+    async fn restart_gpu_mining() -> Result<(), Error> {
+        let mut gpu_manager = GpuManager::write().await;
+        if !gpu_manager.is_running() {
+            return Ok(());
+        }
+        gpu_manager.stop_mining().await?;
+        gpu_manager.start_mining().await
+    }
+
+    /// Appends an intervention to the history and notifies the frontend and the system tray.
+    async fn record(
+        device_id: u32,
+        step: GpuWatchdogStep,
+        reason: Option<GpuWatchdogReason>,
+        error: Option<Error>,
+    ) {
+        if let Some(e) = &error {
+            error!(target: LOG_TARGET, "GPU watchdog failed to apply '{step}' on GPU {device_id}: {e}");
+        }
+        // Resuming clears the tray only once no intervention is left, see `check_devices`
+        let tray_intervention =
+            (step != GpuWatchdogStep::Resumed).then(|| format!("GPU {device_id} {step}"));
+        let intervention = GpuWatchdogIntervention {
+            device_id,
+            step,
+            reason,
+            time: Local::now(),
+            error: error.map(|e| e.to_string()),
+        };
+
+        let mut history = HISTORY.write().await;
+        history.push_back(intervention.clone());
+        while history.len() > MAX_HISTORY_RECORDS {
+            history.pop_front();
+        }
+        drop(history);
+
+        if tray_intervention.is_some() {
+            SystemTrayManager::send_event(SystemTrayEvents::GpuWatchdog(tray_intervention)).await;
+        }
+        EventsEmitter::emit_gpu_watchdog_intervention(intervention).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(current_temperature: f32, power_usage_watts: Option<f32>) -> DeviceParameters {
+        DeviceParameters {
+            usage_percentage: 100.0,
+            current_temperature,
+            max_temperature: current_temperature,
+            power_usage_watts,
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = GpuDeviceLimits {
+            max_temperature: Some(80),
+            max_power_watts: Some(200),
+        };
+        assert_eq!(limits.exceeded(&parameters(75.0, Some(150.0))), None);
+        assert_eq!(
+            limits.exceeded(&parameters(82.0, Some(150.0))),
+            Some(GpuWatchdogReason::Temperature {
+                celsius: 82.0,
+                limit: 80
+            })
+        );
+        assert_eq!(
+            limits.exceeded(&parameters(70.0, Some(210.0))),
+            Some(GpuWatchdogReason::Power {
+                watts: 210.0,
+                limit: 200
+            })
+        );
+        // Devices without power reporting are only held to the temperature limit
+        assert_eq!(limits.exceeded(&parameters(70.0, None)), None);
+
+        assert!(!limits.recovered(&parameters(78.0, Some(150.0))));
+        assert!(!limits.recovered(&parameters(70.0, Some(190.0))));
+        assert!(limits.recovered(&parameters(75.0, Some(180.0))));
+    }
+
+    #[test]
+    fn test_intervenes_after_sustained_period_and_resumes_with_hysteresis() {
+        let limits = GpuDeviceLimits {
+            max_temperature: Some(80),
+            max_power_watts: None,
+        };
+        let start = Instant::now();
+        let mut state = DeviceWatchdogState::default();
+
+        assert!(state
+            .evaluate(&limits, &parameters(85.0, None), start)
+            .is_none());
+        // A short dip below the limit restarts the sustained period
+        assert!(state
+            .evaluate(
+                &limits,
+                &parameters(79.0, None),
+                start + Duration::from_secs(20)
+            )
+            .is_none());
+        assert!(state
+            .evaluate(
+                &limits,
+                &parameters(85.0, None),
+                start + Duration::from_secs(40)
+            )
+            .is_none());
+        assert!(matches!(
+            state.evaluate(
+                &limits,
+                &parameters(85.0, None),
+                start + Duration::from_secs(70)
+            ),
+            Some(WatchdogDecision::Intervene(_))
+        ));
+        state.restore = Some(GpuWatchdogRestore {
+            intensity_percentage: None,
+            excluded: false,
+            paused_mining: false,
+        });
+
+        // Below the limit but within the hysteresis margin is not recovered yet
+        assert!(state
+            .evaluate(
+                &limits,
+                &parameters(78.0, None),
+                start + Duration::from_secs(80)
+            )
+            .is_none());
+        assert!(state
+            .evaluate(
+                &limits,
+                &parameters(74.0, None),
+                start + Duration::from_secs(100)
+            )
+            .is_none());
+        assert!(state.recovered_since.is_some());
+        assert!(matches!(
+            state.evaluate(
+                &limits,
+                &parameters(74.0, None),
+                start + Duration::from_secs(160)
+            ),
+            Some(WatchdogDecision::Resume)
+        ));
+    }
+
+    #[test]
+    fn test_plan_step() {
+        assert_eq!(
+            plan_step(GpuWatchdogAction::ReduceIntensity, 100, true),
+            GpuWatchdogStep::ReducedIntensity { from: 100, to: 75 }
+        );
+        assert_eq!(
+            plan_step(GpuWatchdogAction::ReduceIntensity, 30, false),
+            GpuWatchdogStep::ExcludedDevice
+        );
+        assert_eq!(
+            plan_step(GpuWatchdogAction::ReduceIntensity, 30, true),
+            GpuWatchdogStep::PausedMining
+        );
+        assert_eq!(
+            plan_step(GpuWatchdogAction::ExcludeDevice, 100, false),
+            GpuWatchdogStep::ExcludedDevice
+        );
+        assert_eq!(
+            plan_step(GpuWatchdogAction::PauseMining, 100, false),
+            GpuWatchdogStep::PausedMining
+        );
+    }
+}
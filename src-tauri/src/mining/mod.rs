@@ -24,6 +24,7 @@ use serde::{Deserialize, Serialize};
 
 pub mod cpu;
 pub mod gpu;
+pub mod mode_selection;
 pub mod pools;
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
@@ -62,6 +63,8 @@ pub enum CpuConnectionType {
     Pool {
         pool_url: String,
         worker_name: Option<String>,
+        /// Pools xmrig switches to, in order, when the pool above is unreachable
+        fallback_pools: Vec<PoolEndpoint>,
     },
 }
 
@@ -70,10 +73,17 @@ impl Default for CpuConnectionType {
         CpuConnectionType::Pool {
             pool_url: String::new(),
             worker_name: None,
+            fallback_pools: Vec::new(),
         }
     }
 }
 
+#[derive(Clone, Serialize, PartialEq, Eq, Deserialize, Debug)]
+pub struct PoolEndpoint {
+    pub pool_url: String,
+    pub worker_name: Option<String>,
+}
+
 impl CpuConnectionType {
     pub fn is_pool(&self) -> bool {
         matches!(self, CpuConnectionType::Pool { .. })
@@ -0,0 +1,93 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! Switching the mining mode.
+//!
+//! Shared by the mode command, the control API and scheduled events, so all of them retune the
+//! CPU miner in place and restart a running GPU miner the same way.
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+
+use crate::{
+    configs::{
+        config_mining::{ConfigMining, ConfigMiningContent, MiningMode, MiningModeType},
+        trait_config::ConfigImpl,
+    },
+    events_emitter::EventsEmitter,
+    mining::{cpu::manager::CpuManager, gpu::manager::GpuManager},
+    systemtray_manager::{SystemTrayEvents, SystemTrayManager},
+};
+
+/// Selects the mining mode and applies it to the running miners.
+///
+/// The GPU miner reads the mode only when it starts, so it is restarted when it was running and
+/// the mode changed. The CPU miner is retuned in place.
+///
+/// ### Parameters
+/// * `mode` - Name of the mining mode to select
+///
+/// ### Returns
+/// * `Ok(())` - Mode selected
+/// * `Err(anyhow::Error)` - If the mode does not exist, updating the config or applying the mode
+///   to a miner fails
+pub async fn select_mining_mode(mode: String) -> Result<(), anyhow::Error> {
+    let config = ConfigMining::content().await;
+    ensure_mining_mode_exists(config.mining_modes(), &mode)?;
+    let previous_mode = config.selected_mining_mode().clone();
+
+    ConfigMining::update_field(ConfigMiningContent::set_selected_mining_mode, mode.clone()).await?;
+    if mode != "Eco" {
+        ConfigMining::update_field(ConfigMiningContent::set_eco_alert_needed, false).await?;
+    }
+    SystemTrayManager::send_event(SystemTrayEvents::MiningMode(MiningModeType::from(
+        mode.clone(),
+    )))
+    .await;
+    EventsEmitter::emit_mining_config_loaded(&ConfigMining::content().await).await;
+
+    let restart_gpu_miner = previous_mode != mode && GpuManager::read().await.is_running();
+    if restart_gpu_miner {
+        GpuManager::write().await.stop_mining().await?;
+    }
+    CpuManager::write()
+        .await
+        .apply_selected_mining_mode()
+        .await?;
+    if restart_gpu_miner {
+        GpuManager::write().await.start_mining().await?;
+    }
+    Ok(())
+}
+
+/// Rejects names that are not one of the configured mining modes, they would be saved as the
+/// selected mode without any settings to apply
+pub fn ensure_mining_mode_exists(
+    mining_modes: &HashMap<String, MiningMode>,
+    mode: &str,
+) -> Result<(), anyhow::Error> {
+    if mining_modes.contains_key(mode) {
+        Ok(())
+    } else {
+        Err(anyhow!("Unknown mining mode: {mode}"))
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use anyhow::anyhow;
+use log::info;
+use serde_json::Value;
+
+use crate::{
+    configs::pools::custom_pools::{PoolStatsField, PoolStatsMapping},
+    mining::pools::{adapters::PoolApiAdapter, PoolStatus},
+    requests::clients::http_client::HttpClient,
+    LOG_TARGET_STATUSES,
+};
+
+#[derive(Clone, Debug)]
+pub struct CustomPoolAdapter {
+    name: String,
+    stats_url: String,
+    stats_mapping: PoolStatsMapping,
+}
+
+impl CustomPoolAdapter {
+    pub fn new(name: String, stats_url: String, stats_mapping: PoolStatsMapping) -> Self {
+        Self {
+            name,
+            stats_url,
+            stats_mapping,
+        }
+    }
+
+    /// Sums the values of all paths of the field, missing or null values count as 0.
+    /// Numbers sent as strings are accepted as some pools report them that way.
+    fn read_field(data: &Value, field: Option<&PoolStatsField>) -> Result<f64, anyhow::Error> {
+        let Some(field) = field else {
+            return Ok(0.0);
+        };
+
+        let mut sum = 0.0;
+        for path in &field.paths {
+            let value = match path.resolve(data) {
+                None | Some(Value::Null) => 0.0,
+                Some(Value::Number(number)) => number
+                    .as_f64()
+                    .ok_or_else(|| anyhow!("Value at {path} is not a valid number"))?,
+                Some(Value::String(text)) => text
+                    .trim()
+                    .parse()
+                    .map_err(|_| anyhow!("Value at {path} is not a number: {text:?}"))?,
+                Some(other) => return Err(anyhow!("Value at {path} is not a number: {other}")),
+            };
+            sum += value;
+        }
+        Ok(sum * field.multiplier)
+    }
+}
+
+impl PoolApiAdapter for CustomPoolAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn convert_api_data(&self, data: &str) -> Result<PoolStatus, anyhow::Error> {
+        let response: Value = serde_json::from_str(data)?;
+        let mapping = &self.stats_mapping;
+        let pool_status = PoolStatus {
+            accepted_shares: Self::read_field(&response, mapping.accepted_shares.as_ref())?
+                .max(0.0)
+                .round() as u64,
+            unpaid: Self::read_field(&response, mapping.unpaid.as_ref())?,
+            balance: Self::read_field(&response, mapping.balance.as_ref())?,
+            min_payout: Self::read_field(&response, mapping.min_payout.as_ref())?
+                .max(0.0)
+                .round() as u64,
+            workers: Vec::new(),
+        };
+        Ok(pool_status)
+    }
+
+    async fn request_pool_status(&self, address: String) -> Result<PoolStatus, anyhow::Error> {
+        if self.stats_url.is_empty() {
+            return Ok(PoolStatus::default());
+        }
+        let url = self
+            .stats_url
+            .replace("%TARI_ADDRESS%", &address.to_string());
+        info!(target: LOG_TARGET_STATUSES, "Requesting custom pool status from: {url}");
+        let pool_status_response = HttpClient::with_retries(3).send_get_request(&url).await?;
+        let response_text = pool_status_response.text().await?;
+        let pool_status = self.convert_api_data(response_text.as_str())?;
+        Ok(pool_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use serde_json::json;
+
+    fn adapter(stats_mapping: Value) -> CustomPoolAdapter {
+        CustomPoolAdapter::new(
+            "Custom:test".to_string(),
+            "https://pool.example.com/api/%TARI_ADDRESS%".to_string(),
+            serde_json::from_value(stats_mapping).unwrap(),
+        )
+    }
+
+    // Response in the format of the LuckyPool stats API, numbers are sent as strings
+    const LUCKY_POOL_RESPONSE: &str = r#"{"stats":{"hashes":"1452033","lastShare":"1721822375","acceptedShares":"128","rejectedShares":"2","paid":"2500000000","unlocked":"1200000","locked":"340000","paymentThreshold":"30000000","donations":"0"},"payments":[],"charts":{}}"#;
+
+    // Response in the format of the Kryptex balance API, amounts are in XTM
+    const KRYPTEX_RESPONSE: &str = r#"{"total":1.4567,"unconfirmed":0.2,"confirmed":1.2567,"threshold":5.0,"reached_pct":29.13,"last_active":1721822375}"#;
+
+    // Response in the format of the SupportXTM miner stats API
+    const SUPPORT_XTM_RESPONSE: &str = r#"{"hash":12345,"identifier":"global","lastHash":1721822375,"totalHashes":987654321,"validShares":421,"invalidShares":3,"expiry":1721908775,"amtPaid":15000000,"amtDue":2750000,"txnCount":4}"#;
+
+    #[test]
+    fn test_lucky_pool_response() {
+        let adapter = adapter(json!({
+            "accepted_shares": { "paths": ["$.stats.acceptedShares"] },
+            "unpaid": { "paths": ["$.stats.unlocked", "$.stats.locked"] },
+            "balance": { "paths": ["$.stats.paid"] },
+            "min_payout": { "paths": ["$.stats.paymentThreshold"] }
+        }));
+
+        let status = adapter.convert_api_data(LUCKY_POOL_RESPONSE).unwrap();
+        assert_eq!(status.accepted_shares, 128);
+        assert_eq!(status.unpaid, 1540000.0);
+        assert_eq!(status.balance, 2500000000.0);
+        assert_eq!(status.min_payout, 30000000);
+    }
+
+    #[test]
+    fn test_kryptex_response_with_multiplier() {
+        let adapter = adapter(json!({
+            "unpaid": { "paths": ["total"], "multiplier": 1000000.0 },
+            "min_payout": { "paths": ["threshold"], "multiplier": 1000000.0 }
+        }));
+
+        let status = adapter.convert_api_data(KRYPTEX_RESPONSE).unwrap();
+        assert_eq!(status.accepted_shares, 0);
+        assert_eq!(status.unpaid.round(), 1456700.0);
+        assert_eq!(status.balance, 0.0);
+        assert_eq!(status.min_payout, 5000000);
+    }
+
+    #[test]
+    fn test_support_xtm_response() {
+        let adapter = adapter(json!({
+            "accepted_shares": { "paths": ["validShares"] },
+            "unpaid": { "paths": ["amtDue"] },
+            "balance": { "paths": ["amtPaid", "amtDue"] }
+        }));
+
+        let status = adapter.convert_api_data(SUPPORT_XTM_RESPONSE).unwrap();
+        assert_eq!(status.accepted_shares, 421);
+        assert_eq!(status.unpaid, 2750000.0);
+        assert_eq!(status.balance, 17750000.0);
+        assert_eq!(status.min_payout, 0);
+    }
+
+    #[test]
+    fn test_missing_values_count_as_zero() {
+        let adapter = adapter(json!({
+            "accepted_shares": { "paths": ["$.stats.acceptedShares"] },
+            "unpaid": { "paths": ["$.stats.unlocked"] }
+        }));
+
+        let status = adapter.convert_api_data(r#"{"stats":null}"#).unwrap();
+        assert_eq!(status.accepted_shares, 0);
+        assert_eq!(status.unpaid, 0.0);
+    }
+
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        let adapter = adapter(json!({
+            "unpaid": { "paths": ["$.stats"] }
+        }));
+
+        assert!(adapter.convert_api_data(LUCKY_POOL_RESPONSE).is_err());
+        assert!(adapter.convert_api_data("Address not found").is_err());
+    }
+}
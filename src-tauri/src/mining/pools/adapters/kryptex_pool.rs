@@ -63,6 +63,7 @@ impl PoolApiAdapter for KryptexPoolAdapter {
             unpaid: response.total * 1000000.0, // kryptex provides already formatted value so we need to revert it
             balance: 0.0,                       // Kryptex API does not provide this info
             min_payout: (response.threshold * 1000000.0) as u64, // kryptex provides already formatted value so we need to revert it
+            workers: Vec::new(), // Balance endpoint does not list workers
         };
         Ok(pool_status)
     }
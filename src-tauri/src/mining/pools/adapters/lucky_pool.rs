@@ -21,7 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
-    mining::pools::{adapters::PoolApiAdapter, PoolStatus},
+    mining::pools::{adapters::PoolApiAdapter, PoolStatus, PoolWorkerStatus},
     requests::clients::http_client::HttpClient,
     LOG_TARGET_STATUSES,
 };
@@ -124,9 +124,39 @@ pub struct LuckyPoolStats {
     pub locked: LuckyPoolNumber,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct LuckyPoolWorker {
+    pub name: String,
+    // Estimated by the pool, so it is not always a whole number
+    pub hashrate: serde_json::Value,
+    #[serde(deserialize_with = "parse_lucky_pool_number")]
+    pub hashes: LuckyPoolNumber,
+    #[serde(rename = "lastShare", deserialize_with = "parse_lucky_pool_number")]
+    pub last_share: LuckyPoolNumber,
+}
+
+impl LuckyPoolWorker {
+    fn to_worker_status(&self) -> PoolWorkerStatus {
+        let hashrate = match &self.hashrate {
+            serde_json::Value::String(s) => s.parse().unwrap_or(0.0),
+            value => value.as_f64().unwrap_or(0.0),
+        };
+        let last_share = self.last_share.get_number();
+        PoolWorkerStatus {
+            name: self.name.clone(),
+            hashrate,
+            total_hashes: self.hashes.get_number(),
+            last_share_timestamp: (last_share > 0).then_some(last_share),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LuckyPoolStatusResponseBody {
     pub stats: LuckyPoolStats,
+    #[serde(default)]
+    pub workers: Vec<LuckyPoolWorker>,
 }
 
 #[derive(Clone, Debug)]
@@ -160,6 +190,11 @@ impl PoolApiAdapter for LuckyPoolAdapter {
                 + converted_data.stats.locked.get_number()) as f64,
             balance: converted_data.stats.paid.get_number() as f64,
             min_payout: converted_data.stats.payment_threshold.get_number(),
+            workers: converted_data
+                .workers
+                .iter()
+                .map(LuckyPoolWorker::to_worker_status)
+                .collect(),
         };
         Ok(pool_status)
     }
@@ -174,3 +209,60 @@ impl PoolApiAdapter for LuckyPoolAdapter {
         Ok(pool_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    #[test]
+    fn test_convert_api_data_with_workers() {
+        let adapter = LuckyPoolAdapter::new("LuckyPool".to_string(), String::new());
+        // Response in the format of the stats_address endpoint, trimmed to the used fields
+        let data = r#"{
+            "stats": {
+                "acceptedShares": "120",
+                "rejectedShares": "1",
+                "paid": 5000,
+                "paymentThreshold": "100000",
+                "unlocked": "700",
+                "locked": 300
+            },
+            "workers": [
+                { "name": "rig-1", "hashrate": 1523.5, "hashes": "9000000", "lastShare": "1760000000" },
+                { "name": "rig-2", "hashrate": 0, "hashes": 0, "lastShare": 0 }
+            ]
+        }"#;
+
+        let status = adapter.convert_api_data(data).unwrap();
+        assert_eq!(status.accepted_shares, 120);
+        assert_eq!(status.unpaid, 1000.0);
+        assert_eq!(
+            status.workers,
+            vec![
+                PoolWorkerStatus {
+                    name: "rig-1".to_string(),
+                    hashrate: 1523.5,
+                    total_hashes: 9_000_000,
+                    last_share_timestamp: Some(1_760_000_000),
+                },
+                PoolWorkerStatus {
+                    name: "rig-2".to_string(),
+                    hashrate: 0.0,
+                    total_hashes: 0,
+                    last_share_timestamp: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_api_data_without_workers() {
+        let adapter = LuckyPoolAdapter::new("LuckyPool".to_string(), String::new());
+        let status = adapter
+            .convert_api_data(r#"{ "stats": { "acceptedShares": 3 } }"#)
+            .unwrap();
+        assert_eq!(status.accepted_shares, 3);
+        assert!(status.workers.is_empty());
+    }
+}
@@ -22,12 +22,13 @@
 
 use crate::mining::pools::{
     adapters::{
-        kryptex_pool::KryptexPoolAdapter, lucky_pool::LuckyPoolAdapter,
-        support_xmr_pool::SupportXmrPoolAdapter,
+        custom_pool::CustomPoolAdapter, kryptex_pool::KryptexPoolAdapter,
+        lucky_pool::LuckyPoolAdapter, support_xmr_pool::SupportXmrPoolAdapter,
     },
     PoolStatus,
 };
 
+pub mod custom_pool;
 pub mod kryptex_pool;
 pub mod lucky_pool;
 pub mod support_xmr_pool;
@@ -43,6 +44,7 @@ pub enum PoolApiAdapters {
     LuckyPool(LuckyPoolAdapter),
     SupportXmr(SupportXmrPoolAdapter),
     Kryptex(KryptexPoolAdapter),
+    Custom(CustomPoolAdapter),
 }
 
 impl PoolApiAdapter for PoolApiAdapters {
@@ -51,6 +53,7 @@ impl PoolApiAdapter for PoolApiAdapters {
             PoolApiAdapters::LuckyPool(adapter) => adapter.name(),
             PoolApiAdapters::SupportXmr(adapter) => adapter.name(),
             PoolApiAdapters::Kryptex(adapter) => adapter.name(),
+            PoolApiAdapters::Custom(adapter) => adapter.name(),
         }
     }
 
@@ -59,6 +62,7 @@ impl PoolApiAdapter for PoolApiAdapters {
             PoolApiAdapters::LuckyPool(adapter) => adapter.convert_api_data(data),
             PoolApiAdapters::SupportXmr(adapter) => adapter.convert_api_data(data),
             PoolApiAdapters::Kryptex(adapter) => adapter.convert_api_data(data),
+            PoolApiAdapters::Custom(adapter) => adapter.convert_api_data(data),
         }
     }
     async fn request_pool_status(&self, address: String) -> Result<PoolStatus, anyhow::Error> {
@@ -66,6 +70,7 @@ impl PoolApiAdapter for PoolApiAdapters {
             PoolApiAdapters::LuckyPool(adapter) => adapter.request_pool_status(address).await,
             PoolApiAdapters::SupportXmr(adapter) => adapter.request_pool_status(address).await,
             PoolApiAdapters::Kryptex(adapter) => adapter.request_pool_status(address).await,
+            PoolApiAdapters::Custom(adapter) => adapter.request_pool_status(address).await,
         }
     }
 }
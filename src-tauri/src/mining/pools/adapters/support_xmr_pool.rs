@@ -72,6 +72,7 @@ impl PoolApiAdapter for SupportXmrPoolAdapter {
             unpaid: response.amt_due as f64,
             balance: response.amt_paid as f64 + response.amt_due as f64,
             min_payout: 0,
+            workers: Vec::new(), // Stats endpoint does not list workers
         };
         Ok(pool_status)
     }
@@ -24,19 +24,19 @@ use std::{collections::HashMap, sync::LazyLock};
 
 use tokio::{
     spawn,
-    sync::{RwLock, RwLockWriteGuard},
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
 };
 
 use crate::{
     configs::{
         config_pools::ConfigPoolsContent,
-        pools::{cpu_pools::CpuPool, BasePoolData},
+        pools::{cpu_pools::CpuPool, custom_pools::PoolStatsMapping, BasePoolData, PoolOrigin},
     },
     events_emitter::EventsEmitter,
     mining::pools::{
         adapters::{
-            kryptex_pool::KryptexPoolAdapter, lucky_pool::LuckyPoolAdapter,
-            support_xmr_pool::SupportXmrPoolAdapter, PoolApiAdapters,
+            custom_pool::CustomPoolAdapter, kryptex_pool::KryptexPoolAdapter,
+            lucky_pool::LuckyPoolAdapter, support_xmr_pool::SupportXmrPoolAdapter, PoolApiAdapters,
         },
         pools_manager::PoolManager,
         PoolManagerInterfaceTrait, PoolStatus,
@@ -90,6 +90,10 @@ impl CpuPoolManager {
 }
 
 impl PoolManagerInterfaceTrait<CpuPool> for CpuPoolManager {
+    async fn get_read_manager() -> RwLockReadGuard<'static, PoolManager> {
+        INSTANCE.pool_status_manager.read().await
+    }
+
     async fn get_write_manager() -> RwLockWriteGuard<'static, PoolManager> {
         INSTANCE.pool_status_manager.write().await
     }
@@ -121,6 +125,17 @@ impl PoolManagerInterfaceTrait<CpuPool> for CpuPoolManager {
                 pool.pool_type.key_string(),
                 pool.stats_url,
             )),
+            CpuPool::Custom(_) => {
+                let stats_mapping = match pool.pool_origin {
+                    PoolOrigin::Custom(settings) => settings.stats_mapping,
+                    _ => PoolStatsMapping::default(),
+                };
+                PoolApiAdapters::Custom(CustomPoolAdapter::new(
+                    pool.pool_type.key_string(),
+                    pool.stats_url,
+                    stats_mapping,
+                ))
+            }
         }
     }
 }
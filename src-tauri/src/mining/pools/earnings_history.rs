@@ -0,0 +1,830 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! Time series of polled pool statuses used for earnings reports.
+//!
+//! Every status fetched by the `PoolManager` is appended to a JSON Lines file next to the app
+//! configs, per pool and mining address. Payouts are detected when the unpaid amount or the
+//! balance reported by the pool drops between two polls and are kept without a time limit,
+//! while samples are dropped after `SAMPLE_RETENTION_DAYS`. Expired samples leave memory on
+//! every poll and the file is compacted once per `COMPACTION_INTERVAL_HOURS`.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    env::temp_dir,
+    fmt::Write as _,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock},
+};
+
+use anyhow::Error;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use dirs::config_dir;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::AsyncWriteExt,
+    sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::{mining::pools::PoolStatus, APPLICATION_FOLDER_ID};
+
+const LOG_TARGET: &str = "tari::universe::pool_earnings_history";
+
+const HISTORY_FILE_NAME: &str = "pool_earnings_history.jsonl";
+const SAMPLE_RETENTION_DAYS: i64 = 180;
+const COMPACTION_INTERVAL_HOURS: i64 = 24;
+/// Smaller drops are treated as rounding differences of the pool API, amounts are in µT
+const MIN_PAYOUT_AMOUNT: f64 = 1.0;
+
+static INSTANCE: LazyLock<RwLock<PoolEarningsHistory>> = LazyLock::new(|| {
+    RwLock::new(PoolEarningsHistory::load(
+        PoolEarningsHistory::get_history_path(),
+    ))
+});
+
+/// Pool status as polled at a given time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolEarningsSample {
+    pub timestamp: DateTime<Utc>,
+    pub pool_name: String,
+    pub mining_address: String,
+    #[serde(flatten)]
+    pub status: PoolStatus,
+}
+
+/// Payout detected from the drop of the unpaid amount or balance between two samples.
+/// Earnings made between the two polls are not included in the amount.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolPayout {
+    pub timestamp: DateTime<Utc>,
+    pub pool_name: String,
+    pub mining_address: String,
+    pub amount: f64,
+    pub unpaid_before: f64,
+    pub unpaid_after: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum PoolEarningsEntry {
+    Sample(PoolEarningsSample),
+    Payout(PoolPayout),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PoolEarningsPeriod {
+    /// UTC days
+    Daily,
+    /// ISO weeks starting on Monday, UTC
+    Weekly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PoolEarningsDataset {
+    Samples,
+    Payouts,
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PoolEarningsExportFormat {
+    Csv,
+    Json,
+}
+
+/// Restricts the returned data, every field is optional.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolEarningsFilter {
+    pub pool_name: Option<String>,
+    pub mining_address: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl PoolEarningsFilter {
+    fn matches(&self, timestamp: &DateTime<Utc>, pool_name: &str, mining_address: &str) -> bool {
+        self.pool_name
+            .as_deref()
+            .is_none_or(|name| name == pool_name)
+            && self
+                .mining_address
+                .as_deref()
+                .is_none_or(|address| address == mining_address)
+            && self.from.is_none_or(|from| *timestamp >= from)
+            && self.to.is_none_or(|to| *timestamp < to)
+    }
+}
+
+/// Earnings of one pool and mining address within a day or week.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolEarningsAggregate {
+    pub period_start: NaiveDate,
+    pub pool_name: String,
+    pub mining_address: String,
+    /// Increase of the unpaid amount, not counting drops caused by payouts
+    pub earned: f64,
+    pub paid_out: f64,
+    pub payouts: usize,
+    pub accepted_shares: u64,
+    /// Unpaid amount reported by the last sample of the period
+    pub unpaid: f64,
+    pub samples: usize,
+}
+
+/// Checks whether the pool paid out between two samples.
+///
+/// ### Returns
+/// * `Some(amount)` - The larger of the unpaid and balance drops
+/// * `None` - Neither value dropped noticeably
+fn detect_payout(previous: &PoolStatus, current: &PoolStatus) -> Option<f64> {
+    let unpaid_drop = previous.unpaid - current.unpaid;
+    let balance_drop = previous.balance - current.balance;
+    let amount = unpaid_drop.max(balance_drop);
+    (amount >= MIN_PAYOUT_AMOUNT).then_some(amount)
+}
+
+fn period_start(timestamp: &DateTime<Utc>, period: PoolEarningsPeriod) -> NaiveDate {
+    let date = timestamp.date_naive();
+    match period {
+        PoolEarningsPeriod::Daily => date,
+        PoolEarningsPeriod::Weekly => {
+            date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+        }
+    }
+}
+
+/// Parses a line of the history file.
+///
+/// ### Returns
+/// * `Some(entry)` - A payout or a sample within the retention period
+/// * `None` - An expired sample or a line that cannot be parsed, e.g. after a crash while writing
+fn parse_retained_entry(line: &str, retention_start: DateTime<Utc>) -> Option<PoolEarningsEntry> {
+    match serde_json::from_str::<PoolEarningsEntry>(line).ok()? {
+        PoolEarningsEntry::Sample(sample) if sample.timestamp < retention_start => None,
+        entry => Some(entry),
+    }
+}
+
+/// Quotes a CSV field when it contains separators, quotes or line breaks.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub struct PoolEarningsHistory {
+    path: PathBuf,
+    samples: Vec<PoolEarningsSample>,
+    payouts: Vec<PoolPayout>,
+    /// Serializes file writes, which happen outside of the history lock
+    file_lock: Arc<Mutex<()>>,
+    last_compaction: Option<DateTime<Utc>>,
+}
+
+/// File changes of a recorded status, saved after the history lock is released.
+#[must_use]
+struct PoolEarningsHistoryWrite {
+    path: PathBuf,
+    file_lock: Arc<Mutex<()>>,
+    entries: Vec<PoolEarningsEntry>,
+    /// Samples older than this are removed from the file before appending
+    compact_before: Option<DateTime<Utc>>,
+}
+
+impl PoolEarningsHistory {
+    pub async fn read() -> RwLockReadGuard<'static, Self> {
+        INSTANCE.read().await
+    }
+
+    pub async fn write() -> RwLockWriteGuard<'static, Self> {
+        INSTANCE.write().await
+    }
+
+    fn get_history_path() -> PathBuf {
+        let config_dir = config_dir().unwrap_or_else(|| {
+            debug!(target: LOG_TARGET, "Failed to get config directory, using temp dir");
+            temp_dir()
+        });
+        config_dir
+            .join(APPLICATION_FOLDER_ID)
+            .join(HISTORY_FILE_NAME)
+    }
+
+    /// Loads the history saved by previous runs, dropping samples past the retention period.
+    /// Lines that cannot be parsed, e.g. after a crash while writing, are skipped.
+    fn load(path: PathBuf) -> Self {
+        let mut history = Self {
+            path,
+            samples: Vec::new(),
+            payouts: Vec::new(),
+            file_lock: Arc::new(Mutex::new(())),
+            last_compaction: None,
+        };
+        let content = match std::fs::read_to_string(&history.path) {
+            Ok(content) => content,
+            Err(e) => {
+                debug!(target: LOG_TARGET, "No pool earnings history loaded: {e}");
+                return history;
+            }
+        };
+
+        let retention_start = Utc::now() - Duration::days(SAMPLE_RETENTION_DAYS);
+        let mut dropped_lines = 0;
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            match parse_retained_entry(line, retention_start) {
+                Some(PoolEarningsEntry::Sample(sample)) => history.samples.push(sample),
+                Some(PoolEarningsEntry::Payout(payout)) => history.payouts.push(payout),
+                None => dropped_lines += 1,
+            }
+        }
+        info!(target: LOG_TARGET, "Loaded {} pool earnings samples and {} payouts", history.samples.len(), history.payouts.len());
+
+        if dropped_lines > 0 {
+            debug!(target: LOG_TARGET, "Compacting pool earnings history, dropping {dropped_lines} entries");
+            if let Err(e) = history.rewrite_history_file() {
+                warn!(target: LOG_TARGET, "Failed to compact pool earnings history: {e}");
+            }
+        }
+        history
+    }
+
+    /// Records a polled pool status, checks it for a payout and saves it.
+    ///
+    /// The history lock is only held while updating the in-memory history, the file is
+    /// written after releasing it so readers are not blocked by disk I/O.
+    ///
+    /// ### Returns
+    /// * `Some(PoolPayout)` - A payout was detected since the previous sample of the pool and address
+    /// * `None` - No payout detected
+    pub async fn record_status(
+        pool_name: &str,
+        mining_address: &str,
+        status: &PoolStatus,
+        timestamp: DateTime<Utc>,
+    ) -> Option<PoolPayout> {
+        let (payout, history_write) =
+            Self::write()
+                .await
+                .record(pool_name, mining_address, status, timestamp);
+        history_write.save().await;
+        payout
+    }
+
+    /// Records a polled pool status in memory and checks it for a payout.
+    /// Samples past the retention period, counted from the new sample, are dropped.
+    ///
+    /// ### Returns
+    /// * `(payout, history_write)` - The payout detected since the previous sample of the pool
+    ///   and address, if any, and the file changes to save
+    fn record(
+        &mut self,
+        pool_name: &str,
+        mining_address: &str,
+        status: &PoolStatus,
+        timestamp: DateTime<Utc>,
+    ) -> (Option<PoolPayout>, PoolEarningsHistoryWrite) {
+        // Samples are appended in time order
+        let retention_start = timestamp - Duration::days(SAMPLE_RETENTION_DAYS);
+        let expired_samples = self
+            .samples
+            .partition_point(|sample| sample.timestamp < retention_start);
+        self.samples.drain(..expired_samples);
+
+        let previous = self.samples.iter().rev().find(|sample| {
+            sample.pool_name == pool_name && sample.mining_address == mining_address
+        });
+        let payout = previous.and_then(|previous| {
+            detect_payout(&previous.status, status).map(|amount| PoolPayout {
+                timestamp,
+                pool_name: pool_name.to_string(),
+                mining_address: mining_address.to_string(),
+                amount,
+                unpaid_before: previous.status.unpaid,
+                unpaid_after: status.unpaid,
+            })
+        });
+
+        let sample = PoolEarningsSample {
+            timestamp,
+            pool_name: pool_name.to_string(),
+            mining_address: mining_address.to_string(),
+            // Worker stats are only shown live, keeping them would bloat the history
+            status: PoolStatus {
+                workers: Vec::new(),
+                ..status.clone()
+            },
+        };
+        let mut entries = vec![PoolEarningsEntry::Sample(sample.clone())];
+        self.samples.push(sample);
+        if let Some(payout) = &payout {
+            info!(target: LOG_TARGET, "Detected payout of {} from {pool_name}", payout.amount);
+            entries.push(PoolEarningsEntry::Payout(payout.clone()));
+            self.payouts.push(payout.clone());
+        }
+
+        let compaction_due = self.last_compaction.is_some_and(|last_compaction| {
+            timestamp - last_compaction >= Duration::hours(COMPACTION_INTERVAL_HOURS)
+        });
+        if compaction_due || self.last_compaction.is_none() {
+            self.last_compaction = Some(timestamp);
+        }
+
+        let history_write = PoolEarningsHistoryWrite {
+            path: self.path.clone(),
+            file_lock: self.file_lock.clone(),
+            entries,
+            compact_before: compaction_due.then_some(retention_start),
+        };
+        (payout, history_write)
+    }
+
+    /// Returns the samples matching the filter, oldest first.
+    pub fn samples(&self, filter: &PoolEarningsFilter) -> Vec<PoolEarningsSample> {
+        self.samples
+            .iter()
+            .filter(|sample| {
+                filter.matches(&sample.timestamp, &sample.pool_name, &sample.mining_address)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the payouts matching the filter, newest first.
+    pub fn payouts(&self, filter: &PoolEarningsFilter) -> Vec<PoolPayout> {
+        self.payouts
+            .iter()
+            .rev()
+            .filter(|payout| {
+                filter.matches(&payout.timestamp, &payout.pool_name, &payout.mining_address)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Sums up the samples per period, pool and mining address, oldest period first.
+    pub fn aggregates(
+        &self,
+        period: PoolEarningsPeriod,
+        filter: &PoolEarningsFilter,
+    ) -> Vec<PoolEarningsAggregate> {
+        let mut aggregates: BTreeMap<(NaiveDate, String, String), PoolEarningsAggregate> =
+            BTreeMap::new();
+        let mut previous_samples: HashMap<(&str, &str), &PoolEarningsSample> = HashMap::new();
+
+        for sample in &self.samples {
+            let key = (sample.pool_name.as_str(), sample.mining_address.as_str());
+            let previous = previous_samples.insert(key, sample);
+            if !filter.matches(&sample.timestamp, &sample.pool_name, &sample.mining_address) {
+                continue;
+            }
+
+            let start = period_start(&sample.timestamp, period);
+            let aggregate = aggregates
+                .entry((
+                    start,
+                    sample.pool_name.clone(),
+                    sample.mining_address.clone(),
+                ))
+                .or_insert_with(|| PoolEarningsAggregate {
+                    period_start: start,
+                    pool_name: sample.pool_name.clone(),
+                    mining_address: sample.mining_address.clone(),
+                    earned: 0.0,
+                    paid_out: 0.0,
+                    payouts: 0,
+                    accepted_shares: 0,
+                    unpaid: 0.0,
+                    samples: 0,
+                });
+            aggregate.samples += 1;
+            aggregate.unpaid = sample.status.unpaid;
+
+            if let Some(previous) = previous {
+                if detect_payout(&previous.status, &sample.status).is_none() {
+                    aggregate.earned += (sample.status.unpaid - previous.status.unpaid).max(0.0);
+                }
+                // Share counters restart e.g. after a pool resets its statistics
+                aggregate.accepted_shares += sample
+                    .status
+                    .accepted_shares
+                    .saturating_sub(previous.status.accepted_shares);
+            }
+        }
+
+        for payout in &self.payouts {
+            if !filter.matches(&payout.timestamp, &payout.pool_name, &payout.mining_address) {
+                continue;
+            }
+            let start = period_start(&payout.timestamp, period);
+            if let Some(aggregate) = aggregates.get_mut(&(
+                start,
+                payout.pool_name.clone(),
+                payout.mining_address.clone(),
+            )) {
+                aggregate.paid_out += payout.amount;
+                aggregate.payouts += 1;
+            }
+        }
+
+        aggregates.into_values().collect()
+    }
+
+    /// Serializes the requested data for the accounting export.
+    pub fn export(
+        &self,
+        dataset: PoolEarningsDataset,
+        format: PoolEarningsExportFormat,
+        filter: &PoolEarningsFilter,
+    ) -> Result<String, Error> {
+        match (dataset, format) {
+            (PoolEarningsDataset::Samples, PoolEarningsExportFormat::Json) => {
+                Ok(serde_json::to_string_pretty(&self.samples(filter))?)
+            }
+            (PoolEarningsDataset::Payouts, PoolEarningsExportFormat::Json) => {
+                Ok(serde_json::to_string_pretty(&self.payouts(filter))?)
+            }
+            (PoolEarningsDataset::Daily, PoolEarningsExportFormat::Json) => Ok(
+                serde_json::to_string_pretty(&self.aggregates(PoolEarningsPeriod::Daily, filter))?,
+            ),
+            (PoolEarningsDataset::Weekly, PoolEarningsExportFormat::Json) => Ok(
+                serde_json::to_string_pretty(&self.aggregates(PoolEarningsPeriod::Weekly, filter))?,
+            ),
+            (PoolEarningsDataset::Samples, PoolEarningsExportFormat::Csv) => {
+                let mut csv = String::from(
+                    "timestamp,pool_name,mining_address,accepted_shares,unpaid,balance,min_payout\n",
+                );
+                for sample in self.samples(filter) {
+                    writeln!(
+                        csv,
+                        "{},{},{},{},{},{},{}",
+                        sample.timestamp.to_rfc3339(),
+                        csv_field(&sample.pool_name),
+                        csv_field(&sample.mining_address),
+                        sample.status.accepted_shares,
+                        sample.status.unpaid,
+                        sample.status.balance,
+                        sample.status.min_payout
+                    )?;
+                }
+                Ok(csv)
+            }
+            (PoolEarningsDataset::Payouts, PoolEarningsExportFormat::Csv) => {
+                let mut csv = String::from(
+                    "timestamp,pool_name,mining_address,amount,unpaid_before,unpaid_after\n",
+                );
+                for payout in self.payouts(filter) {
+                    writeln!(
+                        csv,
+                        "{},{},{},{},{},{}",
+                        payout.timestamp.to_rfc3339(),
+                        csv_field(&payout.pool_name),
+                        csv_field(&payout.mining_address),
+                        payout.amount,
+                        payout.unpaid_before,
+                        payout.unpaid_after
+                    )?;
+                }
+                Ok(csv)
+            }
+            (
+                PoolEarningsDataset::Daily | PoolEarningsDataset::Weekly,
+                PoolEarningsExportFormat::Csv,
+            ) => {
+                let period = if dataset == PoolEarningsDataset::Daily {
+                    PoolEarningsPeriod::Daily
+                } else {
+                    PoolEarningsPeriod::Weekly
+                };
+                let mut csv = String::from(
+                    "period_start,pool_name,mining_address,earned,paid_out,payouts,accepted_shares,unpaid,samples\n",
+                );
+                for aggregate in self.aggregates(period, filter) {
+                    writeln!(
+                        csv,
+                        "{},{},{},{},{},{},{},{},{}",
+                        aggregate.period_start,
+                        csv_field(&aggregate.pool_name),
+                        csv_field(&aggregate.mining_address),
+                        aggregate.earned,
+                        aggregate.paid_out,
+                        aggregate.payouts,
+                        aggregate.accepted_shares,
+                        aggregate.unpaid,
+                        aggregate.samples
+                    )?;
+                }
+                Ok(csv)
+            }
+        }
+    }
+
+    /// Removes all samples and payouts, including the saved ones.
+    pub async fn clear(&mut self) -> Result<(), Error> {
+        self.samples.clear();
+        self.payouts.clear();
+        let _file_guard = self.file_lock.lock().await;
+        if tokio::fs::try_exists(&self.path).await? {
+            tokio::fs::remove_file(&self.path).await?;
+        }
+        Ok(())
+    }
+
+    fn rewrite_history_file(&self) -> Result<(), Error> {
+        let mut content = String::new();
+        let entries = self
+            .samples
+            .iter()
+            .cloned()
+            .map(PoolEarningsEntry::Sample)
+            .chain(self.payouts.iter().cloned().map(PoolEarningsEntry::Payout));
+        for entry in entries {
+            content.push_str(&serde_json::to_string(&entry)?);
+            content.push('\n');
+        }
+        std::fs::write(&self.path, content)?;
+        Ok(())
+    }
+}
+
+impl PoolEarningsHistoryWrite {
+    /// Writes the recorded entries to the history file, compacting it first when due.
+    async fn save(self) {
+        let _file_guard = self.file_lock.lock().await;
+        if let Some(retention_start) = self.compact_before {
+            match compact_history_file(&self.path, retention_start).await {
+                Ok(0) => {}
+                Ok(dropped_lines) => {
+                    debug!(target: LOG_TARGET, "Compacted pool earnings history, dropped {dropped_lines} entries");
+                }
+                Err(e) => warn!(target: LOG_TARGET, "Failed to compact pool earnings history: {e}"),
+            }
+        }
+        if let Err(e) = append_to_history_file(&self.path, &self.entries).await {
+            warn!(target: LOG_TARGET, "Failed to save pool earnings history: {e}");
+        }
+    }
+}
+
+async fn append_to_history_file(path: &Path, entries: &[PoolEarningsEntry]) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(&serde_json::to_string(entry)?);
+        content.push('\n');
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(content.as_bytes()).await?;
+    file.flush().await?;
+    Ok(())
+}
+
+/// Removes expired samples and unreadable lines from the history file.
+///
+/// The file is filtered line by line instead of being rewritten from memory, so entries
+/// appended by other writes are never lost. The result replaces the file atomically.
+///
+/// ### Returns
+/// * `Ok(dropped_lines)` - Number of removed lines
+async fn compact_history_file(path: &Path, retention_start: DateTime<Utc>) -> Result<usize, Error> {
+    let content = match tokio::fs::read_to_string(path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut retained_content = String::new();
+    let mut dropped_lines = 0;
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        if parse_retained_entry(line, retention_start).is_some() {
+            retained_content.push_str(line);
+            retained_content.push('\n');
+        } else {
+            dropped_lines += 1;
+        }
+    }
+
+    if dropped_lines > 0 {
+        let temp_path = path.with_extension("jsonl.tmp");
+        tokio::fs::write(&temp_path, retained_content).await?;
+        tokio::fs::rename(&temp_path, path).await?;
+    }
+    Ok(dropped_lines)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use chrono::TimeZone;
+
+    const POOL: &str = "LuckyPoolRANDOMX";
+    const ADDRESS: &str = "12ABcd";
+
+    fn test_history() -> PoolEarningsHistory {
+        PoolEarningsHistory {
+            path: temp_dir().join(format!("pool_earnings_{}.jsonl", uuid::Uuid::new_v4())),
+            samples: Vec::new(),
+            payouts: Vec::new(),
+            file_lock: Arc::new(Mutex::new(())),
+            last_compaction: None,
+        }
+    }
+
+    async fn record(
+        history: &mut PoolEarningsHistory,
+        mining_address: &str,
+        status: &PoolStatus,
+        timestamp: DateTime<Utc>,
+    ) -> Option<PoolPayout> {
+        let (payout, history_write) = history.record(POOL, mining_address, status, timestamp);
+        history_write.save().await;
+        payout
+    }
+
+    fn status(accepted_shares: u64, unpaid: f64, balance: f64) -> PoolStatus {
+        PoolStatus {
+            accepted_shares,
+            unpaid,
+            balance,
+            min_payout: 1000,
+            workers: Vec::new(),
+        }
+    }
+
+    fn time(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_payout_detected_when_unpaid_drops() {
+        let mut history = test_history();
+        assert!(
+            record(&mut history, ADDRESS, &status(10, 800.0, 0.0), time(3, 10))
+                .await
+                .is_none()
+        );
+        assert!(
+            record(&mut history, ADDRESS, &status(20, 1200.0, 0.0), time(3, 11))
+                .await
+                .is_none()
+        );
+
+        let payout = record(
+            &mut history,
+            ADDRESS,
+            &status(25, 100.0, 1100.0),
+            time(3, 12),
+        )
+        .await
+        .unwrap();
+        assert_eq!(payout.amount, 1100.0);
+        assert_eq!(payout.unpaid_before, 1200.0);
+
+        // Another address mining on the same pool is tracked separately
+        assert!(
+            record(&mut history, "34EFgh", &status(1, 50.0, 0.0), time(3, 12))
+                .await
+                .is_none()
+        );
+
+        history.clear().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_history_survives_reload() {
+        let mut history = test_history();
+        record(&mut history, ADDRESS, &status(10, 800.0, 0.0), Utc::now()).await;
+        record(&mut history, ADDRESS, &status(12, 10.0, 800.0), Utc::now()).await;
+
+        let reloaded = PoolEarningsHistory::load(history.path.clone());
+        assert_eq!(reloaded.samples.len(), 2);
+        assert_eq!(reloaded.payouts.len(), 1);
+
+        history.clear().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_expired_samples_pruned_and_compacted() {
+        let mut history = test_history();
+        record(&mut history, ADDRESS, &status(10, 800.0, 0.0), time(3, 10)).await;
+        record(&mut history, ADDRESS, &status(12, 10.0, 800.0), time(3, 11)).await;
+
+        let later = time(3, 12) + Duration::days(SAMPLE_RETENTION_DAYS);
+        record(&mut history, ADDRESS, &status(20, 100.0, 800.0), later).await;
+        assert_eq!(history.samples.len(), 1);
+        assert_eq!(history.payouts.len(), 1);
+
+        // Only the payout and the new sample are left in the file
+        let content = std::fs::read_to_string(&history.path).unwrap();
+        assert_eq!(content.lines().count(), 2);
+
+        history.clear().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_daily_and_weekly_aggregates() {
+        let mut history = test_history();
+        // Monday 3rd, Tuesday 4th and Monday 10th of March 2025
+        record(&mut history, ADDRESS, &status(0, 0.0, 0.0), time(3, 0)).await;
+        record(&mut history, ADDRESS, &status(10, 400.0, 0.0), time(3, 12)).await;
+        record(&mut history, ADDRESS, &status(15, 600.0, 0.0), time(4, 12)).await;
+        record(&mut history, ADDRESS, &status(18, 50.0, 0.0), time(4, 13)).await;
+        record(&mut history, ADDRESS, &status(30, 250.0, 0.0), time(10, 12)).await;
+
+        let daily = history.aggregates(PoolEarningsPeriod::Daily, &PoolEarningsFilter::default());
+        assert_eq!(daily.len(), 3);
+        assert_eq!(daily[0].earned, 400.0);
+        assert_eq!(daily[0].accepted_shares, 10);
+        assert_eq!(daily[1].earned, 200.0);
+        assert_eq!(daily[1].paid_out, 550.0);
+        assert_eq!(daily[1].payouts, 1);
+        assert_eq!(daily[1].unpaid, 50.0);
+
+        let weekly = history.aggregates(PoolEarningsPeriod::Weekly, &PoolEarningsFilter::default());
+        assert_eq!(weekly.len(), 2);
+        assert_eq!(weekly[0].period_start, time(3, 0).date_naive());
+        assert_eq!(weekly[0].earned, 600.0);
+        assert_eq!(weekly[1].period_start, time(10, 0).date_naive());
+        assert_eq!(weekly[1].earned, 200.0);
+        assert_eq!(weekly[1].accepted_shares, 12);
+
+        let filter = PoolEarningsFilter {
+            from: Some(time(4, 0)),
+            ..Default::default()
+        };
+        assert_eq!(
+            history.aggregates(PoolEarningsPeriod::Daily, &filter).len(),
+            2
+        );
+
+        history.clear().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_csv_and_json_export() {
+        let mut history = test_history();
+        record(&mut history, ADDRESS, &status(10, 800.0, 0.0), time(3, 10)).await;
+        record(&mut history, ADDRESS, &status(12, 10.0, 800.0), time(3, 11)).await;
+
+        let csv = history
+            .export(
+                PoolEarningsDataset::Payouts,
+                PoolEarningsExportFormat::Csv,
+                &PoolEarningsFilter::default(),
+            )
+            .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "timestamp,pool_name,mining_address,amount,unpaid_before,unpaid_after",
+                "2025-03-03T11:00:00+00:00,LuckyPoolRANDOMX,12ABcd,790,800,10",
+            ]
+        );
+
+        let json = history
+            .export(
+                PoolEarningsDataset::Samples,
+                PoolEarningsExportFormat::Json,
+                &PoolEarningsFilter::default(),
+            )
+            .unwrap();
+        let samples: Vec<PoolEarningsSample> = serde_json::from_str(&json).unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1].status.balance, 800.0);
+
+        assert_eq!(csv_field("Pool, \"A\""), "\"Pool, \"\"A\"\"\"");
+
+        history.clear().await.unwrap();
+    }
+}
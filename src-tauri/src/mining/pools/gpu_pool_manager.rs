@@ -28,7 +28,7 @@ use tokio::{spawn, sync::RwLock};
 use crate::{
     configs::{
         config_pools::{ConfigPools, ConfigPoolsContent},
-        pools::{gpu_pools::GpuPool, BasePoolData},
+        pools::{custom_pools::PoolStatsMapping, gpu_pools::GpuPool, BasePoolData, PoolOrigin},
         trait_config::ConfigImpl,
     },
     events_emitter::EventsEmitter,
@@ -146,6 +146,10 @@ impl GpuPoolManager {
 }
 
 impl PoolManagerInterfaceTrait<GpuPool> for GpuPoolManager {
+    async fn get_read_manager() -> tokio::sync::RwLockReadGuard<'static, PoolManager> {
+        INSTANCE.pool_status_manager.read().await
+    }
+
     async fn get_write_manager() -> tokio::sync::RwLockWriteGuard<'static, PoolManager> {
         INSTANCE.pool_status_manager.write().await
     }
@@ -186,6 +190,17 @@ impl PoolManagerInterfaceTrait<GpuPool> for GpuPoolManager {
             GpuPool::SupportXTMPoolSHA3X => PoolApiAdapters::SupportXmr(
                 SupportXmrPoolAdapter::new(pool.pool_type.key_string(), pool.stats_url),
             ),
+            GpuPool::Custom { .. } => {
+                let stats_mapping = match pool.pool_origin {
+                    PoolOrigin::Custom(settings) => settings.stats_mapping,
+                    _ => PoolStatsMapping::default(),
+                };
+                PoolApiAdapters::Custom(CustomPoolAdapter::new(
+                    pool.pool_type.key_string(),
+                    pool.stats_url,
+                    stats_mapping,
+                ))
+            }
         }
     }
 }
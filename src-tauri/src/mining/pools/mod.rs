@@ -22,9 +22,9 @@
 
 use std::collections::HashMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tari_common_types::tari_address::TariAddress;
-use tokio::sync::RwLockWriteGuard;
+use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
     configs::pools::BasePoolData,
@@ -33,20 +33,39 @@ use crate::{
 
 mod adapters;
 pub mod cpu_pool_manager;
+pub mod earnings_history;
 pub mod gpu_pool_manager;
+pub mod pool_failover;
+pub mod pool_probe;
+pub mod pool_selection;
 pub mod pools_manager;
 
-#[derive(Clone, Debug, Serialize, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub(crate) struct PoolStatus {
     pub accepted_shares: u64,
     pub unpaid: f64,
     pub balance: f64,
     pub min_payout: u64,
+    /// Stats of each worker mining to the address, empty when the pool does not report them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workers: Vec<PoolWorkerStatus>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq)]
+pub(crate) struct PoolWorkerStatus {
+    /// Worker name without the wallet address and separator
+    pub name: String,
+    /// Hashes per second as estimated by the pool
+    pub hashrate: f64,
+    pub total_hashes: u64,
+    /// Unix timestamp in seconds, `None` when the worker has not submitted a share yet
+    pub last_share_timestamp: Option<u64>,
 }
 
 pub trait PoolManagerInterfaceTrait<T> {
     // =============== Getters ===============
 
+    async fn get_read_manager() -> RwLockReadGuard<'static, PoolManager>;
     async fn get_write_manager() -> RwLockWriteGuard<'static, PoolManager>;
 
     // =============== To be implemented by the specific pool manager (CPU/GPU) ===============
@@ -105,6 +124,11 @@ pub trait PoolManagerInterfaceTrait<T> {
             .await;
     }
 
+    /// Latest statuses fetched for each pool the wallet address was mining to
+    async fn get_pool_statuses() -> HashMap<String, PoolStatus> {
+        Self::get_read_manager().await.get_pool_statuses().await
+    }
+
     /// Start a periodic task to fetch and update pool statuses
     /// This should be called when mining starts, it has handled to not start multiple tasks
     async fn start_stats_watcher() {
@@ -0,0 +1,177 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Switching between the configured pools when the current one goes down.
+//!
+//! xmrig handles CPU failover natively with its list of pools, so for CPU mining only the
+//! switches reported by the status monitor are turned into events. GPU miners only accept a
+//! single pool, so the GPU manager restarts the miner on the next pool once the miner has been
+//! disconnected for the configured time and returns to the primary pool once it is reachable.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::net::TcpStream;
+
+const POOL_REACHABILITY_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often a miner running on a fallback pool checks if its primary pool is back
+pub const SWITCH_BACK_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum PoolFailoverMiner {
+    Cpu,
+    Gpu,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum PoolFailoverKind {
+    /// Moved to the next pool as the previous one was unreachable
+    Failover,
+    /// Returned to the primary pool after it recovered
+    SwitchBack,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolFailoverPayload {
+    pub miner: PoolFailoverMiner,
+    pub kind: PoolFailoverKind,
+    pub from_pool: String,
+    pub to_pool: String,
+}
+
+/// Tracks for how long a miner has been disconnected from its pool.
+#[derive(Debug, Clone)]
+pub struct DisconnectionTracker {
+    timeout: Duration,
+    disconnected_since: Option<Instant>,
+}
+
+impl DisconnectionTracker {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            disconnected_since: None,
+        }
+    }
+
+    /// Records the latest connection state.
+    ///
+    /// ### Returns
+    /// * `true` - The miner has been disconnected for longer than the timeout
+    /// * `false` - The miner is connected or the timeout has not passed yet
+    pub fn update(&mut self, is_connected: bool, now: Instant) -> bool {
+        if is_connected {
+            self.disconnected_since = None;
+            return false;
+        }
+        let disconnected_since = *self.disconnected_since.get_or_insert(now);
+        now.duration_since(disconnected_since) >= self.timeout
+    }
+
+    pub fn reset(&mut self) {
+        self.disconnected_since = None;
+    }
+}
+
+/// Picks the pool following `current` in the failover order, wrapping around to the primary.
+///
+/// ### Returns
+/// * `Some(pool)` - Pool to switch to
+/// * `None` - There is no other pool to switch to
+pub fn next_failover_pool<T: PartialEq + Clone>(pools: &[T], current: &T) -> Option<T> {
+    if pools.len() < 2 {
+        return None;
+    }
+    let next_index = pools
+        .iter()
+        .position(|pool| pool == current)
+        .map_or(0, |index| (index + 1) % pools.len());
+    pools.get(next_index).cloned()
+}
+
+/// Checks if a TCP connection to the pool can be opened.
+///
+/// ### Parameters
+/// * `pool_url` - Pool address as `host:port`, optionally prefixed with a scheme
+pub async fn is_pool_reachable(pool_url: &str) -> bool {
+    let address = pool_url
+        .split_once("://")
+        .map_or(pool_url, |(_, address)| address);
+    matches!(
+        tokio::time::timeout(POOL_REACHABILITY_TIMEOUT, TcpStream::connect(address)).await,
+        Ok(Ok(_))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_tracker_times_out_after_continuous_disconnection() {
+        let start = Instant::now();
+        let mut tracker = DisconnectionTracker::new(Duration::from_secs(60));
+
+        assert!(!tracker.update(false, start));
+        assert!(!tracker.update(false, start + Duration::from_secs(59)));
+        assert!(tracker.update(false, start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_tracker_resets_on_reconnection() {
+        let start = Instant::now();
+        let mut tracker = DisconnectionTracker::new(Duration::from_secs(60));
+
+        assert!(!tracker.update(false, start));
+        assert!(!tracker.update(true, start + Duration::from_secs(30)));
+        assert!(!tracker.update(false, start + Duration::from_secs(70)));
+        assert!(tracker.update(false, start + Duration::from_secs(130)));
+
+        tracker.reset();
+        assert!(!tracker.update(false, start + Duration::from_secs(131)));
+    }
+
+    #[test]
+    fn test_next_failover_pool_wraps_around() {
+        let pools = vec!["primary", "backup", "last_resort"];
+
+        assert_eq!(next_failover_pool(&pools, &"primary"), Some("backup"));
+        assert_eq!(next_failover_pool(&pools, &"backup"), Some("last_resort"));
+        assert_eq!(next_failover_pool(&pools, &"last_resort"), Some("primary"));
+        assert_eq!(next_failover_pool(&pools, &"unknown"), Some("primary"));
+        assert_eq!(next_failover_pool(&["primary"], &"primary"), None);
+    }
+
+    #[tokio::test]
+    async fn test_pool_reachability() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        assert!(is_pool_reachable(&address.to_string()).await);
+        assert!(is_pool_reachable(&format!("stratum+tcp://{address}")).await);
+
+        drop(listener);
+        assert!(!is_pool_reachable(&address.to_string()).await);
+    }
+}
@@ -0,0 +1,553 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! Connectivity check of a pool before mining on it.
+//!
+//! The probe resolves the pool's host, opens a TCP connection (wrapped in TLS for
+//! `stratum+ssl://` and `stratum+tls://` urls) and performs a stratum login, the first message
+//! every miner sends. Each stage is timed and failures are classified, so the user learns why a
+//! pool cannot be used before switching to it instead of once the miner stops hashing.
+
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{lookup_host, TcpStream},
+    time::timeout,
+};
+use tokio_native_tls::{native_tls, TlsConnector};
+
+use crate::{
+    configs::{
+        config_pools::ConfigPools,
+        pools::{cpu_pools::CpuPool, gpu_pools::GpuPool, BasePoolData},
+        trait_config::ConfigImpl,
+    },
+    internal_wallet::InternalWallet,
+    mining::gpu::consts::GpuMiningAlgorithm,
+    LOG_TARGET_APP_LOGIC,
+};
+
+pub const POOL_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Upper bound of the login response, pools answer with a single job
+const MAX_RESPONSE_BYTES: u64 = 64 * 1024;
+const PROBE_AGENT: &str = "tari-universe-probe";
+const TLS_SCHEMES: [&str; 4] = ["stratum+ssl", "stratum+tls", "ssl", "tls"];
+
+/// Flavour of the stratum login the pool expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum StratumProtocol {
+    /// xmrig style `login` with numeric ids, used by RandomX and SHA3X pools
+    Monero,
+    /// Grin style `login` with string ids, used by Cuckaroo (C29) pools
+    Grin,
+}
+
+impl StratumProtocol {
+    pub fn for_gpu_algorithm(algorithm: &GpuMiningAlgorithm) -> Self {
+        match algorithm {
+            GpuMiningAlgorithm::SHA3X => StratumProtocol::Monero,
+            GpuMiningAlgorithm::C29 => StratumProtocol::Grin,
+        }
+    }
+
+    fn login_request(&self, login: &str) -> Value {
+        match self {
+            StratumProtocol::Monero => json!({
+                "id": 1,
+                "jsonrpc": "2.0",
+                "method": "login",
+                "params": { "login": login, "pass": "x", "agent": PROBE_AGENT },
+            }),
+            StratumProtocol::Grin => json!({
+                "id": "1",
+                "jsonrpc": "2.0",
+                "method": "login",
+                "params": { "login": login, "pass": "x", "agent": PROBE_AGENT },
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PoolProbeFailure {
+    /// The url is not a valid `host:port`
+    InvalidUrl,
+    /// The host name could not be resolved
+    Dns,
+    /// The pool's host actively refused the connection
+    Refused,
+    /// A stage did not finish within the probe timeout
+    Timeout,
+    /// The TLS handshake failed
+    Tls,
+    /// The pool answered with something that is not a stratum response
+    Protocol,
+    /// Any other network error, e.g. the connection was reset
+    Connection,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum PoolProbeOutcome {
+    /// The pool answered the login. A rejected login still proves the pool speaks stratum,
+    /// e.g. when the address is not accepted by the pool.
+    Reachable {
+        login_accepted: bool,
+        message: Option<String>,
+    },
+    Failed {
+        failure: PoolProbeFailure,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolProbeResult {
+    pub pool_url: String,
+    pub resolved_address: Option<String>,
+    pub tls: bool,
+    /// Time to open the TCP connection
+    pub connect_latency_ms: Option<u64>,
+    /// Time of the TLS handshake, only set for TLS pools
+    pub tls_latency_ms: Option<u64>,
+    /// Time from sending the stratum login until the pool answered
+    pub login_latency_ms: Option<u64>,
+    pub outcome: PoolProbeOutcome,
+}
+
+impl PoolProbeResult {
+    pub fn is_reachable(&self) -> bool {
+        matches!(self.outcome, PoolProbeOutcome::Reachable { .. })
+    }
+
+    /// Error for callers refusing to use a pool that failed the probe.
+    ///
+    /// ### Returns
+    /// * `Ok(())` - The pool is reachable
+    /// * `Err(anyhow::Error)` - Describes why the pool can not be used
+    pub fn ensure_reachable(&self) -> Result<(), anyhow::Error> {
+        match &self.outcome {
+            PoolProbeOutcome::Reachable { .. } => Ok(()),
+            PoolProbeOutcome::Failed { failure, message } => Err(anyhow::anyhow!(
+                "Pool {} is not reachable ({failure:?}): {message}",
+                self.pool_url
+            )),
+        }
+    }
+
+    fn failed(mut self, failure: PoolProbeFailure, message: impl Into<String>) -> Self {
+        self.outcome = PoolProbeOutcome::Failed {
+            failure,
+            message: message.into(),
+        };
+        self
+    }
+}
+
+fn classify_io_error(error: &std::io::Error) -> PoolProbeFailure {
+    match error.kind() {
+        std::io::ErrorKind::ConnectionRefused => PoolProbeFailure::Refused,
+        std::io::ErrorKind::TimedOut => PoolProbeFailure::Timeout,
+        _ => PoolProbeFailure::Connection,
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+/// Splits the pool url into the host, port and whether TLS is used.
+fn parse_pool_url(pool_url: &str) -> Option<(String, u16, bool)> {
+    let (scheme, address) = match pool_url.trim().split_once("://") {
+        Some((scheme, address)) => (Some(scheme.to_lowercase()), address),
+        None => (None, pool_url.trim()),
+    };
+    let tls = scheme.is_some_and(|scheme| TLS_SCHEMES.contains(&scheme.as_str()));
+    let address = address.trim_end_matches('/');
+    let (host, port) = address.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return None;
+    }
+    Some((host.to_string(), port.parse().ok()?, tls))
+}
+
+/// Sends the login and waits for the response with the same id.
+///
+/// ### Returns
+/// * `Ok((login_accepted, message))` - The pool answered the login
+/// * `Err((failure, message))` - No valid stratum response was received
+async fn stratum_login<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: S,
+    protocol: StratumProtocol,
+    login: &str,
+) -> Result<(bool, Option<String>), (PoolProbeFailure, String)> {
+    let request = protocol.login_request(login);
+    let mut reader = BufReader::new(stream);
+    let mut payload = request.to_string();
+    payload.push('\n');
+    reader
+        .get_mut()
+        .write_all(payload.as_bytes())
+        .await
+        .map_err(|e| (classify_io_error(&e), e.to_string()))?;
+
+    // Pools may push notifications before answering, only the response to the login counts
+    let mut limited_reader = reader.take(MAX_RESPONSE_BYTES);
+    loop {
+        let mut line = String::new();
+        let read = limited_reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| (classify_io_error(&e), e.to_string()))?;
+        if read == 0 {
+            return Err((
+                PoolProbeFailure::Protocol,
+                "Connection closed before the login response".to_string(),
+            ));
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response: Value = serde_json::from_str(line.trim()).map_err(|_| {
+            (
+                PoolProbeFailure::Protocol,
+                format!("Not a stratum response: {}", line.trim()),
+            )
+        })?;
+        if response.get("id") != request.get("id") {
+            continue;
+        }
+        return match response.get("error") {
+            Some(error) if !error.is_null() => {
+                let message = error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .map_or_else(|| error.to_string(), str::to_string);
+                Ok((false, Some(message)))
+            }
+            _ if response.get("result").is_some() => Ok((true, None)),
+            _ => Err((
+                PoolProbeFailure::Protocol,
+                "Login response has neither a result nor an error".to_string(),
+            )),
+        };
+    }
+}
+
+/// Probes the pool at `pool_url`.
+///
+/// ### Parameters
+/// * `pool_url` - Pool address as `host:port`, optionally prefixed with a scheme
+/// * `protocol` - Stratum flavour used for the login
+/// * `login` - Login sent to the pool, usually the mining address and worker name
+/// * `stage_timeout` - Timeout applied to each stage separately
+pub async fn probe_pool(
+    pool_url: &str,
+    protocol: StratumProtocol,
+    login: &str,
+    stage_timeout: Duration,
+) -> PoolProbeResult {
+    let mut result = PoolProbeResult {
+        pool_url: pool_url.to_string(),
+        resolved_address: None,
+        tls: false,
+        connect_latency_ms: None,
+        tls_latency_ms: None,
+        login_latency_ms: None,
+        outcome: PoolProbeOutcome::Failed {
+            failure: PoolProbeFailure::InvalidUrl,
+            message: format!("Invalid pool url {pool_url:?}"),
+        },
+    };
+    let Some((host, port, tls)) = parse_pool_url(pool_url) else {
+        return result;
+    };
+    result.tls = tls;
+
+    let address = match timeout(stage_timeout, lookup_host((host.as_str(), port))).await {
+        Ok(Ok(mut addresses)) => match addresses.next() {
+            Some(address) => address,
+            None => {
+                return result.failed(
+                    PoolProbeFailure::Dns,
+                    format!("No address found for {host}"),
+                )
+            }
+        },
+        Ok(Err(e)) => return result.failed(PoolProbeFailure::Dns, e.to_string()),
+        Err(_) => {
+            return result.failed(
+                PoolProbeFailure::Timeout,
+                format!("Resolving {host} timed out"),
+            )
+        }
+    };
+    result.resolved_address = Some(address.to_string());
+
+    let connect_start = Instant::now();
+    let tcp_stream = match timeout(stage_timeout, TcpStream::connect(address)).await {
+        Ok(Ok(stream)) => stream,
+        Ok(Err(e)) => return result.failed(classify_io_error(&e), e.to_string()),
+        Err(_) => {
+            return result.failed(
+                PoolProbeFailure::Timeout,
+                format!("Connecting to {address} timed out"),
+            )
+        }
+    };
+
+    result.connect_latency_ms = Some(elapsed_ms(connect_start));
+
+    let login_start;
+    let login_result = if tls {
+        let connector = match native_tls::TlsConnector::new() {
+            Ok(connector) => TlsConnector::from(connector),
+            Err(e) => return result.failed(PoolProbeFailure::Tls, e.to_string()),
+        };
+        let tls_start = Instant::now();
+        let tls_stream = match timeout(stage_timeout, connector.connect(&host, tcp_stream)).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => return result.failed(PoolProbeFailure::Tls, e.to_string()),
+            Err(_) => return result.failed(PoolProbeFailure::Timeout, "TLS handshake timed out"),
+        };
+        result.tls_latency_ms = Some(elapsed_ms(tls_start));
+
+        login_start = Instant::now();
+        timeout(stage_timeout, stratum_login(tls_stream, protocol, login)).await
+    } else {
+        login_start = Instant::now();
+        timeout(stage_timeout, stratum_login(tcp_stream, protocol, login)).await
+    };
+
+    match login_result {
+        Ok(Ok((login_accepted, message))) => {
+            result.login_latency_ms = Some(elapsed_ms(login_start));
+            result.outcome = PoolProbeOutcome::Reachable {
+                login_accepted,
+                message,
+            };
+            info!(target: LOG_TARGET_APP_LOGIC, "Pool {pool_url} reachable, connect: {:?}ms, tls: {:?}ms, login: {:?}ms", result.connect_latency_ms, result.tls_latency_ms, result.login_latency_ms);
+            result
+        }
+        Ok(Err((failure, message))) => {
+            warn!(target: LOG_TARGET_APP_LOGIC, "Pool {pool_url} probe failed: {failure:?} {message}");
+            result.failed(failure, message)
+        }
+        Err(_) => result.failed(PoolProbeFailure::Timeout, "Login response timed out"),
+    }
+}
+
+/// Login the miners would send to the pool, the wallet address followed by the worker name.
+async fn pool_login<T>(pool: &BasePoolData<T>) -> String {
+    let tari_address = InternalWallet::tari_address().await.to_base58();
+    let worker_name = ConfigPools::content().await.worker_name().clone();
+    match pool.pool_origin.worker_name(&worker_name) {
+        Some(worker_name) => format!("{tari_address}{worker_name}"),
+        None => tari_address,
+    }
+}
+
+/// Probes a CPU pool the way xmrig connects to it.
+pub async fn probe_cpu_pool(pool: &BasePoolData<CpuPool>) -> PoolProbeResult {
+    let login = pool_login(pool).await;
+    probe_pool(
+        &pool.pool_url,
+        StratumProtocol::Monero,
+        &login,
+        POOL_PROBE_TIMEOUT,
+    )
+    .await
+}
+
+/// Probes a GPU pool with the stratum flavour of its algorithm.
+pub async fn probe_gpu_pool(pool: &BasePoolData<GpuPool>) -> PoolProbeResult {
+    let login = pool_login(pool).await;
+    probe_pool(
+        &pool.pool_url,
+        StratumProtocol::for_gpu_algorithm(&pool.pool_type.algorithm()),
+        &login,
+        POOL_PROBE_TIMEOUT,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use tokio::net::TcpListener;
+
+    const TEST_TIMEOUT: Duration = Duration::from_millis(500);
+
+    /// Fake stratum server answering the first request of a single connection with `response`.
+    /// The placeholder `%ID%` is replaced with the JSON id of the request.
+    async fn fake_stratum_server(response: Option<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            reader.read_line(&mut line).await.unwrap();
+            let request: Value = serde_json::from_str(&line).unwrap();
+            assert_eq!(request["method"], "login");
+
+            match response {
+                Some(response) => {
+                    let response = response.replace("%ID%", &request["id"].to_string());
+                    reader
+                        .get_mut()
+                        .write_all(format!("{response}\n").as_bytes())
+                        .await
+                        .unwrap();
+                }
+                // Keep the connection open without answering
+                None => tokio::time::sleep(Duration::from_secs(5)).await,
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn test_parse_pool_url() {
+        assert_eq!(
+            parse_pool_url("pool.example.com:3333"),
+            Some(("pool.example.com".to_string(), 3333, false))
+        );
+        assert_eq!(
+            parse_pool_url("stratum+ssl://pool.example.com:443"),
+            Some(("pool.example.com".to_string(), 443, true))
+        );
+        assert_eq!(
+            parse_pool_url("stratum+tcp://[::1]:3333"),
+            Some(("::1".to_string(), 3333, false))
+        );
+        assert_eq!(parse_pool_url("pool.example.com"), None);
+        assert_eq!(parse_pool_url("pool.example.com:port"), None);
+    }
+
+    #[tokio::test]
+    async fn test_monero_login_accepted() {
+        let address = fake_stratum_server(Some(
+            r#"{"id":%ID%,"jsonrpc":"2.0","error":null,"result":{"id":"1","job":{"blob":"00","job_id":"1","target":"ffff"},"status":"OK"}}"#,
+        ))
+        .await;
+
+        let result = probe_pool(&address, StratumProtocol::Monero, "address", TEST_TIMEOUT).await;
+        assert_eq!(
+            result.outcome,
+            PoolProbeOutcome::Reachable {
+                login_accepted: true,
+                message: None
+            }
+        );
+        assert!(result.connect_latency_ms.is_some());
+        assert!(result.login_latency_ms.is_some());
+        assert!(result.tls_latency_ms.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_grin_login_rejected_is_reachable() {
+        let address = fake_stratum_server(Some(
+            r#"{"id":%ID%,"jsonrpc":"2.0","method":"login","error":{"code":-32500,"message":"invalid address"}}"#,
+        ))
+        .await;
+
+        let result = probe_pool(&address, StratumProtocol::Grin, "address", TEST_TIMEOUT).await;
+        assert_eq!(
+            result.outcome,
+            PoolProbeOutcome::Reachable {
+                login_accepted: false,
+                message: Some("invalid address".to_string())
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_protocol_error() {
+        let address = fake_stratum_server(Some("HTTP/1.1 400 Bad Request")).await;
+
+        let result = probe_pool(&address, StratumProtocol::Monero, "address", TEST_TIMEOUT).await;
+        assert!(matches!(
+            result.outcome,
+            PoolProbeOutcome::Failed {
+                failure: PoolProbeFailure::Protocol,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_login_timeout() {
+        let address = fake_stratum_server(None).await;
+
+        let result = probe_pool(&address, StratumProtocol::Monero, "address", TEST_TIMEOUT).await;
+        assert!(matches!(
+            result.outcome,
+            PoolProbeOutcome::Failed {
+                failure: PoolProbeFailure::Timeout,
+                ..
+            }
+        ));
+        assert!(result.connect_latency_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_connection_refused() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let result = probe_pool(&address, StratumProtocol::Monero, "address", TEST_TIMEOUT).await;
+        assert!(matches!(
+            result.outcome,
+            PoolProbeOutcome::Failed {
+                failure: PoolProbeFailure::Refused,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dns_failure() {
+        let result = probe_pool(
+            "pool.does-not-exist.invalid:3333",
+            StratumProtocol::Monero,
+            "address",
+            Duration::from_secs(5),
+        )
+        .await;
+        assert!(matches!(
+            result.outcome,
+            PoolProbeOutcome::Failed {
+                failure: PoolProbeFailure::Dns,
+                ..
+            }
+        ));
+        assert!(result.resolved_address.is_none());
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! Switching the pool a miner mines on.
+//!
+//! Shared by the pool commands, the control API and scheduled actions, so all of them probe the
+//! new pool, stop the affected miner, apply the selection and restart the miner the same way.
+
+use anyhow::anyhow;
+
+use crate::{
+    configs::{
+        config_pools::{ConfigPools, ConfigPoolsContent},
+        pools::{cpu_pools::CpuPool, gpu_pools::GpuPool},
+        trait_config::ConfigImpl,
+    },
+    events_emitter::EventsEmitter,
+    mining::{
+        cpu::manager::CpuManager,
+        gpu::manager::GpuManager,
+        pools::{
+            cpu_pool_manager::CpuPoolManager,
+            gpu_pool_manager::GpuPoolManager,
+            pool_probe::{probe_cpu_pool, probe_gpu_pool},
+            PoolManagerInterfaceTrait,
+        },
+    },
+};
+
+/// Selects the pool used for CPU pool mining.
+///
+/// The CPU miner is stopped while the selection changes and started again when it was running.
+///
+/// ### Parameters
+/// * `cpu_pool` - Pool to mine on
+/// * `force` - Skips the connectivity check of the pool
+///
+/// ### Returns
+/// * `Ok(())` - Pool selected
+/// * `Err(anyhow::Error)` - If the pool is unknown or unreachable, or updating the config or restarting the miner fails
+pub async fn select_cpu_pool(cpu_pool: CpuPool, force: bool) -> Result<(), anyhow::Error> {
+    if !force {
+        // Keep mining on the current pool when the new one is unreachable
+        let pool_data = ConfigPools::content()
+            .await
+            .cpu_pools()
+            .get(&cpu_pool)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown CPU pool: {cpu_pool}"))?;
+        probe_cpu_pool(&pool_data).await.ensure_reachable()?;
+    }
+
+    let was_mining = CpuManager::read().await.is_running();
+    if was_mining {
+        CpuManager::write().await.stop_mining().await?;
+    }
+
+    ConfigPools::update_field(ConfigPoolsContent::set_current_cpu_pool, cpu_pool).await?;
+    CpuPoolManager::handle_new_selected_pool(ConfigPools::content().await.current_cpu_pool()).await;
+    EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
+
+    if was_mining {
+        CpuManager::write().await.start_mining().await?;
+    }
+    Ok(())
+}
+
+/// Selects the pool used for GPU pool mining.
+///
+/// The GPU miner is stopped while the selection changes and started again when it was running.
+///
+/// ### Parameters
+/// * `gpu_pool` - Pool to mine on
+/// * `force` - Skips the connectivity check of the pool
+///
+/// ### Returns
+/// * `Ok(())` - Pool selected
+/// * `Err(anyhow::Error)` - If the pool is unknown or unreachable, or updating the config or restarting the miner fails
+pub async fn select_gpu_pool(gpu_pool: GpuPool, force: bool) -> Result<(), anyhow::Error> {
+    if !force {
+        // Keep mining on the current pool when the new one is unreachable
+        let pool_data = ConfigPools::content()
+            .await
+            .gpu_pools()
+            .get(&gpu_pool)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown GPU pool: {gpu_pool}"))?;
+        probe_gpu_pool(&pool_data).await.ensure_reachable()?;
+    }
+
+    let was_mining = GpuManager::read().await.is_running();
+    if was_mining {
+        GpuManager::write().await.stop_mining().await?;
+    }
+
+    ConfigPools::update_field(ConfigPoolsContent::set_current_gpu_pool, gpu_pool).await?;
+    // The selected pool replaces the fallback pool a failover may have moved the miner to
+    GpuManager::write().await.clear_failover_pool();
+    GpuPoolManager::handle_new_selected_pool(ConfigPools::content().await.current_gpu_pool()).await;
+    EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
+
+    if was_mining {
+        GpuManager::write().await.start_mining().await?;
+    }
+    Ok(())
+}
@@ -22,6 +22,7 @@
 
 use std::{collections::HashMap, sync::Arc};
 
+use chrono::Utc;
 use log::{debug, info, warn};
 use tari_common_types::tari_address::TariAddress;
 use tokio::{
@@ -32,6 +33,7 @@ use tokio::{
 use crate::{
     mining::pools::{
         adapters::{PoolApiAdapter, PoolApiAdapters},
+        earnings_history::PoolEarningsHistory,
         PoolStatus,
     },
     tasks_tracker::TaskTrackerUtil,
@@ -85,6 +87,7 @@ pub enum PoolManagerThreadCommands {
 /// - 300-second intervals when mining is inactive  
 /// - 1-hour grace period after mining stops before task shuts down
 /// - Automatic updates when pool or mining address configuration changes
+/// - Every fetched status is recorded in the `PoolEarningsHistory`
 /// - Integration with TasksTrackers for proper shutdown handling
 pub struct PoolManager {
     pool_adapter: PoolApiAdapters,
@@ -118,11 +121,22 @@ impl PoolManager {
         }
     }
 
+    pub async fn get_pool_statuses(&self) -> HashMap<String, PoolStatus> {
+        self.pool_stats.read().await.clone()
+    }
+
     pub async fn update_current_pool_status(&self) {
         if let Some(address) = &self.cached_mining_address {
             let pool_status = self.pool_adapter.request_pool_status(address.clone()).await;
             match pool_status {
                 Ok(status) => {
+                    PoolEarningsHistory::record_status(
+                        self.pool_adapter.name(),
+                        address,
+                        &status,
+                        Utc::now(),
+                    )
+                    .await;
                     {
                         let mut statuses = self.pool_stats.write().await;
                         statuses.insert(self.pool_adapter.name().to_string(), status.clone());
@@ -371,6 +385,13 @@ impl PoolManager {
             .await;
         match pool_status {
             Ok(status) => {
+                PoolEarningsHistory::record_status(
+                    task_state.pool_adapter.name(),
+                    &task_state.cached_mining_address,
+                    &status,
+                    Utc::now(),
+                )
+                .await;
                 {
                     let mut statuses = task_state.pool_statuses.write().await;
                     statuses.insert(task_state.pool_adapter.name().to_string(), status.clone());
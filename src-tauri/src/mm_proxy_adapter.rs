@@ -23,9 +23,12 @@
 use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::binaries::Binaries;
 use crate::process_adapter::{
     HealthStatus, ProcessAdapter, ProcessInstance, ProcessStartupSpec, StatusMonitor,
 };
+use crate::process_isolation::ProcessIsolation;
+use crate::process_restart_policy::StderrTail;
 use crate::utils::file_utils::convert_to_string;
 use crate::utils::logging_utils::setup_logging;
 use crate::LOG_TARGET_STATUSES;
@@ -156,6 +159,7 @@ impl ProcessAdapter for MergeMiningProxyAdapter {
             ProcessInstance {
                 shutdown: inner_shutdown,
                 handle: None,
+                stderr_tail: StderrTail::default(),
                 startup_spec: ProcessStartupSpec {
                     file_path: binary_verison_path,
                     envs: None,
@@ -163,6 +167,8 @@ impl ProcessAdapter for MergeMiningProxyAdapter {
                     data_dir,
                     pid_file_name: self.pid_file_name().to_string(),
                     name: self.name().to_string(),
+                    binary: Binaries::MergeMiningProxy,
+                    isolation: ProcessIsolation::default(),
                 },
             },
             MergeMiningProxyStatusMonitor {
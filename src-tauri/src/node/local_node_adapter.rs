@@ -21,12 +21,15 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::ab_test_selector::ABTestSelector;
+use crate::binaries::Binaries;
 use crate::node::node_adapter::{
     BaseNodeStatus, NodeAdapter, NodeAdapterService, NodeStatusMonitor,
 };
 use crate::node::node_manager::NodeType;
 use crate::port_allocator::PortAllocator;
 use crate::process_adapter::{ProcessAdapter, ProcessInstance, ProcessStartupSpec};
+use crate::process_isolation::ProcessIsolation;
+use crate::process_restart_policy::{RestartPolicy, StderrTail};
 use crate::utils::file_utils::convert_to_string;
 use crate::utils::logging_utils::setup_logging;
 use crate::LOG_TARGET_APP_LOGIC;
@@ -370,6 +373,7 @@ impl ProcessAdapter for LocalNodeAdapter {
             ProcessInstance {
                 shutdown: inner_shutdown,
                 handle: None,
+                stderr_tail: StderrTail::default(),
                 startup_spec: ProcessStartupSpec {
                     file_path: binary_version_path,
                     envs: None,
@@ -377,6 +381,8 @@ impl ProcessAdapter for LocalNodeAdapter {
                     data_dir: data_dir.clone(),
                     pid_file_name: self.pid_file_name().to_string(),
                     name: self.name().to_string(),
+                    binary: Binaries::MinotariNode,
+                    isolation: ProcessIsolation::base_node(),
                 },
             },
             NodeStatusMonitor::new(
@@ -399,4 +405,8 @@ impl ProcessAdapter for LocalNodeAdapter {
     fn pid_file_name(&self) -> &str {
         "node_pid"
     }
+
+    fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy::unlimited()
+    }
 }
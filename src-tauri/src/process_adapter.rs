@@ -29,7 +29,6 @@ use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
-use sysinfo::System;
 use tari_shutdown::Shutdown;
 use tauri_plugin_sentry::sentry;
 use tokio::runtime::Handle;
@@ -37,11 +36,21 @@ use tokio::select;
 use tokio::task::JoinHandle;
 use tokio_util::task::TaskTracker;
 
+use crate::binaries::Binaries;
 use crate::download_utils::set_permissions;
 use crate::events::CriticalProblemPayload;
 use crate::events_emitter::EventsEmitter;
+use crate::process_identity::{
+    app_start_time, envs_with_launch_nonce, identify_unrecorded, is_session_nonce,
+    new_launch_nonce, ObservedProcess, ProcessIdentity, ProcessLaunchRecord,
+    UnrelatedProcessWarning,
+};
+use crate::process_isolation::ProcessIsolation;
 use crate::process_killer::kill_process;
+use crate::process_output::{OutputStream, ProcessOutputBuffers};
+use crate::process_restart_policy::{RestartPolicy, StderrTail};
 use crate::process_utils::{launch_child_process, write_pid_file};
+use crate::tasks_tracker::TasksTrackers;
 use crate::LOG_TARGET_APP_LOGIC;
 
 const SPACE_ERROR_MESSAGE: &str = "No space left on device";
@@ -80,6 +89,11 @@ pub(crate) trait ProcessAdapter {
 
     fn pid_file_name(&self) -> &str;
 
+    /// How the process watcher restarts this process after it crashed or became unhealthy
+    fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy::default()
+    }
+
     #[allow(dead_code)]
     fn pid_file_exisits(&self, base_folder: PathBuf) -> bool {
         std::path::Path::new(&base_folder)
@@ -87,63 +101,83 @@ pub(crate) trait ProcessAdapter {
             .exists()
     }
 
-    fn find_process_pid_by_name(binary_name: &OsStr) -> Option<u32> {
-        let mut sys = System::new_all();
-        sys.refresh_all();
-
-        for (pid, process) in sys.processes() {
-            if process.name() == binary_name {
-                return Some(pid.as_u32());
-            }
-        }
-        None
+    async fn warn_about_unrelated_process(&self, pid: u32, reason: String) {
+        warn!(target: LOG_TARGET_APP_LOGIC, "Not killing {} process with PID {}: {}", self.name(), pid, reason);
+        let warning = UnrelatedProcessWarning {
+            process_name: self.name().to_string(),
+            pid,
+            reason,
+        };
+        // Emitting waits for the frontend, which must not delay starting the process
+        TasksTrackers::current()
+            .common
+            .get_task_tracker()
+            .await
+            .spawn(EventsEmitter::emit_unrelated_process_warning(warning));
     }
 
+    /// Kills processes with this adapter's name that were launched during the current session
     async fn ensure_no_hanging_processes_are_running(&self) -> Result<(), Error> {
-        let binary_name = OsStr::new(self.name());
-        if let Some(process) = Self::find_process_pid_by_name(binary_name) {
-            let parsed_id =
-                i32::try_from(process).expect("Failed to parse process ID from u32 to i32");
-            warn!(target: LOG_TARGET_APP_LOGIC, "{} process is already running with PID {}. Attempting to kill it.", self.name(), parsed_id);
-            kill_process(parsed_id).await?;
+        for (pid, process) in ObservedProcess::find_by_name(OsStr::new(self.name())) {
+            let launched_by_session = match process.nonce.as_deref() {
+                Some(nonce) => is_session_nonce(nonce).await,
+                None => false,
+            };
+            if launched_by_session {
+                warn!(target: LOG_TARGET_APP_LOGIC, "{} process is already running with PID {}. Attempting to kill it.", self.name(), pid);
+                kill_process(i32::try_from(pid)?).await?;
+            } else {
+                self.warn_about_unrelated_process(
+                    pid,
+                    "it was not launched by this session".to_string(),
+                )
+                .await;
+            }
         }
         Ok(())
     }
 
+    /// Kills the process left behind by a previous run, if its launch record proves it is ours.
+    /// PID files without a launch record fall back to matching `binary_path` and the start time
     async fn kill_previous_instances(
         &self,
         base_folder: PathBuf,
         binary_path: &Path,
     ) -> Result<(), Error> {
         info!(target: LOG_TARGET_APP_LOGIC, "Killing previous instances of {}", self.name());
-        let binary_name = binary_path
-            .file_name()
-            .expect("binary path must have a file name");
-        match fs::read_to_string(base_folder.join(self.pid_file_name())) {
-            Ok(pid) => match pid.trim().parse::<i32>() {
-                Ok(pid) => {
-                    warn!(target: LOG_TARGET_APP_LOGIC, "{} process did not shut down cleanly: {} pid file was created", pid, self.pid_file_name());
-                    kill_process(pid).await?;
-                }
-                Err(_) => {
-                    warn!(target: LOG_TARGET_APP_LOGIC, "pid file is not a valid integer: {pid}. Attempting to kill process by name");
-                    let pid_by_name = Self::find_process_pid_by_name(binary_name);
-                    if let Some(process) = pid_by_name {
-                        let parsed_id = i32::try_from(process)
-                            .expect("Failed to parse process ID from u32 to i32");
-                        kill_process(parsed_id).await?;
-                    } else {
-                        warn!(target: LOG_TARGET_APP_LOGIC, "No process found with name {}", binary_name.to_str().unwrap_or_default());
-                    }
-                }
-            },
-            Err(e) => {
-                if let Ok(true) = std::path::Path::new(&base_folder)
-                    .join(self.pid_file_name())
-                    .try_exists()
-                {
-                    warn!(target: LOG_TARGET_APP_LOGIC, "{} pid file exists, but the error occurred while killing: {}", self.name(), e);
-                }
+        let pid_file = base_folder.join(self.pid_file_name());
+        if !matches!(pid_file.try_exists(), Ok(true)) {
+            return Ok(());
+        }
+        let (pid, identity) = match ProcessLaunchRecord::read(&pid_file) {
+            Some(record) => (
+                record.pid,
+                record.identify(ObservedProcess::find(record.pid).as_ref()),
+            ),
+            None => {
+                let contents = fs::read_to_string(&pid_file).unwrap_or_default();
+                let Ok(pid) = contents.trim().parse::<u32>() else {
+                    warn!(target: LOG_TARGET_APP_LOGIC, "{} pid file is not a valid PID: {contents:?}", self.pid_file_name());
+                    return Ok(());
+                };
+                info!(target: LOG_TARGET_APP_LOGIC, "{} pid file has no launch record, identifying PID {pid} by its binary", self.pid_file_name());
+                let observed = ObservedProcess::find(pid);
+                (
+                    pid,
+                    identify_unrecorded(binary_path, observed.as_ref(), app_start_time()),
+                )
+            }
+        };
+        match identity {
+            ProcessIdentity::Launched => {
+                warn!(target: LOG_TARGET_APP_LOGIC, "{} process did not shut down cleanly: {} pid file was created", pid, self.pid_file_name());
+                kill_process(i32::try_from(pid)?).await?;
+            }
+            ProcessIdentity::NotRunning => {
+                info!(target: LOG_TARGET_APP_LOGIC, "Previous {} process with PID {} is no longer running", self.name(), pid);
+            }
+            ProcessIdentity::Unrelated(reason) => {
+                self.warn_about_unrelated_process(pid, reason).await;
             }
         }
         Ok(())
@@ -173,6 +207,11 @@ pub(crate) trait StatusMonitor: Clone + Sync + Send + 'static {
     ) -> Result<HandleUnhealthyResult, anyhow::Error> {
         Ok(HandleUnhealthyResult::Continue)
     }
+    /// Called when the restart policy gives up with `GiveUpAction::Fallback`.
+    /// Returns the name of the process that took over, if any
+    async fn handle_fallback(&self) -> Result<Option<String>, anyhow::Error> {
+        Ok(None)
+    }
 }
 
 // TODO: Rename to ProcessInstance
@@ -187,6 +226,10 @@ pub(crate) trait ProcessInstanceTrait: Sync + Send + 'static {
         &mut self,
         task_tracker: TaskTracker,
     ) -> Result<(i32, Vec<String>, Vec<String>), anyhow::Error>;
+    /// Last stderr lines of the current or last run, used for crash reports
+    async fn last_stderr_lines(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 #[derive(Clone)]
@@ -197,12 +240,15 @@ pub(crate) struct ProcessStartupSpec {
     pub pid_file_name: String,
     pub data_dir: PathBuf,
     pub name: String,
+    pub binary: Binaries,
+    pub isolation: ProcessIsolation,
 }
 
 pub(crate) struct ProcessInstance {
     pub shutdown: Shutdown,
     pub handle: Option<JoinHandle<Result<i32, anyhow::Error>>>,
     pub startup_spec: ProcessStartupSpec,
+    pub stderr_tail: StderrTail,
 }
 
 #[async_trait]
@@ -230,6 +276,10 @@ impl ProcessInstanceTrait for ProcessInstance {
             return Ok(());
         };
 
+        let stderr_tail = self.stderr_tail.clone();
+        stderr_tail.clear().await;
+        let nonce = new_launch_nonce().await;
+        let output_task_tracker = task_tracker.clone();
         self.handle = Some(task_tracker.spawn(async move {
             if let Err(e) = set_permissions(&spec.file_path).await {
                 error!(target: LOG_TARGET_APP_LOGIC, "{e}");
@@ -238,16 +288,37 @@ impl ProcessInstanceTrait for ProcessInstance {
             }
             // start
             info!(target: LOG_TARGET_APP_LOGIC, "Launching process for: {}", spec.name);
+            // Output is read continuously, otherwise the process blocks once a pipe is full
+            let envs = envs_with_launch_nonce(spec.envs.as_ref(), &nonce);
             let mut child = launch_child_process(
                 &spec.file_path,
                 spec.data_dir.as_path(),
-                spec.envs.as_ref(),
+                Some(&envs),
                 &spec.args,
-                false
+                true,
+                &spec.isolation,
             )?;
+            if let Some(stdout) = child.stdout.take() {
+                output_task_tracker.spawn(ProcessOutputBuffers::capture(
+                    spec.binary,
+                    spec.name.clone(),
+                    OutputStream::Stdout,
+                    stdout,
+                    None,
+                ));
+            }
+            if let Some(stderr) = child.stderr.take() {
+                output_task_tracker.spawn(ProcessOutputBuffers::capture(
+                    spec.binary,
+                    spec.name.clone(),
+                    OutputStream::Stderr,
+                    stderr,
+                    Some(stderr_tail),
+                ));
+            }
 
             if let Some(id) = child.id() {
-                let pid_file_res = write_pid_file(&spec, id);
+                let pid_file_res = write_pid_file(&spec, id, &nonce);
                 if let Err(e) = pid_file_res {
                     let error_msg = format!("Failed to write PID file: {e}");
                     error!(target: LOG_TARGET_APP_LOGIC, "{error_msg}");
@@ -285,9 +356,11 @@ impl ProcessInstanceTrait for ProcessInstance {
             }
             info!(target: LOG_TARGET_APP_LOGIC, "Stopping {} process with exit code: {}", spec.name, exit_code);
 
-            if let Err(error) = fs::remove_file(spec.data_dir.join(spec.pid_file_name)) {
+            let pid_file = spec.data_dir.join(spec.pid_file_name);
+            if let Err(error) = fs::remove_file(&pid_file) {
                 warn!(target: LOG_TARGET_APP_LOGIC, "Could not clear {}'s pid file: {:?}", spec.name, error);
             }
+            ProcessLaunchRecord::remove(&pid_file);
 
             Ok(exit_code)
         }));
@@ -312,19 +385,19 @@ impl ProcessInstanceTrait for ProcessInstance {
             return Ok((0, vec![], vec![]));
         };
 
+        let nonce = new_launch_nonce().await;
+        let envs = envs_with_launch_nonce(spec.envs.as_ref(), &nonce);
         let child = launch_child_process(
             &spec.file_path,
             spec.data_dir.as_path(),
-            spec.envs.as_ref(),
+            Some(&envs),
             &spec.args,
             true,
+            &spec.isolation,
         )?;
 
         if let Some(id) = child.id() {
-            fs::write(
-                spec.data_dir.join(spec.pid_file_name.clone()),
-                id.to_string(),
-            )?;
+            write_pid_file(&spec, id, &nonce).map_err(|e| anyhow!(e))?;
         }
         let result = child.wait_with_output().await?;
         let exit_code = result.status.code().unwrap_or(0);
@@ -339,9 +412,11 @@ impl ProcessInstanceTrait for ProcessInstance {
 
         info!(target: LOG_TARGET_APP_LOGIC, "Stopping {} process with exit code: {}", spec.name, exit_code);
 
-        if let Err(error) = fs::remove_file(spec.data_dir.join(spec.pid_file_name)) {
+        let pid_file = spec.data_dir.join(spec.pid_file_name);
+        if let Err(error) = fs::remove_file(&pid_file) {
             warn!(target: LOG_TARGET_APP_LOGIC, "Could not clear {}'s pid file: {:?}", spec.name, error);
         }
+        ProcessLaunchRecord::remove(&pid_file);
 
         Ok((exit_code, stdout_lines, stderr_lines))
     }
@@ -366,6 +441,10 @@ impl ProcessInstanceTrait for ProcessInstance {
             None => Err(anyhow!("No process handle available")),
         }
     }
+
+    async fn last_stderr_lines(&self) -> Vec<String> {
+        self.stderr_tail.lines().await
+    }
 }
 
 impl Drop for ProcessInstance {
@@ -0,0 +1,398 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! Identification of processes left behind by a previous run of Universe.
+//!
+//! A PID alone is not enough to decide whether a process may be killed: once the original process
+//! is gone the OS can hand the same PID to any other program, possibly one with the same name.
+//! Next to every PID file a launch record is written holding the executable path, the start time
+//! of the process and a random nonce which is also passed to the process in its environment. A
+//! process is only treated as launched by Universe when all of them still match.
+//!
+//! PID files written by versions without launch records are still honoured: the process is
+//! killed when it runs the expected binary and was started before the current run of Universe.
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, Process, System};
+use tokio::sync::RwLock;
+
+use crate::LOG_TARGET_APP_LOGIC;
+
+pub const LAUNCH_NONCE_ENV: &str = "TARI_UNIVERSE_LAUNCH_NONCE";
+/// Start times are derived from the boot time, so two reads of the same process can differ slightly
+const START_TIME_TOLERANCE_SECS: u64 = 2;
+
+/// Start time of the current run of Universe, seconds since the unix epoch
+static APP_START_TIME: LazyLock<u64> = LazyLock::new(|| {
+    ObservedProcess::find(std::process::id())
+        .map(|process| process.start_time)
+        .unwrap_or_default()
+});
+
+/// Nonces of all processes launched during the current session
+static SESSION_NONCES: LazyLock<RwLock<HashSet<String>>> =
+    LazyLock::new(|| RwLock::new(HashSet::new()));
+
+pub async fn new_launch_nonce() -> String {
+    let nonce = uuid::Uuid::new_v4().to_string();
+    SESSION_NONCES.write().await.insert(nonce.clone());
+    nonce
+}
+
+pub async fn is_session_nonce(nonce: &str) -> bool {
+    SESSION_NONCES.read().await.contains(nonce)
+}
+
+pub fn envs_with_launch_nonce(
+    envs: Option<&HashMap<String, String>>,
+    nonce: &str,
+) -> HashMap<String, String> {
+    let mut envs = envs.cloned().unwrap_or_default();
+    envs.insert(LAUNCH_NONCE_ENV.to_string(), nonce.to_string());
+    envs
+}
+
+fn canonical_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// What the OS reports about a running process
+#[derive(Debug, Clone, Default)]
+pub struct ObservedProcess {
+    pub executable: Option<PathBuf>,
+    /// Seconds since the unix epoch
+    pub start_time: u64,
+    pub nonce: Option<String>,
+}
+
+impl ObservedProcess {
+    fn from_process(process: &Process) -> Self {
+        let nonce = process.environ().iter().find_map(|var| {
+            let var: &OsStr = var.as_ref();
+            var.to_str()?
+                .strip_prefix(LAUNCH_NONCE_ENV)?
+                .strip_prefix('=')
+                .map(str::to_string)
+        });
+        Self {
+            executable: process.exe().map(Path::to_path_buf),
+            start_time: process.start_time(),
+            nonce,
+        }
+    }
+
+    pub fn find(pid: u32) -> Option<Self> {
+        let system = System::new_all();
+        system.process(Pid::from_u32(pid)).map(Self::from_process)
+    }
+
+    pub fn find_by_name(name: &OsStr) -> Vec<(u32, Self)> {
+        let system = System::new_all();
+        system
+            .processes()
+            .iter()
+            .filter(|(_, process)| process.name() == name)
+            .map(|(pid, process)| (pid.as_u32(), Self::from_process(process)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessIdentity {
+    /// The process is still the one described by the launch record
+    Launched,
+    NotRunning,
+    /// A process with the recorded PID is running, but it was not launched by Universe
+    Unrelated(String),
+}
+
+/// Sent to the frontend when a process was left running because it could not be identified
+#[derive(Debug, Clone, Serialize)]
+pub struct UnrelatedProcessWarning {
+    pub process_name: String,
+    pub pid: u32,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessLaunchRecord {
+    pub pid: u32,
+    pub executable: PathBuf,
+    /// Seconds since the unix epoch, `0` when the OS did not report it.
+    /// An unknown start time is not compared, the nonce alone identifies the process then
+    pub start_time: u64,
+    pub nonce: String,
+}
+
+impl ProcessLaunchRecord {
+    pub fn capture(pid: u32, executable: &Path, nonce: &str) -> Self {
+        let start_time = ObservedProcess::find(pid)
+            .map(|process| process.start_time)
+            .unwrap_or_default();
+        Self {
+            pid,
+            executable: canonical_path(executable),
+            start_time,
+            nonce: nonce.to_string(),
+        }
+    }
+
+    fn path_for(pid_file: &Path) -> PathBuf {
+        let mut file_name = pid_file
+            .file_name()
+            .map(OsStr::to_os_string)
+            .unwrap_or_default();
+        file_name.push("_launch.json");
+        pid_file.with_file_name(file_name)
+    }
+
+    pub fn write(&self, pid_file: &Path) -> Result<(), String> {
+        let contents = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize launch record: {e}"))?;
+        fs::write(Self::path_for(pid_file), contents)
+            .map_err(|e| format!("Failed to write launch record: {e}"))
+    }
+
+    pub fn read(pid_file: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(Self::path_for(pid_file)).ok()?;
+        match serde_json::from_str(&contents) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                warn!(target: LOG_TARGET_APP_LOGIC, "Invalid launch record next to {}: {e}", pid_file.display());
+                None
+            }
+        }
+    }
+
+    pub fn remove(pid_file: &Path) {
+        let path = Self::path_for(pid_file);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!(target: LOG_TARGET_APP_LOGIC, "Could not clear launch record {}: {e}", path.display());
+            }
+        }
+    }
+
+    pub fn identify(&self, observed: Option<&ObservedProcess>) -> ProcessIdentity {
+        let Some(observed) = observed else {
+            return ProcessIdentity::NotRunning;
+        };
+        let Some(executable) = observed.executable.as_deref() else {
+            return ProcessIdentity::Unrelated("its executable path is unknown".to_string());
+        };
+        let executable = canonical_path(executable);
+        if executable != self.executable {
+            return ProcessIdentity::Unrelated(format!(
+                "it runs {} instead of {}",
+                executable.display(),
+                self.executable.display()
+            ));
+        }
+        let start_times_known = self.start_time != 0 && observed.start_time != 0;
+        if start_times_known
+            && observed.start_time.abs_diff(self.start_time) > START_TIME_TOLERANCE_SECS
+        {
+            return ProcessIdentity::Unrelated(format!(
+                "it was started at {} instead of {}",
+                observed.start_time, self.start_time
+            ));
+        }
+        if observed.nonce.as_deref() != Some(self.nonce.as_str()) {
+            return ProcessIdentity::Unrelated("its launch nonce does not match".to_string());
+        }
+        ProcessIdentity::Launched
+    }
+}
+
+/// Identifies the process of a PID file that has no launch record, as written before launch
+/// records were introduced. Without a nonce the process has to run `executable` and be older
+/// than the current run of Universe, `app_start_time` being `0` when it is unknown.
+pub fn identify_unrecorded(
+    executable: &Path,
+    observed: Option<&ObservedProcess>,
+    app_start_time: u64,
+) -> ProcessIdentity {
+    let Some(observed) = observed else {
+        return ProcessIdentity::NotRunning;
+    };
+    let Some(observed_executable) = observed.executable.as_deref() else {
+        return ProcessIdentity::Unrelated("its executable path is unknown".to_string());
+    };
+    let observed_executable = canonical_path(observed_executable);
+    let executable = canonical_path(executable);
+    if observed_executable != executable {
+        return ProcessIdentity::Unrelated(format!(
+            "it runs {} instead of {}",
+            observed_executable.display(),
+            executable.display()
+        ));
+    }
+    if observed.start_time == 0 || app_start_time == 0 {
+        return ProcessIdentity::Unrelated("its start time is unknown".to_string());
+    }
+    if observed.start_time >= app_start_time {
+        return ProcessIdentity::Unrelated("it was started after this run of Universe".to_string());
+    }
+    ProcessIdentity::Launched
+}
+
+pub fn app_start_time() -> u64 {
+    *APP_START_TIME
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn record() -> ProcessLaunchRecord {
+        ProcessLaunchRecord {
+            pid: 4242,
+            executable: PathBuf::from("/opt/universe/bin/xmrig"),
+            start_time: 1_700_000_000,
+            nonce: "nonce".to_string(),
+        }
+    }
+
+    fn observed() -> ObservedProcess {
+        ObservedProcess {
+            executable: Some(PathBuf::from("/opt/universe/bin/xmrig")),
+            start_time: 1_700_000_001,
+            nonce: Some("nonce".to_string()),
+        }
+    }
+
+    #[test]
+    fn identifies_launched_process() {
+        assert_eq!(
+            record().identify(Some(&observed())),
+            ProcessIdentity::Launched
+        );
+        assert_eq!(record().identify(None), ProcessIdentity::NotRunning);
+    }
+
+    #[test]
+    fn rejects_any_mismatch() {
+        let reused_pid = ObservedProcess {
+            start_time: 1_700_009_000,
+            ..observed()
+        };
+        let other_binary = ObservedProcess {
+            executable: Some(PathBuf::from("/usr/bin/xmrig")),
+            ..observed()
+        };
+        let foreign_launch = ObservedProcess {
+            nonce: None,
+            ..observed()
+        };
+        let unknown_binary = ObservedProcess {
+            executable: None,
+            ..observed()
+        };
+        for process in [reused_pid, other_binary, foreign_launch, unknown_binary] {
+            assert!(matches!(
+                record().identify(Some(&process)),
+                ProcessIdentity::Unrelated(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn relies_on_nonce_when_start_time_is_unknown() {
+        let record = ProcessLaunchRecord {
+            start_time: 0,
+            ..record()
+        };
+        assert_eq!(
+            record.identify(Some(&observed())),
+            ProcessIdentity::Launched
+        );
+        let foreign_launch = ObservedProcess {
+            nonce: Some("other".to_string()),
+            ..observed()
+        };
+        assert!(matches!(
+            record.identify(Some(&foreign_launch)),
+            ProcessIdentity::Unrelated(_)
+        ));
+    }
+
+    #[test]
+    fn identifies_unrecorded_process_by_binary_and_age() {
+        let executable = Path::new("/opt/universe/bin/xmrig");
+        let app_start_time = 1_700_000_100;
+        let legacy = ObservedProcess {
+            nonce: None,
+            ..observed()
+        };
+        assert_eq!(
+            identify_unrecorded(executable, Some(&legacy), app_start_time),
+            ProcessIdentity::Launched
+        );
+        assert_eq!(
+            identify_unrecorded(executable, None, app_start_time),
+            ProcessIdentity::NotRunning
+        );
+
+        let started_later = ObservedProcess {
+            start_time: 1_700_000_200,
+            ..legacy.clone()
+        };
+        let other_binary = ObservedProcess {
+            executable: Some(PathBuf::from("/usr/bin/xmrig")),
+            ..legacy.clone()
+        };
+        let unknown_start = ObservedProcess {
+            start_time: 0,
+            ..legacy.clone()
+        };
+        for process in [started_later, other_binary, unknown_start] {
+            assert!(matches!(
+                identify_unrecorded(executable, Some(&process), app_start_time),
+                ProcessIdentity::Unrelated(_)
+            ));
+        }
+        assert!(matches!(
+            identify_unrecorded(executable, Some(&legacy), 0),
+            ProcessIdentity::Unrelated(_)
+        ));
+    }
+
+    #[test]
+    fn record_round_trips_next_to_pid_file() {
+        let dir = std::env::temp_dir().join(format!("launch_record_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let pid_file = dir.join("xmrig_pid");
+        assert!(ProcessLaunchRecord::read(&pid_file).is_none());
+        record().write(&pid_file).unwrap();
+        assert_eq!(ProcessLaunchRecord::read(&pid_file), Some(record()));
+        ProcessLaunchRecord::remove(&pid_file);
+        assert!(ProcessLaunchRecord::read(&pid_file).is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
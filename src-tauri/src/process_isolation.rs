@@ -0,0 +1,503 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Scheduling and resource limits of child processes.
+//!
+//! On Linux the niceness, `SCHED_IDLE` and the I/O priority are set in the forked child right
+//! before it executes the binary, so every thread the process creates inherits them. CPU quota
+//! and memory limits need a delegated cgroup v2 subtree and are skipped when none is available.
+//! The limited processes are grouped in a cgroup next to the app's own one. Only when that is
+//! not possible the app moves itself into a leaf of its own cgroup, which is reversed by
+//! [`release_cgroups`] on exit.
+//! Everything is best effort, a missing privilege never prevents a process from starting.
+//! Other platforms ignore the isolation settings.
+
+use serde::{Deserialize, Serialize};
+
+use crate::configs::config_mining::{MiningMode, MiningModeType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IoPriority {
+    /// Only gets disk time when no other process needs it
+    Idle,
+    /// Default class, 0 is the highest and 7 the lowest priority
+    BestEffort(u8),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessIsolation {
+    /// Niceness from -20 to 19, going below the current niceness needs privileges
+    pub nice: Option<i32>,
+    /// Only gives the process CPU time no other process wants
+    pub sched_idle: bool,
+    pub io_priority: Option<IoPriority>,
+    /// CPU time as a percentage of a single core
+    pub cpu_quota_percent: Option<u32>,
+    pub memory_limit_mb: Option<u64>,
+}
+
+impl ProcessIsolation {
+    /// Isolation of the CPU miner, the mode can override the defaults of its type
+    pub fn for_mining_mode(mode: &MiningMode) -> Self {
+        if let Some(isolation) = &mode.process_isolation {
+            return isolation.clone();
+        }
+        match mode.mode_type {
+            MiningModeType::Eco => Self {
+                sched_idle: true,
+                ..Self::default()
+            },
+            MiningModeType::Turbo => Self {
+                nice: Some(15),
+                ..Self::default()
+            },
+            MiningModeType::Ludicrous | MiningModeType::Custom | MiningModeType::User => Self {
+                nice: Some(10),
+                ..Self::default()
+            },
+        }
+    }
+
+    /// GPU miners mostly wait for the GPU, a lower priority keeps the desktop responsive
+    /// without starving the threads feeding the GPU
+    pub fn gpu_miner() -> Self {
+        Self {
+            nice: Some(10),
+            ..Self::default()
+        }
+    }
+
+    /// Keeps the LMDB I/O of the base node from competing with the user's work
+    pub fn base_node() -> Self {
+        Self {
+            io_priority: Some(IoPriority::BestEffort(7)),
+            ..Self::default()
+        }
+    }
+
+    /// Checks that the values are accepted by the kernel.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if self.nice.is_some_and(|nice| !(-20..=19).contains(&nice)) {
+            return Err(anyhow::anyhow!("Nice must be between -20 and 19"));
+        }
+        if matches!(self.io_priority, Some(IoPriority::BestEffort(level)) if level > 7) {
+            return Err(anyhow::anyhow!(
+                "I/O priority level must be between 0 and 7"
+            ));
+        }
+        if self.cpu_quota_percent == Some(0) {
+            return Err(anyhow::anyhow!("CPU quota must be at least 1%"));
+        }
+        if self.memory_limit_mb == Some(0) {
+            return Err(anyhow::anyhow!("Memory limit must be at least 1 MB"));
+        }
+        Ok(())
+    }
+
+    /// Sets up the scheduling policy to be applied when the command spawns the process
+    #[cfg(target_os = "linux")]
+    pub fn prepare(&self, command: &mut tokio::process::Command) {
+        if self.nice.is_none() && !self.sched_idle && self.io_priority.is_none() {
+            return;
+        }
+        let (nice, sched_idle, io_priority) = (self.nice, self.sched_idle, self.io_priority);
+        // SAFETY: the closure only makes async-signal-safe syscalls and does not allocate
+        unsafe {
+            command.pre_exec(move || {
+                linux::apply_scheduling(nice, sched_idle, io_priority);
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn prepare(&self, _command: &mut tokio::process::Command) {}
+
+    /// Moves the spawned process into a cgroup with the CPU quota and memory limit
+    #[cfg(target_os = "linux")]
+    pub fn attach(&self, name: &str, pid: u32) {
+        if self.cpu_quota_percent.is_none() && self.memory_limit_mb.is_none() {
+            return;
+        }
+        linux::limit_in_cgroup(name, pid, self.cpu_quota_percent, self.memory_limit_mb);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn attach(&self, _name: &str, _pid: u32) {}
+}
+
+/// Removes the cgroups created for the child processes and moves the app back into its
+/// original cgroup. Called on exit once the child processes are stopped.
+#[cfg(target_os = "linux")]
+pub fn release_cgroups() {
+    linux::release_cgroups();
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn release_cgroups() {}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        fs,
+        io::ErrorKind,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            OnceLock,
+        },
+    };
+
+    use log::{info, warn};
+
+    use super::IoPriority;
+    use crate::LOG_TARGET_APP_LOGIC;
+
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+    const IOPRIO_CLASS_BE: libc::c_int = 2;
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+
+    const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+    const CPU_PERIOD_US: u64 = 100_000;
+
+    /// Set up on the first limited process, so apps without limits never touch the cgroups
+    static DELEGATED_CGROUP: OnceLock<Option<DelegatedCgroup>> = OnceLock::new();
+    static RELEASED: AtomicBool = AtomicBool::new(false);
+
+    struct DelegatedCgroup {
+        /// Parent of the cgroups of the limited processes
+        path: PathBuf,
+        /// Original cgroup of the app when it had to move itself into a leaf of it
+        moved_from: Option<PathBuf>,
+    }
+
+    fn delegated_cgroup() -> Option<&'static DelegatedCgroup> {
+        DELEGATED_CGROUP
+            .get_or_init(|| match setup_delegated_cgroup() {
+                Ok(cgroup) => cgroup,
+                Err(e) => {
+                    info!(target: LOG_TARGET_APP_LOGIC, "No delegated cgroup v2 subtree available: {e}");
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    impl IoPriority {
+        pub(super) fn to_ioprio(self) -> libc::c_int {
+            match self {
+                IoPriority::Idle => IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+                IoPriority::BestEffort(level) => {
+                    (IOPRIO_CLASS_BE << IOPRIO_CLASS_SHIFT) | libc::c_int::from(level.min(7))
+                }
+            }
+        }
+    }
+
+    /// Runs in the forked child before exec. Errors are ignored, there is no safe way to
+    /// report them from here and the process should start either way
+    pub(super) fn apply_scheduling(
+        nice: Option<i32>,
+        sched_idle: bool,
+        io_priority: Option<IoPriority>,
+    ) {
+        // SAFETY: plain syscalls on the calling process without pointers held past the call
+        unsafe {
+            if let Some(nice) = nice {
+                libc::setpriority(libc::PRIO_PROCESS, 0, nice);
+            }
+            if sched_idle {
+                let param = libc::sched_param { sched_priority: 0 };
+                libc::sched_setscheduler(0, libc::SCHED_IDLE, &param);
+            }
+            if let Some(io_priority) = io_priority {
+                libc::syscall(
+                    libc::SYS_ioprio_set,
+                    IOPRIO_WHO_PROCESS,
+                    0,
+                    io_priority.to_ioprio(),
+                );
+            }
+        }
+    }
+
+    pub(super) fn limit_in_cgroup(
+        name: &str,
+        pid: u32,
+        cpu_quota_percent: Option<u32>,
+        memory_limit_mb: Option<u64>,
+    ) {
+        if RELEASED.load(Ordering::SeqCst) {
+            return;
+        }
+        let Some(delegated) = delegated_cgroup() else {
+            info!(target: LOG_TARGET_APP_LOGIC, "Not limiting {name}, no delegated cgroup v2 subtree available");
+            return;
+        };
+        let cgroup = delegated.path.join(name);
+        let result = fs::create_dir_all(&cgroup)
+            .and_then(|_| {
+                fs::write(
+                    cgroup.join("cpu.max"),
+                    cpu_quota_percent.map_or_else(|| format!("max {CPU_PERIOD_US}"), cpu_max),
+                )
+            })
+            .and_then(|_| {
+                fs::write(
+                    cgroup.join("memory.max"),
+                    memory_limit_mb.map_or_else(
+                        || "max".to_string(),
+                        |limit| (limit * 1024 * 1024).to_string(),
+                    ),
+                )
+            })
+            .and_then(|_| fs::write(cgroup.join("cgroup.procs"), pid.to_string()));
+        match result {
+            Ok(()) => {
+                info!(target: LOG_TARGET_APP_LOGIC, "Limited {name} in cgroup {cgroup:?}");
+            }
+            Err(e) => {
+                warn!(target: LOG_TARGET_APP_LOGIC, "Failed to limit {name} in cgroup {cgroup:?}: {e}");
+            }
+        }
+    }
+
+    /// Processes are only allowed in the leaves once controllers are enabled for a subtree.
+    /// The limited processes get a cgroup next to the app's own one when the parent already
+    /// provides the controllers, so the app stays where it was started. Otherwise the app moves
+    /// itself into a leaf of its own cgroup before enabling them there.
+    fn setup_delegated_cgroup() -> Result<Option<DelegatedCgroup>, std::io::Error> {
+        let content = fs::read_to_string("/proc/self/cgroup")?;
+        let Some(path) = parse_cgroup_v2_path(&content) else {
+            return Ok(None);
+        };
+        let own = Path::new(CGROUP_ROOT).join(path.trim_start_matches('/'));
+
+        if let Some(sibling) = setup_sibling_cgroup(&own) {
+            info!(target: LOG_TARGET_APP_LOGIC, "Using cgroup {sibling:?} for process limits");
+            return Ok(Some(DelegatedCgroup {
+                path: sibling,
+                moved_from: None,
+            }));
+        }
+
+        let controllers = fs::read_to_string(own.join("cgroup.controllers"))?;
+        if !has_controllers(&controllers) {
+            return Ok(None);
+        }
+        let app = own.join("app");
+        fs::create_dir_all(&app)?;
+        fs::write(app.join("cgroup.procs"), std::process::id().to_string())?;
+        fs::write(own.join("cgroup.subtree_control"), "+cpu +memory")?;
+        info!(target: LOG_TARGET_APP_LOGIC, "Moved the app into {app:?} to use delegated cgroup {own:?} for process limits");
+        Ok(Some(DelegatedCgroup {
+            path: own.clone(),
+            moved_from: Some(own),
+        }))
+    }
+
+    /// Moving a child out of the app's cgroup needs write access to the common parent
+    fn setup_sibling_cgroup(own: &Path) -> Option<PathBuf> {
+        let parent = own
+            .parent()
+            .filter(|parent| parent.starts_with(CGROUP_ROOT))?;
+        let subtree_control = fs::read_to_string(parent.join("cgroup.subtree_control")).ok()?;
+        if !has_controllers(&subtree_control) {
+            return None;
+        }
+        fs::OpenOptions::new()
+            .write(true)
+            .open(parent.join("cgroup.procs"))
+            .ok()?;
+
+        let sibling = parent.join(format!("tari-universe-{}", std::process::id()));
+        let result = fs::create_dir_all(&sibling)
+            .and_then(|_| fs::write(sibling.join("cgroup.subtree_control"), "+cpu +memory"));
+        if let Err(e) = result {
+            info!(target: LOG_TARGET_APP_LOGIC, "Could not create cgroup {sibling:?}: {e}");
+            let _unused = fs::remove_dir(&sibling);
+            return None;
+        }
+        Some(sibling)
+    }
+
+    pub(super) fn release_cgroups() {
+        if RELEASED.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let Some(Some(delegated)) = DELEGATED_CGROUP.get() else {
+            return;
+        };
+
+        if let Ok(entries) = fs::read_dir(&delegated.path) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let is_app_leaf = delegated.moved_from.is_some() && path.ends_with("app");
+                if path.is_dir() && !is_app_leaf {
+                    remove_cgroup(&path);
+                }
+            }
+        }
+
+        match &delegated.moved_from {
+            Some(own) => {
+                let result = fs::write(own.join("cgroup.subtree_control"), "-cpu -memory")
+                    .and_then(|_| {
+                        fs::write(own.join("cgroup.procs"), std::process::id().to_string())
+                    });
+                match result {
+                    Ok(()) => {
+                        info!(target: LOG_TARGET_APP_LOGIC, "Moved the app back into cgroup {own:?}");
+                        remove_cgroup(&own.join("app"));
+                    }
+                    Err(e) => {
+                        warn!(target: LOG_TARGET_APP_LOGIC, "Failed to move the app back into cgroup {own:?}: {e}");
+                    }
+                }
+            }
+            None => remove_cgroup(&delegated.path),
+        }
+    }
+
+    /// Only empty cgroups can be removed, a process still running keeps its cgroup
+    fn remove_cgroup(path: &Path) {
+        match fs::remove_dir(path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => {
+                warn!(target: LOG_TARGET_APP_LOGIC, "Failed to remove cgroup {path:?}: {e}");
+            }
+        }
+    }
+
+    pub(super) fn parse_cgroup_v2_path(content: &str) -> Option<&str> {
+        content.lines().find_map(|line| line.strip_prefix("0::"))
+    }
+
+    pub(super) fn has_controllers(controllers: &str) -> bool {
+        let controllers: Vec<&str> = controllers.split_whitespace().collect();
+        controllers.contains(&"cpu") && controllers.contains(&"memory")
+    }
+
+    pub(super) fn cpu_max(cpu_quota_percent: u32) -> String {
+        let quota = u64::from(cpu_quota_percent.max(1)) * CPU_PERIOD_US / 100;
+        format!("{quota} {CPU_PERIOD_US}")
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::path::Path;
+
+    use super::linux::{cpu_max, has_controllers, parse_cgroup_v2_path};
+    use super::*;
+    use crate::process_utils::launch_child_process;
+
+    /// Fields of `/proc/<pid>/stat` after the command name, starting with the state (field 3)
+    fn read_stat(pid: u32) -> Vec<String> {
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).unwrap();
+        let (_, fields) = stat.rsplit_once(')').unwrap();
+        fields.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn spawn_sleep(isolation: &ProcessIsolation) -> tokio::process::Child {
+        launch_child_process(
+            Path::new("sleep"),
+            &std::env::temp_dir(),
+            None,
+            &["5".to_string()],
+            false,
+            isolation,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn applies_nice_and_sched_idle() {
+        let mut child = spawn_sleep(&ProcessIsolation {
+            nice: Some(19),
+            sched_idle: true,
+            ..ProcessIsolation::default()
+        });
+        let stat = read_stat(child.id().unwrap());
+        child.kill().await.unwrap();
+
+        // Fields 19 (nice) and 41 (policy)
+        assert_eq!(stat.get(16).unwrap(), "19");
+        assert_eq!(stat.get(38).unwrap(), &libc::SCHED_IDLE.to_string());
+    }
+
+    #[tokio::test]
+    async fn applies_io_priority() {
+        let mut child = spawn_sleep(&ProcessIsolation::base_node());
+        let pid = libc::c_int::try_from(child.id().unwrap()).unwrap();
+        // SAFETY: plain syscall without pointers
+        let ioprio = unsafe { libc::syscall(libc::SYS_ioprio_get, 1, pid) };
+        child.kill().await.unwrap();
+
+        assert_eq!(
+            ioprio,
+            libc::c_long::from(IoPriority::BestEffort(7).to_ioprio())
+        );
+    }
+
+    #[tokio::test]
+    async fn default_isolation_keeps_default_scheduling() {
+        let mut child = spawn_sleep(&ProcessIsolation::default());
+        let stat = read_stat(child.id().unwrap());
+        child.kill().await.unwrap();
+
+        assert_eq!(stat.get(38).unwrap(), &libc::SCHED_OTHER.to_string());
+    }
+
+    #[test]
+    fn validates_values() {
+        assert!(ProcessIsolation::base_node().validate().is_ok());
+        assert!(ProcessIsolation {
+            nice: Some(20),
+            ..ProcessIsolation::default()
+        }
+        .validate()
+        .is_err());
+        assert!(ProcessIsolation {
+            io_priority: Some(IoPriority::BestEffort(8)),
+            ..ProcessIsolation::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn parses_cgroup_files() {
+        assert_eq!(
+            parse_cgroup_v2_path("0::/user.slice/user-1000.slice/app.scope\n"),
+            Some("/user.slice/user-1000.slice/app.scope")
+        );
+        assert_eq!(parse_cgroup_v2_path("4:memory:/app\n1:cpu:/\n"), None);
+        assert!(has_controllers("cpuset cpu io memory pids"));
+        assert!(!has_controllers("cpuset io pids"));
+        assert_eq!(cpu_max(150), "150000 100000");
+    }
+}
@@ -0,0 +1,365 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! In-memory ring buffers of the console output of managed processes.
+//!
+//! `ProcessInstance` pipes stdout and stderr of every child and feeds their lines in small batches
+//! into the buffer of its binary, tagged with the process name and a log level parsed from the line. The buffers
+//! can be tailed, filtered and searched from the frontend, new lines are streamed to it in
+//! batches, and the last lines of every process are added to the feedback archive.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    sync::LazyLock,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    select,
+    sync::RwLock,
+    time::interval,
+};
+
+use crate::{
+    binaries::Binaries, events_emitter::EventsEmitter, process_restart_policy::StderrTail,
+    tasks_tracker::TasksTrackers,
+};
+
+/// Lines kept per binary
+const BUFFER_CAPACITY: usize = 1000;
+/// Lines waiting to be streamed, older ones are dropped if the frontend does not keep up
+const MAX_PENDING_LINES: usize = 500;
+const STREAM_INTERVAL: Duration = Duration::from_secs(1);
+/// Lines a reader collects before adding them to the buffers, so the lock is not taken per line
+const CAPTURE_BATCH_LINES: usize = 100;
+/// Longest time a captured line waits before it is added to the buffers
+const CAPTURE_FLUSH_INTERVAL: Duration = Duration::from_millis(200);
+/// Characters at the start of a line searched for a log level
+const LEVEL_SEARCH_PREFIX: usize = 64;
+
+static INSTANCE: LazyLock<RwLock<ProcessOutputBuffers>> =
+    LazyLock::new(|| RwLock::new(ProcessOutputBuffers::default()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ProcessLogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ProcessLogLevel {
+    /// Looks for a level word near the start of the line, e.g. `INFO`, `[warn]` or tor's `[notice]`.
+    /// Lines without one are treated as info
+    pub fn parse(line: &str) -> Self {
+        line.split(|c: char| !c.is_ascii_alphabetic())
+            .take_while({
+                let mut length = 0;
+                move |word| {
+                    length += word.len() + 1;
+                    length <= LEVEL_SEARCH_PREFIX
+                }
+            })
+            .find_map(|word| match word.to_ascii_lowercase().as_str() {
+                "error" | "err" | "fatal" | "critical" => Some(Self::Error),
+                "warn" | "warning" => Some(Self::Warn),
+                "info" | "notice" => Some(Self::Info),
+                "debug" => Some(Self::Debug),
+                "trace" => Some(Self::Trace),
+                _ => None,
+            })
+            .unwrap_or(Self::Info)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessOutputLine {
+    pub binary: Binaries,
+    /// Name of the process, binaries like the wallet are run by more than one process
+    pub process: String,
+    pub stream: OutputStream,
+    pub level: ProcessLogLevel,
+    pub line: String,
+    pub time: DateTime<Local>,
+}
+
+impl Display for ProcessOutputLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} [{}] [{:?}] {}",
+            self.time.format("%Y-%m-%d %H:%M:%S%.3f"),
+            self.process,
+            self.stream,
+            self.line
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct ProcessOutputBuffers {
+    buffers: HashMap<Binaries, VecDeque<ProcessOutputLine>>,
+    pending: VecDeque<ProcessOutputLine>,
+    is_streaming: bool,
+}
+
+impl ProcessOutputBuffers {
+    fn push(&mut self, line: ProcessOutputLine) {
+        push_bounded(&mut self.pending, line.clone(), MAX_PENDING_LINES);
+        push_bounded(
+            self.buffers.entry(line.binary).or_default(),
+            line,
+            BUFFER_CAPACITY,
+        );
+    }
+
+    /// Reads the output of a process line by line until it is closed
+    pub async fn capture<R: AsyncRead + Unpin>(
+        binary: Binaries,
+        process: String,
+        stream: OutputStream,
+        output: R,
+        stderr_tail: Option<StderrTail>,
+    ) {
+        Self::start_streaming().await;
+        let mut reader = BufReader::new(output).lines();
+        let mut batch: Vec<ProcessOutputLine> = Vec::new();
+        let mut flush_interval = interval(CAPTURE_FLUSH_INTERVAL);
+        loop {
+            select! {
+                next_line = reader.next_line() => {
+                    let Ok(Some(line)) = next_line else {
+                        break;
+                    };
+                    if let Some(stderr_tail) = &stderr_tail {
+                        stderr_tail.push(line.clone()).await;
+                    }
+                    batch.push(ProcessOutputLine {
+                        binary,
+                        process: process.clone(),
+                        stream,
+                        level: ProcessLogLevel::parse(&line),
+                        line,
+                        time: Local::now(),
+                    });
+                    if batch.len() >= CAPTURE_BATCH_LINES {
+                        Self::push_batch(&mut batch).await;
+                    }
+                }
+                _ = flush_interval.tick() => {
+                    Self::push_batch(&mut batch).await;
+                }
+            }
+        }
+        Self::push_batch(&mut batch).await;
+    }
+
+    /// Adds the lines collected by a reader under a single lock
+    async fn push_batch(batch: &mut Vec<ProcessOutputLine>) {
+        if batch.is_empty() {
+            return;
+        }
+        let mut buffers = INSTANCE.write().await;
+        for line in batch.drain(..) {
+            buffers.push(line);
+        }
+    }
+
+    /// Last `limit` lines of the binary with at least the given level
+    pub async fn tail(
+        binary: Binaries,
+        limit: usize,
+        min_level: Option<ProcessLogLevel>,
+    ) -> Vec<ProcessOutputLine> {
+        let buffers = INSTANCE.read().await;
+        filter_lines(
+            buffers.buffers.get(&binary).into_iter().flatten(),
+            limit,
+            |line| min_level.is_none_or(|level| line.level >= level),
+        )
+    }
+
+    /// Last `limit` lines of the binary containing the query, ignoring case
+    pub async fn search(binary: Binaries, query: &str, limit: usize) -> Vec<ProcessOutputLine> {
+        let query = query.to_lowercase();
+        let buffers = INSTANCE.read().await;
+        filter_lines(
+            buffers.buffers.get(&binary).into_iter().flatten(),
+            limit,
+            |line| line.line.to_lowercase().contains(&query),
+        )
+    }
+
+    /// Last `limit` lines of every binary that produced output
+    pub async fn snapshot(limit: usize) -> Vec<(Binaries, Vec<ProcessOutputLine>)> {
+        let buffers = INSTANCE.read().await;
+        buffers
+            .buffers
+            .iter()
+            .map(|(binary, lines)| (*binary, filter_lines(lines.iter(), limit, |_| true)))
+            .collect()
+    }
+
+    async fn start_streaming() {
+        {
+            let mut buffers = INSTANCE.write().await;
+            if buffers.is_streaming {
+                return;
+            }
+            buffers.is_streaming = true;
+        }
+
+        let mut shutdown_signal = TasksTrackers::current().common.get_signal().await;
+        TasksTrackers::current()
+            .common
+            .get_task_tracker()
+            .await
+            .spawn(async move {
+                let mut interval = interval(STREAM_INTERVAL);
+                loop {
+                    select! {
+                        _ = interval.tick() => {
+                            let lines: Vec<ProcessOutputLine> =
+                                INSTANCE.write().await.pending.drain(..).collect();
+                            if !lines.is_empty() {
+                                EventsEmitter::emit_process_output(lines).await;
+                            }
+                        }
+                        _ = shutdown_signal.wait() => {
+                            INSTANCE.write().await.is_streaming = false;
+                            break;
+                        }
+                    }
+                }
+            });
+    }
+}
+
+fn push_bounded<T>(lines: &mut VecDeque<T>, line: T, capacity: usize) {
+    if lines.len() >= capacity {
+        lines.pop_front();
+    }
+    lines.push_back(line);
+}
+
+/// Last `limit` matching lines, oldest first
+fn filter_lines<'a>(
+    lines: impl DoubleEndedIterator<Item = &'a ProcessOutputLine>,
+    limit: usize,
+    predicate: impl Fn(&ProcessOutputLine) -> bool,
+) -> Vec<ProcessOutputLine> {
+    let mut matching: Vec<ProcessOutputLine> = lines
+        .rev()
+        .filter(|line| predicate(line))
+        .take(limit)
+        .cloned()
+        .collect();
+    matching.reverse();
+    matching
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn line(text: &str) -> ProcessOutputLine {
+        ProcessOutputLine {
+            binary: Binaries::Xmrig,
+            process: "xmrig".to_string(),
+            stream: OutputStream::Stdout,
+            level: ProcessLogLevel::parse(text),
+            line: text.to_string(),
+            time: Local::now(),
+        }
+    }
+
+    #[test]
+    fn parses_log_levels() {
+        assert_eq!(
+            ProcessLogLevel::parse("2025-01-01 12:00:00.000 ERROR Failed to connect"),
+            ProcessLogLevel::Error
+        );
+        assert_eq!(
+            ProcessLogLevel::parse("Jan 01 12:00:00.000 [warn] Socks version 71 not recognized"),
+            ProcessLogLevel::Warn
+        );
+        assert_eq!(
+            ProcessLogLevel::parse("Jan 01 12:00:00.000 [notice] Bootstrapped 100% (done)"),
+            ProcessLogLevel::Info
+        );
+        assert_eq!(
+            ProcessLogLevel::parse("[2025-01-01 12:00:00.000]  cpu  use profile rx"),
+            ProcessLogLevel::Info
+        );
+        assert_eq!(
+            ProcessLogLevel::parse(
+                "[2025-01-01 12:00:00.000]  net  new job from pool.example.com diff 100 the error"
+            ),
+            ProcessLogLevel::Info
+        );
+    }
+
+    #[test]
+    fn ring_buffer_drops_oldest_lines() {
+        let mut lines = VecDeque::new();
+        for i in 0..5 {
+            push_bounded(&mut lines, i, 3);
+        }
+        assert_eq!(lines, VecDeque::from([2, 3, 4]));
+    }
+
+    #[test]
+    fn filters_last_matching_lines_in_order() {
+        let lines: Vec<ProcessOutputLine> = [
+            "INFO started",
+            "WARN slow share",
+            "ERROR lost connection",
+            "INFO reconnected",
+            "WARN slow share again",
+        ]
+        .iter()
+        .map(|text| line(text))
+        .collect();
+
+        let warnings = filter_lines(lines.iter(), 2, |line| line.level >= ProcessLogLevel::Warn);
+        assert_eq!(
+            warnings.iter().map(|l| l.line.as_str()).collect::<Vec<_>>(),
+            vec!["ERROR lost connection", "WARN slow share again"]
+        );
+
+        let all = filter_lines(lines.iter(), 10, |_| true);
+        assert_eq!(all.len(), 5);
+        assert_eq!(all.first().unwrap().line, "INFO started");
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Restart policy shared by all processes run by a `ProcessWatcher`.
+//!
+//! Every crash of a watched process is recorded in a `CrashTracker`. As long as the process
+//! crashed fewer times than allowed within the policy window it is restarted with an exponential
+//! backoff, otherwise the watcher gives up and either stops or hands over to a fallback. Each
+//! crash is reported to the frontend together with the exit code and the last stderr lines, so
+//! the user can see why a process keeps dying.
+
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+/// Number of stderr lines kept for a crash report
+const STDERR_TAIL_LINES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GiveUpAction {
+    /// Stop the process watcher
+    Stop,
+    /// Stop the process watcher and let the status monitor switch to a fallback adapter
+    Fallback,
+}
+
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    /// Restarts allowed within `window` before giving up
+    pub max_restarts: u32,
+    pub window: Duration,
+    /// Delay before the first restart, doubled on every following restart within the window
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub give_up_action: GiveUpAction,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            window: Duration::from_secs(10 * 60),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            give_up_action: GiveUpAction::Stop,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Never gives up, for core services the app cannot run without
+    pub fn unlimited() -> Self {
+        Self {
+            max_restarts: u32::MAX,
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before the given restart, counted from 1 within the window
+    pub fn backoff(&self, restart: u32) -> Duration {
+        let multiplier = 2u32.saturating_pow(restart.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(multiplier)
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartDecision {
+    Restart { backoff: Duration },
+    GiveUp,
+}
+
+/// Crashes of a single process within the window of its restart policy
+#[derive(Debug, Default)]
+pub struct CrashTracker {
+    crashes: VecDeque<Instant>,
+}
+
+impl CrashTracker {
+    pub fn record_crash(&mut self, now: Instant, policy: &RestartPolicy) -> RestartDecision {
+        while self
+            .crashes
+            .front()
+            .is_some_and(|crash| now.duration_since(*crash) > policy.window)
+        {
+            self.crashes.pop_front();
+        }
+        self.crashes.push_back(now);
+
+        let crashes = self.crashes_in_window();
+        if crashes > policy.max_restarts {
+            RestartDecision::GiveUp
+        } else {
+            RestartDecision::Restart {
+                backoff: policy.backoff(crashes),
+            }
+        }
+    }
+
+    pub fn crashes_in_window(&self) -> u32 {
+        u32::try_from(self.crashes.len()).unwrap_or(u32::MAX)
+    }
+}
+
+/// Last stderr lines of a running process
+#[derive(Debug, Clone, Default)]
+pub struct StderrTail {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl StderrTail {
+    pub async fn clear(&self) {
+        self.lines.lock().await.clear();
+    }
+
+    pub async fn lines(&self) -> Vec<String> {
+        self.lines.lock().await.iter().cloned().collect()
+    }
+
+    pub async fn push(&self, line: String) {
+        let mut lines = self.lines.lock().await;
+        if lines.len() >= STDERR_TAIL_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum CrashAction {
+    Restart {
+        backoff_secs: u64,
+    },
+    Stop,
+    /// `adapter` is the name of the process that took over, if any
+    Fallback {
+        adapter: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessCrashReport {
+    pub process_name: String,
+    /// `None` when the process was still running and got stopped because of failed health checks
+    pub exit_code: Option<i32>,
+    pub stderr_tail: Vec<String>,
+    pub crashes_in_window: u32,
+    pub action: CrashAction,
+    pub time: DateTime<Local>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RestartPolicy {
+        RestartPolicy {
+            max_restarts: 3,
+            window: Duration::from_secs(60),
+            initial_backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(5),
+            give_up_action: GiveUpAction::Stop,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let policy = policy();
+        assert_eq!(policy.backoff(1), Duration::from_secs(2));
+        assert_eq!(policy.backoff(2), Duration::from_secs(4));
+        assert_eq!(policy.backoff(3), Duration::from_secs(5));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn gives_up_after_max_restarts_within_window() {
+        let policy = policy();
+        let mut tracker = CrashTracker::default();
+        let start = Instant::now();
+
+        for i in 0..3 {
+            assert!(matches!(
+                tracker.record_crash(start + Duration::from_secs(i), &policy),
+                RestartDecision::Restart { .. }
+            ));
+        }
+        assert_eq!(
+            tracker.record_crash(start + Duration::from_secs(3), &policy),
+            RestartDecision::GiveUp
+        );
+    }
+
+    #[test]
+    fn unlimited_policy_never_gives_up() {
+        let policy = RestartPolicy::unlimited();
+        let mut tracker = CrashTracker::default();
+        let start = Instant::now();
+
+        for i in 0..100 {
+            assert_eq!(
+                tracker.record_crash(start + Duration::from_secs(i), &policy),
+                RestartDecision::Restart {
+                    backoff: policy.backoff(tracker.crashes_in_window())
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn crashes_outside_window_are_forgotten() {
+        let policy = policy();
+        let mut tracker = CrashTracker::default();
+        let start = Instant::now();
+
+        for i in 0..3 {
+            tracker.record_crash(start + Duration::from_secs(i * 10), &policy);
+        }
+        let decision = tracker.record_crash(start + Duration::from_secs(90), &policy);
+
+        assert_eq!(tracker.crashes_in_window(), 1);
+        assert_eq!(
+            decision,
+            RestartDecision::Restart {
+                backoff: Duration::from_secs(2)
+            }
+        );
+    }
+}
@@ -20,15 +20,19 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::collections::HashMap;
+
 use tokio::sync::watch::{Receiver, Sender};
 
-use crate::process_watcher::ProcessWatcherStats;
+use crate::{mining::gpu::consts::GpuMinerType, process_watcher::ProcessWatcherStats};
 
 pub(crate) struct ProcessStatsCollectorBuilder {
     cpu_miner_tx: Option<Sender<ProcessWatcherStats>>,
     cpu_miner_rx: Receiver<ProcessWatcherStats>,
     gpu_miner_tx: Option<Sender<ProcessWatcherStats>>,
     gpu_miner_rx: Receiver<ProcessWatcherStats>,
+    additional_gpu_miners_tx: Option<Sender<HashMap<GpuMinerType, ProcessWatcherStats>>>,
+    additional_gpu_miners_rx: Receiver<HashMap<GpuMinerType, ProcessWatcherStats>>,
     mm_proxy_tx: Option<Sender<ProcessWatcherStats>>,
     mm_proxy_rx: Receiver<ProcessWatcherStats>,
     node_tx: Option<Sender<ProcessWatcherStats>>,
@@ -45,6 +49,8 @@ impl ProcessStatsCollectorBuilder {
             tokio::sync::watch::channel(ProcessWatcherStats::default());
         let (gpu_miner_tx, gpu_miner_rx) =
             tokio::sync::watch::channel(ProcessWatcherStats::default());
+        let (additional_gpu_miners_tx, additional_gpu_miners_rx) =
+            tokio::sync::watch::channel(HashMap::new());
         let (mm_proxy_tx, mm_proxy_rx) =
             tokio::sync::watch::channel(ProcessWatcherStats::default());
         let (node_tx, node_rx) = tokio::sync::watch::channel(ProcessWatcherStats::default());
@@ -56,6 +62,8 @@ impl ProcessStatsCollectorBuilder {
             cpu_miner_rx,
             gpu_miner_tx: Some(gpu_miner_tx),
             gpu_miner_rx,
+            additional_gpu_miners_tx: Some(additional_gpu_miners_tx),
+            additional_gpu_miners_rx,
             mm_proxy_tx: Some(mm_proxy_tx),
             mm_proxy_rx,
             node_tx: Some(node_tx),
@@ -79,6 +87,14 @@ impl ProcessStatsCollectorBuilder {
             .expect("Cannot take gpu_miner more than once")
     }
 
+    pub fn take_additional_gpu_miners(
+        &mut self,
+    ) -> Sender<HashMap<GpuMinerType, ProcessWatcherStats>> {
+        self.additional_gpu_miners_tx
+            .take()
+            .expect("Cannot take additional_gpu_miners more than once")
+    }
+
     pub fn take_mm_proxy(&mut self) -> Sender<ProcessWatcherStats> {
         self.mm_proxy_tx
             .take()
@@ -105,6 +121,7 @@ impl ProcessStatsCollectorBuilder {
         ProcessStatsCollector {
             cpu_miner_rx: self.cpu_miner_rx,
             gpu_miner_rx: self.gpu_miner_rx,
+            additional_gpu_miners_rx: self.additional_gpu_miners_rx,
             mm_proxy_rx: self.mm_proxy_rx,
             node_rx: self.node_rx,
             tor_rx: self.tor_rx,
@@ -117,6 +134,7 @@ impl ProcessStatsCollectorBuilder {
 pub(crate) struct ProcessStatsCollector {
     cpu_miner_rx: Receiver<ProcessWatcherStats>,
     gpu_miner_rx: Receiver<ProcessWatcherStats>,
+    additional_gpu_miners_rx: Receiver<HashMap<GpuMinerType, ProcessWatcherStats>>,
     mm_proxy_rx: Receiver<ProcessWatcherStats>,
     node_rx: Receiver<ProcessWatcherStats>,
     tor_rx: Receiver<ProcessWatcherStats>,
@@ -132,6 +150,11 @@ impl ProcessStatsCollector {
         self.gpu_miner_rx.borrow().clone()
     }
 
+    /// Stats of the gpu miners running next to the selected one
+    pub fn get_additional_gpu_miners_stats(&self) -> HashMap<GpuMinerType, ProcessWatcherStats> {
+        self.additional_gpu_miners_rx.borrow().clone()
+    }
+
     pub fn get_mm_proxy_stats(&self) -> ProcessWatcherStats {
         self.mm_proxy_rx.borrow().clone()
     }
@@ -23,6 +23,8 @@
 use std::{fs, future::Future, io::Write, path::Path, pin::Pin, time::Duration};
 
 use crate::process_adapter::ProcessStartupSpec;
+use crate::process_identity::ProcessLaunchRecord;
+use crate::process_isolation::ProcessIsolation;
 
 pub fn launch_child_process(
     file_path: &Path,
@@ -30,6 +32,7 @@ pub fn launch_child_process(
     envs: Option<&std::collections::HashMap<String, String>>,
     args: &[String],
     allow_output: bool,
+    isolation: &ProcessIsolation,
 ) -> Result<tokio::process::Child, anyhow::Error> {
     let stdout = if allow_output {
         std::process::Stdio::piped()
@@ -41,31 +44,26 @@ pub fn launch_child_process(
     } else {
         std::process::Stdio::null()
     };
-    #[cfg(not(target_os = "windows"))]
-    {
-        Ok(tokio::process::Command::new(file_path)
-            .args(args)
-            .current_dir(current_dir)
-            .envs(envs.cloned().unwrap_or_default())
-            .stdout(stdout)
-            .stderr(stderr)
-            .kill_on_drop(true)
-            .spawn()?)
-    }
+    let mut command = tokio::process::Command::new(file_path);
+    command
+        .args(args)
+        .current_dir(current_dir)
+        .envs(envs.cloned().unwrap_or_default())
+        .stdout(stdout)
+        .stderr(stderr)
+        .kill_on_drop(true);
     #[cfg(target_os = "windows")]
     {
         use crate::consts::PROCESS_CREATION_NO_WINDOW;
+        command.creation_flags(PROCESS_CREATION_NO_WINDOW);
+    }
+    isolation.prepare(&mut command);
 
-        Ok(tokio::process::Command::new(file_path)
-            .args(args)
-            .current_dir(current_dir)
-            .envs(envs.cloned().unwrap_or_default())
-            .stdout(stdout)
-            .stderr(stderr)
-            .kill_on_drop(true)
-            .creation_flags(PROCESS_CREATION_NO_WINDOW)
-            .spawn()?)
+    let child = command.spawn()?;
+    if let (Some(pid), Some(name)) = (child.id(), file_path.file_stem()) {
+        isolation.attach(&name.to_string_lossy(), pid);
     }
+    Ok(child)
 }
 
 // pub async fn launch_and_get_outputs(
@@ -134,12 +132,14 @@ where
     ))
 }
 
-pub fn write_pid_file(spec: &ProcessStartupSpec, id: u32) -> Result<(), String> {
-    let mut file = fs::File::create(spec.data_dir.join(spec.pid_file_name.clone()))
-        .map_err(|e| format!("Failed to create PID file: {e}"))?;
+/// Writes the PID file together with the launch record used to recognise the process later on
+pub fn write_pid_file(spec: &ProcessStartupSpec, id: u32, nonce: &str) -> Result<(), String> {
+    let pid_file = spec.data_dir.join(spec.pid_file_name.clone());
+    let mut file =
+        fs::File::create(&pid_file).map_err(|e| format!("Failed to create PID file: {e}"))?;
     file.write_all(id.to_string().as_bytes())
         .map_err(|e| format!("Failed to write PID file: {e}"))?;
     file.flush()
         .map_err(|e| format!("Failed to flush PID file: {e}"))?;
-    Ok(())
+    ProcessLaunchRecord::capture(id, &spec.file_path, nonce).write(&pid_file)
 }
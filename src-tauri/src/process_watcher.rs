@@ -21,8 +21,12 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::binaries::{Binaries, BinaryResolver};
+use crate::events_emitter::EventsEmitter;
 use crate::process_adapter::{HandleUnhealthyResult, ProcessInstanceTrait};
 use crate::process_adapter::{HealthStatus, ProcessAdapter, StatusMonitor};
+use crate::process_restart_policy::{
+    CrashAction, CrashTracker, GiveUpAction, ProcessCrashReport, RestartDecision, RestartPolicy,
+};
 use crate::{LOG_TARGET_APP_LOGIC, LOG_TARGET_STATUSES};
 use futures_util::future::FusedFuture;
 use log::{error, info, warn};
@@ -141,6 +145,7 @@ impl<TAdapter: ProcessAdapter> ProcessWatcher<TAdapter> {
         let task_tracker = task_tracker.clone();
         let stop_on_exit_codes = self.stop_on_exit_codes.clone();
         let stats_broadcast = self.stats_broadcast.clone();
+        let restart_policy = self.adapter.restart_policy();
         self.watcher_task = Some(task_tracker.clone().spawn(async move {
             child.start(task_tracker.clone()).await?;
             let mut uptime = Instant::now();
@@ -159,6 +164,7 @@ impl<TAdapter: ProcessAdapter> ProcessWatcher<TAdapter> {
             watch_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
             let mut warning_count = 0;
             let mut duration_since_last_healthy_status = Duration::from_secs(0);
+            let mut crash_tracker = CrashTracker::default();
             // read events such as stdout
             loop {
                 let unhealthy_timer = Instant::now();
@@ -187,6 +193,8 @@ impl<TAdapter: ProcessAdapter> ProcessWatcher<TAdapter> {
                             inner_shutdown.clone(),
                             &mut warning_count,
                             &stop_on_exit_codes,
+                            &restart_policy,
+                            &mut crash_tracker,
                             &mut stats
                         ).await? {
                             return Ok(exit_code);
@@ -251,6 +259,8 @@ async fn do_health_check<TStatusMonitor: StatusMonitor, TProcessInstance: Proces
     inner_shutdown: ShutdownSignal,
     warning_count: &mut u32,
     stop_on_exit_codes: &[i32],
+    restart_policy: &RestartPolicy,
+    crash_tracker: &mut CrashTracker,
     stats: &mut ProcessWatcherStats,
 ) -> Result<Option<i32>, anyhow::Error> {
     let mut is_healthy = false;
@@ -310,7 +320,7 @@ async fn do_health_check<TStatusMonitor: StatusMonitor, TProcessInstance: Proces
         if uptime.elapsed() < expected_startup_time && !ping_failed {
             warn!(target: LOG_TARGET_STATUSES, "{name} is not healthy. Waiting for startup time to elapse");
         } else {
-            match child.stop().await {
+            let exit_code = match child.stop().await {
                 Ok(exit_code) => {
                     if exit_code != 0 {
                         if stop_on_exit_codes.contains(&exit_code) {
@@ -322,15 +332,40 @@ async fn do_health_check<TStatusMonitor: StatusMonitor, TProcessInstance: Proces
                     } else {
                         info!(target: LOG_TARGET_STATUSES, "{name} exited successfully");
                     }
+                    Some(exit_code)
                 }
                 Err(e) => {
                     error!(target: LOG_TARGET_STATUSES, "{name} exited with error: {e}");
                     //   return Err(e);
+                    None
                 }
-            }
+            };
+            // The exit code is only meaningful if the process died on its own, otherwise we killed it
+            let exit_code = exit_code.filter(|_| ping_failed);
+            let stderr_tail = child.last_stderr_lines().await;
+
+            let Some(backoff) = apply_restart_policy(
+                &name,
+                exit_code,
+                stderr_tail,
+                &status_monitor3,
+                restart_policy,
+                crash_tracker,
+                &task_tracker,
+            )
+            .await
+            else {
+                return Ok(Some(exit_code.unwrap_or(1)));
+            };
 
             // Restart dead app
-            sleep(Duration::from_secs(1)).await;
+            let mut inner_shutdown2 = inner_shutdown.clone();
+            let mut app_shutdown2 = global_shutdown_signal.clone();
+            select! {
+                _ = sleep(backoff) => {},
+                _ = inner_shutdown2.wait() => return Ok(Some(0)),
+                _ = app_shutdown2.wait() => return Ok(Some(0)),
+            }
             warn!(target: LOG_TARGET_STATUSES, "Restarting {name} after health check failure");
             *uptime = Instant::now();
             stats.num_restarts += 1;
@@ -371,3 +406,51 @@ async fn do_health_check<TStatusMonitor: StatusMonitor, TProcessInstance: Proces
 
     Ok(None)
 }
+
+/// Records the crash and reports it. Returns the backoff before the restart,
+/// or `None` when the policy gave up and the watcher has to stop
+async fn apply_restart_policy<TStatusMonitor: StatusMonitor>(
+    name: &str,
+    exit_code: Option<i32>,
+    stderr_tail: Vec<String>,
+    status_monitor: &TStatusMonitor,
+    restart_policy: &RestartPolicy,
+    crash_tracker: &mut CrashTracker,
+    task_tracker: &TaskTracker,
+) -> Option<Duration> {
+    let action = match crash_tracker.record_crash(std::time::Instant::now(), restart_policy) {
+        RestartDecision::Restart { backoff } => CrashAction::Restart {
+            backoff_secs: backoff.as_secs(),
+        },
+        RestartDecision::GiveUp => {
+            error!(target: LOG_TARGET_STATUSES, "{name} crashed {} times within {:?}, giving up", crash_tracker.crashes_in_window(), restart_policy.window);
+            match restart_policy.give_up_action {
+                GiveUpAction::Stop => CrashAction::Stop,
+                GiveUpAction::Fallback => {
+                    let adapter = status_monitor.handle_fallback().await.unwrap_or_else(|e| {
+                        error!(target: LOG_TARGET_STATUSES, "Error switching to fallback for {name}: {e}");
+                        None
+                    });
+                    CrashAction::Fallback { adapter }
+                }
+            }
+        }
+    };
+    let backoff = match action {
+        CrashAction::Restart { backoff_secs } => Some(Duration::from_secs(backoff_secs)),
+        CrashAction::Stop | CrashAction::Fallback { .. } => None,
+    };
+
+    warn!(target: LOG_TARGET_STATUSES, "{name} crashed with exit code {exit_code:?}, action: {action:?}, last stderr lines:\n{}", stderr_tail.join("\n"));
+    let report = ProcessCrashReport {
+        process_name: name.to_string(),
+        exit_code,
+        stderr_tail,
+        crashes_in_window: crash_tracker.crashes_in_window(),
+        action,
+        time: chrono::Local::now(),
+    };
+    // Emitting waits for the frontend, which must not delay the restart
+    task_tracker.spawn(EventsEmitter::emit_process_crash_report(report));
+    backoff
+}
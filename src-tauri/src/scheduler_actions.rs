@@ -0,0 +1,249 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Operational actions that can be scheduled besides mining.
+//!
+//! Every action sets a single piece of configuration. Before a recurring event applies its
+//! action the current value is captured as the restore action, which is applied again when
+//! the event's window is left.
+
+use std::fmt::Display;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    configs::{
+        config_core::{ConfigCore, ConfigCoreContent},
+        config_mining::{ConfigMining, ConfigMiningContent},
+        config_pools::ConfigPools,
+        pools::{cpu_pools::CpuPool, gpu_pools::GpuPool},
+        trait_config::ConfigImpl,
+    },
+    events_emitter::EventsEmitter,
+    mining::{
+        cpu::manager::CpuManager,
+        gpu::{consts::GpuMinerType, manager::GpuManager},
+        pools::pool_selection::{select_cpu_pool, select_gpu_pool},
+    },
+    setup::setup_manager::{SetupManager, SetupPhase},
+    systemtray_manager::{SystemTrayEvents, SystemTrayManager},
+};
+
+const LOG_TARGET: &str = "tari::universe::scheduler_actions";
+
+/// A configuration change performed by a scheduled event.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SchedulerAction {
+    /// Enables or disables CPU mining
+    SetCpuMiningEnabled { enabled: bool },
+    /// Enables or disables GPU mining
+    SetGpuMiningEnabled { enabled: bool },
+    /// Selects the pool used for CPU pool mining
+    ChangeCpuPool { cpu_pool: CpuPool },
+    /// Selects the pool used for GPU pool mining
+    ChangeGpuPool { gpu_pool: GpuPool },
+    /// Switches the miner used for GPU mining
+    SwitchGpuMiner { gpu_miner_type: GpuMinerType },
+    /// Switches CPU mining between pool and solo mining
+    SetCpuPoolMining { enabled: bool },
+    /// Switches GPU mining between pool and solo mining
+    SetGpuPoolMining { enabled: bool },
+    /// Turns Tor on or off
+    SetUseTor { enabled: bool },
+}
+
+impl SchedulerAction {
+    /// Checks if both actions change the same setting, regardless of the value they set.
+    pub fn is_same_kind(&self, other: &SchedulerAction) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// Builds the action of the same kind that sets the value currently in use.
+    ///
+    /// ### Returns
+    /// * `SchedulerAction` - Action restoring the current value
+    pub async fn capture_current_value(&self) -> SchedulerAction {
+        match self {
+            SchedulerAction::SetCpuMiningEnabled { .. } => SchedulerAction::SetCpuMiningEnabled {
+                enabled: *ConfigMining::content().await.cpu_mining_enabled(),
+            },
+            SchedulerAction::SetGpuMiningEnabled { .. } => SchedulerAction::SetGpuMiningEnabled {
+                enabled: *ConfigMining::content().await.gpu_mining_enabled(),
+            },
+            SchedulerAction::ChangeCpuPool { .. } => SchedulerAction::ChangeCpuPool {
+                cpu_pool: ConfigPools::content().await.current_cpu_pool().pool_type,
+            },
+            SchedulerAction::ChangeGpuPool { .. } => SchedulerAction::ChangeGpuPool {
+                gpu_pool: ConfigPools::content().await.current_gpu_pool().pool_type,
+            },
+            SchedulerAction::SwitchGpuMiner { .. } => SchedulerAction::SwitchGpuMiner {
+                gpu_miner_type: ConfigMining::content().await.gpu_miner_type().clone(),
+            },
+            SchedulerAction::SetCpuPoolMining { .. } => SchedulerAction::SetCpuPoolMining {
+                enabled: *ConfigPools::content().await.cpu_pool_enabled(),
+            },
+            SchedulerAction::SetGpuPoolMining { .. } => SchedulerAction::SetGpuPoolMining {
+                enabled: *ConfigPools::content().await.gpu_pool_enabled(),
+            },
+            SchedulerAction::SetUseTor { .. } => SchedulerAction::SetUseTor {
+                enabled: *ConfigCore::content().await.use_tor(),
+            },
+        }
+    }
+
+    /// Applies the action, mirroring what the matching command and frontend flow do.
+    ///
+    /// Miners affected by the change are stopped and, when they were running, started
+    /// again with the new configuration. Enabling a miner starts it.
+    ///
+    /// ### Returns
+    /// * `Ok(())` - Action applied
+    /// * `Err(anyhow::Error)` - If updating the config or restarting a miner fails
+    pub async fn execute(&self) -> Result<(), anyhow::Error> {
+        info!(target: LOG_TARGET, "Executing scheduled action: {self}");
+        match self {
+            SchedulerAction::SetCpuMiningEnabled { enabled } => {
+                ConfigMining::update_field(ConfigMiningContent::set_cpu_mining_enabled, *enabled)
+                    .await?;
+                SystemTrayManager::send_event(SystemTrayEvents::CpuMiningState(*enabled)).await;
+                EventsEmitter::emit_mining_config_loaded(&ConfigMining::content().await).await;
+
+                if *enabled {
+                    if !CpuManager::read().await.is_running() {
+                        CpuManager::write().await.start_mining().await?;
+                    }
+                } else {
+                    CpuManager::write().await.stop_mining().await?;
+                }
+            }
+            SchedulerAction::SetGpuMiningEnabled { enabled } => {
+                ConfigMining::update_field(ConfigMiningContent::set_gpu_mining_enabled, *enabled)
+                    .await?;
+                SystemTrayManager::send_event(SystemTrayEvents::GpuMiningState(*enabled)).await;
+                EventsEmitter::emit_mining_config_loaded(&ConfigMining::content().await).await;
+
+                if *enabled {
+                    if !GpuManager::read().await.is_running() {
+                        GpuManager::write().await.start_mining().await?;
+                    }
+                } else {
+                    GpuManager::write().await.stop_mining().await?;
+                }
+            }
+            SchedulerAction::ChangeCpuPool { cpu_pool } => {
+                select_cpu_pool(cpu_pool.clone(), false).await?;
+            }
+            SchedulerAction::ChangeGpuPool { gpu_pool } => {
+                select_gpu_pool(gpu_pool.clone(), false).await?;
+            }
+            SchedulerAction::SwitchGpuMiner { gpu_miner_type } => {
+                // Switching stops the current miner, so remember whether it has to be started again
+                let was_mining = GpuManager::read().await.is_running();
+                GpuManager::write()
+                    .await
+                    .switch_miner(gpu_miner_type.clone())
+                    .await?;
+
+                if was_mining {
+                    GpuManager::write().await.start_mining().await?;
+                }
+            }
+            SchedulerAction::SetCpuPoolMining { enabled } => {
+                // Toggling restarts the CPU mining phase, solo mining needs mmproxy to be ready
+                // before the miner starts, so mining is resumed once the phase has finished
+                if CpuManager::read().await.is_running() {
+                    CpuManager::write().await.stop_mining().await?;
+                }
+                if *enabled {
+                    SetupManager::get_instance()
+                        .turn_on_cpu_pool_feature()
+                        .await?;
+                } else {
+                    SetupManager::get_instance()
+                        .turn_off_cpu_pool_feature()
+                        .await?;
+                }
+            }
+            SchedulerAction::SetGpuPoolMining { enabled } => {
+                let was_mining = GpuManager::read().await.is_running();
+                if was_mining {
+                    GpuManager::write().await.stop_mining().await?;
+                }
+
+                if *enabled {
+                    SetupManager::get_instance()
+                        .turn_on_gpu_pool_feature()
+                        .await?;
+                } else {
+                    SetupManager::get_instance()
+                        .turn_off_gpu_pool_feature()
+                        .await?;
+                }
+
+                if was_mining {
+                    GpuManager::write().await.start_mining().await?;
+                }
+            }
+            SchedulerAction::SetUseTor { enabled } => {
+                ConfigCore::update_field_requires_restart(
+                    ConfigCoreContent::set_use_tor,
+                    *enabled,
+                    vec![SetupPhase::Node, SetupPhase::Wallet],
+                )
+                .await?;
+                EventsEmitter::emit_core_config_loaded(&ConfigCore::content().await).await;
+
+                SetupManager::get_instance()
+                    .restart_phases_from_queue()
+                    .await;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for SchedulerAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let on_off = |enabled: &bool| if *enabled { "on" } else { "off" };
+        match self {
+            SchedulerAction::SetCpuMiningEnabled { enabled } => {
+                write!(f, "CPU mining {}", on_off(enabled))
+            }
+            SchedulerAction::SetGpuMiningEnabled { enabled } => {
+                write!(f, "GPU mining {}", on_off(enabled))
+            }
+            SchedulerAction::ChangeCpuPool { cpu_pool } => write!(f, "CPU pool {cpu_pool}"),
+            SchedulerAction::ChangeGpuPool { gpu_pool } => write!(f, "GPU pool {gpu_pool}"),
+            SchedulerAction::SwitchGpuMiner { gpu_miner_type } => {
+                write!(f, "GPU miner {gpu_miner_type}")
+            }
+            SchedulerAction::SetCpuPoolMining { enabled } => {
+                write!(f, "CPU pool mining {}", on_off(enabled))
+            }
+            SchedulerAction::SetGpuPoolMining { enabled } => {
+                write!(f, "GPU pool mining {}", on_off(enabled))
+            }
+            SchedulerAction::SetUseTor { enabled } => write!(f, "Tor {}", on_off(enabled)),
+        }
+    }
+}
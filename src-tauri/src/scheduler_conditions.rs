@@ -0,0 +1,225 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Condition based triggers for the event scheduler.
+//!
+//! Conditions are sampled periodically from the scheduler's message loop and an event is
+//! entered when its condition starts to hold and exited when it stops holding.
+
+extern crate starship_battery as battery;
+
+use std::time::Duration;
+
+use battery::units::ratio::percent;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    event_scheduler::SchedulerError, hardware::hardware_status_monitor::HardwareStatusMonitor,
+    utils::idle_utils::get_system_idle_duration,
+};
+
+const LOG_TARGET: &str = "tari::universe::scheduler_conditions";
+
+const MAX_IDLE_MINUTES: i64 = 24 * 60;
+const MAX_TEMPERATURE_CELSIUS: f32 = 120.0;
+
+/// Hardware the temperature condition reads from.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum TemperatureSource {
+    Cpu,
+    Gpu,
+}
+
+/// A machine state that can drive a scheduled event.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum SchedulerCondition {
+    /// No keyboard or mouse input for the given amount of minutes
+    IdleFor { minutes: i64 },
+    /// Hottest device of the given kind is above the threshold
+    TemperatureAbove {
+        source: TemperatureSource,
+        celsius: f32,
+    },
+    /// Average battery charge is below the given percentage
+    BatteryBelow { percentage: f32 },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConditionVariantPayload {
+    pub condition: SchedulerCondition,
+}
+
+impl ConditionVariantPayload {
+    /// Validates the condition thresholds.
+    ///
+    /// ### Returns
+    /// * `Ok(())` - Condition is valid
+    /// * `Err(SchedulerError::InvalidTimingFormat)` - If a threshold is out of range
+    pub fn validate(&self) -> Result<(), SchedulerError> {
+        match &self.condition {
+            SchedulerCondition::IdleFor { minutes } => {
+                if *minutes <= 0 || *minutes > MAX_IDLE_MINUTES {
+                    return Err(SchedulerError::InvalidTimingFormat(format!(
+                        "Idle minutes must be between 1 and {}",
+                        MAX_IDLE_MINUTES
+                    )));
+                }
+            }
+            SchedulerCondition::TemperatureAbove { celsius, .. } => {
+                if *celsius <= 0.0 || *celsius > MAX_TEMPERATURE_CELSIUS {
+                    return Err(SchedulerError::InvalidTimingFormat(format!(
+                        "Temperature must be between 1 and {} degrees",
+                        MAX_TEMPERATURE_CELSIUS
+                    )));
+                }
+            }
+            SchedulerCondition::BatteryBelow { percentage } => {
+                if *percentage <= 0.0 || *percentage > 100.0 {
+                    return Err(SchedulerError::InvalidTimingFormat(
+                        "Battery percentage must be between 1 and 100".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Values sampled from the machine during a single condition check.
+/// Missing values mean the reading is not available on this machine.
+#[derive(Debug, Clone, Default)]
+pub struct ConditionsSnapshot {
+    pub idle_duration: Option<Duration>,
+    pub cpu_temperature: Option<f32>,
+    pub gpu_temperature: Option<f32>,
+    pub battery_percentage: Option<f32>,
+}
+
+impl SchedulerCondition {
+    /// Checks the condition against sampled values. Unavailable readings never satisfy a condition.
+    pub fn is_met(&self, snapshot: &ConditionsSnapshot) -> bool {
+        match self {
+            SchedulerCondition::IdleFor { minutes } => snapshot
+                .idle_duration
+                .is_some_and(|idle| idle >= Duration::from_secs(*minutes as u64 * 60)),
+            SchedulerCondition::TemperatureAbove { source, celsius } => {
+                let temperature = match source {
+                    TemperatureSource::Cpu => snapshot.cpu_temperature,
+                    TemperatureSource::Gpu => snapshot.gpu_temperature,
+                };
+                temperature.is_some_and(|temperature| temperature > *celsius)
+            }
+            SchedulerCondition::BatteryBelow { percentage } => snapshot
+                .battery_percentage
+                .is_some_and(|charge| charge < *percentage),
+        }
+    }
+}
+
+/// Samples the machine state needed by condition based events.
+pub struct SchedulerConditionsMonitor;
+
+impl SchedulerConditionsMonitor {
+    /// Reads only the values required by the given conditions.
+    pub async fn snapshot(conditions: &[SchedulerCondition]) -> ConditionsSnapshot {
+        let mut snapshot = ConditionsSnapshot::default();
+
+        if conditions
+            .iter()
+            .any(|c| matches!(c, SchedulerCondition::IdleFor { .. }))
+        {
+            snapshot.idle_duration = Self::sample_idle_duration().await;
+        }
+        if conditions.iter().any(|c| {
+            matches!(c, SchedulerCondition::TemperatureAbove { source, .. } if *source == TemperatureSource::Cpu)
+        }) {
+            snapshot.cpu_temperature = Self::sample_cpu_temperature().await;
+        }
+        if conditions.iter().any(|c| {
+            matches!(c, SchedulerCondition::TemperatureAbove { source, .. } if *source == TemperatureSource::Gpu)
+        }) {
+            snapshot.gpu_temperature = Self::sample_gpu_temperature().await;
+        }
+        if conditions
+            .iter()
+            .any(|c| matches!(c, SchedulerCondition::BatteryBelow { .. }))
+        {
+            snapshot.battery_percentage = Self::sample_battery_percentage().await;
+        }
+
+        debug!(target: LOG_TARGET, "Sampled scheduler conditions: {:?}", snapshot);
+        snapshot
+    }
+
+    async fn sample_idle_duration() -> Option<Duration> {
+        // The OS keeps track of the last input, so typing or moving the mouse between two
+        // checks is never missed
+        tokio::task::spawn_blocking(get_system_idle_duration)
+            .await
+            .inspect_err(|e| warn!(target: LOG_TARGET, "Failed to read system idle time: {}", e))
+            .ok()
+            .flatten()
+    }
+
+    async fn sample_cpu_temperature() -> Option<f32> {
+        HardwareStatusMonitor::current()
+            .get_cpu_public_properties()
+            .await
+            .ok()?
+            .iter()
+            .filter_map(|device| device.parameters.as_ref())
+            .map(|parameters| parameters.current_temperature)
+            .reduce(f32::max)
+    }
+
+    async fn sample_gpu_temperature() -> Option<f32> {
+        HardwareStatusMonitor::current()
+            .get_gpu_public_properties()
+            .await
+            .ok()?
+            .iter()
+            .filter_map(|device| device.parameters.as_ref())
+            .map(|parameters| parameters.current_temperature)
+            .reduce(f32::max)
+    }
+
+    async fn sample_battery_percentage() -> Option<f32> {
+        // Used spawn_blocking to handle non-Send battery operations
+        tokio::task::spawn_blocking(|| {
+            let manager = battery::Manager::new().ok()?;
+            let charges: Vec<f32> = manager
+                .batteries()
+                .ok()?
+                .flatten()
+                .map(|battery| battery.state_of_charge().get::<percent>())
+                .collect();
+            if charges.is_empty() {
+                return None;
+            }
+            Some(charges.iter().sum::<f32>() / charges.len() as f32)
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Audit log of what the event scheduler actually did.
+//!
+//! Every enter and exit execution is recorded together with the time it was planned for,
+//! the time it ran and its outcome. The history is bounded and saved to a file next to the
+//! app configs so it survives restarts.
+
+use std::{collections::VecDeque, env::temp_dir, path::PathBuf};
+
+use anyhow::Error;
+use chrono::{DateTime, Local};
+use dirs::config_dir;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    event_scheduler::{SchedulerEventTiming, SchedulerEventType, SchedulerTransitionKind},
+    events_emitter::EventsEmitter,
+    APPLICATION_FOLDER_ID,
+};
+
+const LOG_TARGET: &str = "tari::universe::scheduler_history";
+
+const HISTORY_FILE_NAME: &str = "scheduler_history.json";
+/// Oldest records are dropped once the history grows above this size
+const MAX_HISTORY_RECORDS: usize = 500;
+
+/// Result of a single scheduler execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchedulerExecutionOutcome {
+    /// All actions of the event were performed
+    Executed,
+    /// Nothing was changed, e.g. a higher priority event decides the mining mode
+    Skipped { reason: String },
+    /// At least one action failed, the errors come from the miners or config updates
+    Failed { errors: Vec<String> },
+}
+
+impl SchedulerExecutionOutcome {
+    /// Builds the outcome from the errors collected during an execution.
+    pub fn from_errors(errors: Vec<String>) -> Self {
+        if errors.is_empty() {
+            SchedulerExecutionOutcome::Executed
+        } else {
+            SchedulerExecutionOutcome::Failed { errors }
+        }
+    }
+}
+
+/// A single entry of the scheduler history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerExecutionRecord {
+    pub event_id: String,
+    pub event_type: SchedulerEventType,
+    pub timing: SchedulerEventTiming,
+    pub kind: SchedulerTransitionKind,
+    /// Time the execution was scheduled for, `None` for condition changes and clock jumps
+    pub planned_time: Option<DateTime<Local>>,
+    pub actual_time: DateTime<Local>,
+    pub outcome: SchedulerExecutionOutcome,
+}
+
+/// Bounded history of scheduler executions, owned by the scheduler's message loop.
+#[derive(Debug, Default)]
+pub struct SchedulerHistory {
+    records: VecDeque<SchedulerExecutionRecord>,
+}
+
+impl SchedulerHistory {
+    /// Loads the history saved by a previous run, starting empty if there is none.
+    pub fn load() -> Self {
+        match Self::read_history_file() {
+            Ok(records) => {
+                debug!(target: LOG_TARGET, "Loaded {} scheduler history records", records.len());
+                let mut history = SchedulerHistory {
+                    records: VecDeque::from(records),
+                };
+                history.truncate();
+                history
+            }
+            Err(e) => {
+                debug!(target: LOG_TARGET, "No scheduler history loaded: {e}");
+                SchedulerHistory::default()
+            }
+        }
+    }
+
+    /// Appends a record, saves the history and notifies the frontend.
+    pub async fn push(&mut self, record: SchedulerExecutionRecord) {
+        self.records.push_back(record.clone());
+        self.truncate();
+        self.save().await;
+        tokio::spawn(EventsEmitter::emit_scheduler_execution(record));
+    }
+
+    /// Returns the records, newest first.
+    ///
+    /// ### Parameters
+    /// * `limit` - Maximum number of records to return, all of them when `None`
+    pub fn records(&self, limit: Option<usize>) -> Vec<SchedulerExecutionRecord> {
+        self.records
+            .iter()
+            .rev()
+            .take(limit.unwrap_or(MAX_HISTORY_RECORDS))
+            .cloned()
+            .collect()
+    }
+
+    /// Removes all records, including the saved ones.
+    pub async fn clear(&mut self) {
+        self.records.clear();
+        self.save().await;
+    }
+
+    fn truncate(&mut self) {
+        while self.records.len() > MAX_HISTORY_RECORDS {
+            self.records.pop_front();
+        }
+    }
+
+    async fn save(&self) {
+        if let Err(e) = self.write_history_file().await {
+            warn!(target: LOG_TARGET, "Failed to save scheduler history: {e}");
+        }
+    }
+
+    fn get_history_path() -> PathBuf {
+        let config_dir = config_dir().unwrap_or_else(|| {
+            debug!(target: LOG_TARGET, "Failed to get config directory, using temp dir");
+            temp_dir()
+        });
+        config_dir
+            .join(APPLICATION_FOLDER_ID)
+            .join(HISTORY_FILE_NAME)
+    }
+
+    fn read_history_file() -> Result<Vec<SchedulerExecutionRecord>, Error> {
+        let content = std::fs::read_to_string(Self::get_history_path())?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    async fn write_history_file(&self) -> Result<(), Error> {
+        let history_path = Self::get_history_path();
+        if let Some(parent) = history_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let content = serde_json::to_string(&self.records)?;
+        tokio::fs::write(history_path, content).await?;
+        Ok(())
+    }
+}
@@ -27,6 +27,7 @@ use crate::{
     mining::gpu::{
         consts::{EngineType, GpuMinerType},
         manager::GpuManager,
+        watchdog::GpuWatchdog,
     },
     progress_trackers::{
         progress_plans::SetupStep,
@@ -323,6 +324,7 @@ impl SetupPhaseImpl for GpuMiningSetupPhase {
             .decide_if_gpu_mining_is_recommended()
             .await;
         GpuManager::write().await.load_saved_miner().await?;
+        GpuWatchdog::start().await;
 
         Ok(())
     }
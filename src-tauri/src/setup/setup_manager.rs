@@ -33,9 +33,11 @@ use crate::configs::config_mining::ConfigMiningContent;
 use crate::configs::config_pools::{ConfigPools, ConfigPoolsContent};
 use crate::configs::config_ui::WalletUIMode;
 use crate::configs::config_wallet::ConfigWalletContent;
+use crate::control_api::ControlApi;
 use crate::event_scheduler::EventScheduler;
 use crate::events::CriticalProblemPayload;
 use crate::internal_wallet::InternalWallet;
+use crate::metrics_server::MetricsServer;
 use crate::mining::cpu::manager::CpuManager;
 use crate::mining::gpu::consts::GpuMinerType;
 use crate::mining::gpu::manager::GpuManager;
@@ -354,6 +356,15 @@ impl SetupManager {
         EventsEmitter::emit_ui_config_loaded(&ConfigUI::content().await).await;
         EventsEmitter::emit_pools_config_loaded(&ConfigPools::content().await).await;
 
+        let metrics_server_settings = *ConfigCore::content().await.metrics_server();
+        if let Err(e) = MetricsServer::apply_settings(metrics_server_settings).await {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to start metrics endpoint: {e:?}");
+        }
+        let control_api_settings = ConfigCore::content().await.control_api().clone();
+        if let Err(e) = ControlApi::apply_settings(&control_api_settings).await {
+            error!(target: LOG_TARGET_APP_LOGIC, "Failed to start control API: {e:?}");
+        }
+
         let is_on_exchange_specific_variant = ConfigCore::content()
             .await
             .is_on_exchange_specific_variant();
@@ -33,6 +33,7 @@ use crate::{
     configs::{config_core::ConfigCore, config_ui::ConfigUI, trait_config::ConfigImpl},
     events_emitter::EventsEmitter,
     mining::{cpu::manager::CpuManager, gpu::manager::GpuManager},
+    process_isolation::release_cgroups,
     systemtray_manager::SystemTrayManager,
     tasks_tracker::TasksTrackers,
     UniverseAppState, LOG_TARGET_APP_LOGIC,
@@ -304,6 +305,7 @@ impl ShutdownManager {
             state.tor_manager.on_app_exit().await;
             state.wallet_manager.on_app_exit().await;
             state.node_manager.on_app_exit().await;
+            release_cgroups();
 
             app.exit(0);
         }
@@ -59,6 +59,7 @@ pub enum SystemTrayDataItem {
     Power { mode: String },
     PendingRewards { rewards: f64 },
     ShareCount { count: u64 },
+    GpuWatchdog { intervention: Option<String> },
 }
 
 impl Display for SystemTrayDataItem {
@@ -113,6 +114,10 @@ impl Display for SystemTrayDataItem {
                     write!(f, "Share Count: {}", count)
                 }
             }
+            SystemTrayDataItem::GpuWatchdog { intervention } => match intervention {
+                Some(intervention) => write!(f, "GPU Watchdog: {intervention}"),
+                None => write!(f, "GPU Watchdog: OK"),
+            },
         }
     }
 }
@@ -127,6 +132,7 @@ impl SystemTrayDataItem {
             SystemTrayDataItem::Power { .. } => "power",
             SystemTrayDataItem::PendingRewards { .. } => "pending_rewards",
             SystemTrayDataItem::ShareCount { .. } => "share_count",
+            SystemTrayDataItem::GpuWatchdog { .. } => "gpu_watchdog",
         }
     }
 }
@@ -195,6 +201,8 @@ pub enum SystemTrayEvents {
     },
     CpuMiningActivity(bool),
     GpuMiningActivity(bool),
+    /// Latest active GPU watchdog intervention, `None` once every device is back within its limits
+    GpuWatchdog(Option<String>),
 }
 
 #[derive(Clone)]
@@ -312,6 +320,11 @@ impl SystemTrayManager {
                                         );
                                         Self::write().await.data.is_mining = is_mining;
                                     },
+                                    SystemTrayEvents::GpuWatchdog(intervention) => {
+                                        Self::write().await.update_menu_data_item(
+                                            SystemTrayDataItem::GpuWatchdog { intervention }
+                                        );
+                                    },
                                     SystemTrayEvents::CpuPoolStats { pending_rewards, share_count } => {
                                         last_cpu_pool_pending_rewards = pending_rewards;
                                         last_cpu_pool_share_count = share_count;
@@ -418,6 +431,11 @@ impl SystemTrayManager {
             false,
         );
 
+        let gpu_watchdog = self.initialize_menu_data_item(
+            SystemTrayDataItem::GpuWatchdog { intervention: None },
+            false,
+        );
+
         let pool_pending_rewards = self
             .initialize_menu_data_item(SystemTrayDataItem::PendingRewards { rewards: 0.0 }, false);
 
@@ -447,6 +465,7 @@ impl SystemTrayManager {
                 &gpu_hashrate,
                 &cpu_mining_state,
                 &gpu_mining_state,
+                &gpu_watchdog,
                 &power,
                 &rewards_separator,
                 &pool_pending_rewards,
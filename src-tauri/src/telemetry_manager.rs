@@ -542,6 +542,33 @@ async fn get_telemetry_data_inner(
         tor_status.circuit_ok.to_string(),
     );
 
+    if let Some(gpu_telemetry) = &gpu_status.telemetry {
+        extra_data.insert(
+            "gpu_accepted_shares".to_string(),
+            gpu_telemetry.accepted_shares.to_string(),
+        );
+        extra_data.insert(
+            "gpu_rejected_shares".to_string(),
+            gpu_telemetry.rejected_shares.to_string(),
+        );
+        extra_data.insert(
+            "gpu_acceptance_rate".to_string(),
+            gpu_telemetry.acceptance_rate.to_string(),
+        );
+        if let Some(pool_latency_ms) = gpu_telemetry.pool_latency_ms {
+            extra_data.insert(
+                "gpu_pool_latency_ms".to_string(),
+                pool_latency_ms.to_string(),
+            );
+        }
+        if let Some(gpu_temperature) = gpu_telemetry.gpu_temperature {
+            extra_data.insert("gpu_temperature".to_string(), gpu_temperature.to_string());
+        }
+        if let Some(gpu_power_usage) = gpu_telemetry.gpu_power_usage {
+            extra_data.insert("gpu_power_usage".to_string(), gpu_power_usage.to_string());
+        }
+    }
+
     if !all_cpus.is_empty() {
         extra_data.insert("all_cpus".to_string(), all_cpus.join(","));
     }
@@ -605,6 +632,13 @@ async fn get_telemetry_data_inner(
         stats_collector.get_gpu_miner_stats(),
         "gpu_miner",
     );
+    for (miner_type, stats) in stats_collector.get_additional_gpu_miners_stats() {
+        add_process_stats(
+            &mut extra_data,
+            stats,
+            &format!("gpu_miner_{}", miner_type.to_string().to_lowercase()),
+        );
+    }
     add_process_stats(
         &mut extra_data,
         stats_collector.get_minotari_node_stats(),
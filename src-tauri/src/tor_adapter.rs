@@ -35,8 +35,11 @@ use tokio::net::TcpStream;
 use tokio::sync::watch;
 use tokio::time::timeout;
 
+use crate::binaries::Binaries;
 use crate::port_allocator::PortAllocator;
 use crate::process_adapter::HandleUnhealthyResult;
+use crate::process_isolation::ProcessIsolation;
+use crate::process_restart_policy::{RestartPolicy, StderrTail};
 use crate::tor_control_client::{TorControlClient, TorStatus};
 use crate::{
     process_adapter::{
@@ -288,6 +291,7 @@ impl ProcessAdapter for TorAdapter {
             ProcessInstance {
                 shutdown: inner_shutdown,
                 handle: None,
+                stderr_tail: StderrTail::default(),
                 startup_spec: ProcessStartupSpec {
                     file_path: binary_version_path,
                     envs,
@@ -295,6 +299,8 @@ impl ProcessAdapter for TorAdapter {
                     data_dir: data_dir.clone(),
                     pid_file_name: self.pid_file_name().to_string(),
                     name: self.name().to_string(),
+                    binary: Binaries::Tor,
+                    isolation: ProcessIsolation::default(),
                 },
             },
             TorStatusMonitor {
@@ -312,6 +318,10 @@ impl ProcessAdapter for TorAdapter {
     fn pid_file_name(&self) -> &str {
         "tor_pid"
     }
+
+    fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy::unlimited()
+    }
 }
 
 #[derive(Clone)]
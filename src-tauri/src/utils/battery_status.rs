@@ -98,7 +98,8 @@ impl BatteryStatus {
             if GpuManager::read().await.is_running() || CpuManager::read().await.is_running() {
                 info!(target: LOG_TARGET, "Mining is running, pausing mining due to discharging battery.");
                 let _unused = GpuManager::write().await.stop_mining().await;
-                let _unused = CpuManager::write().await.stop_mining().await;
+                // Pausing keeps the RandomX dataset, so mining resumes right away once charging
+                let _unused = CpuManager::write().await.pause_mining().await;
                 EventsEmitter::emit_set_show_battery_alert(true).await;
                 INSTANCE
                     .should_resume_mining_once_charging
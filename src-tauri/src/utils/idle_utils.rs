@@ -0,0 +1,118 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Time since the last keyboard or mouse input, read from the operating system.
+
+use std::time::Duration;
+
+/// Returns how long the user has not touched the keyboard or mouse.
+///
+/// Uses `GetLastInputInfo` on Windows, `CGEventSourceSecondsSinceLastEventType` on macOS
+/// and the XScreenSaver extension on Linux. `None` when the OS does not provide it, e.g. a
+/// Linux session without an X server or libXss.
+pub fn get_system_idle_duration() -> Option<Duration> {
+    platform::get_system_idle_duration()
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::time::Duration;
+
+    use windows_sys::Win32::{
+        System::SystemInformation::GetTickCount,
+        UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
+    };
+
+    pub fn get_system_idle_duration() -> Option<Duration> {
+        let mut last_input = LASTINPUTINFO {
+            cbSize: u32::try_from(std::mem::size_of::<LASTINPUTINFO>()).ok()?,
+            dwTime: 0,
+        };
+        // SAFETY: last_input is a valid LASTINPUTINFO with cbSize set as the API requires
+        if unsafe { GetLastInputInfo(&mut last_input) } == 0 {
+            return None;
+        }
+        // Both are milliseconds since boot, wrapping after ~49 days
+        // SAFETY: GetTickCount has no preconditions
+        let idle_ms = unsafe { GetTickCount() }.wrapping_sub(last_input.dwTime);
+        Some(Duration::from_millis(u64::from(idle_ms)))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::time::Duration;
+
+    const K_CG_EVENT_SOURCE_STATE_COMBINED_SESSION_STATE: i32 = 0;
+    const K_CG_ANY_INPUT_EVENT_TYPE: u32 = u32::MAX;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(source_state_id: i32, event_type: u32) -> f64;
+    }
+
+    pub fn get_system_idle_duration() -> Option<Duration> {
+        // SAFETY: plain query without pointers, valid for any state and event type
+        let idle_seconds = unsafe {
+            CGEventSourceSecondsSinceLastEventType(
+                K_CG_EVENT_SOURCE_STATE_COMBINED_SESSION_STATE,
+                K_CG_ANY_INPUT_EVENT_TYPE,
+            )
+        };
+        Duration::try_from_secs_f64(idle_seconds).ok()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::time::Duration;
+
+    use x11_dl::{xlib::Xlib, xss::Xss};
+
+    pub fn get_system_idle_duration() -> Option<Duration> {
+        // Loaded at runtime so a missing X server or libXss only disables the reading
+        let xlib = Xlib::open().ok()?;
+        let xss = Xss::open().ok()?;
+
+        // SAFETY: the display and info pointers are checked for null and freed before returning
+        unsafe {
+            let display = (xlib.XOpenDisplay)(std::ptr::null());
+            if display.is_null() {
+                return None;
+            }
+            let info = (xss.XScreenSaverAllocInfo)();
+            let mut idle = None;
+            if !info.is_null() {
+                let root_window = (xlib.XDefaultRootWindow)(display);
+                if (xss.XScreenSaverQueryInfo)(display, root_window, info) != 0 {
+                    // c_ulong is only 32 bits wide on some targets
+                    #[allow(clippy::useless_conversion)]
+                    let idle_ms = u64::from((*info).idle);
+                    idle = Some(Duration::from_millis(idle_ms));
+                }
+                (xlib.XFree)(info.cast());
+            }
+            (xlib.XCloseDisplay)(display);
+            idle
+        }
+    }
+}
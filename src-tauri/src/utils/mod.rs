@@ -27,12 +27,14 @@ pub mod commands_builder;
 pub mod cryptography;
 pub mod file_utils;
 pub mod formatting_utils;
+pub mod idle_utils;
 pub mod logging_utils;
 pub mod macos_utils;
 pub mod math_utils;
 pub mod network_status;
 pub mod platform_utils;
 pub mod rand_utils;
+pub mod server_utils;
 
 mod speed_test_utils;
 pub mod system_status;
@@ -0,0 +1,103 @@
+// Copyright 2024. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use anyhow::Context;
+use axum::Router;
+use log::{error, info};
+use tokio::net::TcpListener;
+use tokio::select;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::tasks_tracker::TasksTrackers;
+use crate::LOG_TARGET_APP_LOGIC;
+
+/// Time given to open connections to finish before a stopped server is abandoned
+const SERVER_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct LocalServerHandle {
+    bind_address: SocketAddr,
+    cancel_token: CancellationToken,
+    task: JoinHandle<()>,
+}
+
+impl LocalServerHandle {
+    pub fn bind_address(&self) -> SocketAddr {
+        self.bind_address
+    }
+
+    /// Stops the server and waits until its address can be bound again
+    pub async fn stop(self) {
+        self.cancel_token.cancel();
+        let _unused = tokio::time::timeout(SERVER_STOP_TIMEOUT, self.task).await;
+    }
+}
+
+/// Binds the address up front, so a failure can be reported before a running server is stopped
+pub async fn bind_local_server(
+    name: &'static str,
+    bind_address: SocketAddr,
+) -> Result<TcpListener, anyhow::Error> {
+    TcpListener::bind(bind_address)
+        .await
+        .with_context(|| format!("Failed to bind {name} to {bind_address}"))
+}
+
+/// Serves `app` on `listener` until the returned handle is stopped or the app shuts down
+pub async fn spawn_local_server(
+    name: &'static str,
+    listener: TcpListener,
+    app: Router,
+) -> Result<LocalServerHandle, anyhow::Error> {
+    let bind_address = listener
+        .local_addr()
+        .with_context(|| format!("Failed to read the address of {name}"))?;
+    let cancel_token = CancellationToken::new();
+    let cancelled = cancel_token.clone();
+    let mut shutdown_signal = TasksTrackers::current().common.get_signal().await;
+    let task = TasksTrackers::current()
+        .common
+        .get_task_tracker()
+        .await
+        .spawn(async move {
+            let shutdown = async move {
+                select! {
+                    _ = cancelled.cancelled() => {},
+                    _ = shutdown_signal.wait() => {},
+                }
+            };
+            if let Err(e) = axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await
+            {
+                error!(target: LOG_TARGET_APP_LOGIC, "{name} stopped with error: {e:?}");
+            }
+            info!(target: LOG_TARGET_APP_LOGIC, "{name} stopped");
+        });
+    Ok(LocalServerHandle {
+        bind_address,
+        cancel_token,
+        task,
+    })
+}
@@ -39,6 +39,8 @@ use crate::process_adapter::{
     HealthStatus, ProcessAdapter, ProcessInstance, ProcessInstanceTrait, ProcessStartupSpec,
     StatusMonitor,
 };
+use crate::process_isolation::ProcessIsolation;
+use crate::process_restart_policy::StderrTail;
 use crate::tasks_tracker::TasksTrackers;
 use crate::utils::commands_builder::CommandBuilder;
 use crate::utils::logging_utils::setup_logging;
@@ -320,6 +322,7 @@ impl ProcessAdapter for SpendWallet {
         let instance = ProcessInstance {
             shutdown: Shutdown::new(),
             handle: None,
+            stderr_tail: StderrTail::default(),
             startup_spec: ProcessStartupSpec {
                 file_path: binary_version_path,
                 envs: Some(envs),
@@ -327,6 +330,8 @@ impl ProcessAdapter for SpendWallet {
                 pid_file_name: self.pid_file_name().to_string(),
                 data_dir,
                 name: self.name().to_string(),
+                binary: Binaries::Wallet,
+                isolation: ProcessIsolation::default(),
             },
         };
 
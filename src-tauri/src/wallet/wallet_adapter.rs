@@ -20,10 +20,13 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use crate::binaries::Binaries;
 use crate::events_emitter::EventsEmitter;
 use crate::port_allocator::PortAllocator;
 use crate::process_adapter::{ProcessAdapter, ProcessInstance, ProcessStartupSpec};
 use crate::process_adapter_utils::setup_working_directory;
+use crate::process_isolation::ProcessIsolation;
+use crate::process_restart_policy::{RestartPolicy, StderrTail};
 use crate::tasks_tracker::TasksTrackers;
 use crate::utils::file_utils::convert_to_string;
 use crate::utils::logging_utils::setup_logging;
@@ -431,6 +434,7 @@ impl ProcessAdapter for WalletAdapter {
             ProcessInstance {
                 shutdown: inner_shutdown,
                 handle: None,
+                stderr_tail: StderrTail::default(),
                 startup_spec: ProcessStartupSpec {
                     file_path: binary_version_path,
                     envs: Some(envs),
@@ -438,6 +442,8 @@ impl ProcessAdapter for WalletAdapter {
                     data_dir,
                     pid_file_name: self.pid_file_name().to_string(),
                     name: self.name().to_string(),
+                    binary: Binaries::Wallet,
+                    isolation: ProcessIsolation::default(),
                 },
             },
             WalletStatusMonitor::new(self.grpc_port, self.state_broadcast.clone()),
@@ -451,4 +457,8 @@ impl ProcessAdapter for WalletAdapter {
     fn pid_file_name(&self) -> &str {
         "wallet_pid"
     }
+
+    fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy::unlimited()
+    }
 }
@@ -18,7 +18,8 @@ import {
 import { useMiningPoolsStore } from '@app/store/useMiningPoolsStore.ts';
 import { PoolStats } from '@app/containers/floating/Settings/sections/pools/PoolStats.tsx';
 import { Select } from '@app/components/elements/inputs/Select';
-import { useCallback, useMemo } from 'react';
+import { Button } from '@app/components/elements/buttons/Button';
+import { useCallback, useMemo, useState } from 'react';
 import { getAvailableCpuPools, getSelectedCpuPool } from '@app/store/selectors/appConfigStoreSelectors';
 import { useShallow } from 'zustand/react/shallow';
 import { PoolConfiguration } from './PoolsConfiguration';
@@ -26,6 +27,7 @@ import { BasePoolData, CpuPools } from '@app/types/configs';
 
 export const CpuPoolsSettings = () => {
     const { t } = useTranslation('settings');
+    const [unreachablePool, setUnreachablePool] = useState<CpuPools>();
 
     const isCpuPoolEnabled = useConfigPoolsStore((state) => state.cpu_pool_enabled);
     const isMining = useMiningMetricsStore((s) => s.cpu_mining_status.is_mining);
@@ -45,9 +47,18 @@ export const CpuPoolsSettings = () => {
     }, [availableCpuPools]);
 
     const handlePoolChange = useCallback(async (value: string) => {
-        await changeCpuPool(value as CpuPools);
+        const changed = await changeCpuPool(value as CpuPools);
+        setUnreachablePool(changed ? undefined : (value as CpuPools));
     }, []);
 
+    const handleForcePoolChange = useCallback(async () => {
+        if (!unreachablePool) return;
+        const changed = await changeCpuPool(unreachablePool, true);
+        if (changed) {
+            setUnreachablePool(undefined);
+        }
+    }, [unreachablePool]);
+
     const handlePoolConfigurationChange = useCallback(async (updatedConfig: BasePoolData) => {
         await changeCpuPoolConfiguration(updatedConfig);
     }, []);
@@ -97,6 +108,18 @@ export const CpuPoolsSettings = () => {
                             variant="bordered"
                             forceHeight={36}
                         />
+                        {unreachablePool && (
+                            <SettingsGroup>
+                                <SettingsGroupContent>
+                                    <Typography>{t('pool-unreachable')}</Typography>
+                                </SettingsGroupContent>
+                                <SettingsGroupAction>
+                                    <Button variant="outlined" onClick={handleForcePoolChange}>
+                                        {t('switch-pool-anyway')}
+                                    </Button>
+                                </SettingsGroupAction>
+                            </SettingsGroup>
+                        )}
                         <PoolConfiguration
                             poolConfig={selectedCpuPoolData}
                             onSave={handlePoolConfigurationChange}
@@ -19,13 +19,15 @@ import { useMiningPoolsStore } from '@app/store/useMiningPoolsStore.ts';
 import { PoolStats } from '@app/containers/floating/Settings/sections/pools/PoolStats.tsx';
 import { getAvailableGpuPools, getSelectedGpuPool } from '@app/store/selectors/appConfigStoreSelectors';
 import { useShallow } from 'zustand/react/shallow';
-import { useCallback, useMemo } from 'react';
+import { useCallback, useMemo, useState } from 'react';
 import { Select } from '@app/components/elements/inputs/Select';
+import { Button } from '@app/components/elements/buttons/Button';
 import { PoolConfiguration } from './PoolsConfiguration';
 import { BasePoolData, GpuPools } from '@app/types/configs';
 
 export const GpuPoolsSettings = () => {
     const { t } = useTranslation('settings');
+    const [unreachablePool, setUnreachablePool] = useState<GpuPools>();
     const isGpuPoolEnabled = useConfigPoolsStore((state) => state.gpu_pool_enabled);
     const pool_status = useMiningPoolsStore((s) => s.gpuPoolStats);
     const isMining = useMiningMetricsStore((s) => s.gpu_mining_status.is_mining);
@@ -44,9 +46,18 @@ export const GpuPoolsSettings = () => {
     }, [availableGpuPools]);
 
     const handlePoolChange = useCallback(async (value: string) => {
-        await changeGpuPool(value as GpuPools);
+        const changed = await changeGpuPool(value as GpuPools);
+        setUnreachablePool(changed ? undefined : (value as GpuPools));
     }, []);
 
+    const handleForcePoolChange = useCallback(async () => {
+        if (!unreachablePool) return;
+        const changed = await changeGpuPool(unreachablePool, true);
+        if (changed) {
+            setUnreachablePool(undefined);
+        }
+    }, [unreachablePool]);
+
     const handlePoolConfigurationChange = useCallback(async (updatedConfig: BasePoolData) => {
         await changeGpuPoolConfiguration(updatedConfig);
     }, []);
@@ -96,6 +107,18 @@ export const GpuPoolsSettings = () => {
                             variant="bordered"
                             forceHeight={36}
                         />
+                        {unreachablePool && (
+                            <SettingsGroup>
+                                <SettingsGroupContent>
+                                    <Typography>{t('pool-unreachable')}</Typography>
+                                </SettingsGroupContent>
+                                <SettingsGroupAction>
+                                    <Button variant="outlined" onClick={handleForcePoolChange}>
+                                        {t('switch-pool-anyway')}
+                                    </Button>
+                                </SettingsGroupAction>
+                            </SettingsGroup>
+                        )}
                         <PoolConfiguration
                             poolConfig={selectedGpuPoolData}
                             onSave={handlePoolConfigurationChange}
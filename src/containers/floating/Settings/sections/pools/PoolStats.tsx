@@ -5,11 +5,11 @@ import LoadingDots from '@app/components/elements/loaders/LoadingDots.tsx';
 import { convertHexToRGBA, formatNumber, FormatPreset } from '@app/utils';
 import { PoolStats as IPoolStats } from '@app/types/app-status.ts';
 import styled from 'styled-components';
-import { PoolOrigin } from '@app/types/configs';
+import { BasePoolData, PoolOrigin } from '@app/types/configs';
 
 interface PoolStatsProps {
     poolStatus?: IPoolStats;
-    poolOrigin?: PoolOrigin;
+    poolOrigin?: BasePoolData['pool_origin'];
     isMining?: boolean;
 }
 
@@ -20,6 +20,7 @@ import {
 import {
     handleAvailableMinersChanged,
     handleCpuMinerControlsStateChanged,
+    handleGpuEnginePreferencesIgnored,
     handleGpuMinerControlsStateChanged,
     handleSelectedMinerChanged,
     setAvailableEngines,
@@ -35,6 +36,7 @@ import {
     setNetworkStatus,
     setIsSettingsOpen,
     handleSystrayAppShutdownRequested,
+    handleUnrelatedProcessWarning,
 } from '@app/store/actions/appStateStoreActions';
 import {
     handleBaseNodeStatusUpdate,
@@ -165,6 +167,9 @@ const useTauriEventsListener = () => {
                         case 'DetectedAvailableGpuEngines':
                             setAvailableEngines(event.payload.engines, event.payload.selected_engine);
                             break;
+                        case 'GpuEnginePreferencesIgnored':
+                            handleGpuEnginePreferencesIgnored(event.payload);
+                            break;
                         case 'CriticalProblem': {
                             const isMacAppFolderError =
                                 event.payload.title === 'common:installation-problem' &&
@@ -273,6 +278,9 @@ const useTauriEventsListener = () => {
                         case 'SetShowBatteryAlert':
                             setShowBatteryAlert(event.payload);
                             break;
+                        case 'UnrelatedProcessWarning':
+                            handleUnrelatedProcessWarning(event.payload);
+                            break;
                         default:
                             console.warn('Unknown event', JSON.stringify(event));
                             break;
@@ -183,16 +183,10 @@ export const selectMiningMode = async (mode: string) => {
 
     useMiningStore.setState((c) => ({ ...c, isChangingMode: true }));
 
-    const cpu_mining_status = useMiningMetricsStore.getState().cpu_mining_status;
     const gpu_mining_status = useMiningMetricsStore.getState().gpu_mining_status;
-    const wasCpuMiningInitiated = useMiningStore.getState().isCpuMiningInitiated;
     const wasGpuMiningInitiated = useMiningStore.getState().isGpuMiningInitiated;
 
-    if (cpu_mining_status.is_mining) {
-        console.info('Stopping CPU mining...');
-        await stopCpuMining();
-    }
-
+    // CPU mining is retuned by the backend without a restart
     if (gpu_mining_status.is_mining || wasGpuMiningInitiated) {
         console.info('Stopping GPU mining...');
         await stopGpuMining();
@@ -201,11 +195,6 @@ export const selectMiningMode = async (mode: string) => {
     invoke('select_mining_mode', { mode })
         .then(() => {
             useConfigMiningStore.setState((c) => ({ ...c, selected_mining_mode: mode }));
-            if (wasCpuMiningInitiated) {
-                console.info('Restarting CPU mining...');
-                startCpuMining();
-            }
-
             if (wasGpuMiningInitiated) {
                 console.info('Restarting GPU mining...');
                 startGpuMining();
@@ -360,7 +349,7 @@ export const toggleGpuPool = async (enabled: boolean) => {
     }
 };
 
-export const changeGpuPool = async (gpuPool: GpuPools) => {
+export const changeGpuPool = async (gpuPool: GpuPools, force = false) => {
     const previousGpuPool = useConfigPoolsStore.getState().current_gpu_pool;
     useConfigPoolsStore.setState((c) => ({ ...c, current_gpu_pool: gpuPool }));
 
@@ -371,24 +360,24 @@ export const changeGpuPool = async (gpuPool: GpuPools) => {
     const gpuMining = useMiningMetricsStore.getState().gpu_mining_status.is_mining;
 
     try {
-        if (gpuMining || isGpuMiningInitiated) {
-            await stopGpuMining();
-        }
-
-        await invoke('change_gpu_pool', { gpuPool });
+        // The backend checks the pool before stopping the miner and restarts it once the pool is changed,
+        // so a failed change keeps mining on the previous pool
+        await invoke('change_gpu_pool', { gpuPool, force });
         console.info('GPU pool changed to:', gpuPool);
 
-        if (anyMiningInitiated && isGpuMiningEnabled) {
+        if (anyMiningInitiated && isGpuMiningEnabled && !gpuMining && !isGpuMiningInitiated) {
             await startGpuMining();
         }
+        return true;
     } catch (e) {
         console.error('Could not change GPU pool', e);
-        setError('Could not change GPU pool');
+        setError(`Could not change GPU pool: ${e}`);
         useConfigPoolsStore.setState((c) => ({ ...c, current_gpu_pool: previousGpuPool }));
+        return false;
     }
 };
 
-export const changeCpuPool = async (cpuPool: CpuPools) => {
+export const changeCpuPool = async (cpuPool: CpuPools, force = false) => {
     const previousCpuPool = useConfigPoolsStore.getState().current_cpu_pool;
     useConfigPoolsStore.setState((c) => ({ ...c, current_cpu_pool: cpuPool }));
 
@@ -399,20 +388,20 @@ export const changeCpuPool = async (cpuPool: CpuPools) => {
     const cpuMining = useMiningMetricsStore.getState().cpu_mining_status.is_mining;
 
     try {
-        if (cpuMining || isCpuMiningInitiated) {
-            await stopCpuMining();
-        }
-
-        await invoke('change_cpu_pool', { cpuPool });
+        // The backend checks the pool before stopping the miner and restarts it once the pool is changed,
+        // so a failed change keeps mining on the previous pool
+        await invoke('change_cpu_pool', { cpuPool, force });
         console.info('CPU pool changed to:', cpuPool);
 
-        if (anyMiningInitiated && isCpuMiningEnabled) {
+        if (anyMiningInitiated && isCpuMiningEnabled && !cpuMining && !isCpuMiningInitiated) {
             await startCpuMining();
         }
+        return true;
     } catch (e) {
         console.error('Could not change CPU pool', e);
-        setError('Could not change CPU pool');
+        setError(`Could not change CPU pool: ${e}`);
         useConfigPoolsStore.setState((c) => ({ ...c, current_cpu_pool: previousCpuPool }));
+        return false;
     }
 };
 
@@ -1,8 +1,14 @@
 import { invoke } from '@tauri-apps/api/core';
+import { t } from 'i18next';
 import { useAppStateStore } from '../appStateStore.ts';
 import { NetworkStatus, SystemDependency, SystemDependencyStatus } from '@app/types/app-status.ts';
 import { addToast } from '@app/components/ToastStack/useToastStore.tsx';
-import { CriticalProblemPayload, SetupPhase, ShowReleaseNotesPayload } from '@app/types/events-payloads.ts';
+import {
+    CriticalProblemPayload,
+    SetupPhase,
+    ShowReleaseNotesPayload,
+    UnrelatedProcessWarning,
+} from '@app/types/events-payloads.ts';
 import { setDialogToShow, useMiningStore, useUIStore } from '../index.ts';
 
 import { setIsReconnecting, setShowExternalDependenciesDialog, setShowResumeAppModal } from './uiStoreActions.ts';
@@ -101,3 +107,11 @@ export const handleRestartingPhases = async (phasesToRestart: SetupPhase[]) => {
 export const handleSystrayAppShutdownRequested = () => {
     useAppStateStore.setState({ isSystrayAppShutdownRequested: true });
 };
+
+export const handleUnrelatedProcessWarning = ({ process_name, pid, reason }: UnrelatedProcessWarning) => {
+    addToast({
+        title: t('unrelated-process-left-running', { ns: 'common' }),
+        text: t('unrelated-process-left-running-description', { ns: 'common', process_name, pid, reason }),
+        type: 'warning',
+    });
+};
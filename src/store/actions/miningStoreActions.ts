@@ -8,11 +8,18 @@ import { useSetupStore } from '@app/store/useSetupStore.ts';
 import { useConfigMiningStore } from '../useAppConfigStore.ts';
 import { Network } from '@app/utils/network.ts';
 import { setupStoreSelectors } from '../selectors/setupStoreSelectors.ts';
-import { GpuMiner, GpuMinerType, MinerControlsState } from '@app/types/events-payloads.ts';
+import {
+    GpuEnginePreferencesIgnoredPayload,
+    GpuMiner,
+    GpuMinerType,
+    MinerControlsState,
+} from '@app/types/events-payloads.ts';
 import { MiningModeType } from '@app/types/configs.ts';
 import { useAirdropStore } from '@app/store';
 import { FEATURE_FLAGS } from '@app/store/consts.ts';
 import { TimeUnit } from '@app/types/mining/schedule.ts';
+import { addToast } from '@app/components/ToastStack/useToastStore.tsx';
+import { t } from 'i18next';
 
 export const restartMining = async () => {
     const isMining =
@@ -206,6 +213,18 @@ export const handleAvailableMinersChanged = (miners: Record<GpuMinerType, GpuMin
     useMiningStore.setState({ availableMiners: miners });
 };
 
+export const handleGpuEnginePreferencesIgnored = ({ engine, device_indexes }: GpuEnginePreferencesIgnoredPayload) => {
+    addToast({
+        title: t('gpu-engine', { ns: 'settings' }),
+        text: t('gpu-engine-preferences-ignored', {
+            ns: 'settings',
+            engine,
+            devices: device_indexes.join(', '),
+        }),
+        type: 'warning',
+    });
+};
+
 export const switchSelectedMiner = async (newGpuMiner: GpuMinerType) => {
     const oldMiner = useMiningStore.getState().selectedMiner;
     useMiningStore.setState({ selectedMiner: newGpuMiner });
@@ -1,4 +1,5 @@
 import { AppModule } from '@app/store/types/setup';
+import { GpuMiningAlgorithm } from './events-payloads';
 
 export interface TorConfig {
     control_port: number;
@@ -59,6 +60,31 @@ export interface CpuMinerStatus {
     hash_rate: number;
     estimated_earnings: number;
     connection: CpuMinerConnectionStatus;
+    hash_rates?: CpuMinerHashRates;
+    thread_hash_rates?: CpuMinerHashRates[];
+    highest_hash_rate?: number;
+    shares?: CpuMinerShares;
+    difficulty?: number;
+    algorithm?: string;
+    uptime?: number;
+    huge_pages?: CpuMinerHugePages;
+}
+
+export interface CpuMinerHashRates {
+    ten_seconds?: number;
+    sixty_seconds?: number;
+    fifteen_minutes?: number;
+}
+
+export interface CpuMinerShares {
+    accepted: number;
+    rejected: number;
+    invalid: number;
+}
+
+export interface CpuMinerHugePages {
+    allocated: number;
+    total: number;
 }
 
 export interface PoolStats {
@@ -66,10 +92,21 @@ export interface PoolStats {
     unpaid: number;
     balance: number;
     min_payout: number;
+    workers?: PoolWorkerStats[]; // Only reported by pools listing the workers of an address
+}
+
+export interface PoolWorkerStats {
+    name: string;
+    hashrate: number;
+    total_hashes: number;
+    last_share_timestamp?: number; // Unix seconds
 }
 
 interface CpuMinerConnectionStatus {
     is_connected: boolean;
+    pool_address?: string;
+    ping?: number;
+    uptime?: number;
 }
 
 export interface GpuMinerStatus {
@@ -77,6 +114,24 @@ export interface GpuMinerStatus {
     hash_rate: number;
     estimated_earnings: number;
     is_available: boolean;
+    algorithm_hash_rates?: Partial<Record<GpuMiningAlgorithm, number>>; // Includes miners running next to the selected one
+    telemetry?: GpuMinerTelemetry; // Only reported by miners with a telemetry feed
+}
+
+export interface GpuMinerTelemetry {
+    session_average_hash_rate: number;
+    accepted_shares: number;
+    rejected_shares: number;
+    submitted_shares: number;
+    acceptance_rate: number; // Percentage of submitted shares accepted by the pool
+    average_luck: number;
+    thread_hash_rates: number[];
+    is_pool_connected: boolean;
+    pool_latency_ms?: number;
+    gpu_temperature?: number;
+    gpu_power_usage?: number;
+    gpu_utilization?: number;
+    uptime_secs: number;
 }
 
 export interface BaseNodeStatus {
@@ -4,15 +4,22 @@ import {
     CriticalProblemPayload,
     DetectedAvailableGpuEngines,
     DetectedDevicesPayload,
+    GpuBenchmarkProgressPayload,
+    GpuEnginePreferencesIgnoredPayload,
+    GpuWatchdogIntervention,
     GpuMiner,
     GpuMinerType,
     MinerControlsState,
     NewBlockHeightPayload,
     NodeTypeUpdatePayload,
+    PoolFailoverPayload,
+    ProcessCrashReport,
+    ProcessOutputLine,
     ProgressTrackerUpdatePayload,
     SetupPhase,
     ShowReleaseNotesPayload,
     TariAddressUpdatePayload,
+    UnrelatedProcessWarning,
     WalletUIMode,
 } from './events-payloads.ts';
 import {
@@ -28,6 +35,7 @@ import { ConfigMining, ConfigPools, ConfigUI, ConfigWallet, GpuDeviceSettings }
 import { DisabledPhasesPayload } from '@app/store/actions/setupStoreActions.ts';
 import { AppModuleState } from '@app/store/types/setup.ts';
 import { ConfigCore } from '@app/types/config/core.ts';
+import { SchedulerExecutionRecord } from '@app/types/mining/schedule.ts';
 
 export const BACKEND_STATE_UPDATE = 'backend_state_update';
 export type BackendStateUpdateEvent =
@@ -249,4 +257,36 @@ export type BackendStateUpdateEvent =
     | {
           event_type: 'SetShowBatteryAlert';
           payload: boolean;
+      }
+    | {
+          event_type: 'SchedulerExecution';
+          payload: SchedulerExecutionRecord;
+      }
+    | {
+          event_type: 'PoolFailover';
+          payload: PoolFailoverPayload;
+      }
+    | {
+          event_type: 'GpuBenchmarkProgress';
+          payload: GpuBenchmarkProgressPayload;
+      }
+    | {
+          event_type: 'GpuWatchdogIntervention';
+          payload: GpuWatchdogIntervention;
+      }
+    | {
+          event_type: 'GpuEnginePreferencesIgnored';
+          payload: GpuEnginePreferencesIgnoredPayload;
+      }
+    | {
+          event_type: 'ProcessCrashReport';
+          payload: ProcessCrashReport;
+      }
+    | {
+          event_type: 'ProcessOutput';
+          payload: ProcessOutputLine[];
+      }
+    | {
+          event_type: 'UnrelatedProcessWarning';
+          payload: UnrelatedProcessWarning;
       };
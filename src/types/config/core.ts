@@ -23,6 +23,17 @@ export interface ConfigCore {
     should_auto_launch: boolean;
     use_tor: boolean;
     shutdown_mode: ShutdownMode;
+    metrics_server: MetricsServerSettings;
+    control_api: ControlApiSettings;
+}
+export interface MetricsServerSettings {
+    enabled: boolean;
+    bind_address: string; // e.g. 127.0.0.1:9464
+}
+export interface ControlApiSettings {
+    enabled: boolean;
+    bind_address: string; // e.g. 127.0.0.1:9465
+    token: string; // Sent as `Authorization: Bearer <token>`
 }
 export enum ShutdownMode {
     Direct = 'Direct',
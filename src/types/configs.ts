@@ -1,4 +1,4 @@
-import { WalletUIMode } from './events-payloads';
+import { GpuBenchmarkReport, GpuMinerType, GpuMiningAlgorithm, WalletUIMode } from './events-payloads';
 
 export interface ConfigWallet {
     created_at: string;
@@ -55,6 +55,8 @@ export interface ConfigMining {
     eco_alert_needed: boolean;
     mode_mining_times?: MiningModeTimes;
     pause_on_battery_mode: PauseOnBatteryModeState;
+    gpu_benchmark_report?: GpuBenchmarkReport;
+    gpu_watchdog_action: GpuWatchdogAction;
 }
 
 export interface ConfigMiningSelectors {
@@ -64,8 +66,19 @@ export interface ConfigMiningSelectors {
 export interface GpuDeviceSettings {
     device_id: number;
     is_excluded: boolean;
+    intensity_percentage?: number; // Overrides the selected mining mode's GPU usage for this device
+    engine?: 'OpenCL' | 'Cuda' | 'Metal'; // Preferred engine, the selected gpu_engine when not set
+    miner?: GpuMinerType; // Miner the device is assigned to, the selected gpu_miner_type when not set
+    limits: GpuDeviceLimits; // Enforced by the GPU watchdog
 }
 
+export interface GpuDeviceLimits {
+    max_temperature?: number; // Degrees Celsius
+    max_power_watts?: number; // Only enforced on devices reporting their power draw
+}
+
+export type GpuWatchdogAction = 'ReduceIntensity' | 'ExcludeDevice' | 'PauseMining';
+
 export enum MiningModeType {
     Eco = 'Eco',
     Turbo = 'Turbo',
@@ -79,6 +92,35 @@ export interface MiningMode {
     mode_name: string;
     cpu_usage_percentage: number;
     gpu_usage_percentage: number;
+    cpu_tuning?: CpuTuningOverrides;
+    process_isolation?: ProcessIsolation; // Defaults of the mode type when not set
+}
+
+export type IoPriority = 'Idle' | { BestEffort: number };
+
+// Only applied on Linux
+export interface ProcessIsolation {
+    nice?: number;
+    sched_idle?: boolean;
+    io_priority?: IoPriority;
+    cpu_quota_percent?: number;
+    memory_limit_mb?: number;
+}
+
+export type RandomXMode = 'Auto' | 'Fast' | 'Light';
+export type AsmMode = 'Auto' | 'Disabled' | 'Intel' | 'Ryzen' | 'Bulldozer';
+
+export interface CpuTuningOverrides {
+    huge_pages?: boolean;
+    huge_pages_jit?: boolean;
+    one_gb_pages?: boolean;
+    affinity_mask?: number;
+    priority?: number;
+    max_threads_hint?: number;
+    randomx_init_threads?: number;
+    randomx_mode?: RandomXMode;
+    yield_between_rounds?: boolean;
+    asm?: AsmMode;
 }
 
 export interface ConfigPools {
@@ -95,6 +137,12 @@ export interface ConfigPools {
     cpu_pool_enabled: boolean; // Whether CPU pool mining is enabled | defaults to true
     current_cpu_pool?: CpuPools; // Name of the selected CPU pool => defaults to LuckyPool
     cpu_pools?: Record<CpuPools, BasePoolData>; // Available CPU pools
+    // ======= Pool Failover =======
+    cpu_fallback_pools?: CpuPools[]; // CPU pools tried in order when the current one is unreachable
+    gpu_fallback_pools?: Partial<Record<GpuMiningAlgorithm, GpuPools[]>>; // GPU fallback pools per algorithm
+    pool_failover_timeout_secs?: number; // Seconds without a pool connection before failing over | defaults to 120
+    // ======= Worker =======
+    worker_name?: string; // Appended to the mining address to identify this rig | defaults to the sanitized host name
 }
 
 export enum GpuPools {
@@ -117,10 +165,36 @@ export enum PoolOrigin {
     Kryptex = 'Kryptex',
 }
 
+export type CustomPoolAlgorithm = 'RandomX' | 'SHA3X' | 'C29';
+
+export interface PoolStatsField {
+    paths: string[]; // JSON paths summed into the value, e.g. "$.stats.unlocked"
+    multiplier?: number; // Applied to the summed value | defaults to 1
+}
+
+export interface PoolStatsMapping {
+    accepted_shares?: PoolStatsField;
+    unpaid?: PoolStatsField;
+    balance?: PoolStatsField;
+    min_payout?: PoolStatsField;
+}
+
+export interface CustomPoolSettings {
+    worker_name_template?: string; // Appended to the wallet address, "%WORKER_NAME%" is replaced with the worker name
+    stats_mapping: PoolStatsMapping;
+}
+
+export interface CustomPoolDefinition extends CustomPoolSettings {
+    pool_name: string;
+    pool_url: string;
+    algorithm: CustomPoolAlgorithm;
+    stats_url: string; // "%TARI_ADDRESS%" is replaced with the mining address
+}
+
 export interface BasePoolData {
     pool_name: string;
-    pool_type: GpuPools | CpuPools;
-    pool_origin: PoolOrigin;
+    pool_type: GpuPools | CpuPools | string; // Custom pools use "Custom:<id>" for CPU and "Custom:<algorithm>:<id>" for GPU
+    pool_origin: PoolOrigin | { Custom: CustomPoolSettings };
     pool_url: string;
     stats_url: string;
 }
@@ -107,6 +107,8 @@ export enum GpuMinerFeature {
     DeviceExclusion = 'DeviceExclusion',
     MiningIntensity = 'MiningIntensity',
     EngineSelection = 'EngineSelection',
+    PerDeviceIntensity = 'PerDeviceIntensity',
+    PerDeviceEngineSelection = 'PerDeviceEngineSelection',
 }
 
 export enum GpuMiningAlgorithm {
@@ -129,3 +131,109 @@ export interface GpuMiner {
     is_healthy: boolean;
     last_error?: string;
 }
+
+export interface PoolFailoverPayload {
+    miner: 'Cpu' | 'Gpu';
+    kind: 'Failover' | 'SwitchBack';
+    from_pool: string;
+    to_pool: string;
+}
+
+export interface GpuBenchmarkRun {
+    device_id: number;
+    miner: GpuMinerType;
+    engine: 'OpenCL' | 'Cuda' | 'Metal';
+    intensity_percentage: number;
+}
+
+export interface GpuBenchmarkResult extends GpuBenchmarkRun {
+    algorithm: GpuMiningAlgorithm;
+    average_hash_rate: number;
+    stability: number; // Share of samples with a reported hashrate, from 0 to 1
+    average_power_watts?: number;
+    error?: string;
+}
+
+export interface GpuDeviceRecommendation extends GpuBenchmarkRun {
+    average_hash_rate: number;
+}
+
+export interface GpuBenchmarkReport {
+    finished_at: number; // Unix timestamp in seconds
+    algorithm: GpuMiningAlgorithm;
+    results: GpuBenchmarkResult[];
+    recommendations: GpuDeviceRecommendation[];
+}
+
+export interface GpuBenchmarkProgressPayload {
+    state: 'Running' | 'Completed' | 'Cancelled' | 'Failed';
+    completed_runs: number;
+    total_runs: number;
+    current_run?: GpuBenchmarkRun;
+    error?: string;
+}
+
+export interface GpuEnginePreferencesIgnoredPayload {
+    engine: string; // Engine the devices are mined with
+    device_indexes: number[];
+}
+
+export type GpuWatchdogReason =
+    | { Temperature: { celsius: number; limit: number } }
+    | { Power: { watts: number; limit: number } };
+
+export type GpuWatchdogStep =
+    | { ReducedIntensity: { from: number; to: number } }
+    | 'ExcludedDevice'
+    | 'PausedMining'
+    | 'Resumed';
+
+export interface GpuWatchdogIntervention {
+    device_id: number;
+    step: GpuWatchdogStep;
+    reason?: GpuWatchdogReason; // Not set when resuming
+    time: string;
+    error?: string;
+}
+
+export type ProcessCrashAction =
+    | { Restart: { backoff_secs: number } }
+    | 'Stop'
+    | { Fallback: { adapter?: string } }; // Name of the process that took over
+
+export type ProcessBinary =
+    | 'Xmrig'
+    | 'MergeMiningProxy'
+    | 'MinotariNode'
+    | 'Wallet'
+    | 'Tor'
+    | 'BridgeTapplet'
+    | 'Glytex'
+    | 'Graxil'
+    | 'LolMiner';
+
+export type ProcessLogLevel = 'Trace' | 'Debug' | 'Info' | 'Warn' | 'Error';
+
+export interface ProcessOutputLine {
+    binary: ProcessBinary;
+    process: string;
+    stream: 'Stdout' | 'Stderr';
+    level: ProcessLogLevel;
+    line: string;
+    time: string;
+}
+
+export interface ProcessCrashReport {
+    process_name: string;
+    exit_code?: number; // Not set when the process was stopped because of failed health checks
+    stderr_tail: string[];
+    crashes_in_window: number;
+    action: ProcessCrashAction;
+    time: string;
+}
+
+export interface UnrelatedProcessWarning {
+    process_name: string;
+    pid: number;
+    reason: string;
+}
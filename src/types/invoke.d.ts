@@ -5,14 +5,45 @@ import {
     BridgeEnvs,
     TariAddressVariants,
     BaseNodeStatus,
+    CpuMinerStatus,
 } from './app-status';
 import { Language } from '@app/i18initializer';
 import { PaperWalletDetails } from './app-status.ts';
 import { displayMode } from '../store/types.ts';
-import { BasePoolData, ConfigBackendInMemory, PauseOnBatteryModeState } from './configs.ts';
+import {
+    BasePoolData,
+    ConfigBackendInMemory,
+    CpuTuningOverrides,
+    CustomPoolDefinition,
+    GpuWatchdogAction,
+    PauseOnBatteryModeState,
+    ProcessIsolation,
+} from './configs.ts';
 import { ExchangeMiner } from './exchange';
 import { ActiveTapplet } from './tapplets/tapplet.types';
-import { SchedulerEventTiming, SchedulerEventType } from './mining/schedule.ts';
+import {
+    GpuMiningAlgorithm,
+    GpuWatchdogIntervention,
+    ProcessBinary,
+    ProcessLogLevel,
+    ProcessOutputLine,
+} from './events-payloads';
+import {
+    SchedulerEventTiming,
+    SchedulerEventType,
+    SchedulerExecutionRecord,
+    SchedulerTransition,
+} from './mining/schedule.ts';
+import { PoolProbeResult } from './mining/pool-probe.ts';
+import {
+    PoolEarningsAggregate,
+    PoolEarningsDataset,
+    PoolEarningsExportFormat,
+    PoolEarningsFilter,
+    PoolEarningsPeriod,
+    PoolPayout,
+} from './mining/pool-earnings.ts';
+import { ControlApiSettings, MetricsServerSettings } from './config/core.ts';
 
 declare module '@tauri-apps/api/core' {
     function invoke(
@@ -44,6 +75,12 @@ declare module '@tauri-apps/api/core' {
             customGpuUsage: number;
         }
     ): Promise<void>;
+    function invoke(param: 'get_cpu_miner_stats'): Promise<CpuMinerStatus>;
+    function invoke(param: 'update_mode_cpu_tuning', payload: { modeName: string; cpuTuning: CpuTuningOverrides }): Promise<void>;
+    function invoke(
+        param: 'update_custom_mode_process_isolation',
+        payload: { processIsolation?: ProcessIsolation }
+    ): Promise<void>;
     function invoke(param: 'set_display_mode', payload: { displayMode: displayMode }): Promise<void>;
     function invoke(param: 'get_seed_words'): Promise<string[]>;
     function invoke(param: 'revert_to_internal_wallet'): Promise<void>;
@@ -79,6 +116,38 @@ declare module '@tauri-apps/api/core' {
         param: 'toggle_device_exclusion',
         payload: { device_index: number; excluded: boolean }
     ): Promise<void>;
+    function invoke(
+        param: 'set_gpu_device_intensity',
+        payload: { deviceIndex: number; intensityPercentage?: number }
+    ): Promise<void>;
+    function invoke(
+        param: 'set_gpu_device_engine',
+        payload: { deviceIndex: number; engine?: 'OpenCL' | 'CUDA' | 'Metal' }
+    ): Promise<void>;
+    function invoke(
+        param: 'set_gpu_device_miner',
+        payload: { deviceIndex: number; miner?: GpuMinerType }
+    ): Promise<void>;
+    function invoke(
+        param: 'start_gpu_benchmark',
+        payload: { runDurationSecs?: number; applyRecommendations: boolean }
+    ): Promise<void>;
+    function invoke(param: 'cancel_gpu_benchmark'): Promise<void>;
+    function invoke(param: 'apply_gpu_benchmark_recommendations'): Promise<void>;
+    function invoke(
+        param: 'set_gpu_device_limits',
+        payload: { deviceIndex: number; maxTemperature?: number; maxPowerWatts?: number }
+    ): Promise<void>;
+    function invoke(param: 'set_gpu_watchdog_action', payload: { action: GpuWatchdogAction }): Promise<void>;
+    function invoke(param: 'get_gpu_watchdog_interventions'): Promise<GpuWatchdogIntervention[]>;
+    function invoke(
+        param: 'get_process_logs',
+        payload: { binary: ProcessBinary; limit?: number; minLevel?: ProcessLogLevel }
+    ): Promise<ProcessOutputLine[]>;
+    function invoke(
+        param: 'search_process_logs',
+        payload: { binary: ProcessBinary; query: string; limit?: number }
+    ): Promise<ProcessOutputLine[]>;
     function invoke(
         param: 'set_show_experimental_settings',
         payload: { showExperimentalSettings: boolean }
@@ -125,8 +194,33 @@ declare module '@tauri-apps/api/core' {
         payload: { paymentId: string; tariAddress: string }
     ): Promise<string>;
     function invoke(param: 'save_wxtm_address', payload: { address: string; exchangeId: string }): Promise<void>;
-    function invoke(param: 'change_cpu_pool', payload: { cpuPool: string }): Promise<void>;
-    function invoke(param: 'change_gpu_pool', payload: { gpuPool: string }): Promise<void>;
+    function invoke(param: 'change_cpu_pool', payload: { cpuPool: string; force?: boolean }): Promise<void>;
+    function invoke(param: 'change_gpu_pool', payload: { gpuPool: string; force?: boolean }): Promise<void>;
+    function invoke(param: 'probe_pool', payload: { poolKey: string }): Promise<PoolProbeResult>;
+    function invoke(param: 'set_cpu_fallback_pools', payload: { cpuPools: string[] }): Promise<void>;
+    function invoke(
+        param: 'set_gpu_fallback_pools',
+        payload: { algorithm: GpuMiningAlgorithm; gpuPools: string[] }
+    ): Promise<void>;
+    function invoke(param: 'set_pool_failover_timeout', payload: { timeoutSecs: number }): Promise<void>;
+    function invoke(param: 'set_pool_worker_name', payload: { workerName: string }): Promise<void>;
+    function invoke(param: 'use_hostname_as_pool_worker_name'): Promise<string>;
+    function invoke(param: 'create_custom_pool', payload: { customPool: CustomPoolDefinition }): Promise<string>;
+    function invoke(
+        param: 'update_custom_pool',
+        payload: { poolKey: string; customPool: CustomPoolDefinition }
+    ): Promise<void>;
+    function invoke(param: 'delete_custom_pool', payload: { poolKey: string }): Promise<void>;
+    function invoke(
+        param: 'get_pool_earnings_aggregates',
+        payload: { period: PoolEarningsPeriod; filter?: PoolEarningsFilter }
+    ): Promise<PoolEarningsAggregate[]>;
+    function invoke(param: 'get_pool_payouts', payload?: { filter?: PoolEarningsFilter }): Promise<PoolPayout[]>;
+    function invoke(
+        param: 'export_pool_earnings',
+        payload: { dataset: PoolEarningsDataset; format: PoolEarningsExportFormat; filter?: PoolEarningsFilter }
+    ): Promise<string>;
+    function invoke(param: 'clear_pool_earnings_history'): Promise<void>;
     function invoke(param: 'update_selected_cpu_pool', payload: { updated_config: BasePoolData }): Promise<void>;
     function invoke(param: 'update_selected_gpu_pool', payload: { updated_config: BasePoolData }): Promise<void>;
     function invoke(param: 'reset_gpu_pool_config', payload: { gpuPoolType: string }): Promise<void>;
@@ -140,6 +234,12 @@ declare module '@tauri-apps/api/core' {
     function invoke(param: 'mark_shutdown_selection_as_completed', payload: { dontAskAgain: boolean }): Promise<void>;
     function invoke(param: 'mark_feedback_survey_as_completed'): Promise<void>;
     function invoke(param: 'update_shutdown_mode_selection', payload: { shutdownMode: ShutdownMode }): Promise<void>;
+    function invoke(param: 'update_metrics_server_settings', payload: { settings: MetricsServerSettings }): Promise<void>;
+    function invoke(
+        param: 'update_control_api_settings',
+        payload: { enabled: boolean; bindAddress: string }
+    ): Promise<ControlApiSettings>;
+    function invoke(param: 'regenerate_control_api_token'): Promise<ControlApiSettings>;
     function invoke(
         param: 'set_pause_on_battery_mode',
         payload: { pauseOnBatteryMode: PauseOnBatteryModeState }
@@ -147,9 +247,17 @@ declare module '@tauri-apps/api/core' {
     // Scheduler commands
     function invoke(
         param: 'add_scheduler_event',
-        payload: { eventId: string; eventTime: SchedulerEventTiming; eventType: SchedulerEventType }
+        payload: {
+            eventId: string;
+            eventTime: SchedulerEventTiming;
+            eventType: SchedulerEventType;
+            priority?: number;
+        }
     ): Promise<void>;
     function invoke(param: 'remove_scheduler_event', payload: { eventId: string }): Promise<void>;
     function invoke(param: 'pause_scheduler_event', payload: { eventId: string }): Promise<void>;
     function invoke(param: 'resume_scheduler_event', payload: { eventId: string }): Promise<void>;
+    function invoke(param: 'get_scheduler_next_transitions', payload: { count: number }): Promise<SchedulerTransition[]>;
+    function invoke(param: 'get_scheduler_history', payload: { limit?: number }): Promise<SchedulerExecutionRecord[]>;
+    function invoke(param: 'clear_scheduler_history'): Promise<void>;
 }
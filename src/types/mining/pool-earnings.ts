@@ -0,0 +1,39 @@
+import { PoolStats } from '@app/types/app-status.ts';
+
+export type PoolEarningsPeriod = 'Daily' | 'Weekly';
+export type PoolEarningsDataset = 'Samples' | 'Payouts' | 'Daily' | 'Weekly';
+export type PoolEarningsExportFormat = 'Csv' | 'Json';
+
+export interface PoolEarningsFilter {
+    pool_name?: string;
+    mining_address?: string;
+    from?: string; // RFC 3339, inclusive
+    to?: string; // RFC 3339, exclusive
+}
+
+export interface PoolEarningsSample extends PoolStats {
+    timestamp: string;
+    pool_name: string;
+    mining_address: string;
+}
+
+export interface PoolPayout {
+    timestamp: string;
+    pool_name: string;
+    mining_address: string;
+    amount: number;
+    unpaid_before: number;
+    unpaid_after: number;
+}
+
+export interface PoolEarningsAggregate {
+    period_start: string; // UTC date, weeks start on Monday
+    pool_name: string;
+    mining_address: string;
+    earned: number;
+    paid_out: number;
+    payouts: number;
+    accepted_shares: number;
+    unpaid: number;
+    samples: number;
+}
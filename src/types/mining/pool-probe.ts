@@ -0,0 +1,15 @@
+export type PoolProbeFailure = 'InvalidUrl' | 'Dns' | 'Refused' | 'Timeout' | 'Tls' | 'Protocol' | 'Connection';
+
+export type PoolProbeOutcome =
+    | { Reachable: { login_accepted: boolean; message?: string } }
+    | { Failed: { failure: PoolProbeFailure; message: string } };
+
+export interface PoolProbeResult {
+    pool_url: string;
+    resolved_address?: string;
+    tls: boolean;
+    connect_latency_ms?: number;
+    tls_latency_ms?: number;
+    login_latency_ms?: number;
+    outcome: PoolProbeOutcome;
+}
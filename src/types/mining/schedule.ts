@@ -1,9 +1,38 @@
+import { CpuPools, GpuPools } from '../configs';
+import { GpuMinerType } from '../events-payloads';
+
 export const TIME_PERIOD = ['AM', 'PM'] as const;
 type TimePeriodTuple = typeof TIME_PERIOD;
 export type TimePeriod = TimePeriodTuple[number];
 
-export type SchedulerEventType = 'ResumeMining' | { Mine: { mining_mode: string } };
-export type SchedulerEventTiming = { In: InTime } | { Between: BetweenTime };
+export type SchedulerAction =
+    | { SetCpuMiningEnabled: { enabled: boolean } }
+    | { SetGpuMiningEnabled: { enabled: boolean } }
+    | { ChangeCpuPool: { cpu_pool: CpuPools } }
+    | { ChangeGpuPool: { gpu_pool: GpuPools } }
+    | { SwitchGpuMiner: { gpu_miner_type: GpuMinerType } }
+    | { SetCpuPoolMining: { enabled: boolean } }
+    | { SetGpuPoolMining: { enabled: boolean } }
+    | { SetUseTor: { enabled: boolean } };
+export type SchedulerEventType =
+    | 'ResumeMining'
+    | { Mine: { mining_mode: string } }
+    | { Action: { action: SchedulerAction } };
+export type SchedulerEventTiming =
+    | { In: InTime }
+    | { Between: BetweenTime }
+    | { BetweenWindows: { windows: BetweenTime[] } }
+    | { Condition: { condition: SchedulerCondition } };
+
+export type TemperatureSource = 'Cpu' | 'Gpu';
+export type SchedulerCondition =
+    | { IdleFor: { minutes: number } }
+    | { TemperatureAbove: { source: TemperatureSource; celsius: number } }
+    | { BatteryBelow: { percentage: number } };
+
+export const DAYS_OF_WEEK = ['Monday', 'Tuesday', 'Wednesday', 'Thursday', 'Friday', 'Saturday', 'Sunday'] as const;
+type DaysOfWeekTuple = typeof DAYS_OF_WEEK;
+export type DayOfWeek = DaysOfWeekTuple[number];
 
 export interface TimeParts {
     hour: number;
@@ -20,6 +49,7 @@ export enum TimeUnit {
 export interface InTime {
     time_value: number;
     time_unit: TimeUnit;
+    deadline?: string | null;
 }
 
 export interface BetweenTime {
@@ -29,6 +59,7 @@ export interface BetweenTime {
     end_hour: number;
     end_minute: number;
     end_period: TimePeriod;
+    days_of_week?: DayOfWeek[];
 }
 
 export enum SchedulerEventState {
@@ -43,4 +74,25 @@ export interface SchedulerEvent {
     event_type: SchedulerEventType;
     timing: SchedulerEventTiming;
     state: SchedulerEventState;
+    priority?: number;
+    restore_action?: SchedulerAction | null;
+}
+
+export interface SchedulerTransition {
+    time: string;
+    event_id: string;
+    kind: 'Enter' | 'Exit';
+    resulting_mining_mode?: string | null;
+}
+
+export type SchedulerExecutionOutcome = 'Executed' | { Skipped: { reason: string } } | { Failed: { errors: string[] } };
+
+export interface SchedulerExecutionRecord {
+    event_id: string;
+    event_type: SchedulerEventType;
+    timing: SchedulerEventTiming;
+    kind: 'Enter' | 'Exit';
+    planned_time?: string | null;
+    actual_time: string;
+    outcome: SchedulerExecutionOutcome;
 }